@@ -2,18 +2,100 @@ use std::{
     env,
     path::{Path, PathBuf},
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use log::{info, debug, warn, error};
 
+use pinepods_firewood::announce::Announce;
+use pinepods_firewood::audiobook;
+use pinepods_firewood::backups::{self, Backup, Settings};
+use pinepods_firewood::cache::Cache;
+use pinepods_firewood::changelog::{self, Release};
+use pinepods_firewood::color_depth;
+use pinepods_firewood::errors;
+use pinepods_firewood::format;
+use pinepods_firewood::feed::{self, Chapter, Feed, Funding, LiveItem, LiveStatus, Quality, Soundbite};
 use pinepods_firewood::gen_funcs;
+use pinepods_firewood::handoff;
+use pinepods_firewood::html;
+use pinepods_firewood::key_sequences::Motion;
+use pinepods_firewood::downloads::{Downloads, Finished};
+use pinepods_firewood::episode_settings::EpisodeSettings;
+use pinepods_firewood::local_files::{self, Entry};
+use pinepods_firewood::local_store::{self, Kept, LocalStore};
+use pinepods_firewood::media_keys::{MediaKeyEvent, MediaKeyStatus, MediaKeys, NowPlaying};
 use pinepods_firewood::music_handler::MusicHandle;
+use pinepods_firewood::music_handler::{MAX_SPEED, MIN_SPEED};
+use pinepods_firewood::notifications::{Desktop, Kind};
+use pinepods_firewood::outbox::{Action, Flushed, Outbox};
+use pinepods_firewood::paste::{self, Pasted};
+use pinepods_firewood::palette::{self, Entry as PaletteEntry, Palette};
+use pinepods_firewood::play_history;
+use pinepods_firewood::playlist_import::{self, Import, Link};
+use pinepods_firewood::podcast_filter::PodcastFilter;
+use pinepods_firewood::podcast_flags::{Flags, Notify, PodcastFlags};
+use pinepods_firewood::podcast_search::{self, Keys, Provider};
+use pinepods_firewood::profiler::Profile;
+use pinepods_firewood::profiles::{self, Profiles};
 use pinepods_firewood::queue::Queue;
+use pinepods_firewood::queue_merge::{Merge, QueueSync};
+use pinepods_firewood::refresh::Scheduler;
+use pinepods_firewood::search_index::SearchIndex;
+use pinepods_firewood::searches::{self, SavedSearches};
+use pinepods_firewood::server_downloads::ServerDownloads;
+use pinepods_firewood::smart_sort::SmartSort;
+use pinepods_firewood::snapshots::Snapshots;
 use pinepods_firewood::stateful_list::StatefulList;
 use pinepods_firewood::stateful_table::StatefulTable;
+use pinepods_firewood::stats::{PodcastStats, Stats};
+use pinepods_firewood::subscriptions::{self, SubscriptionChanges};
+use pinepods_firewood::telemetry;
+use pinepods_firewood::transcripts::{self, Segment};
 use pinepods_firewood::helpers::requests::ReqwestValues;
 use pinepods_firewood::requests::{PinepodsEpisodes, PinepodsPodcasts};
+use crate::config::Config;
+use crate::remote::access::{Access, AccessLog, Client};
+use crate::remote::cast;
+use crate::remote::discovery::{self, Browser, Peer};
+use crate::remote::webhooks::Webhooks;
+use crate::remote::{Command, Reply, Request, Status};
+use crate::tutorial::{Regions, Step};
+use crossterm::event::KeyCode;
+use ratatui::style::Color;
+use futures::StreamExt;
+use tokio::sync::oneshot;
+
+// how often the server hears where we are in an episode
+const POSITION_REPORT_INTERVAL: Duration = Duration::from_secs(30);
+// how long to wait before retrying actions the server didn't get
+const OUTBOX_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+// between looks at what the server has downloaded, while it's downloading
+const SERVER_DOWNLOAD_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+// how far the global seek, volume and speed shortcuts move things
+pub const SEEK_STEP: i64 = 15;
+pub const VOLUME_STEP: f32 = 0.05;
+pub const SPEED_STEP: f32 = 0.1;
+// how long the overlay stays up after a playback shortcut
+const OSD_DURATION: Duration = Duration::from_millis(1500);
+// how often queued telemetry reports are sent, when turned on
+const TELEMETRY_UPLOAD_INTERVAL: Duration = Duration::from_secs(600);
+// feeds fetched at once while checking
+const LIVE_CHECK_CONCURRENCY: usize = 8;
+// how long a notification stays up
+const NOTIFICATION_DURATION: Duration = Duration::from_secs(10);
+// search results listed at a time, with a row for the rest below
+const SEARCH_PAGE: usize = 50;
+// this close to that row and the next page is listed without asking
+const SEARCH_AHEAD: usize = 10;
+// this close to the end and an episode counts as played to it
+const FINISHED_SLACK: u16 = 30;
+// how far a podcast's intro and outro skips move at a time, and the most
+const SKIP_STEP: u16 = 5;
+const MAX_SKIP: u16 = 600;
+// how far + and - move a color in the theme editor
+const COLOR_STEP: u8 = 8;
 
 #[derive(Clone, Copy)]
 pub enum InputMode {
@@ -40,49 +122,616 @@ impl AppTab {
     }
 }
 
+/// What key presses go to. Global shortcuts only fire for `Main`, so whatever
+/// takes focus (popups, text inputs) can use any key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    Main,
+    Popup,
+    TextInput,
+}
+
+/// What a line of text being typed in is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prompt {
+    LogFilters,
+    SnapshotName,
+    SavedSearch,
+    Search,
+    ImportLink,
+    FindPodcasts,
+    PlanMinutes,
+    RemoteAddress,
+    // typed in masked
+    UserPin,
+    NewPin,
+}
+
+/// A change to the remote control server, made by the loop that owns it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteChange {
+    /// on if it's off, off if it's on
+    Toggle,
+    /// "port" or "address:port", as typed
+    Listen(String),
+    /// off for a while, then back on by itself
+    Pause,
+    /// a new token in place of the one clients have, for this run
+    NewToken,
+}
+
+/// Who's been using the remote control, while the list of them is open.
+pub struct RemoteClients {
+    pub clients: StatefulList<Client>,
+    /// newest first
+    pub requests: Vec<Access>,
+    /// made while the list's been open, to show until it's closed
+    pub new_token: Option<String>,
+}
+
+pub struct TextInput {
+    pub prompt: Prompt,
+    pub value: String,
+}
+
+/// What the detail popup shows for a podcast.
+pub struct PodcastDetail {
+    pub name: String,
+    pub author: String,
+    pub website: String,
+    pub description: String,
+    // from the feed itself, the selected one can be opened or copied
+    pub funding: StatefulList<Funding>,
+    // a feed that couldn't be read, or what happened to the last link
+    pub notice: Option<String>,
+    feed_url: String,
+    /// since the feed's been fetched for the funding links, until it's in
+    pub loading: Option<Instant>,
+    pub flags: Flags,
+    // from the listening history, once it's been fetched, None if nothing of
+    // it's been played
+    pub stats: Option<PodcastStats>,
+}
+
+impl PodcastDetail {
+    // the funding links, from the feed once it's been read
+    fn fill(&mut self, feed: Option<&Feed>) {
+        match feed {
+            Some(feed) => {
+                self.funding = StatefulList::with_items(feed.funding.clone());
+                self.funding.next();
+            }
+            None => self.notice = Some(String::from("Couldn't read the feed for funding links")),
+        }
+    }
+}
+
+/// How one podcast's episodes play, over how everything else does, while the
+/// popup for it is open.
+pub struct PodcastSettings {
+    pub podcast: String,
+    pub flags: Flags,
+    // speed, intro or outro, being changed
+    pub row: usize,
+}
+
+/// The theme's colors being tweaked, shown everywhere as they change until
+/// they're saved, or put back if they aren't.
+pub struct ThemeEditor {
+    /// foreground, background, highlight foreground and highlight background
+    pub colors: [(u8, u8, u8); 4],
+    pub row: usize,
+    /// red, green or blue, being changed
+    pub channel: usize,
+    original: [Color; 4],
+}
+
+impl ThemeEditor {
+    pub fn shown(&self) -> [Color; 4] {
+        self.colors.map(|(r, g, b)| Color::Rgb(r, g, b))
+    }
+}
+
+/// A change to the theme, made by the loop that owns the config.
+pub enum ThemeChange {
+    Show([Color; 4]),
+    Save([Color; 4]),
+}
+
+/// What the detail popup shows for an episode.
+pub struct EpisodeDetail {
+    pub episode: PinepodsEpisodes,
+    // from the feed, the selected one can be played as a preview
+    pub soundbites: StatefulList<Soundbite>,
+    // which of the feed's enclosures the episode plays, when it has several
+    pub variant: Option<String>,
+    pub notice: Option<String>,
+    feed_url: Option<String>,
+    /// since the feed's been fetched for the soundbites, until it's in
+    pub loading: Option<Instant>,
+    // in the show notes, by their number from 1, and the number being typed
+    // to open one
+    pub links: Vec<String>,
+    pub link_typed: usize,
+    /// lines of the show notes scrolled past
+    pub scroll: u16,
+}
+
+impl EpisodeDetail {
+    // the soundbites and the enclosure played, from the feed once it's been read
+    fn fill(&mut self, feed: Option<&Feed>) {
+        let Some(feed) = feed else {
+            self.notice = Some(String::from("Couldn't read the feed for soundbites"));
+            return;
+        };
+        let item = feed.item(&self.episode.EpisodeURL, &self.episode.EpisodeTitle);
+        let soundbites = item.map(|item| item.soundbites.clone()).unwrap_or_default();
+        self.variant = item
+            .filter(|item| !item.alternates.is_empty())
+            .map(|item| item.label(&self.episode.EpisodeURL));
+        self.soundbites = StatefulList::with_items(soundbites);
+        self.soundbites.next();
+    }
+}
+
+/// A row of the list of episodes kept on this computer.
+#[derive(Debug, Clone)]
+pub enum KeptItem {
+    /// still on its way, by url, see `App::downloads` for how far along
+    Downloading(String),
+    Kept(Box<Kept>),
+}
+
+/// What to put back from a backup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Restore {
+    Everything,
+    Queue,
+    /// subscribe again to the podcasts that have gone since
+    Podcasts,
+    /// takes effect the next time firewood starts
+    Settings,
+}
+
+/// The podcast an episode was played from in the browser, so next and
+/// previous go through its episodes rather than the queue.
+pub struct PlayingFrom {
+    pub podcast: String,
+    /// oldest first
+    episodes: Vec<PinepodsEpisodes>,
+}
+
+/// Episodes that fit the time there is, and what they were picked from so
+/// another plan can be made.
+pub struct ListeningPlan {
+    pub episodes: Vec<PinepodsEpisodes>,
+    candidates: Vec<PinepodsEpisodes>,
+    // plans made from these candidates so far
+    made: u64,
+}
+
+/// Asking before having the server download a podcast's unplayed episodes.
+pub struct DownloadConfirm {
+    pub podcast: String,
+    pub episodes: Vec<i64>,
+    // seconds, the server doesn't say how big episodes are
+    pub duration: i64,
+}
+
+/// Asking which order the queue goes in, when it's been reordered both here
+/// and elsewhere since it was last synced with the server's.
+pub struct QueueConflict {
+    pub merge: Merge,
+    /// the server's queue, in its order
+    pub server: Vec<PinepodsEpisodes>,
+}
+
+/// How a queue conflict's settled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Settle {
+    /// everything added on either, in the order here
+    Merge,
+    Here,
+    Server,
+}
+
+/// A search of a podcast directory for podcasts to subscribe to.
+pub struct PodcastSearch {
+    pub query: String,
+    pub provider: Provider,
+    pub results: StatefulList<PinepodsPodcasts>,
+    // still waiting on the directory, or on the feed of the one selected
+    pub searching: bool,
+    pub previewing: bool,
+}
+
+/// Audio files on this computer, a folder at a time.
+pub struct FileBrowser {
+    pub dir: PathBuf,
+    pub entries: StatefulList<Entry>,
+    /// whether the folder's files are an audiobook's parts
+    pub audiobook: bool,
+}
+
+/// Where an entry in the palette goes.
+pub enum Jump {
+    Podcast(PinepodsPodcasts),
+    Search(String),
+    Episode(PinepodsEpisodes),
+    // by where it is in the queue
+    Queued(usize),
+    Kept(Kept),
+    Downloading,
+    Go(InputMode),
+    /// a key from the Controls tab, pressed with the part of the screen it's
+    /// for in focus, or whatever has it for those that work everywhere
+    Command(Option<InputMode>, KeyCode),
+}
+
+// the episodes of the search index put in the palette, the newest
+const PALETTE_EPISODES: usize = 500;
+
+/// The transcript of what's playing, following along with it unless a line's
+/// been picked out to jump to.
+pub struct TranscriptView {
+    pub url: String,
+    pub lines: StatefulList<Segment>,
+    pub loading: bool,
+    pub error: Option<String>,
+    pub follow: bool,
+}
+
+// the audiobook part playing, the part after it and how long those after it
+// are altogether
+struct Book {
+    url: String,
+    next: Option<PinepodsEpisodes>,
+    later: i64,
+}
+
+/// A live or upcoming stream from one of the subscribed feeds.
+#[derive(Debug, Clone)]
+pub struct LiveShow {
+    pub podcast: String,
+    pub item: LiveItem,
+}
+
+/// Feedback overlay for playback shortcuts used away from the Music tab.
+#[derive(Debug, Clone, Copy)]
+pub enum Osd {
+    Position,
+    Volume,
+    Speed,
+}
+
 pub enum ContentState {
     PodcastMode { feed_url: String },
-    EpisodeMode { podcast_id: i64 },
+    EpisodeMode { podcast_id: i64, feed_url: String },
+    // the episodes of a saved search
+    SearchMode { term: String },
     PlayingEpisode { ep_url: String }
 }
 
+#[derive(Clone)]
 pub enum BrowserItem {
     Podcast(PinepodsPodcasts),
     Episode(PinepodsEpisodes),
+    // a saved search, listed with the podcasts
+    Search(String),
+    // the end of a page of search results, with how many more there are
+    More(usize),
+}
+
+/// What gets refreshed in the background. The podcast list and live shows go
+/// together on one interval, the episode list has its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Refresh {
+    Podcasts,
+    Live,
+    Episodes,
+    Searches,
+    Stats,
+    Backup,
+    Queue,
+}
+
+// a page refresh that came back, for the list it was fetched for
+enum Refreshed {
+    Podcasts(Vec<PinepodsPodcasts>),
+    Episodes(i64, Vec<PinepodsEpisodes>),
+}
+
+// what a search refresh found, with the unplayed counts from the same
+// episodes and the episodes themselves for the search index
+struct SearchRefresh {
+    results: HashMap<String, Vec<PinepodsEpisodes>>,
+    unplayed: HashMap<String, usize>,
+    episodes: Vec<PinepodsEpisodes>,
 }
 
+// the server's queue, and the url of the episode that finished before it was
+// asked for
+type ServerQueue = (String, Result<Vec<PinepodsEpisodes>, String>);
+// what a directory found, with the provider and query it was asked
+type DirectoryResults = (Provider, String, Result<Vec<PinepodsPodcasts>, String>);
+// a transcript, by the url of the episode it's for
+type TranscriptLines = (String, Result<Vec<Segment>, String>);
+
 pub enum SelectedItem<'a> {
     Podcast(&'a PinepodsPodcasts),
     Episode(&'a PinepodsEpisodes),
 }
 
 
+// saved searches go above the subscriptions, which are filtered
+fn podcast_items(searches: &SavedSearches, filter: &PodcastFilter, podcasts: &[PinepodsPodcasts]) -> Vec<BrowserItem> {
+    let podcasts = podcasts
+        .iter()
+        .filter(|podcast| filter.shows(&podcast.PodcastName))
+        .cloned()
+        .map(BrowserItem::Podcast);
+    searches
+        .terms()
+        .iter()
+        .cloned()
+        .map(BrowserItem::Search)
+        .chain(podcasts)
+        .collect()
+}
+
+fn never_started(episode: &PinepodsEpisodes) -> bool {
+    episode.ListenDuration.unwrap_or(0) == 0
+}
+
+fn unplayed(episodes: &[PinepodsEpisodes]) -> usize {
+    episodes.iter().filter(|episode| never_started(episode)).count()
+}
+
+
+fn subscription_feeds(podcasts: &[PinepodsPodcasts]) -> Vec<(String, String)> {
+    podcasts
+        .iter()
+        .map(|podcast| (podcast.PodcastName.clone(), podcast.FeedURL.clone()))
+        .collect()
+}
+
+// an empty list is far more likely a failed fetch than a mass unsubscribe
+fn subscription_changes(podcasts: &[PinepodsPodcasts]) -> Option<SubscriptionChanges> {
+    if podcasts.is_empty() {
+        return None;
+    }
+    let changes = subscriptions::detect_changes(podcasts);
+    (!changes.is_empty()).then_some(changes)
+}
+
 pub struct App<'a> {
     pub browser_items: StatefulList<BrowserItem>,
     pub queue_items: Queue,
     pub control_table: StatefulTable<'a>,
     pub music_handle: MusicHandle,
+    pub media_keys: Option<MediaKeys>,
     input_mode: InputMode,
     pub titles: Vec<&'a str>,
     pub active_tab: AppTab,
     pub pinepods_values: Arc<Mutex<ReqwestValues>>,
     pub content_state: ContentState,
+    pub data_saver: bool,
+    // playing from partway down the queue keeps the items above it
+    pub keep_earlier: bool,
+    // carry on with the next in the queue, the server's once this one's empty
+    pub continuous: bool,
+    // played before carrying on, and whether the one that just finished has
+    // had it yet
+    pub announce: Announce,
+    announce_next: bool,
+    // subscriptions as tiles instead of a list
+    pub podcast_grid: bool,
+    // tiles per row, kept up to date while drawing
+    pub grid_columns: usize,
+    // the podcast list stays down the side of the episodes
+    pub podcast_rail: bool,
+    // episodes never started, by podcast name, as of the last time they
+    // were fetched
+    unplayed: HashMap<String, usize>,
+    // how far Page Up and Page Down move, kept up to date while drawing
+    pub page_size: usize,
+    pub outbox: Outbox,
+    pub subscription_changes: Option<SubscriptionChanges>,
+    pub text_input: Option<TextInput>,
+    pub telemetry_preview: bool,
+    pub whats_new: Option<&'static Release>,
+    pub tutorial: Option<Step>,
+    // where the tour's targets are, kept up to date while drawing
+    pub regions: Regions,
+    // which enclosure to play, overridden per podcast by name
+    pub quality: Quality,
+    pub podcast_quality: HashMap<String, Quality>,
+    pub podcast_detail: Option<PodcastDetail>,
+    // over the podcast detail, while open
+    pub podcast_settings: Option<PodcastSettings>,
+    // url of the episode its podcast's settings were last put on for, and the
+    // speed from before that podcast's own
+    settings_applied: Option<String>,
+    speed_before: Option<f32>,
+    // what's playing, when it's part of an audiobook
+    book: Option<Book>,
+    pub episode_detail: Option<EpisodeDetail>,
+    // parsed RSS feeds by url, fetched the first time something needs them
+    feeds: HashMap<String, Feed>,
+    // feeds being fetched in the background, by url
+    feed_checks: HashMap<String, oneshot::Receiver<Result<Feed, String>>>,
+    pub snapshots: Snapshots,
+    pub searches: SavedSearches,
+    pub podcast_filter: PodcastFilter,
+    // set with set_smart_sort, so the podcasts flagged high priority are kept
+    pub smart_sort: SmartSort,
+    pub podcast_flags: PodcastFlags,
+    // how episodes left partway were being played
+    episode_settings: EpisodeSettings,
+    // episode lists in smart order rather than newest first
+    pub smart_order: bool,
+    // episodes that fit the time there is, while the plan popup is open
+    pub listening_plan: Option<ListeningPlan>,
+    // None when playing from the queue
+    pub playing_from: Option<PlayingFrom>,
+    // asked for from the Controls tab, not made yet
+    pub remote_change: Option<RemoteChange>,
+    // the remote control's, once it's been on this run
+    pub remote_access: Option<AccessLog>,
+    pub remote_clients: Option<RemoteClients>,
+    // every subscription's latest episodes, for a plan made from the podcast list
+    plan_check: Option<oneshot::Receiver<Result<Vec<PinepodsEpisodes>, String>>>,
+    pub download_confirm: Option<DownloadConfirm>,
+    // every subscription to pick from, while the filter popup is open
+    pub filter_picker: Option<StatefulList<String>>,
+    // the last podcast list fetched, before filtering
+    podcasts: Vec<PinepodsPodcasts>,
+    // what pages were made from last time, to show again straight away
+    cache: Cache,
+    /// When what the browser's showing was fetched, while it's from the cache
+    /// and the fresh copy hasn't come in yet, in unix seconds.
+    pub stale: Option<i64>,
+    /// Since when the browser's been waiting on its page, with nothing cached
+    /// to show meanwhile.
+    pub loading: Option<Instant>,
+    // what each saved search found last time it ran
+    pub search_results: HashMap<String, Vec<PinepodsEpisodes>>,
+    // how many of the search results open are listed so far
+    search_shown: usize,
+    // every subscription's episodes, for searching without the server
+    search_index: SearchIndex,
+    // names of saved queues to pick from, while the picker is open
+    pub snapshot_picker: Option<StatefulList<String>>,
+    // backups to restore from, newest first, while the picker is open
+    pub backup_picker: Option<StatefulList<Backup>>,
+    backup_keep: usize,
+    // the settings file to back up, as the config found it
+    settings_path: Option<PathBuf>,
+    // podcasts subscribed to again from a backup, and how many of them failed
+    resubscribe_check: Option<oneshot::Receiver<(usize, usize)>>,
+    // episodes kept on this computer, to play without the server
+    local_store: LocalStore,
+    // what's kept, while the list of them is open
+    pub kept_picker: Option<StatefulList<KeptItem>>,
+    // audio files that aren't from the server, while the browser for them is
+    // open, and the folder it was last left in
+    pub file_browser: Option<FileBrowser>,
+    browsed_dir: Option<PathBuf>,
+    // episodes on their way into the local store
+    pub downloads: Downloads,
+    osd: Option<(Osd, Instant)>,
+    // timings for the debug overlay, None while it's closed
+    pub profile: Option<Profile>,
+    last_position_report: Instant,
+    last_outbox_flush: Instant,
+    // the last flush stopped with more to send, so the next goes right away
+    outbox_backlog: bool,
+    // the batch being sent, taken off the outbox once it's through
+    outbox_check: Option<oneshot::Receiver<Flushed>>,
+    /// asked of the server with Shift+D, until they're done there
+    pub server_downloads: ServerDownloads,
+    server_download_check: Option<oneshot::Receiver<Vec<i64>>>,
+    last_server_download_check: Instant,
+    // None until the first upload, which goes out right away
+    last_telemetry_upload: Option<Instant>,
+    // name and feed url of every subscription, for the live check
+    subscriptions: Vec<(String, String)>,
+    // running or scheduled live streams, running ones first
+    pub live_shows: Vec<LiveShow>,
+    live_check: Option<oneshot::Receiver<Vec<LiveShow>>>,
+    refresh: Scheduler<Refresh>,
+    // the page refresh in flight, if any
+    refreshed: Option<oneshot::Receiver<Refreshed>>,
+    // chapters of what's playing, by episode url, empty when it has none
+    chapters: Option<(String, Vec<Chapter>)>,
+    chapter_check: Option<oneshot::Receiver<(String, Vec<Chapter>)>>,
+    // the chapters to jump to, while the list of them is open
+    pub chapter_picker: Option<StatefulList<Chapter>>,
+    // everyone set up here, while the list of them is open
+    pub user_picker: Option<StatefulList<String>>,
+    // waiting on their PIN
+    switching_to: Option<String>,
+    // who to switch to, made by the loop that owns the app
+    pub user_switch: Option<String>,
+    pub theme_editor: Option<ThemeEditor>,
+    pub theme_change: Option<ThemeChange>,
+    // listening stats, None without any history to work them out from
+    pub stats: Option<Stats>,
+    stats_check: Option<oneshot::Receiver<Option<Stats>>>,
+    // for the podcast detail open, by podcast name
+    podcast_stats_check: Option<oneshot::Receiver<(String, Option<PodcastStats>)>>,
+    import_check: Option<oneshot::Receiver<Result<Import, String>>>,
+    // where new podcasts are looked for first, and PodcastIndex's keys if
+    // it's searched directly
+    search_provider: Provider,
+    podcastindex_keys: Option<Keys>,
+    pub podcast_search: Option<PodcastSearch>,
+    directory_check: Option<oneshot::Receiver<DirectoryResults>>,
+    // a result filled in from its feed, by feed url
+    preview_check: Option<oneshot::Receiver<(String, Result<PinepodsPodcasts, String>)>>,
+    // a pasted link being looked into, then what it came to while that's
+    // being asked about, then the podcast it's being subscribed to
+    paste_check: Option<oneshot::Receiver<Result<Pasted, String>>>,
+    pub pasted: Option<Pasted>,
+    subscribe_check: Option<oneshot::Receiver<Result<String, String>>>,
+    // other Firewoods on the LAN, while the list of them to cast to is open,
+    // and the remote control token to cast with
+    pub cast_picker: Option<StatefulList<Peer>>,
+    cast_browser: Option<Browser>,
+    cast_token: Option<String>,
+    // who it was cast to and from where
+    cast_check: Option<oneshot::Receiver<Result<(String, u16), String>>>,
+    pub transcript: Option<TranscriptView>,
+    pub palette: Option<Palette<Jump>>,
+    transcript_check: Option<oneshot::Receiver<TranscriptLines>>,
+    // what was playing on another device, to carry on with here
+    handoff_check: Option<oneshot::Receiver<Result<Option<PinepodsEpisodes>, String>>>,
+    // saved searches being run again, by term
+    search_check: Option<oneshot::Receiver<SearchRefresh>>,
+    // url, id, seconds in and length of the episode playing as of the last
+    // look, to tell once it's gone whether it played to the end
+    last_playing: Option<(String, Option<i64>, u16, u16)>,
+    // to carry on with once the queue here runs out
+    server_queue_check: Option<oneshot::Receiver<ServerQueue>>,
+    // the server's queue, to merge the one here with
+    queue_check: Option<oneshot::Receiver<Vec<PinepodsEpisodes>>>,
+    // reordered here since it was last merged, and when that was last tried
+    queue_edited: bool,
+    last_queue_check: Instant,
+    queue_sync: QueueSync,
+    pub queue_conflict: Option<QueueConflict>,
+    notification: Option<(String, Instant)>,
+    pub webhooks: Webhooks,
+    /// what's shown in the banner and worth telling the desktop too
+    pub desktop: Desktop,
 }
 
 impl<'a> App<'a> {
     pub async fn new(pinepods_values: Arc<Mutex<ReqwestValues>>) -> App<'a> {
-        let podcasts = gen_funcs::scan_folder(&pinepods_values).await;
-        error!("Fetched podcasts: {:?}", podcasts);
-        let podcast_items = podcasts.into_iter()
-            .map(BrowserItem::Podcast)
-            .collect();
+        // the last list fetched goes up straight away, or else the ui starts
+        // with it loading, the fresh one comes in the background either way
+        let cache = Cache::load();
+        let cached = cache.podcasts().map(|saved| (saved.fetched, saved.value.clone()));
+        let (stale, podcasts) = cached.map_or((None, Vec::new()), |(fetched, podcasts)| (Some(fetched), podcasts));
+        let mut queue_items = Queue::with_items();
+        queue_items.restore(cache.queue());
+        let changes = subscription_changes(&podcasts);
+        let subscriptions = subscription_feeds(&podcasts);
+        let searches = SavedSearches::load();
+        let podcast_filter = PodcastFilter::load();
+        let podcast_flags = PodcastFlags::load();
+        let podcast_items = podcast_items(&searches, &podcast_filter, &podcasts);
+
 
+        // new users get the tour instead of a list of what changed
+        let first_run = changelog::first_run();
 
-        App {
+        let mut app = App {
             browser_items: StatefulList::with_items(podcast_items),
-            queue_items: Queue::with_items(),
+            queue_items,
             control_table: StatefulTable::new(),
             music_handle: MusicHandle::new(),
+            media_keys: MediaKeys::new(),
             input_mode: InputMode::Browser,
             titles: vec!["Podcasts", "Controls"],
             active_tab: AppTab::Music,
@@ -90,128 +739,3816 @@ impl<'a> App<'a> {
             content_state: ContentState::PodcastMode {
                 feed_url: String::from("some_feed_url"), // Replace with an actual URL or appropriate default value
             },
-        }
+            data_saver: false,
+            keep_earlier: true,
+            continuous: true,
+            announce: Announce::Off,
+            announce_next: false,
+            podcast_grid: false,
+            grid_columns: 1,
+            podcast_rail: false,
+            unplayed: HashMap::new(),
+            page_size: 10,
+            outbox: Outbox::load(),
+            subscription_changes: changes,
+            text_input: None,
+            telemetry_preview: false,
+            whats_new: if first_run { None } else { changelog::unseen() },
+            tutorial: first_run.then_some(Step::Tabs),
+            regions: Regions::default(),
+            quality: Quality::Feed,
+            podcast_quality: HashMap::new(),
+            podcast_detail: None,
+            podcast_settings: None,
+            settings_applied: None,
+            book: None,
+            speed_before: None,
+            episode_detail: None,
+            feeds: HashMap::new(),
+            feed_checks: HashMap::new(),
+            snapshots: Snapshots::load(),
+            searches,
+            search_results: HashMap::new(),
+            search_shown: SEARCH_PAGE,
+            search_index: SearchIndex::load(),
+            podcast_filter,
+            smart_sort: SmartSort {
+                high_priority: podcast_flags.high_priority(),
+                ..SmartSort::default()
+            },
+            podcast_flags,
+            episode_settings: EpisodeSettings::load(),
+            smart_order: false,
+            listening_plan: None,
+            playing_from: None,
+            remote_change: None,
+            remote_access: None,
+            remote_clients: None,
+            plan_check: None,
+            download_confirm: None,
+            filter_picker: None,
+            podcasts,
+            cache,
+            stale,
+            loading: stale.is_none().then(Instant::now),
+            snapshot_picker: None,
+            backup_picker: None,
+            backup_keep: 1,
+            settings_path: None,
+            resubscribe_check: None,
+            local_store: LocalStore::load(),
+            kept_picker: None,
+            file_browser: None,
+            browsed_dir: None,
+            downloads: Downloads::default(),
+            osd: None,
+            profile: None,
+            last_position_report: Instant::now(),
+            last_outbox_flush: Instant::now(),
+            outbox_backlog: false,
+            outbox_check: None,
+            server_downloads: ServerDownloads::default(),
+            server_download_check: None,
+            last_server_download_check: Instant::now(),
+            last_telemetry_upload: None,
+            subscriptions,
+            live_shows: Vec::new(),
+            live_check: None,
+            refresh: Scheduler::default(),
+            refreshed: None,
+            search_check: None,
+            stats: None,
+            stats_check: None,
+            podcast_stats_check: None,
+            import_check: None,
+            search_provider: Provider::default(),
+            podcastindex_keys: None,
+            podcast_search: None,
+            directory_check: None,
+            preview_check: None,
+            paste_check: None,
+            pasted: None,
+            subscribe_check: None,
+            cast_picker: None,
+            cast_browser: None,
+            cast_token: None,
+            cast_check: None,
+            transcript: None,
+            palette: None,
+            transcript_check: None,
+            handoff_check: None,
+            chapters: None,
+            chapter_check: None,
+            chapter_picker: None,
+            user_picker: None,
+            switching_to: None,
+            user_switch: None,
+            theme_editor: None,
+            theme_change: None,
+            last_playing: None,
+            server_queue_check: None,
+            queue_check: None,
+            queue_edited: false,
+            last_queue_check: Instant::now(),
+            queue_sync: QueueSync::load(),
+            queue_conflict: None,
+            notification: None,
+            webhooks: Webhooks::default(),
+            desktop: Desktop::default(),
+        };
+        app.start_podcast_refresh();
+        app
     }
 
     pub fn next(&mut self) {
         self.active_tab = self.active_tab.next();
     }
 
-    pub fn input_mode(&self) -> InputMode {
-        self.input_mode
+    // skip anything that downloads ahead of the user asking for it
+    pub fn toggle_data_saver(&mut self) {
+        self.data_saver = !self.data_saver;
     }
 
-    pub fn set_input_mode(&mut self, in_mode: InputMode) {
-        self.input_mode = in_mode
+    pub fn seek_by(&mut self, seconds: i64) {
+        self.music_handle.seek_by(seconds);
+        self.show_osd(Osd::Position);
     }
 
-    pub fn current_song(&self) -> String {
-        if self.music_handle.sink_empty() && self.queue_items.is_empty() {
-            "CURRENT SONG".to_string()
+    pub fn seek_to(&mut self, position: u16) {
+        self.music_handle.seek_to(position);
+        self.show_osd(Osd::Position);
+    }
+
+    pub fn change_volume(&mut self, delta: f32) {
+        self.music_handle.change_volume(delta);
+        self.show_osd(Osd::Volume);
+    }
+
+    pub fn toggle_mute(&mut self) {
+        self.music_handle.toggle_mute();
+        self.show_osd(Osd::Volume);
+    }
+
+    // turned down to talk over, then back up again
+    pub fn toggle_duck(&mut self) {
+        self.music_handle.toggle_duck();
+        self.show_osd(Osd::Volume);
+    }
+
+    pub fn change_speed(&mut self, delta: f32) {
+        let speed = self.music_handle.speed() + delta;
+        self.music_handle.set_speed(speed);
+        self.remember_speed();
+        self.show_osd(Osd::Speed);
+    }
+
+    // for when the episode playing is gone back to
+    fn remember_speed(&mut self) {
+        if self.music_handle.is_live() {
+            return;
+        }
+        let speed = self.music_handle.speed();
+        if let Some(episode) = self.music_handle.playing() {
+            self.episode_settings.set_speed(episode, speed);
+        }
+    }
+
+    // the grid only replaces the list of podcasts, episodes stay a list
+    pub fn showing_podcast_grid(&self) -> bool {
+        self.podcast_grid && matches!(self.content_state, ContentState::PodcastMode { .. })
+    }
+
+    // whether the grid is showing and has a tile `dx` columns over in the same row
+    pub fn grid_has_neighbour(&self, dx: isize) -> bool {
+        let Some(i) = self.browser_items.selected().filter(|_| self.showing_podcast_grid()) else {
+            return false;
+        };
+        let column = (i % self.grid_columns) as isize + dx;
+        let target = i as isize + dx;
+        (0..self.grid_columns as isize).contains(&column)
+            && (0..self.browser_items.items().len() as isize).contains(&target)
+    }
+
+    pub fn show_osd(&mut self, osd: Osd) {
+        self.osd = Some((osd, Instant::now()));
+    }
+
+    // the overlay to draw, if it hasn't timed out yet
+    pub fn toggle_profile(&mut self) {
+        self.profile = match self.profile {
+            Some(_) => None,
+            None => Some(Profile::default()),
+        };
+    }
+
+    /// Background fetches that haven't come back yet.
+    pub fn background_tasks(&self) -> usize {
+        [
+            self.live_check.is_some(),
+            self.refreshed.is_some(),
+            self.search_check.is_some(),
+            self.stats_check.is_some(),
+            self.podcast_stats_check.is_some(),
+            self.chapter_check.is_some(),
+            self.import_check.is_some(),
+            self.directory_check.is_some(),
+            self.preview_check.is_some(),
+            self.paste_check.is_some(),
+            self.subscribe_check.is_some(),
+            self.handoff_check.is_some(),
+            self.cast_check.is_some(),
+            self.transcript_check.is_some(),
+            self.server_queue_check.is_some(),
+            self.plan_check.is_some(),
+            self.resubscribe_check.is_some(),
+            self.downloads.active() > 0,
+        ]
+        .iter()
+        .filter(|pending| **pending)
+        .count()
+    }
+
+    pub fn osd(&self) -> Option<Osd> {
+        self.osd
+            .filter(|(_, shown)| shown.elapsed() < OSD_DURATION)
+            .map(|(osd, _)| osd)
+    }
+
+    pub fn focus(&self) -> Focus {
+        if self.subscription_changes.is_some()
+            || self.telemetry_preview
+            || self.whats_new.is_some()
+            || self.podcast_detail.is_some()
+            || self.podcast_settings.is_some()
+            || self.episode_detail.is_some()
+            || self.snapshot_picker.is_some()
+            || self.backup_picker.is_some()
+            || self.kept_picker.is_some()
+            || self.file_browser.is_some()
+            || self.chapter_picker.is_some()
+            || self.user_picker.is_some()
+            || self.cast_picker.is_some()
+            || self.transcript.is_some()
+            || self.palette.is_some()
+            || self.remote_clients.is_some()
+            || self.filter_picker.is_some()
+            || self.listening_plan.is_some()
+            || self.download_confirm.is_some()
+            || self.queue_conflict.is_some()
+            || self.theme_editor.is_some()
+            || self.pasted.is_some()
+            || self.podcast_search.is_some()
+        {
+            Focus::Popup
+        } else if self.text_input.is_some() {
+            Focus::TextInput
         } else {
-            self.music_handle.currently_playing()
+            Focus::Main
         }
     }
 
-    // if item selected is folder, enter folder, else play record.
-    pub async fn evaluate(&mut self) {
-        match &self.content_state {
-            ContentState::PodcastMode {feed_url} => {
-                let selected_podcast = match self.browser_items.item() {
-                    BrowserItem::Podcast(p) => p,
-                    _ => return, // or handle error if necessary
-                };
-                let podcast_id = selected_podcast.PodcastID.clone();
-                self.content_state = ContentState::EpisodeMode { podcast_id: podcast_id.clone() };
-
-                let mut pinepods_values = self.pinepods_values.lock().unwrap();
-                match pinepods_values.return_eps(selected_podcast).await {
-                    Ok(episodes) => {
-                        let episode_items = episodes.into_iter()
-                            .map(BrowserItem::Episode)
-                            .collect();
-                        self.browser_items = StatefulList::with_items(episode_items);
-                    },
-                    Err(e) => eprintln!("Error fetching episodes: {:?}", e),
+    pub fn close_popup(&mut self) {
+        self.subscription_changes = None;
+        self.telemetry_preview = false;
+        if self.whats_new.take().is_some() {
+            changelog::mark_seen();
+        }
+        self.podcast_detail = None;
+        self.podcast_settings = None;
+        self.episode_detail = None;
+        self.snapshot_picker = None;
+        self.backup_picker = None;
+        self.kept_picker = None;
+        self.file_browser = None;
+        self.chapter_picker = None;
+        self.user_picker = None;
+        self.cast_picker = None;
+        self.cast_browser = None;
+        self.transcript = None;
+        self.palette = None;
+        self.remote_clients = None;
+        self.filter_picker = None;
+        self.listening_plan = None;
+        self.download_confirm = None;
+        // asked again at the next refresh
+        if self.queue_conflict.take().is_some() {
+            self.queue_edited = false;
+        }
+        self.pasted = None;
+        self.podcast_search = None;
+        // anything not saved goes back to how it was
+        if let Some(editor) = self.theme_editor.take() {
+            self.theme_change = Some(ThemeChange::Show(editor.original));
+        }
+    }
+
+    // fetched in the background, what's waiting on it is filled in once it's
+    // in (see check_feeds)
+    fn start_feed(&mut self, url: &str) {
+        if self.feeds.contains_key(url) || self.feed_checks.contains_key(url) {
+            return;
+        }
+        let (tx, rx) = oneshot::channel();
+        self.feed_checks.insert(url.to_string(), rx);
+        let url = url.to_string();
+        tokio::spawn(async move {
+            let _ = tx.send(feed::fetch(&url).await.map_err(|e| format!("{:?}", e)));
+        });
+    }
+
+    fn check_feeds(&mut self) {
+        let mut done = Vec::new();
+        for (url, check) in self.feed_checks.iter_mut() {
+            match check.try_recv() {
+                Ok(result) => done.push((url.clone(), Some(result))),
+                Err(oneshot::error::TryRecvError::Empty) => {}
+                Err(oneshot::error::TryRecvError::Closed) => done.push((url.clone(), None)),
+            }
+        }
+        for (url, result) in done {
+            self.feed_checks.remove(&url);
+            match result {
+                Some(Ok(feed)) => {
+                    self.feeds.insert(url.clone(), feed);
                 }
-            },
-            ContentState::EpisodeMode { podcast_id } => {
-                let selected_episode = match self.browser_items.item() {
-                    BrowserItem::Episode(e) => e,
-                    _ => return, // or handle error if necessary
-                };
-                let episode_url = selected_episode.EpisodeURL.clone();
-                let episode_duration = selected_episode.EpisodeDuration.clone();
-                let listen_duration = selected_episode.ListenDuration.clone();
-                self.music_handle.play(selected_episode);
-                // Logic to handle episode selection and playback
-                // For example, change state to PlayingEpisode or perform other actions
-            },
-            _ => {
-                // Handle other states, like PlayingEpisode
+                Some(Err(e)) => error!("Unable to read feed {}: {}", url, e),
+                None => {}
             }
+            self.feed_arrived(&url);
         }
     }
-    pub async fn backpedal(&mut self) {
 
-        // Fetch the podcasts and wrap them as BrowserItem
-        self.content_state = ContentState::PodcastMode {
-            feed_url: String::from("some_feed_url"), // Replace with an actual URL or appropriate default value
-        };
+    // whatever was waiting on the feed at `url` filled in from it, or told it
+    // couldn't be read
+    fn feed_arrived(&mut self, url: &str) {
+        let feed = self.feeds.get(url);
+        if let Some(detail) = self.podcast_detail.as_mut().filter(|detail| detail.feed_url == url) {
+            if detail.loading.take().is_some() {
+                detail.fill(feed);
+            }
+        }
+        if let Some(detail) = self.episode_detail.as_mut().filter(|detail| detail.feed_url.as_deref() == Some(url)) {
+            if detail.loading.take().is_some() {
+                detail.fill(feed);
+            }
+        }
+        // the episodes showing, pointed at the alternate enclosures now there's a feed to pick from
+        let showing = matches!(&self.content_state, ContentState::EpisodeMode { feed_url, .. } if feed_url == url);
+        let quality = self.quality_of(url);
+        if showing && feed.is_some() && quality != Quality::Feed {
+            let mut items = self.browser_items.items().clone();
+            let mut episodes: Vec<PinepodsEpisodes> = items
+                .iter()
+                .filter_map(|item| match item {
+                    BrowserItem::Episode(episode) => Some(episode.clone()),
+                    _ => None,
+                })
+                .collect();
+            self.pick_variants(url, quality, &mut episodes);
+            let mut picked = episodes.into_iter();
+            for item in items.iter_mut() {
+                if let (BrowserItem::Episode(episode), Some(variant)) = (&mut *item, picked.next()) {
+                    *episode = variant;
+                }
+            }
+            self.replace_browser_items(items);
+        }
+    }
 
-        let podcasts = gen_funcs::scan_folder(&self.pinepods_values).await;
-        let podcast_items = podcasts.into_iter()
-            .map(BrowserItem::Podcast)
-            .collect();
+    // the quality the episodes of the podcast with `feed_url` stream at
+    fn quality_of(&self, feed_url: &str) -> Quality {
+        let quality = self
+            .subscriptions
+            .iter()
+            .find(|(_, url)| *url == feed_url)
+            .and_then(|(name, _)| self.podcast_quality.get(name))
+            .copied()
+            .unwrap_or(self.quality);
+        self.saving_data(quality)
+    }
 
-        // Update the browser_items with the new list
-        self.browser_items = StatefulList::with_items(podcast_items);
-        self.browser_items.next();
+    // details of whatever is selected in the browser
+    pub fn open_detail(&mut self) {
+        if self.browser_items.empty() {
+            return;
+        }
+        match self.selected_item() {
+            Some(SelectedItem::Podcast(_)) => self.open_podcast_detail(),
+            Some(SelectedItem::Episode(_)) => self.open_episode_detail(),
+            None => {}
+        }
     }
 
-    // if queue has items and nothing playing, auto play
-    pub fn auto_play(&mut self) {
-        thread::sleep(Duration::from_millis(250));
-        if self.music_handle.sink_empty() && !self.queue_items.is_empty() {
-            self.music_handle.set_time_played(0);
-            let episode = self.queue_items.pop(); // Directly get the episode
-            self.music_handle.play(&episode);
+    fn open_podcast_detail(&mut self) {
+        let Some(SelectedItem::Podcast(podcast)) = self.selected_item() else {
+            return;
+        };
+        let feed_url = podcast.FeedURL.clone();
+        let mut detail = PodcastDetail {
+            name: podcast.PodcastName.clone(),
+            author: podcast.Author.clone(),
+            website: podcast.WebsiteURL.clone(),
+            description: podcast.Description.clone(),
+            funding: StatefulList::with_items(Vec::new()),
+            notice: None,
+            feed_url: feed_url.clone(),
+            loading: None,
+            flags: self.podcast_flags.get(&podcast.PodcastName),
+            stats: None,
+        };
+        self.start_podcast_stats(&detail.name);
+
+        // the server doesn't keep funding links, so they come from the feed
+        match self.feeds.get(&feed_url) {
+            Some(feed) => detail.fill(Some(feed)),
+            None => {
+                detail.loading = Some(Instant::now());
+                self.start_feed(&feed_url);
+            }
         }
+        self.podcast_detail = Some(detail);
     }
 
+    fn open_episode_detail(&mut self) {
+        let Some(SelectedItem::Episode(episode)) = self.selected_item() else {
+            return;
+        };
+        let episode = episode.clone();
+        // the episodes showing are the podcast's, search results could be anyone's
+        let feed_url = match &self.content_state {
+            ContentState::EpisodeMode { feed_url, .. } => Some(feed_url.clone()),
+            _ => self.feed_url_of(&episode),
+        };
+        self.show_episode_detail(episode, feed_url);
+    }
 
-    // if playing and
-    pub fn song_progress(&mut self) -> u16 {
-        let progress = || {
-            let percentage =
-                (self.music_handle.time_played() * 100) / self.music_handle.song_length();
-            if percentage >= 100 {
-                100
-            } else {
-                percentage
+    /// The detail of the episode selected in the queue.
+    pub fn open_queued_detail(&mut self) {
+        let Some(episode) = self.queue_items.item().cloned() else {
+            return;
+        };
+        let feed_url = self.feed_url_of(&episode);
+        self.show_episode_detail(episode, feed_url);
+    }
+
+    // the feed of a subscribed podcast's episode
+    fn feed_url_of(&self, episode: &PinepodsEpisodes) -> Option<String> {
+        self.podcasts
+            .iter()
+            .find(|podcast| {
+                episode.PodcastID == Some(podcast.PodcastID)
+                    || episode.PodcastName.as_deref() == Some(podcast.PodcastName.as_str())
+            })
+            .map(|podcast| podcast.FeedURL.clone())
+    }
+
+    fn show_episode_detail(&mut self, episode: PinepodsEpisodes, feed_url: Option<String>) {
+        let mut detail = EpisodeDetail {
+            links: html::render(&episode.EpisodeDescription, "").links,
+            episode,
+            soundbites: StatefulList::with_items(Vec::new()),
+            variant: None,
+            notice: None,
+            feed_url: feed_url.clone(),
+            loading: None,
+            link_typed: 0,
+            scroll: 0,
+        };
+        // without its feed there are only the show notes
+        if let Some(feed_url) = feed_url {
+            match self.feeds.get(&feed_url) {
+                Some(feed) => detail.fill(Some(feed)),
+                None => {
+                    detail.loading = Some(Instant::now());
+                    self.start_feed(&feed_url);
+                }
             }
+        }
+        self.episode_detail = Some(detail);
+    }
+
+    // the selected soundbite in the episode detail popup, on its own
+    pub fn play_soundbite(&mut self) {
+        let Some(detail) = self.episode_detail.as_mut() else {
+            return;
+        };
+        let Some(soundbite) = detail
+            .soundbites
+            .selected()
+            .and_then(|i| detail.soundbites.items().get(i))
+        else {
+            return;
         };
 
-        // edge case if nothing queued or playing
-        if self.music_handle.sink_empty() && self.queue_items.is_empty() {
-            0
+        let start = soundbite.start as u16;
+        let end = (soundbite.start + soundbite.duration).ceil() as u16;
+        self.music_handle.play_segment(&detail.episode, start, end);
+        detail.notice = Some(format!(
+            "Playing {} to {}",
+            format::clock(start.into()),
+            format::clock(end.into())
+        ));
+    }
 
-            // if something playing, calculate progress
-        } else if !self.music_handle.sink_empty() {
-            progress()
-            // if nothing playing keep rolling
-        } else {
-            self.auto_play();
-            0
+    /// A digit typed in the episode detail. The link with the number typed
+    /// opens as soon as another digit couldn't make it a different one.
+    pub fn type_link_number(&mut self, digit: u32) {
+        let Some(detail) = self.episode_detail.as_mut() else {
+            return;
+        };
+        let number = detail.link_typed * 10 + digit as usize;
+        if number == 0 || number > detail.links.len() {
+            detail.link_typed = 0;
+            detail.notice = Some(format!("There's no link {}", number));
+            return;
+        }
+        detail.link_typed = number;
+        match number * 10 > detail.links.len() {
+            true => self.open_typed_link(),
+            false => detail.notice = Some(format!("Link {}, Enter to open it or another digit", number)),
+        }
+    }
+
+    pub fn open_typed_link(&mut self) {
+        let Some(detail) = self.episode_detail.as_mut() else {
+            return;
+        };
+        let number = std::mem::take(&mut detail.link_typed);
+        let Some(url) = number.checked_sub(1).and_then(|i| detail.links.get(i)) else {
+            return;
+        };
+        detail.notice = Some(match gen_funcs::open_link(url) {
+            Ok(()) => format!("Opened {}", url),
+            Err(e) => format!("Unable to open a browser: {}", e),
+        });
+    }
+
+    pub fn start_tutorial(&mut self) {
+        self.tutorial = Some(Step::Tabs);
+    }
+
+    // called after `code` has done whatever it normally does
+    pub fn advance_tutorial(&mut self, code: KeyCode) {
+        let Some(step) = self.tutorial else {
+            return;
+        };
+        self.tutorial = step.advance(code, self.active_tab);
+        if self.tutorial.is_none() {
+            self.end_tutorial();
         }
     }
 
+    pub fn end_tutorial(&mut self) {
+        self.tutorial = None;
+        // so a first run doesn't get the tour again next time
+        changelog::mark_seen();
+    }
 
-    // get file path
-    pub fn selected_item(&self) -> Option<SelectedItem> {
+    pub fn prompt(&mut self, prompt: Prompt, value: String) {
+        self.text_input = Some(TextInput { prompt, value });
+    }
+
+    pub fn save_snapshot(&mut self, name: &str) {
+        let episodes = self.queue_items.items().iter().cloned().collect();
+        self.snapshots.save(name, episodes);
+    }
+
+    pub fn open_snapshot_picker(&mut self) {
+        let mut picker = StatefulList::with_items(self.snapshots.names());
+        picker.next();
+        self.snapshot_picker = Some(picker);
+    }
+
+    /// Put a saved queue back, either in place of the current one or after it.
+    pub fn restore_snapshot(&mut self, name: &str, append: bool) {
+        let Some(episodes) = self.snapshots.get(name) else {
+            return;
+        };
+        let episodes = episodes.to_vec();
+        if !append {
+            self.back_up("before restoring a saved queue");
+            self.queue_items.clear();
+        }
+        for episode in episodes {
+            let duration = episode.EpisodeDuration;
+            self.queue_items.add(episode, duration);
+        }
+    }
+
+    /// Back up the subscriptions every `interval`, None for only before bulk
+    /// changes, keeping the newest `keep`. The settings file goes with them.
+    pub fn set_backups(&mut self, interval: Option<Duration>, keep: usize, settings: Option<PathBuf>) {
+        self.backup_keep = keep;
+        self.settings_path = settings;
+        // overdue if the last run of firewood didn't get round to it
+        let overdue = interval.is_some_and(|interval| {
+            backups::last_taken()
+                .is_none_or(|taken| chrono::Utc::now().timestamp() - taken >= interval.as_secs() as i64)
+        });
+        self.refresh.set(Refresh::Backup, interval, overdue);
+    }
+
+    /// Copy the subscriptions, the queue and the settings to a backup file.
+    pub fn back_up(&mut self, reason: &str) {
+        // the podcast list failing to load isn't worth keeping
+        if self.podcasts.is_empty() && self.queue_items.is_empty() {
+            return;
+        }
+        let queue = self.queue_items.items().iter().cloned().collect();
+        let settings = self.settings_path.as_deref().and_then(Settings::read);
+        let backup = Backup::new(reason, &self.podcasts, queue, settings);
+        match backups::save(&backup, self.backup_keep) {
+            Ok(path) => info!("Backed up {} to {:?}", reason, path),
+            Err(e) => error!("Unable to back up {}: {}", reason, e),
+        }
+    }
+
+    pub fn open_backup_picker(&mut self) {
+        let mut picker = StatefulList::with_items(backups::list());
+        picker.next();
+        self.backup_picker = Some(picker);
+    }
+
+    /// Put back `what` from the backup selected in the picker, after backing
+    /// up what's there now in case it was the wrong one.
+    pub fn restore_backup(&mut self, what: Restore) {
+        let Some(backup) = self
+            .backup_picker
+            .take()
+            .and_then(|picker| picker.selected().and_then(|i| picker.items().get(i).cloned()))
+        else {
+            return;
+        };
+        self.back_up("before restoring a backup");
+
+        let mut restored = Vec::new();
+        if matches!(what, Restore::Everything | Restore::Queue) {
+            self.queue_items.clear();
+            for episode in backup.queue {
+                let duration = episode.EpisodeDuration;
+                self.queue_items.add(episode, duration);
+            }
+            restored.push("the queue");
+        }
+        if matches!(what, Restore::Everything | Restore::Settings) {
+            match &backup.settings {
+                Some(settings) => match std::fs::write(&settings.path, &settings.content) {
+                    Ok(()) => restored.push("the settings, for the next start"),
+                    Err(e) => error!("Unable to restore settings to {:?}: {}", settings.path, e),
+                },
+                None => restored.push("no settings, there was no settings file"),
+            }
+        }
+        if matches!(what, Restore::Everything | Restore::Podcasts) {
+            self.resubscribe(backup.podcasts);
+        }
+        if !restored.is_empty() {
+            self.notify(format!("Restored {}", restored.join(" and ")));
+        }
+    }
+
+    /// Keep the selected episode on this computer, so it plays offline.
+    pub fn keep_selected(&mut self) {
+        let Some(SelectedItem::Episode(episode)) = self.selected_item() else {
+            return;
+        };
+        if self.local_store.has(&episode.EpisodeURL) {
+            self.notify(String::from("Already kept on this computer"));
+            return;
+        }
+        let episode = episode.clone();
+        if self.downloads.add(&episode) {
+            self.notify(format!("Keeping {} on this computer", episode.EpisodeTitle));
+            self.refresh_kept_picker();
+        }
+    }
+
+    fn check_downloads(&mut self) {
+        let finished = self.downloads.take_finished();
+        if finished.is_empty() {
+            return;
+        }
+        for finished in finished {
+            match finished {
+                Finished::Kept(kept) => {
+                    self.notify(format!("Kept {}, it'll play offline", kept.episode.EpisodeTitle));
+                    self.desktop.send(Kind::Downloads, "Download finished", &kept.episode.EpisodeTitle);
+                    self.local_store.add(kept);
+                }
+                Finished::Failed(episode, e) => {
+                    self.notify(format!("Couldn't keep {}: {}", episode.EpisodeTitle, e));
+                    self.desktop.send(Kind::Downloads, "Download failed", &format!("{}: {}", episode.EpisodeTitle, e));
+                }
+            }
+        }
+        self.refresh_kept_picker();
+    }
+
+    pub fn open_kept_picker(&mut self) {
+        let mut picker = StatefulList::with_items(self.kept_items());
+        picker.next();
+        self.kept_picker = Some(picker);
+    }
+
+    // downloads first, then what's kept
+    fn kept_items(&self) -> Vec<KeptItem> {
+        let downloading = self
+            .downloads
+            .jobs()
+            .into_iter()
+            .map(|job| KeptItem::Downloading(job.episode.EpisodeURL));
+        let kept = self.local_store.kept().iter().cloned().map(|kept| KeptItem::Kept(Box::new(kept)));
+        downloading.chain(kept).collect()
+    }
+
+    // the same row stays selected, or near enough
+    fn refresh_kept_picker(&mut self) {
+        let items = self.kept_items();
+        if let Some(picker) = self.kept_picker.as_mut() {
+            let selected = picker.selected().unwrap_or(0);
+            *picker = StatefulList::with_items(items);
+            picker.select(selected);
+        }
+    }
+
+    fn picked_kept(&self) -> Option<KeptItem> {
+        let picker = self.kept_picker.as_ref()?;
+        picker.selected().and_then(|i| picker.items().get(i).cloned())
+    }
+
+    pub fn play_kept(&mut self) {
+        let Some(KeptItem::Kept(kept)) = self.picked_kept() else {
+            return;
+        };
+        self.kept_picker = None;
+        self.music_handle.play(&kept.episode);
+    }
+
+    /// Show where the kept episode selected is in the file manager, or over
+    /// ssh, where that'd be no help, copy its path.
+    pub fn reveal_kept(&mut self, copy: bool) {
+        let Some(KeptItem::Kept(kept)) = self.picked_kept() else {
+            return;
+        };
+        let Some(path) = kept.file() else {
+            self.notify(format!("The file for {} has gone", kept.episode.EpisodeTitle));
+            return;
+        };
+        if !copy && !gen_funcs::remote_session() {
+            match gen_funcs::reveal_file(&path) {
+                Ok(()) => return,
+                Err(e) => error!("Unable to show {:?} in the file manager: {}", path, e),
+            }
+        }
+        self.notify(match gen_funcs::copy_to_clipboard(&path.to_string_lossy()) {
+            Ok(()) => format!("{} (copied)", path.display()),
+            Err(_) => path.display().to_string(),
+        });
+    }
+
+    pub fn pause_download(&mut self) {
+        if let Some(KeptItem::Downloading(url)) = self.picked_kept() {
+            self.downloads.toggle_pause(&url);
+        }
+    }
+
+    /// Delete the kept episode selected from this computer, or stop
+    /// downloading it.
+    pub fn remove_kept(&mut self) {
+        match self.picked_kept() {
+            Some(KeptItem::Downloading(url)) => self.downloads.cancel(&url),
+            Some(KeptItem::Kept(kept)) => {
+                if let Err(e) = self.local_store.remove(&kept.episode.EpisodeURL) {
+                    self.notify(format!("Couldn't delete {}: {}", kept.episode.EpisodeTitle, e));
+                    return;
+                }
+            }
+            None => return,
+        }
+        self.refresh_kept_picker();
+    }
+
+    /// Browse this computer's audio files, from the folder the browser was
+    /// last left in.
+    pub fn open_file_browser(&mut self) {
+        let dir = self
+            .browsed_dir
+            .clone()
+            .or_else(dirs::audio_dir)
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(|| PathBuf::from("/"));
+        self.browse(dir, None);
+    }
+
+    // show `dir`, with `select` selected if it's in there
+    fn browse(&mut self, dir: PathBuf, select: Option<&Path>) {
+        let entries = match local_files::list(&dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                self.notify(format!("Couldn't open {}: {}", dir.display(), e));
+                return;
+            }
+        };
+        let mut entries = StatefulList::with_items(entries);
+        match select.and_then(|select| entries.items().iter().position(|entry| entry.path == select)) {
+            Some(i) => entries.select(i),
+            None => entries.next(),
+        }
+        let audiobook = audiobook::folder_name(&dir).is_some_and(|name| self.podcast_flags.get(&name).audiobook);
+        self.browsed_dir = Some(dir.clone());
+        self.file_browser = Some(FileBrowser { dir, entries, audiobook });
+    }
+
+    fn browsed(&self) -> Option<Entry> {
+        let browser = self.file_browser.as_ref()?;
+        browser.entries.selected().and_then(|i| browser.entries.items().get(i).cloned())
+    }
+
+    /// Go into the folder selected, or play the file, from where it was left.
+    pub fn open_browsed(&mut self) {
+        let Some(entry) = self.browsed() else {
+            return;
+        };
+        if entry.folder {
+            self.browse(entry.path, None);
+            return;
+        }
+        self.file_browser = None;
+        self.music_handle.play(&local_files::episode(&entry.path));
+    }
+
+    pub fn browse_up(&mut self) {
+        let Some(dir) = self.file_browser.as_ref().map(|browser| browser.dir.clone()) else {
+            return;
+        };
+        if let Some(parent) = dir.parent() {
+            self.browse(parent.to_path_buf(), Some(&dir));
+        }
+    }
+
+    /// Mark the folder being browsed as an audiobook, or not any more.
+    pub fn toggle_browsed_audiobook(&mut self) {
+        let Some(browser) = self.file_browser.as_mut() else {
+            return;
+        };
+        let Some(name) = audiobook::folder_name(&browser.dir) else {
+            return;
+        };
+        browser.audiobook = self.podcast_flags.toggle_audiobook(&name).audiobook;
+        let notice = match browser.audiobook {
+            true => format!("Playing {} as an audiobook, its files in order", name),
+            false => format!("{} isn't an audiobook any more", name),
+        };
+        // what's playing may be one of its files
+        self.settings_applied = None;
+        self.notify(notice);
+    }
+
+    /// Queue the file selected, or every audio file in the folder selected.
+    pub fn queue_browsed(&mut self) {
+        let Some(entry) = self.browsed() else {
+            return;
+        };
+        let episodes = match entry.folder {
+            true => match local_files::folder(&entry.path) {
+                Ok(episodes) => episodes,
+                Err(e) => {
+                    self.notify(format!("Couldn't open {}: {}", entry.path.display(), e));
+                    return;
+                }
+            },
+            false => vec![local_files::episode(&entry.path)],
+        };
+        let count = episodes.len();
+        for episode in episodes {
+            let duration = episode.EpisodeDuration;
+            self.queue_items.add(episode, duration);
+        }
+        self.notify(match count {
+            0 => format!("No audio files in {}", entry.name()),
+            1 if !entry.folder => format!("Queued {}", entry.name()),
+            count => format!("Queued {} files from {}", count, entry.name()),
+        });
+    }
+
+    // subscribe again to whichever of `podcasts` aren't subscribed to now
+    fn resubscribe(&mut self, podcasts: Vec<PinepodsPodcasts>) {
+        let missing: Vec<PinepodsPodcasts> = podcasts
+            .into_iter()
+            .filter(|podcast| !self.podcasts.iter().any(|now| now.FeedURL == podcast.FeedURL))
+            .collect();
+        if missing.is_empty() {
+            self.notify(String::from("Already subscribed to every podcast in the backup"));
+            return;
+        }
+        if self.resubscribe_check.is_some() {
+            return;
+        }
+        let pinepods_values = self.pinepods_values.lock().unwrap().clone();
+        let (tx, rx) = oneshot::channel();
+        self.resubscribe_check = Some(rx);
+        tokio::spawn(async move {
+            let mut failed = 0;
+            for podcast in &missing {
+                match pinepods_values.add_podcast(podcast).await {
+                    Ok(()) => errors::api_ok(),
+                    Err(e) => {
+                        error!("Unable to subscribe to {} again: {:?}", podcast.PodcastName, e);
+                        errors::api_error("add_podcast", &format!("{:?}", e));
+                        failed += 1;
+                    }
+                }
+            }
+            let _ = tx.send((missing.len() - failed, failed));
+        });
+    }
+
+    fn check_resubscribe(&mut self) {
+        let Some(check) = self.resubscribe_check.as_mut() else {
+            return;
+        };
+        let (added, failed) = match check.try_recv() {
+            Ok(counts) => counts,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.resubscribe_check = None;
+                return;
+            }
+        };
+        self.resubscribe_check = None;
+
+        let mut message = format!("Subscribed to {} podcasts again", added);
+        if failed > 0 {
+            message.push_str(&format!(", {} couldn't be added", failed));
+        }
+        self.notify(message);
+        self.start_podcast_refresh();
+    }
+
+    // what changed elsewhere, backing up the list from before if anything
+    // went, since that's the one to get back to after a mistake
+    fn note_subscription_changes(&mut self, changes: SubscriptionChanges) {
+        if !changes.removed.is_empty() {
+            self.back_up(&format!("before {} podcasts were unsubscribed", changes.removed.len()));
+        }
+        self.subscription_changes = Some(changes);
+    }
+
+    pub fn input_mode(&self) -> InputMode {
+        self.input_mode
+    }
+
+    pub fn set_input_mode(&mut self, in_mode: InputMode) {
+        self.input_mode = in_mode
+    }
+
+    pub fn current_song(&self) -> String {
+        if self.music_handle.sink_empty() && self.queue_items.is_empty() {
+            "CURRENT SONG".to_string()
+        } else {
+            self.music_handle.currently_playing()
+        }
+    }
+
+    // forward OS media key presses and keep the system overlay up to date
+    pub fn handle_media_keys(&mut self) {
+        let Some(media_keys) = self.media_keys.as_mut() else {
+            return;
+        };
+
+        let now = i64::from(self.music_handle.time_played());
+        for event in media_keys.poll() {
+            match event {
+                MediaKeyEvent::Play if self.music_handle.is_paused() => {
+                    self.music_handle.play_pause()
+                }
+                MediaKeyEvent::Pause | MediaKeyEvent::Stop if !self.music_handle.is_paused() => {
+                    self.music_handle.play_pause()
+                }
+                MediaKeyEvent::Toggle => self.music_handle.play_pause(),
+                MediaKeyEvent::Next => self.music_handle.skip(),
+                // back to the start, there's no going back through the queue
+                MediaKeyEvent::Previous => self.music_handle.seek_by(-now),
+                MediaKeyEvent::Seek(forward, seconds) => {
+                    let seconds = seconds.map_or(SEEK_STEP, |seconds| seconds as i64);
+                    self.music_handle.seek_by(if forward { seconds } else { -seconds })
+                }
+                MediaKeyEvent::SetPosition(at) => self.music_handle.seek_by(at as i64 - now),
+                _ => {}
+            }
+        }
+
+        let now_playing = match (self.music_handle.playing(), self.music_handle.is_live()) {
+            (Some(episode), _) => Some(NowPlaying {
+                title: episode.EpisodeTitle.clone(),
+                podcast: episode.PodcastName.clone(),
+                artwork: Some(episode.EpisodeArtwork.clone()).filter(|artwork| !artwork.is_empty()),
+                length: Some(self.music_handle.song_length())
+                    .filter(|length| *length > 0)
+                    .map(|length| Duration::from_secs(length.into())),
+            }),
+            (None, true) if !self.music_handle.sink_empty() => Some(NowPlaying {
+                title: self.music_handle.currently_playing(),
+                podcast: None,
+                artwork: None,
+                length: None,
+            }),
+            _ => None,
+        };
+        media_keys.set_now_playing(now_playing);
+
+        let status = if self.music_handle.sink_empty() {
+            MediaKeyStatus::Stopped
+        } else if self.music_handle.is_paused() {
+            MediaKeyStatus::Paused
+        } else {
+            MediaKeyStatus::Playing
+        };
+        let position = Duration::from_secs(self.music_handle.time_played().into());
+        media_keys.set_status(status, position);
+    }
+
+    // run a remote control command and answer with where playback ended up
+    pub fn handle_remote(&mut self, request: Request) {
+        let reply = match request.command {
+            Command::QueueList => Reply::Episodes(self.queue_items.items().iter().cloned().collect()),
+            Command::Search { query } => Reply::Episodes(self.search_index.search(&query)),
+            Command::QueueMove { from, to } => match self.queue_items.move_item(from, to) {
+                true => {
+                    self.send_queue_order();
+                    Reply::Status(self.status())
+                }
+                false => Reply::Error {
+                    error: format!("the queue has {} episodes", self.queue_items.length()),
+                },
+            },
+            command => {
+                self.run_remote(command);
+                Reply::Status(self.status())
+            }
+        };
+
+        // the receiving end may have hung up, nothing to do about that
+        let _ = request.reply.send(reply);
+    }
+
+    // the commands answered with the status once they've run
+    fn run_remote(&mut self, command: Command) {
+        match command {
+            Command::Play { episode, position: None } => self.music_handle.play(&episode),
+            Command::Play {
+                episode,
+                position: Some(position),
+            } => self.music_handle.play_from(&episode, position),
+            Command::Queue { episode } => {
+                let duration = episode.EpisodeDuration;
+                self.queue_items.add(episode, duration);
+            }
+            Command::PlayPause => self.music_handle.play_pause(),
+            Command::Pause if !self.music_handle.is_paused() => self.music_handle.play_pause(),
+            Command::Resume if self.music_handle.is_paused() => self.music_handle.play_pause(),
+            Command::Skip => self.music_handle.skip(),
+            Command::Seek { position } => self.music_handle.seek_to(position),
+            Command::Volume { volume } => self.music_handle.set_volume(volume),
+            Command::Speed { speed } => {
+                self.music_handle.set_speed(speed);
+                self.remember_speed();
+            }
+            _ => {}
+        }
+    }
+
+    pub fn status(&self) -> Status {
+        let idle = self.music_handle.sink_empty();
+        Status {
+            episode: (!idle).then(|| self.music_handle.currently_playing()),
+            episode_id: self.music_handle.currently_playing_id().filter(|_| !idle),
+            paused: self.music_handle.is_paused(),
+            position: self.music_handle.time_played(),
+            duration: self.music_handle.song_length(),
+            queue_length: self.queue_items.items().len(),
+            volume: self.music_handle.volume(),
+            muted: self.music_handle.is_muted(),
+            ducked: self.music_handle.is_ducked(),
+            speed: self.music_handle.speed(),
+            live: self.music_handle.is_live(),
+        }
+    }
+
+    // if item selected is folder, enter folder, else play record.
+    pub async fn evaluate(&mut self) {
+        // a search can come up empty
+        if self.browser_items.empty() {
+            return;
+        }
+        match &self.content_state {
+            ContentState::PodcastMode {feed_url} => {
+                let selected_podcast = match self.browser_items.item() {
+                    BrowserItem::Podcast(p) => p.clone(),
+                    BrowserItem::Search(term) => {
+                        let term = term.clone();
+                        return self.open_search(term);
+                    }
+                    _ => return, // or handle error if necessary
+                };
+                self.open_podcast(&selected_podcast).await;
+            },
+            ContentState::EpisodeMode { .. } | ContentState::SearchMode { .. } => {
+                let selected_episode = match self.browser_items.item() {
+                    BrowserItem::Episode(e) => e,
+                    BrowserItem::More(_) => return self.show_more_results(),
+                    _ => return, // or handle error if necessary
+                };
+                let episode_url = selected_episode.EpisodeURL.clone();
+                let episode_duration = selected_episode.EpisodeDuration.clone();
+                let listen_duration = selected_episode.ListenDuration.clone();
+                self.music_handle.play(selected_episode);
+                self.playing_from = self.podcast_context();
+                // Logic to handle episode selection and playback
+                // For example, change state to PlayingEpisode or perform other actions
+            },
+            _ => {
+                // Handle other states, like PlayingEpisode
+            }
+        }
+    }
+    async fn open_podcast(&mut self, podcast: &PinepodsPodcasts) {
+        let podcast_id = podcast.PodcastID;
+        let feed_url = podcast.FeedURL.clone();
+        let quality = self
+            .podcast_quality
+            .get(&podcast.PodcastName)
+            .copied()
+            .unwrap_or(self.quality);
+        let quality = self.saving_data(quality);
+        self.content_state = ContentState::EpisodeMode {
+            podcast_id,
+            feed_url: feed_url.clone(),
+        };
+
+        // what was fetched last time, if it's been opened before, while the
+        // fresh list comes in the background
+        let cached = self.cache.episodes(podcast_id).map(|saved| (saved.fetched, saved.value.clone()));
+        match cached {
+            Some((fetched, mut episodes)) => {
+                self.unplayed.insert(podcast.PodcastName.clone(), unplayed(&episodes));
+                if quality != Quality::Feed {
+                    self.pick_variants(&feed_url, quality, &mut episodes);
+                }
+                self.order(&mut episodes);
+                let episode_items = episodes.into_iter()
+                    .map(BrowserItem::Episode)
+                    .collect();
+                self.browser_items = StatefulList::with_items(episode_items);
+                self.stale = Some(fetched);
+                self.loading = None;
+            }
+            None => {
+                self.browser_items = StatefulList::with_items(Vec::new());
+                self.stale = None;
+                self.loading = Some(Instant::now());
+            }
+        }
+        // whatever's refreshing is for the page that was showing
+        self.refreshed = None;
+        self.start_episode_refresh();
+        self.refresh.postpone(Refresh::Episodes);
+        self.refresh_palette();
+    }
+
+    pub fn showing_podcast_rail(&self) -> bool {
+        self.podcast_rail && matches!(self.content_state, ContentState::EpisodeMode { .. })
+    }
+
+    pub fn toggle_podcast_rail(&mut self) {
+        self.podcast_rail = !self.podcast_rail;
+        // the counts come with the search refresh
+        if self.podcast_rail && self.unplayed.is_empty() {
+            self.start_search_refresh();
+        }
+    }
+
+    /// What the rail lists, the podcast list less the saved searches, and
+    /// which of them is open.
+    pub fn rail(&self) -> (Vec<&PinepodsPodcasts>, Option<usize>) {
+        let podcasts: Vec<&PinepodsPodcasts> = self
+            .podcasts
+            .iter()
+            .filter(|podcast| self.podcast_filter.shows(&podcast.PodcastName))
+            .collect();
+        let open = match self.content_state {
+            ContentState::EpisodeMode { podcast_id, .. } => {
+                podcasts.iter().position(|podcast| podcast.PodcastID == podcast_id)
+            }
+            _ => None,
+        };
+        (podcasts, open)
+    }
+
+    pub fn unplayed(&self, podcast: &str) -> Option<usize> {
+        self.unplayed.get(podcast).copied()
+    }
+
+    /// Open the podcast `step` places along the rail from the open one.
+    pub async fn move_along_rail(&mut self, step: isize) {
+        if !self.showing_podcast_rail() {
+            return;
+        }
+        let (podcasts, open) = self.rail();
+        let Some(open) = open else {
+            return;
+        };
+        let next = open.saturating_add_signed(step).min(podcasts.len().saturating_sub(1));
+        if next == open {
+            return;
+        }
+        let podcast = podcasts[next].clone();
+        self.open_podcast(&podcast).await;
+        self.browser_items.next();
+    }
+
+    // a saved search opens like a podcast, with whatever it found last time
+    // or, the first time, what it finds now
+    // with nothing indexed yet the results come in with the next search
+    // refresh (see check_searches), spinning until they do
+    fn open_search(&mut self, term: String) {
+        if !self.search_results.contains_key(&term) {
+            if self.search_index.is_empty() {
+                self.loading = Some(Instant::now());
+            } else {
+                let found = searches::matching(&term, self.search_index.episodes());
+                self.search_results.insert(term.clone(), found);
+            }
+            self.start_search_refresh();
+        }
+        self.search_shown = SEARCH_PAGE;
+        self.browser_items = StatefulList::with_items(self.search_items(&term));
+        self.content_state = ContentState::SearchMode { term };
+    }
+
+    /// Search every subscription's episodes for `query`, from the local
+    /// index straight away, and again once the server's been asked if the
+    /// index is out of date.
+    pub fn search(&mut self, query: &str) {
+        let found = self.search_index.search(query);
+        if found.is_empty() && self.search_index.is_empty() {
+            self.notify(String::from("Nothing indexed yet, asking the server"));
+        }
+        self.search_results.insert(query.to_string(), found);
+        self.search_shown = SEARCH_PAGE;
+        self.browser_items = StatefulList::with_items(self.search_items(query));
+        self.browser_items.next();
+        self.content_state = ContentState::SearchMode { term: query.to_string() };
+        if self.search_index.stale() {
+            self.start_search_refresh();
+        }
+    }
+
+    /// What's being searched for, while the results are showing.
+    pub fn search_term(&self) -> Option<&str> {
+        match &self.content_state {
+            ContentState::SearchMode { term } => Some(term),
+            _ => None,
+        }
+    }
+
+    // the episodes a search found that get through the filter, as many as
+    // are listed so far and a row for the rest
+    fn search_items(&self, term: &str) -> Vec<BrowserItem> {
+        let mut episodes: Vec<PinepodsEpisodes> = self
+            .search_results
+            .get(term)
+            .into_iter()
+            .flatten()
+            .filter(|episode| {
+                episode
+                    .PodcastName
+                    .as_deref()
+                    .is_none_or(|name| self.podcast_filter.shows(name))
+            })
+            .cloned()
+            .collect();
+        self.order(&mut episodes);
+        let more = episodes.len().saturating_sub(self.search_shown);
+        let mut items: Vec<BrowserItem> = episodes
+            .into_iter()
+            .take(self.search_shown)
+            .map(BrowserItem::Episode)
+            .collect();
+        if more > 0 {
+            items.push(BrowserItem::More(more));
+        }
+        items
+    }
+
+    /// List the next page of the search results open, below the ones
+    /// already there, leaving the selection where it was.
+    pub fn show_more_results(&mut self) {
+        let Some(term) = self.search_term().map(String::from) else {
+            return;
+        };
+        let on_more = self
+            .browser_items
+            .selected()
+            .filter(|_| matches!(self.browser_items.item(), BrowserItem::More(_)));
+        self.search_shown += SEARCH_PAGE;
+        let items = self.search_items(&term);
+        self.replace_browser_items(items);
+        // from the row that was there to the first of the new ones
+        if let Some(i) = on_more {
+            self.browser_items.select(i);
+        }
+    }
+
+    // the next page is listed once the selection gets near the end of this
+    // one, so scrolling down doesn't stop at the row for the rest
+    fn list_results_ahead(&mut self) {
+        let items = self.browser_items.items();
+        let (Some(selected), Some(BrowserItem::More(_))) = (self.browser_items.selected(), items.last()) else {
+            return;
+        };
+        if selected + SEARCH_AHEAD >= items.len() - 1 && self.search_term().is_some() {
+            self.show_more_results();
+        }
+    }
+
+    // episode lists keep the server's order unless smart sort is on
+    pub fn set_smart_sort(&mut self, sort: SmartSort) {
+        self.smart_sort = SmartSort {
+            high_priority: self.podcast_flags.high_priority(),
+            ..sort
+        };
+    }
+
+    // the podcast in the open details
+    pub fn cycle_podcast_notify(&mut self) {
+        if let Some(detail) = self.podcast_detail.as_mut() {
+            detail.flags = self.podcast_flags.cycle_notify(&detail.name);
+        }
+    }
+
+    pub fn toggle_podcast_priority(&mut self) {
+        if let Some(detail) = self.podcast_detail.as_mut() {
+            detail.flags = self.podcast_flags.toggle_high_priority(&detail.name);
+            self.smart_sort.high_priority = self.podcast_flags.high_priority();
+        }
+    }
+
+    /// Tweak `colors`, the theme's as they are now.
+    pub fn open_theme_editor(&mut self, colors: [Color; 4]) {
+        self.theme_editor = Some(ThemeEditor {
+            colors: colors.map(color_depth::rgb),
+            row: 0,
+            channel: 0,
+            original: colors,
+        });
+    }
+
+    /// A step lighter or darker, in the selected color's selected channel.
+    pub fn change_theme_color(&mut self, up: bool) {
+        let Some(editor) = self.theme_editor.as_mut() else {
+            return;
+        };
+        let (r, g, b) = &mut editor.colors[editor.row];
+        let value = match editor.channel {
+            0 => r,
+            1 => g,
+            _ => b,
+        };
+        *value = match up {
+            true => value.saturating_add(COLOR_STEP),
+            false => value.saturating_sub(COLOR_STEP),
+        };
+        self.theme_change = Some(ThemeChange::Show(editor.shown()));
+    }
+
+    pub fn save_theme(&mut self) {
+        if let Some(editor) = self.theme_editor.take() {
+            self.theme_change = Some(ThemeChange::Save(editor.shown()));
+        }
+    }
+
+    pub fn open_podcast_settings(&mut self) {
+        if let Some(detail) = &self.podcast_detail {
+            self.podcast_settings = Some(PodcastSettings {
+                podcast: detail.name.clone(),
+                flags: detail.flags,
+                row: 0,
+            });
+        }
+    }
+
+    /// The speed the podcast settings start from, before any podcast's own.
+    pub fn usual_speed(&self) -> f32 {
+        self.speed_before.unwrap_or_else(|| self.music_handle.speed())
+    }
+
+    /// Up or down a step, the selected row of the podcast settings open.
+    pub fn change_podcast_setting(&mut self, up: bool) {
+        let usual = self.usual_speed();
+        let Some(settings) = self.podcast_settings.as_mut() else {
+            return;
+        };
+        let flags = settings.flags;
+        let skip = |seconds: u16| match up {
+            true => (seconds + SKIP_STEP).min(MAX_SKIP),
+            false => seconds.saturating_sub(SKIP_STEP),
+        };
+        settings.flags = match settings.row {
+            0 => {
+                let step = if up { SPEED_STEP } else { -SPEED_STEP };
+                let speed = ((flags.speed.unwrap_or(usual) + step) * 10.0).round() / 10.0;
+                let speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+                // back at the usual speed is the same as none of its own
+                let speed = Some(speed).filter(|speed| (speed - usual).abs() > SPEED_STEP / 2.0);
+                self.podcast_flags.set_speed(&settings.podcast, speed)
+            }
+            1 => self.podcast_flags.set_skips(&settings.podcast, skip(flags.skip_intro), flags.skip_outro),
+            2 => self.podcast_flags.set_skips(&settings.podcast, flags.skip_intro, skip(flags.skip_outro)),
+            _ => self.podcast_flags.toggle_audiobook(&settings.podcast),
+        };
+        if let Some(detail) = self.podcast_detail.as_mut().filter(|detail| detail.name == settings.podcast) {
+            detail.flags = settings.flags;
+        }
+        // what's playing may be one of its episodes
+        self.settings_applied = None;
+    }
+
+    // a podcast's own speed goes on as its episodes start, or the speed the
+    // episode was left at, and its intro and outro are skipped, the outro by
+    // carrying on with what's next
+    fn check_podcast_playback(&mut self) {
+        if self.music_handle.is_live() {
+            return;
+        }
+        let Some(episode) = self.music_handle.playing().cloned() else {
+            return;
+        };
+        let flags = episode
+            .PodcastName
+            .as_deref()
+            .map(|podcast| self.podcast_flags.get(podcast))
+            .unwrap_or_default();
+        let (url, episode_id) = (episode.EpisodeURL.clone(), episode.EpisodeID);
+
+        if self.settings_applied.as_deref() != Some(url.as_str()) {
+            self.settings_applied = Some(url.clone());
+            let remembered = self.episode_settings.get(&episode);
+            match remembered.speed.or(flags.speed) {
+                Some(speed) => {
+                    self.speed_before.get_or_insert(self.music_handle.speed());
+                    self.music_handle.set_speed(speed);
+                }
+                None => {
+                    if let Some(speed) = self.speed_before.take() {
+                        self.music_handle.set_speed(speed);
+                    }
+                }
+            }
+            let played = self.music_handle.time_played();
+            if flags.skip_intro > played && !remembered.intro_skipped {
+                self.music_handle.seek_by(i64::from(flags.skip_intro - played));
+                self.episode_settings.skipped_intro(&episode);
+            } else if flags.audiobook && played > 0 {
+                // picked up partway, from a little before
+                self.music_handle
+                    .seek_to(played.saturating_sub(audiobook::REWIND).max(flags.skip_intro));
+            }
+            self.book = flags.audiobook.then(|| self.book(&episode));
+        }
+
+        let (played, length) = (self.music_handle.time_played(), self.music_handle.song_length());
+        if flags.skip_outro > 0 && length > flags.skip_outro && played >= length - flags.skip_outro {
+            self.last_playing = None;
+            self.music_handle.skip();
+            self.finished(url, episode_id);
+        }
+    }
+
+    fn book(&self, episode: &PinepodsEpisodes) -> Book {
+        let parts = audiobook::parts(episode, self.search_index.episodes());
+        let after = audiobook::after(&parts, &episode.EpisodeURL);
+        Book {
+            url: episode.EpisodeURL.clone(),
+            next: after.first().cloned(),
+            later: after.iter().map(|part| part.EpisodeDuration).sum(),
+        }
+    }
+
+    /// Seconds left of the audiobook playing, the rest of this part and all
+    /// of those after it.
+    pub fn book_left(&self) -> Option<i64> {
+        let book = self.book.as_ref()?;
+        if self.music_handle.playing()?.EpisodeURL != book.url {
+            return None;
+        }
+        let rest = self.music_handle.song_length().saturating_sub(self.music_handle.time_played());
+        Some(book.later + i64::from(rest))
+    }
+
+    fn order(&self, episodes: &mut [PinepodsEpisodes]) {
+        if self.smart_order {
+            self.smart_sort.sort(episodes);
+        }
+    }
+
+    fn listed_episodes(&self) -> Vec<PinepodsEpisodes> {
+        self.browser_items
+            .items()
+            .iter()
+            .filter_map(|item| match item {
+                BrowserItem::Episode(episode) => Some(episode.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Switch the episode list showing between newest first and smart sort.
+    pub fn toggle_smart_sort(&mut self) {
+        self.smart_order = !self.smart_order;
+        // search results go back to the order they were found in
+        if let Some(term) = self.search_term().map(String::from) {
+            let items = self.search_items(&term);
+            self.replace_browser_items(items);
+            return;
+        }
+        let mut episodes = self.listed_episodes();
+        if episodes.is_empty() {
+            return;
+        }
+        match self.smart_order {
+            true => self.smart_sort.sort(&mut episodes),
+            // undated ones last
+            false => episodes.sort_by_key(|episode| std::cmp::Reverse(gen_funcs::pub_date(&episode.EpisodePubDate))),
+        }
+        self.replace_browser_items(episodes.into_iter().map(BrowserItem::Episode).collect());
+    }
+
+    /// Ask how long there is to listen, then plan what fits.
+    pub fn open_listening_plan(&mut self) {
+        let minutes = (self.smart_sort.available / 60).to_string();
+        self.prompt(Prompt::PlanMinutes, minutes);
+    }
+
+    /// What fits in `minutes`, from the episode list showing, or from every
+    /// subscription's unplayed episodes when it's the podcasts showing.
+    pub fn plan_for(&mut self, minutes: &str) {
+        let minutes = match minutes.parse::<i64>() {
+            Ok(minutes) if minutes > 0 => minutes,
+            _ => {
+                self.notify(format!("Not a number of minutes: {}", minutes));
+                return;
+            }
+        };
+        // sticks for the rest of the session, the fit in the smart order too
+        self.smart_sort.available = minutes * 60;
+
+        if !matches!(self.content_state, ContentState::PodcastMode { .. }) {
+            let episodes = self.listed_episodes();
+            self.make_plan(episodes);
+            return;
+        }
+        if self.plan_check.is_some() {
+            return;
+        }
+        let pinepods_values = self.pinepods_values.lock().unwrap().clone();
+        let (tx, rx) = oneshot::channel();
+        self.plan_check = Some(rx);
+        tokio::spawn(async move {
+            let result = pinepods_values.recent_episodes().await;
+            match &result {
+                Ok(_) => errors::api_ok(),
+                Err(e) => errors::api_error("return_episodes", e),
+            }
+            let _ = tx.send(result.map_err(|e| e.to_string()));
+        });
+        self.notify(String::from("Looking through every podcast's unplayed episodes"));
+    }
+
+    fn check_plan(&mut self) {
+        let Some(check) = self.plan_check.as_mut() else {
+            return;
+        };
+        let episodes = match check.try_recv() {
+            Ok(episodes) => episodes,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.plan_check = None;
+                return;
+            }
+        };
+        self.plan_check = None;
+
+        match episodes {
+            // hidden podcasts stay out of it, as they do everywhere else
+            Ok(episodes) => self.make_plan(
+                episodes
+                    .into_iter()
+                    .filter(|episode| {
+                        episode
+                            .PodcastName
+                            .as_deref()
+                            .is_none_or(|name| self.podcast_filter.shows(name))
+                    })
+                    .collect(),
+            ),
+            Err(e) => self.notify(format!("Couldn't fetch episodes to plan with: {}", e)),
+        }
+    }
+
+    fn make_plan(&mut self, candidates: Vec<PinepodsEpisodes>) {
+        if candidates.is_empty() {
+            return;
+        }
+        self.listening_plan = Some(ListeningPlan {
+            episodes: self.smart_sort.plan(&candidates),
+            candidates,
+            made: 1,
+        });
+    }
+
+    /// Pick again, for a plan that isn't just the same top scorers.
+    pub fn regenerate_listening_plan(&mut self) {
+        if let Some(plan) = self.listening_plan.as_mut() {
+            plan.episodes = self.smart_sort.varied_plan(&plan.candidates, plan.made);
+            plan.made += 1;
+        }
+    }
+
+    /// The same episodes, in a different order.
+    pub fn shuffle_listening_plan(&mut self) {
+        if let Some(plan) = self.listening_plan.as_mut() {
+            gen_funcs::shuffle(&mut plan.episodes);
+        }
+    }
+
+    pub fn queue_listening_plan(&mut self) {
+        let episodes = self.listening_plan.take().map(|plan| plan.episodes).unwrap_or_default();
+        for episode in episodes {
+            let duration = episode.EpisodeDuration;
+            self.queue_items.add(episode, duration);
+        }
+    }
+
+    /// Ask before downloading the selected podcast's unplayed episodes on
+    /// the server.
+    pub async fn confirm_download_unplayed(&mut self) {
+        let Some(SelectedItem::Podcast(podcast)) = self.selected_item() else {
+            return;
+        };
+        let podcast = podcast.clone();
+        let pinepods_values = self.pinepods_values.lock().unwrap().clone();
+        match pinepods_values.return_eps(&podcast).await {
+            Ok(episodes) => {
+                errors::api_ok();
+                let unplayed: Vec<&PinepodsEpisodes> =
+                    episodes.iter().filter(|episode| never_started(episode)).collect();
+                self.download_confirm = Some(DownloadConfirm {
+                    podcast: podcast.PodcastName.clone(),
+                    episodes: unplayed.iter().filter_map(|episode| episode.EpisodeID).collect(),
+                    duration: unplayed.iter().map(|episode| episode.EpisodeDuration).sum(),
+                });
+            }
+            Err(e) => {
+                errors::api_error("podcast_episodes", &e);
+                self.notify(format!("Couldn't look up {}'s episodes", podcast.PodcastName));
+            }
+        }
+    }
+
+    // sent a batch at a time through the outbox, which counts them down
+    pub fn download_unplayed(&mut self) {
+        let Some(confirm) = self.download_confirm.take() else {
+            return;
+        };
+        if confirm.episodes.is_empty() {
+            return;
+        }
+        for episode_id in &confirm.episodes {
+            self.outbox.push(Action::Download { episode_id: *episode_id });
+            self.server_downloads.requested(*episode_id);
+        }
+        self.outbox_backlog = true;
+        self.notify(format!(
+            "Downloading {} episodes of {} on the server",
+            confirm.episodes.len(),
+            confirm.podcast
+        ));
+    }
+
+    pub fn save_search(&mut self, term: &str) {
+        self.searches.add(term);
+        self.reload_podcast_list();
+    }
+
+    // only from the podcast list, where searches are listed
+    pub fn delete_search(&mut self) {
+        if self.browser_items.empty() || !matches!(self.content_state, ContentState::PodcastMode { .. }) {
+            return;
+        }
+        let BrowserItem::Search(term) = self.browser_items.item() else {
+            return;
+        };
+        let term = term.clone();
+        self.searches.remove(&term);
+        self.search_results.remove(&term);
+        self.reload_podcast_list();
+    }
+
+    // list the podcasts again after the searches or the filter changed,
+    // without fetching them again
+    fn reload_podcast_list(&mut self) {
+        let items = match &self.content_state {
+            ContentState::PodcastMode { .. } => podcast_items(&self.searches, &self.podcast_filter, &self.podcasts),
+            ContentState::SearchMode { term } => self.search_items(term),
+            _ => return,
+        };
+        self.replace_browser_items(items);
+    }
+
+    pub fn open_filter_picker(&mut self) {
+        let mut names: Vec<String> = self.podcasts.iter().map(|p| p.PodcastName.clone()).collect();
+        names.sort_by_key(|name| name.to_lowercase());
+        let mut picker = StatefulList::with_items(names);
+        picker.next();
+        self.filter_picker = Some(picker);
+    }
+
+    /// Pick or unpick the podcast selected in the filter popup.
+    pub fn toggle_filtered(&mut self) {
+        let Some(picker) = &self.filter_picker else {
+            return;
+        };
+        let Some(name) = picker.selected().and_then(|i| picker.items().get(i)) else {
+            return;
+        };
+        self.podcast_filter.toggle(name);
+        self.reload_podcast_list();
+    }
+
+    pub fn switch_filter_mode(&mut self) {
+        self.podcast_filter.switch_mode();
+        self.reload_podcast_list();
+    }
+
+    // off and on again, keeping what's picked
+    pub fn toggle_filter(&mut self) {
+        self.podcast_filter.toggle_enabled();
+        self.reload_podcast_list();
+    }
+
+    // data saver streams the smallest enclosure there is, whatever the quality
+    fn saving_data(&self, quality: Quality) -> Quality {
+        match self.data_saver {
+            true => Quality::Lowest,
+            false => quality,
+        }
+    }
+
+    // point episodes at the alternate enclosure for `quality`, wherever the
+    // feed has one, so streaming, the queue and pre-caching all use it. Until
+    // the feed's in they're left as they are, and picked again once it is.
+    fn pick_variants(&mut self, feed_url: &str, quality: Quality, episodes: &mut [PinepodsEpisodes]) {
+        let Some(feed) = self.feeds.get(feed_url) else {
+            self.start_feed(feed_url);
+            return;
+        };
+        for episode in episodes {
+            let variant = feed
+                .item(&episode.EpisodeURL, &episode.EpisodeTitle)
+                .and_then(|item| item.variant(quality));
+            if let Some(variant) = variant {
+                episode.EpisodeURL = variant.url.clone();
+            }
+        }
+    }
+
+    pub async fn backpedal(&mut self) {
+
+        // Fetch the podcasts and wrap them as BrowserItem
+        self.content_state = ContentState::PodcastMode {
+            feed_url: String::from("some_feed_url"), // Replace with an actual URL or appropriate default value
+        };
+
+        // the last list fetched, while the fresh one comes in the background
+        match self.cache.podcasts() {
+            Some(saved) => {
+                let (fetched, podcasts) = (saved.fetched, saved.value.clone());
+                self.browser_items = StatefulList::with_items(podcast_items(&self.searches, &self.podcast_filter, &podcasts));
+                self.browser_items.next();
+                self.podcasts = podcasts;
+                self.stale = Some(fetched);
+                self.loading = None;
+            }
+            None => {
+                self.browser_items = StatefulList::with_items(Vec::new());
+                self.stale = None;
+                self.loading = Some(Instant::now());
+            }
+        }
+        // whatever's refreshing is for the page that was showing
+        self.refreshed = None;
+        self.start_podcast_refresh();
+        self.refresh.postpone(Refresh::Podcasts);
+    }
+
+    // if queue has items and nothing playing, auto play
+    pub fn auto_play(&mut self) {
+        thread::sleep(Duration::from_millis(250));
+        // before the next starts, or it'd look like that one's still playing
+        self.check_finished();
+        if !self.continuous {
+            return;
+        }
+        // nothing came next, whatever's queued later is started by hand
+        if self.queue_items.up_next().is_none() && self.server_queue_check.is_none() {
+            self.announce_next = false;
+        }
+        if self.music_handle.sink_empty() && self.queue_items.up_next().is_some() {
+            // the next starts once the announcement's played out
+            if self.music_handle.announcing() {
+                return;
+            }
+            if std::mem::take(&mut self.announce_next) && self.announce != Announce::Off {
+                let title = self.queue_items.up_next().map(|episode| episode.EpisodeTitle.clone());
+                self.music_handle.announce(self.announce, title.unwrap_or_default());
+                return;
+            }
+            self.music_handle.set_time_played(0);
+            let episode = self.queue_items.pop(); // Directly get the episode
+            self.music_handle.play(&episode);
+            self.playing_from = None;
+        }
+    }
+
+    pub fn toggle_continuous(&mut self) {
+        self.continuous = !self.continuous;
+        self.notify(String::from(match self.continuous {
+            true => "Carrying on with the next in the queue",
+            false => "Stopping after each episode",
+        }));
+    }
+
+    // an episode that's played to the end is marked completed on the server,
+    // and with nothing more here the server's queue is asked what's next
+    fn check_finished(&mut self) {
+        if self.music_handle.is_live() {
+            self.last_playing = None;
+            return;
+        }
+        if let Some(episode) = self.music_handle.playing() {
+            self.last_playing = Some((
+                episode.EpisodeURL.clone(),
+                episode.EpisodeID,
+                self.music_handle.time_played(),
+                self.music_handle.song_length(),
+            ));
+            return;
+        }
+        let Some((url, episode_id, played, length)) = self.last_playing.take() else {
+            return;
+        };
+        // the position's only counted a second at a time
+        if length == 0 || played.saturating_add(FINISHED_SLACK) < length {
+            return;
+        }
+        // an audiobook's part isn't done with until the very end of it
+        if self.book.as_ref().is_some_and(|book| book.url == url) && !audiobook::finished(played, length) {
+            return;
+        }
+        self.finished(url, episode_id);
+    }
+
+    fn finished(&mut self, url: String, episode_id: Option<i64>) {
+        self.announce_next = true;
+        self.episode_settings.forget(episode_id, &url);
+        if let Some(episode_id) = episode_id {
+            self.outbox.push(Action::Completed { episode_id });
+            self.outbox_backlog = true;
+        }
+        if local_files::is_local(&url) {
+            local_files::forget(&url);
+        }
+        // the next part of a book comes before the server's queue
+        let next_part = self.book.take().filter(|book| book.url == url).and_then(|book| book.next);
+        if let Some(part) = next_part.filter(|_| self.continuous && self.queue_items.up_next().is_none()) {
+            self.notify(format!("Carrying on with {}", part.EpisodeTitle));
+            let duration = part.EpisodeDuration;
+            self.queue_items.add(part, duration);
+            return;
+        }
+        if self.continuous && self.queue_items.up_next().is_none() && self.server_queue_check.is_none() {
+            let pinepods_values = self.pinepods_values.lock().unwrap().clone();
+            let (tx, rx) = oneshot::channel();
+            self.server_queue_check = Some(rx);
+            tokio::spawn(async move {
+                let result = pinepods_values.queued_episodes().await;
+                match &result {
+                    Ok(_) => errors::api_ok(),
+                    Err(e) => errors::api_error("get_queued_episodes", e),
+                }
+                let _ = tx.send((url, result.map_err(|e| e.to_string())));
+            });
+        }
+    }
+
+    fn check_server_queue(&mut self) {
+        let Some(check) = self.server_queue_check.as_mut() else {
+            return;
+        };
+        let (finished, episodes) = match check.try_recv() {
+            Ok(result) => result,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.server_queue_check = None;
+                return;
+            }
+        };
+        self.server_queue_check = None;
+
+        // unreachable is already on the banner
+        let Ok(episodes) = episodes else {
+            return;
+        };
+        let mut added = 0;
+        for episode in episodes {
+            let queued = self.queue_items.items().iter().any(|queued| queued.EpisodeURL == episode.EpisodeURL);
+            if episode.EpisodeURL == finished || queued {
+                continue;
+            }
+            let duration = episode.EpisodeDuration;
+            self.queue_items.add(episode, duration);
+            added += 1;
+        }
+        if added > 0 {
+            self.notify(format!("Carrying on with {} from the server's queue", added));
+        }
+    }
+
+    /// Move the selected queue item `delta` places, on the server too.
+    pub fn move_queued(&mut self, delta: isize) {
+        if self.queue_items.move_selected(delta) {
+            self.send_queue_order();
+        }
+    }
+
+    /// Move the selected queue item up to play next, on the server too.
+    pub fn move_queued_to_next(&mut self) {
+        if self.queue_items.move_to_next() {
+            self.send_queue_order();
+        }
+    }
+
+    /// Queue the selected episode above the rest of the queue.
+    pub fn play_next(&mut self) {
+        let Some(SelectedItem::Episode(episode)) = self.selected_item() else {
+            return;
+        };
+        let episode = episode.clone();
+        let duration = episode.EpisodeDuration;
+        let title = episode.EpisodeTitle.clone();
+        self.queue_items.play_next(episode, duration);
+        self.send_queue_order();
+        self.notify(format!("{} plays next", title));
+    }
+
+    /// Remove the marked queue items, or the selected one when none are.
+    pub fn remove_queued(&mut self) {
+        if self.queue_items.remove_marked() == 0 {
+            self.queue_items.remove();
+        }
+    }
+
+    // the order here goes to the server once it's been merged with the
+    // server's, in case that's changed too
+    fn send_queue_order(&mut self) {
+        self.queue_edited = true;
+        self.start_queue_check();
+    }
+
+    fn start_queue_check(&mut self) {
+        if self.queue_check.is_some() || self.queue_conflict.is_some() {
+            return;
+        }
+        self.last_queue_check = Instant::now();
+        let pinepods_values = self.pinepods_values.lock().unwrap().clone();
+        let (tx, rx) = oneshot::channel();
+        self.queue_check = Some(rx);
+        tokio::spawn(async move {
+            match pinepods_values.queued_episodes().await {
+                Ok(episodes) => {
+                    errors::api_ok();
+                    let _ = tx.send(episodes);
+                }
+                Err(e) => errors::api_error("get_queued_episodes", &e),
+            }
+        });
+    }
+
+    // merged straight away unless both sides reordered it, which is asked about
+    fn check_queue(&mut self) {
+        // reordered while the server couldn't be reached, tried as often as the outbox is
+        if self.queue_edited && self.last_queue_check.elapsed() >= OUTBOX_RETRY_INTERVAL {
+            self.start_queue_check();
+        }
+        let Some(check) = self.queue_check.as_mut() else {
+            return;
+        };
+        let server = match check.try_recv() {
+            Ok(server) => server,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.queue_check = None;
+                return;
+            }
+        };
+        self.queue_check = None;
+
+        let server_ids: Vec<i64> = server.iter().filter_map(|episode| episode.EpisodeID).collect();
+        let merge = self.queue_sync.merge(&self.queue_items.episode_ids(), &server_ids);
+        match merge.conflict {
+            true => self.queue_conflict = Some(QueueConflict { merge, server }),
+            false => self.sync_queue(merge.order, server),
+        }
+    }
+
+    pub fn settle_queue_conflict(&mut self, settle: Settle) {
+        let Some(conflict) = self.queue_conflict.take() else {
+            return;
+        };
+        let order = match settle {
+            Settle::Merge => conflict.merge.order,
+            Settle::Here => self.queue_items.episode_ids(),
+            Settle::Server => conflict.server.iter().filter_map(|episode| episode.EpisodeID).collect(),
+        };
+        self.sync_queue(order, conflict.server);
+    }
+
+    // the queue here put in `order`, and the server's too where it isn't already
+    fn sync_queue(&mut self, order: Vec<i64>, server: Vec<PinepodsEpisodes>) {
+        let server_ids: Vec<i64> = server.iter().filter_map(|episode| episode.EpisodeID).collect();
+        let here = self.queue_items.episode_ids();
+        let added = order.iter().filter(|id| !here.contains(id)).count();
+        let removed = here.iter().filter(|id| !order.contains(id)).count();
+        let new = server
+            .into_iter()
+            .filter(|episode| episode.EpisodeID.is_some_and(|id| !here.contains(&id)))
+            .collect();
+        self.queue_items.reorder(&order, new);
+
+        // the episodes the server doesn't have queued are left out of it
+        let order = self.queue_items.episode_ids();
+        if order != server_ids {
+            self.outbox.push(Action::QueueOrder {
+                episode_ids: order.clone(),
+            });
+            self.outbox_backlog = true;
+        }
+        self.queue_sync.agreed(&order, &server_ids);
+        self.queue_edited = false;
+        if added + removed > 0 {
+            self.notify(format!(
+                "Queue synced with the server's, {} added and {} removed elsewhere",
+                added, removed
+            ));
+        }
+    }
+
+    /// Play the selected queue item and carry on down the queue after it.
+    pub fn play_from_here(&mut self) {
+        if let Some(episode) = self.queue_items.play_from_here(self.keep_earlier) {
+            self.music_handle.play(&episode);
+            self.playing_from = None;
+        }
+    }
+
+    // the open podcast's episodes, when that's what the browser is showing
+    fn podcast_context(&self) -> Option<PlayingFrom> {
+        if !matches!(self.content_state, ContentState::EpisodeMode { .. }) {
+            return None;
+        }
+        let mut episodes: Vec<PinepodsEpisodes> = self
+            .browser_items
+            .items()
+            .iter()
+            .filter_map(|item| match item {
+                BrowserItem::Episode(episode) => Some(episode.clone()),
+                _ => None,
+            })
+            .collect();
+        episodes.sort_by_key(|episode| gen_funcs::pub_date(&episode.EpisodePubDate));
+        let podcast = episodes.first()?.PodcastName.clone().unwrap_or_default();
+        Some(PlayingFrom { podcast, episodes })
+    }
+
+    /// Play the episode after (or before, for a negative `step`) the playing
+    /// one, by date, in the podcast it was played from.
+    pub fn play_adjacent(&mut self, step: isize) {
+        let playing = self.music_handle.playing().map(|episode| episode.EpisodeURL.clone());
+        // something else may have started playing since
+        let found = self.playing_from.as_ref().and_then(|from| {
+            let i = from.episodes.iter().position(|episode| Some(&episode.EpisodeURL) == playing.as_ref())?;
+            Some((from, i))
+        });
+        let Some((from, i)) = found else {
+            self.notify(String::from("Not playing from a podcast, play one of its episodes from the browser first"));
+            return;
+        };
+        let Some(episode) = i
+            .checked_add_signed(step)
+            .and_then(|i| from.episodes.get(i))
+            .cloned()
+        else {
+            let end = if step > 0 { "newest" } else { "oldest" };
+            self.notify(format!("That's the {} episode of {}", end, from.podcast));
+            return;
+        };
+        self.notify(format!("Playing {}", episode.EpisodeTitle));
+        self.music_handle.play(&episode);
+    }
+
+
+    /// Everything the app keeps a copy of from `cfg`, on the way in, after
+    /// switching user, and in the daemon.
+    pub fn apply_settings(&mut self, cfg: &Config) {
+        self.data_saver = cfg.data_saver();
+        self.quality = cfg.quality();
+        self.podcast_quality = cfg.podcast_quality().clone();
+        self.music_handle.record_live = cfg.record_live();
+        self.keep_earlier = cfg.keep_earlier();
+        self.continuous = cfg.continuous();
+        self.announce = cfg.announce();
+        self.music_handle.set_speed(cfg.speed());
+        self.webhooks = Webhooks::new(cfg.webhooks().to_vec());
+        self.set_refresh_intervals(cfg.refresh_podcasts(), cfg.refresh_episodes());
+        self.set_backups(cfg.backup_interval(), cfg.backup_keep(), cfg.path().map(Path::to_path_buf));
+        self.set_smart_sort(cfg.smart_sort().clone());
+        self.downloads.set_command(cfg.download_command().map(String::from));
+        self.downloads.set_processing(cfg.download_processing());
+        local_store::set_play_processed(cfg.play_processed());
+        self.desktop = Desktop::new(cfg.desktop_notifications().to_vec());
+        self.podcast_rail = cfg.podcast_rail();
+        self.queue_items.set_grouped(cfg.group_queue());
+        self.set_podcast_search(cfg.search_provider(), cfg.podcastindex_keys());
+    }
+
+    /// How often the podcast list, with live shows, and the episode list are
+    /// refreshed in the background, None for never.
+    pub fn set_refresh_intervals(&mut self, podcasts: Option<Duration>, episodes: Option<Duration>) {
+        // the podcast list is fetched on the way in, live shows aren't
+        self.refresh.set(Refresh::Live, podcasts, true);
+        self.refresh.set(Refresh::Podcasts, podcasts, false);
+        self.refresh.set(Refresh::Episodes, episodes, false);
+        // saved searches go with the podcast list
+        self.refresh.set(Refresh::Searches, podcasts, true);
+        self.refresh.set(Refresh::Stats, podcasts, true);
+        // the queue's merged with the server's on the way in and with episodes
+        self.refresh.set(Refresh::Queue, episodes, true);
+    }
+
+    // report the listen position now and then, and retry anything the server missed
+    // also runs background refreshes and calls webhooks, since it runs as
+    // often as anything needs to
+    pub fn sync_server(&mut self) {
+        self.cache.set_queue(self.queue_items.items());
+        self.check_live();
+        self.check_refreshed();
+        self.check_searches();
+        self.check_stats();
+        self.check_podcast_stats();
+        self.check_chapters();
+        self.check_transcript();
+        self.check_feeds();
+        self.check_outbox();
+        self.check_server_downloads();
+        self.check_remote_clients();
+        self.check_import();
+        self.check_paste();
+        self.check_directory();
+        self.check_preview();
+        self.check_subscribe();
+        self.check_handoff();
+        self.check_cast_peers();
+        self.check_cast();
+        self.check_plan();
+        self.check_resubscribe();
+        self.check_downloads();
+        self.check_playback();
+        self.check_podcast_playback();
+        self.check_finished();
+        self.check_server_queue();
+        self.check_queue();
+        self.list_results_ahead();
+        // data saver turns off fetching unasked, the backups are only written here
+        match self.refresh.due() {
+            Some(Refresh::Backup) => self.back_up("scheduled"),
+            Some(_) if self.data_saver => {}
+            Some(Refresh::Live) => self.start_live_check(),
+            Some(Refresh::Podcasts) => self.start_podcast_refresh(),
+            Some(Refresh::Episodes) => self.start_episode_refresh(),
+            Some(Refresh::Searches) => self.start_search_refresh(),
+            Some(Refresh::Stats) => self.start_stats_refresh(),
+            Some(Refresh::Queue) => self.start_queue_check(),
+            None => {}
+        }
+        if self.last_server_download_check.elapsed() >= SERVER_DOWNLOAD_CHECK_INTERVAL {
+            self.start_server_download_check();
+        }
+        let status = self.status();
+        self.webhooks.update(&status);
+        let playing = self.music_handle.playing().filter(|_| !status.paused);
+        play_history::update(playing);
+
+        if self
+            .last_telemetry_upload
+            .is_none_or(|last| last.elapsed() >= TELEMETRY_UPLOAD_INTERVAL)
+        {
+            self.last_telemetry_upload = Some(Instant::now());
+            tokio::spawn(telemetry::upload());
+        }
+
+        let mut pushed = self.outbox_backlog;
+        if self.last_position_report.elapsed() >= POSITION_REPORT_INTERVAL {
+            self.last_position_report = Instant::now();
+            let playing = !self.music_handle.sink_empty() && !self.music_handle.is_paused();
+            if let (true, Some(episode_id)) = (playing, self.music_handle.currently_playing_id()) {
+                self.outbox.push(Action::Position {
+                    episode_id,
+                    position: self.music_handle.time_played() as i64,
+                });
+                pushed = true;
+            }
+            // the server's never heard of local files, they're kept track of here
+            if let Some(episode) = self.music_handle.playing().filter(|_| playing) {
+                if local_files::is_local(&episode.EpisodeURL) {
+                    local_files::remember(&episode.EpisodeURL, self.music_handle.time_played());
+                }
+            }
+        }
+
+        if self.outbox.is_empty()
+            || self.outbox_check.is_some()
+            || (!pushed && self.last_outbox_flush.elapsed() < OUTBOX_RETRY_INTERVAL)
+        {
+            return;
+        }
+        self.last_outbox_flush = Instant::now();
+        self.outbox_backlog = false;
+        let batch = self.outbox.batch();
+        let pinepods_values = self.pinepods_values.lock().unwrap().clone();
+        let (tx, rx) = oneshot::channel();
+        self.outbox_check = Some(rx);
+        tokio::spawn(async move {
+            let _ = tx.send(Outbox::flush(batch, &pinepods_values).await);
+        });
+    }
+
+    fn start_server_download_check(&mut self) {
+        // only once they've been asked for, so there's something to see
+        if self.server_download_check.is_some() || self.server_downloads.is_empty() || self.outbox.downloads() > 0 {
+            return;
+        }
+        self.last_server_download_check = Instant::now();
+        let pinepods_values = self.pinepods_values.lock().unwrap().clone();
+        let (tx, rx) = oneshot::channel();
+        self.server_download_check = Some(rx);
+        tokio::spawn(async move {
+            match pinepods_values.downloaded_episodes().await {
+                Ok(downloaded) => {
+                    errors::api_ok();
+                    let _ = tx.send(downloaded);
+                }
+                Err(e) => errors::api_error("download_episode_list", &e),
+            }
+        });
+    }
+
+    // done ones told about, slow ones asked for again
+    fn check_server_downloads(&mut self) {
+        let Some(check) = self.server_download_check.as_mut() else {
+            return;
+        };
+        let downloaded = match check.try_recv() {
+            Ok(downloaded) => downloaded,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.server_download_check = None;
+                return;
+            }
+        };
+        self.server_download_check = None;
+
+        let checked = self.server_downloads.check(&downloaded);
+        for episode_id in &checked.retry {
+            self.outbox.push(Action::Download { episode_id: *episode_id });
+        }
+        if !checked.retry.is_empty() {
+            info!("Asking the server again for {} downloads", checked.retry.len());
+            self.outbox_backlog = true;
+        }
+        match (checked.done, checked.failed) {
+            (0, 0) => {}
+            (done, 0) => self.notify(format!("{} of the episodes asked for downloaded on the server", done)),
+            (_, failed) => self.notify(format!(
+                "{} of the episodes asked for didn't download on the server, Shift+D on the podcast tries again",
+                failed
+            )),
+        }
+    }
+
+    // take the last batch sent off the outbox, pushed since or not
+    fn check_outbox(&mut self) {
+        if let Some(check) = self.outbox_check.as_mut() {
+            match check.try_recv() {
+                Ok(flushed) => self.outbox_backlog |= self.outbox.sent(flushed),
+                Err(oneshot::error::TryRecvError::Empty) => return,
+                Err(oneshot::error::TryRecvError::Closed) => {}
+            }
+            self.outbox_check = None;
+        }
+    }
+
+    // pick up the last live check if it finished
+    fn check_live(&mut self) {
+        if let Some(check) = self.live_check.as_mut() {
+            match check.try_recv() {
+                Ok(shows) => self.update_live_shows(shows),
+                Err(oneshot::error::TryRecvError::Empty) => return,
+                Err(oneshot::error::TryRecvError::Closed) => {}
+            }
+            self.live_check = None;
+        }
+    }
+
+    fn start_live_check(&mut self) {
+        // every feed every few minutes is a lot to fetch unasked
+        if self.live_check.is_some() || self.data_saver || self.subscriptions.is_empty() {
+            return;
+        }
+
+        let subscriptions = self.subscriptions.clone();
+        let (tx, rx) = oneshot::channel();
+        self.live_check = Some(rx);
+        tokio::spawn(async move {
+            let feeds = futures::stream::iter(subscriptions)
+                .map(|(podcast, url)| async move { (podcast, feed::fetch(&url).await) })
+                .buffer_unordered(LIVE_CHECK_CONCURRENCY)
+                .collect::<Vec<_>>()
+                .await;
+
+            let mut shows = Vec::new();
+            for (podcast, feed) in feeds {
+                match feed {
+                    Ok(feed) => shows.extend(
+                        feed.live
+                            .into_iter()
+                            .filter(|item| item.status != LiveStatus::Ended)
+                            .map(|item| LiveShow {
+                                podcast: podcast.clone(),
+                                item,
+                            }),
+                    ),
+                    Err(e) => debug!("Unable to check {} for live shows: {:?}", podcast, e),
+                }
+            }
+            let _ = tx.send(shows);
+        });
+    }
+
+    // only the page showing is refreshed, there's no point fetching the others
+    fn start_podcast_refresh(&mut self) {
+        if self.refreshed.is_some() || !matches!(self.content_state, ContentState::PodcastMode { .. }) {
+            return;
+        }
+        let pinepods_values = self.pinepods_values.lock().unwrap().clone();
+        let (tx, rx) = oneshot::channel();
+        self.refreshed = Some(rx);
+        tokio::spawn(async move {
+            match pinepods_values.return_pods().await {
+                Ok(podcasts) => {
+                    errors::api_ok();
+                    let _ = tx.send(Refreshed::Podcasts(podcasts));
+                }
+                Err(e) => errors::api_error("return_pods", &e),
+            }
+        });
+    }
+
+    fn start_episode_refresh(&mut self) {
+        let ContentState::EpisodeMode { podcast_id, .. } = self.content_state else {
+            return;
+        };
+        if self.refreshed.is_some() {
+            return;
+        }
+        let pinepods_values = self.pinepods_values.lock().unwrap().clone();
+        let (tx, rx) = oneshot::channel();
+        self.refreshed = Some(rx);
+        tokio::spawn(async move {
+            match pinepods_values.podcast_episodes(podcast_id).await {
+                Ok(episodes) => {
+                    errors::api_ok();
+                    let _ = tx.send(Refreshed::Episodes(podcast_id, episodes));
+                }
+                Err(e) => errors::api_error("podcast_episodes", &e),
+            }
+        });
+    }
+
+    // every saved search at once, a search needs every episode anyway. The
+    // rail's unplayed counts come from the same episodes.
+    fn start_search_refresh(&mut self) {
+        let wanted = !self.searches.is_empty() || self.podcast_rail || self.search_index.stale();
+        if self.search_check.is_some() || !wanted {
+            return;
+        }
+        let terms = self.searches.terms().to_vec();
+        let pinepods_values = self.pinepods_values.lock().unwrap().clone();
+        let (tx, rx) = oneshot::channel();
+        self.search_check = Some(rx);
+        tokio::spawn(async move {
+            match searches::all_episodes(&pinepods_values).await {
+                Ok(episodes) => {
+                    errors::api_ok();
+                    let results = terms
+                        .into_iter()
+                        .map(|term| {
+                            let found = searches::matching(&term, &episodes);
+                            (term, found)
+                        })
+                        .collect();
+                    let mut counts: HashMap<String, usize> = HashMap::new();
+                    for episode in episodes.iter().filter(|episode| never_started(episode)) {
+                        if let Some(podcast) = &episode.PodcastName {
+                            *counts.entry(podcast.clone()).or_default() += 1;
+                        }
+                    }
+                    let _ = tx.send(SearchRefresh {
+                        results,
+                        unplayed: counts,
+                        episodes,
+                    });
+                }
+                Err(e) => errors::api_error("return_pods", &e),
+            }
+        });
+    }
+
+    /// Queue the episodes of a shared episode link or playlist, looked up in
+    /// the background.
+    pub fn start_import(&mut self, link: &str) {
+        let Some(parsed) = Link::parse(link) else {
+            self.notify(format!("Not a playlist id or share link: {}", link));
+            return;
+        };
+        let pinepods_values = self.pinepods_values.lock().unwrap().clone();
+        let (tx, rx) = oneshot::channel();
+        self.import_check = Some(rx);
+        tokio::spawn(async move {
+            let result = playlist_import::resolve(&pinepods_values, &parsed).await;
+            match &result {
+                Ok(_) => errors::api_ok(),
+                Err(e) => errors::api_error("get_playlist_episodes", e),
+            }
+            let _ = tx.send(result.map_err(|e| e.to_string()));
+        });
+    }
+
+    fn check_import(&mut self) {
+        let Some(check) = self.import_check.as_mut() else {
+            return;
+        };
+        let import = match check.try_recv() {
+            Ok(import) => import,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.import_check = None;
+                return;
+            }
+        };
+        self.import_check = None;
+
+        let import = match import {
+            Ok(import) => import,
+            Err(e) => {
+                self.notify(format!("Couldn't import: {}", e));
+                return;
+            }
+        };
+        self.queue_import(import);
+    }
+
+    fn queue_import(&mut self, import: Import) {
+        for title in &import.unmatched {
+            error!("Not imported, nothing to play for: {}", title);
+        }
+        let mut message = format!("Queued {} episodes", import.episodes.len());
+        if !import.unmatched.is_empty() {
+            message.push_str(&format!(", {} couldn't be matched", import.unmatched.len()));
+        }
+        for episode in import.episodes {
+            let duration = episode.EpisodeDuration;
+            self.queue_items.add(episode, duration);
+        }
+        self.notify(message);
+    }
+
+    /// Look into the link on the system clipboard, asking what to do with it
+    /// once it's known what it is.
+    pub fn paste_from_clipboard(&mut self) {
+        self.start_paste(None);
+    }
+
+    /// Look into `text`, pasted into the terminal, the same way.
+    pub fn paste(&mut self, text: String) {
+        self.start_paste(Some(text));
+    }
+
+    fn start_paste(&mut self, text: Option<String>) {
+        if self.paste_check.is_some() {
+            return;
+        }
+        let pinepods_values = self.pinepods_values.lock().unwrap().clone();
+        let (tx, rx) = oneshot::channel();
+        self.paste_check = Some(rx);
+        tokio::spawn(async move {
+            let text = match text {
+                Some(text) => text,
+                None => match paste::clipboard().await {
+                    Ok(text) => text,
+                    Err(e) => {
+                        let _ = tx.send(Err(format!("Couldn't read the clipboard, {}", e)));
+                        return;
+                    }
+                },
+            };
+            let result = paste::resolve(&pinepods_values, &text).await;
+            let _ = tx.send(result.map_err(|e| format!("Nothing to do with that, {}", e)));
+        });
+    }
+
+    fn check_paste(&mut self) {
+        let Some(check) = self.paste_check.as_mut() else {
+            return;
+        };
+        let pasted = match check.try_recv() {
+            Ok(pasted) => pasted,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.paste_check = None;
+                return;
+            }
+        };
+        self.paste_check = None;
+
+        self.pasted = match pasted {
+            // the server's own copy, if it has the episode, so its place is kept
+            Ok(Pasted::Queue(episode)) => Some(Pasted::Queue(
+                self.search_index
+                    .episodes()
+                    .iter()
+                    .find(|known| known.EpisodeURL == episode.EpisodeURL)
+                    .cloned()
+                    .unwrap_or(episode),
+            )),
+            Ok(Pasted::Import(import)) if import.episodes.is_empty() => {
+                self.notify(String::from("Nothing in that link could be played"));
+                None
+            }
+            Ok(pasted) => Some(pasted),
+            Err(e) => {
+                self.notify(e);
+                None
+            }
+        };
+    }
+
+    /// Go ahead with what the pasted link came to.
+    pub fn confirm_paste(&mut self) {
+        match self.pasted.take() {
+            Some(Pasted::Subscribe(podcast)) => self.subscribe(podcast),
+            Some(Pasted::Queue(episode)) => {
+                let duration = episode.EpisodeDuration;
+                let title = episode.EpisodeTitle.clone();
+                self.queue_items.add(episode, duration);
+                self.notify(format!("Queued {}", title));
+            }
+            Some(Pasted::Import(import)) => self.queue_import(import),
+            None => {}
+        }
+    }
+
+    /// Where new podcasts are looked for first, and the PodcastIndex keys to
+    /// search it with directly.
+    pub fn set_podcast_search(&mut self, provider: Provider, keys: Option<Keys>) {
+        self.search_provider = provider;
+        self.podcastindex_keys = keys;
+    }
+
+    /// Look for podcasts to subscribe to matching `query`.
+    pub fn find_podcasts(&mut self, query: &str) {
+        self.podcast_search = Some(PodcastSearch {
+            query: query.to_string(),
+            provider: self.search_provider,
+            results: StatefulList::with_items(Vec::new()),
+            searching: false,
+            previewing: false,
+        });
+        self.start_directory_search();
+    }
+
+    /// Search the other directory for the same thing.
+    pub fn switch_search_provider(&mut self) {
+        let Some(search) = self.podcast_search.as_mut() else {
+            return;
+        };
+        search.provider = search.provider.next();
+        search.results = StatefulList::with_items(Vec::new());
+        // for the next search too
+        self.search_provider = search.provider;
+        self.start_directory_search();
+    }
+
+    // one asked for before this replaces it, its answer is dropped
+    fn start_directory_search(&mut self) {
+        let Some(search) = self.podcast_search.as_mut() else {
+            return;
+        };
+        search.searching = true;
+        let (provider, query) = (search.provider, search.query.clone());
+        let keys = self.podcastindex_keys.clone();
+        let (tx, rx) = oneshot::channel();
+        self.directory_check = Some(rx);
+        tokio::spawn(async move {
+            let result = podcast_search::search(provider, &query, keys.as_ref()).await;
+            if let Err(e) = &result {
+                error!("Unable to search {} for {}: {:?}", provider.name(), query, e);
+            }
+            let _ = tx.send((provider, query, result.map_err(|e| e.to_string())));
+        });
+    }
+
+    fn check_directory(&mut self) {
+        let Some(check) = self.directory_check.as_mut() else {
+            return;
+        };
+        let (provider, query, result) = match check.try_recv() {
+            Ok(found) => found,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.directory_check = None;
+                return;
+            }
+        };
+        self.directory_check = None;
+
+        // closed, or searching for something else by now
+        let Some(search) = self
+            .podcast_search
+            .as_mut()
+            .filter(|search| search.provider == provider && search.query == query)
+        else {
+            return;
+        };
+        search.searching = false;
+        match result {
+            Ok(podcasts) => {
+                search.results = StatefulList::with_items(podcasts);
+                search.results.next();
+            }
+            Err(e) => self.notify(format!("Couldn't search {}: {}", provider.name(), e)),
+        }
+    }
+
+    fn found(&self) -> Option<&PinepodsPodcasts> {
+        let results = &self.podcast_search.as_ref()?.results;
+        results.items().get(results.selected()?)
+    }
+
+    /// Fill in the result selected from its feed, for what the directory
+    /// didn't say.
+    pub fn preview_found(&mut self) {
+        if self.preview_check.is_some() {
+            return;
+        }
+        let Some(podcast) = self.found().cloned() else {
+            return;
+        };
+        if let Some(search) = self.podcast_search.as_mut() {
+            search.previewing = true;
+        }
+        let (tx, rx) = oneshot::channel();
+        self.preview_check = Some(rx);
+        tokio::spawn(async move {
+            let result = podcast_search::preview(&podcast).await;
+            let _ = tx.send((podcast.FeedURL, result.map_err(|e| e.to_string())));
+        });
+    }
+
+    fn check_preview(&mut self) {
+        let Some(check) = self.preview_check.as_mut() else {
+            return;
+        };
+        let (url, result) = match check.try_recv() {
+            Ok(preview) => preview,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.preview_check = None;
+                return;
+            }
+        };
+        self.preview_check = None;
+
+        let Some(search) = self.podcast_search.as_mut() else {
+            return;
+        };
+        search.previewing = false;
+        match result {
+            Ok(podcast) => {
+                let selected = search.results.selected();
+                let mut results = search.results.items().clone();
+                if let Some(found) = results.iter_mut().find(|found| found.FeedURL == url) {
+                    *found = podcast;
+                }
+                search.results = StatefulList::with_items(results);
+                if let Some(i) = selected {
+                    search.results.select(i);
+                }
+            }
+            Err(e) => self.notify(e),
+        }
+    }
+
+    /// Subscribe to the result selected.
+    pub fn subscribe_found(&mut self) {
+        if let Some(podcast) = self.found().cloned() {
+            self.subscribe(podcast);
+        }
+    }
+
+    fn subscribe(&mut self, podcast: PinepodsPodcasts) {
+        if self.podcasts.iter().any(|now| now.FeedURL == podcast.FeedURL) {
+            self.notify(format!("Already subscribed to {}", podcast.PodcastName));
+            return;
+        }
+        if self.subscribe_check.is_some() {
+            return;
+        }
+        let pinepods_values = self.pinepods_values.lock().unwrap().clone();
+        let (tx, rx) = oneshot::channel();
+        self.subscribe_check = Some(rx);
+        tokio::spawn(async move {
+            let result = match pinepods_values.add_podcast(&podcast).await {
+                Ok(()) => {
+                    errors::api_ok();
+                    Ok(podcast.PodcastName)
+                }
+                Err(e) => {
+                    errors::api_error("add_podcast", &format!("{:?}", e));
+                    Err(format!("Couldn't subscribe to {}", podcast.PodcastName))
+                }
+            };
+            let _ = tx.send(result);
+        });
+    }
+
+    fn check_subscribe(&mut self) {
+        let Some(check) = self.subscribe_check.as_mut() else {
+            return;
+        };
+        let subscribed = match check.try_recv() {
+            Ok(subscribed) => subscribed,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.subscribe_check = None;
+                return;
+            }
+        };
+        self.subscribe_check = None;
+
+        match subscribed {
+            Ok(name) => {
+                self.notify(format!("Subscribed to {}", name));
+                self.start_podcast_refresh();
+            }
+            Err(e) => self.notify(e),
+        }
+    }
+
+    /// Carry on here with what the account was last listening to elsewhere.
+    pub fn pull_playback(&mut self) {
+        if self.handoff_check.is_some() {
+            return;
+        }
+        let playing_here = self.music_handle.playing().map(|episode| episode.EpisodeURL.clone());
+        let pinepods_values = self.pinepods_values.lock().unwrap().clone();
+        let (tx, rx) = oneshot::channel();
+        self.handoff_check = Some(rx);
+        tokio::spawn(async move {
+            let result = handoff::elsewhere(&pinepods_values, playing_here.as_deref()).await;
+            match &result {
+                Ok(_) => errors::api_ok(),
+                Err(e) => errors::api_error("user_history", e),
+            }
+            let _ = tx.send(result.map_err(|e| e.to_string()));
+        });
+    }
+
+    fn check_handoff(&mut self) {
+        let Some(check) = self.handoff_check.as_mut() else {
+            return;
+        };
+        let episode = match check.try_recv() {
+            Ok(episode) => episode,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.handoff_check = None;
+                return;
+            }
+        };
+        self.handoff_check = None;
+
+        match episode {
+            Ok(Some(episode)) => {
+                let position = episode.ListenDuration.unwrap_or(0).clamp(0, u16::MAX.into()) as u16;
+                self.music_handle.play_from(&episode, position);
+                self.notify(format!(
+                    "Carrying on with {} from {}",
+                    episode.EpisodeTitle,
+                    format::clock(position.into())
+                ));
+            }
+            Ok(None) => self.notify(String::from("Nothing unfinished playing elsewhere")),
+            Err(e) => self.notify(format!("Couldn't ask the server what's playing: {}", e)),
+        }
+    }
+
+    /// Save where this is up to on the server straight away and stop, for
+    /// carrying on in the web or mobile app.
+    pub fn push_playback(&mut self) {
+        let Some(episode_id) = self.music_handle.currently_playing_id() else {
+            self.notify(String::from("Nothing playing to hand off"));
+            return;
+        };
+        if !self.music_handle.is_paused() {
+            self.music_handle.play_pause();
+        }
+        let position = self.music_handle.time_played();
+        self.outbox.push(Action::Position {
+            episode_id,
+            position: position as i64,
+        });
+        // sent with the next sync rather than waiting for the retry interval
+        self.outbox_backlog = true;
+        self.notify(format!(
+            "Paused at {}, carry on from there on another device",
+            format::clock(position.into())
+        ));
+    }
+
+    /// Look for other Firewoods on the LAN to play what's playing here on.
+    /// `token` is this one's remote control token, sent along to them.
+    pub fn open_cast_picker(&mut self, token: Option<String>) {
+        let Some(episode) = self.music_handle.playing().filter(|_| !self.music_handle.sink_empty()) else {
+            self.notify(String::from("Nothing playing to cast"));
+            return;
+        };
+        if local_files::is_local(&episode.EpisodeURL) {
+            self.notify(String::from("Files on this computer can only be played here"));
+            return;
+        }
+        let Some(browser) = discovery::browse() else {
+            self.notify(String::from("Other Firewoods can't be looked for on this network"));
+            return;
+        };
+        self.cast_browser = Some(browser);
+        self.cast_token = token;
+        self.cast_picker = Some(StatefulList::with_items(Vec::new()));
+    }
+
+    // the list as the browser hears of more, the same one kept selected
+    fn check_cast_peers(&mut self) {
+        let (Some(browser), Some(picker)) = (self.cast_browser.as_mut(), self.cast_picker.as_mut()) else {
+            return;
+        };
+        if !browser.poll() {
+            return;
+        }
+        let selected = picker
+            .selected()
+            .and_then(|i| picker.items().get(i))
+            .map(|peer| peer.fullname.clone());
+        let mut peers = StatefulList::with_items(browser.peers().to_vec());
+        match peers.items().iter().position(|peer| Some(&peer.fullname) == selected.as_ref()) {
+            Some(i) => peers.select(i),
+            None => peers.next(),
+        }
+        *picker = peers;
+    }
+
+    /// Play what's playing here on the Firewood selected in the list, from
+    /// where it's got to, and pause here once it's started there.
+    pub fn cast_to_selected(&mut self) {
+        let peer = self
+            .cast_picker
+            .as_ref()
+            .and_then(|picker| picker.selected().and_then(|i| picker.items().get(i).cloned()));
+        let (Some(peer), Some(episode)) = (peer, self.music_handle.playing().cloned()) else {
+            return;
+        };
+        self.close_popup();
+        if self.cast_check.is_some() {
+            return;
+        }
+        let position = self.music_handle.time_played();
+        let token = self.cast_token.take();
+        let (tx, rx) = oneshot::channel();
+        self.cast_check = Some(rx);
+        tokio::spawn(async move {
+            let result = cast::cast(&peer, token.as_deref(), &episode, position).await;
+            let _ = tx.send(result.map(|_| (peer.name, position)).map_err(|e| e.to_string()));
+        });
+    }
+
+    fn check_cast(&mut self) {
+        let Some(check) = self.cast_check.as_mut() else {
+            return;
+        };
+        let cast = match check.try_recv() {
+            Ok(cast) => cast,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.cast_check = None;
+                return;
+            }
+        };
+        self.cast_check = None;
+
+        match cast {
+            Ok((name, position)) => {
+                if !self.music_handle.is_paused() {
+                    self.music_handle.play_pause();
+                }
+                self.notify(format!("Playing on {} from {}", name, format::clock(position.into())));
+            }
+            Err(e) => self.notify(format!("Couldn't cast: {}", e)),
+        }
+    }
+
+    /// Search everything there is to go to at once.
+    pub fn open_palette(&mut self) {
+        self.palette = Some(Palette::new(self.palette_entries()));
+    }
+
+    // what's loaded changed under the palette open
+    fn refresh_palette(&mut self) {
+        if self.palette.is_some() {
+            let entries = self.palette_entries();
+            if let Some(palette) = self.palette.as_mut() {
+                palette.set_entries(entries);
+            }
+        }
+    }
+
+    // in the order they're listed before anything's typed, and that ties
+    // are broken in
+    fn palette_entries(&self) -> Vec<PaletteEntry<Jump>> {
+        let episode_label = |episode: &PinepodsEpisodes| match &episode.PodcastName {
+            Some(podcast) => format!("{} - {}", episode.EpisodeTitle, podcast),
+            None => episode.EpisodeTitle.clone(),
+        };
+        let entry = |kind, label, target| PaletteEntry {
+            kind,
+            label,
+            detail: String::new(),
+            target,
+        };
+        let mut entries = Vec::new();
+
+        for (i, episode) in self.queue_items.items().iter().enumerate() {
+            entries.push(entry("Queue", episode_label(episode), Jump::Queued(i)));
+        }
+        for podcast in &self.podcasts {
+            entries.push(entry("Podcast", podcast.PodcastName.clone(), Jump::Podcast(podcast.clone())));
+        }
+        for term in self.searches.terms() {
+            entries.push(entry("Search", term.clone(), Jump::Search(term.clone())));
+        }
+
+        // the page open first, then the newest of everything else
+        let mut seen = std::collections::HashSet::new();
+        let open = self.browser_items.items().iter().filter_map(|item| match item {
+            BrowserItem::Episode(episode) => Some(episode),
+            _ => None,
+        });
+        let mut indexed: Vec<&PinepodsEpisodes> = self.search_index.episodes().iter().collect();
+        indexed.sort_by_key(|episode| std::cmp::Reverse(gen_funcs::pub_date(&episode.EpisodePubDate)));
+        for episode in open.chain(indexed.into_iter().take(PALETTE_EPISODES)) {
+            if seen.insert(episode.EpisodeURL.clone()) {
+                entries.push(entry("Episode", episode_label(episode), Jump::Episode(episode.clone())));
+            }
+        }
+
+        for job in self.downloads.jobs() {
+            entries.push(entry("Downloading", episode_label(&job.episode), Jump::Downloading));
+        }
+        for kept in self.local_store.kept() {
+            entries.push(entry("Kept", episode_label(&kept.episode), Jump::Kept(kept.clone())));
+        }
+
+        for (label, mode) in [
+            ("Go To The Podcasts", InputMode::Browser),
+            ("Go To The Queue", InputMode::Queue),
+            ("Go To The Controls Tab, For Settings", InputMode::Controls),
+        ] {
+            entries.push(entry("Command", label.to_string(), Jump::Go(mode)));
+        }
+        for row in &self.control_table.items {
+            let (keys, description) = (row[0], row[1]);
+            let (description, scope) = match description.rsplit_once(" (") {
+                Some((description, scope)) => (description, Some(scope.trim_end_matches(')'))),
+                None => (description, None),
+            };
+            let mode = match scope {
+                Some("Controls Tab") => Some(InputMode::Controls),
+                Some("Queue") => Some(InputMode::Queue),
+                Some("Browser" | "Podcasts" | "Episodes" | "Podcasts / Episodes" | "Podcast Rail") => {
+                    Some(InputMode::Browser)
+                }
+                // keys for popups, which aren't open
+                Some("Details" | "Listening Plan" | "Kept On This Computer") => continue,
+                _ => None,
+            };
+            let Some(key) = palette::key(keys) else {
+                continue;
+            };
+            entries.push(PaletteEntry {
+                kind: "Command",
+                label: description.to_string(),
+                detail: keys.to_string(),
+                target: Jump::Command(mode, key),
+            });
+        }
+        entries
+    }
+
+    /// Go to what's picked out in the palette, or play it. A command comes
+    /// back as its key, to be handled as if it had been pressed.
+    pub async fn jump_from_palette(&mut self) -> Option<KeyCode> {
+        let jump = self.palette.take()?.chosen()?;
+        match jump {
+            Jump::Podcast(podcast) => {
+                self.go_to(InputMode::Browser);
+                self.open_podcast(&podcast).await;
+                self.browser_items.next();
+            }
+            Jump::Search(term) => {
+                self.go_to(InputMode::Browser);
+                self.open_search(term);
+            }
+            Jump::Episode(episode) => {
+                self.music_handle.play(&episode);
+                self.playing_from = None;
+            }
+            Jump::Queued(i) => {
+                self.go_to(InputMode::Queue);
+                self.queue_items.select_item(i);
+            }
+            Jump::Kept(kept) => self.music_handle.play(&kept.episode),
+            Jump::Downloading => self.open_kept_picker(),
+            Jump::Go(mode) => self.go_to(mode),
+            Jump::Command(mode, key) => {
+                if let Some(mode) = mode {
+                    self.go_to(mode);
+                }
+                return Some(key);
+            }
+        }
+        None
+    }
+
+    /// The tab with that part of the screen, and it in focus.
+    pub fn go_to(&mut self, mode: InputMode) {
+        self.active_tab = match mode {
+            InputMode::Controls => AppTab::Controls,
+            InputMode::Browser | InputMode::Queue => AppTab::Music,
+        };
+        // the one not in focus has nothing selected, as from moving across
+        match mode {
+            InputMode::Queue => {
+                self.browser_items.unselect();
+                if self.queue_items.state().selected().is_none() {
+                    self.queue_items.next();
+                }
+            }
+            InputMode::Browser => {
+                self.queue_items.unselect();
+                if self.browser_items.selected().is_none() {
+                    self.browser_items.next();
+                }
+            }
+            InputMode::Controls => {}
+        }
+        self.input_mode = mode;
+    }
+
+    /// List who's been using the remote control and what for.
+    pub fn open_remote_clients(&mut self) {
+        if self.remote_access.is_none() {
+            self.notify(String::from("The remote control hasn't been on yet, R turns it on"));
+            return;
+        }
+        self.remote_clients = Some(RemoteClients {
+            clients: StatefulList::with_items(Vec::new()),
+            requests: Vec::new(),
+            new_token: None,
+        });
+        self.check_remote_clients();
+    }
+
+    // the lists as of now, the same client staying selected
+    fn check_remote_clients(&mut self) {
+        let (Some(access), Some(view)) = (&self.remote_access, self.remote_clients.as_mut()) else {
+            return;
+        };
+        let selected = view
+            .clients
+            .selected()
+            .and_then(|i| view.clients.items().get(i))
+            .map(|client| client.ip);
+        let clients = access.clients();
+        let position = clients.iter().position(|client| Some(client.ip) == selected);
+        view.clients = StatefulList::with_items(clients);
+        match position {
+            Some(i) => view.clients.select(i),
+            None => view.clients.next(),
+        }
+        view.requests = access.requests();
+    }
+
+    /// Turn the client selected away from now on, or let it back in.
+    pub fn revoke_selected_client(&mut self) {
+        let (Some(access), Some(view)) = (&self.remote_access, &self.remote_clients) else {
+            return;
+        };
+        let Some(client) = view.clients.selected().and_then(|i| view.clients.items().get(i)) else {
+            return;
+        };
+        let ip = client.ip;
+        let message = match access.toggle_revoked(ip) {
+            true => format!("{} can't use the remote control any more this run", ip),
+            false => format!("{} can use the remote control again", ip),
+        };
+        self.check_remote_clients();
+        self.notify(message);
+    }
+
+    /// Follow along with what's playing in its transcript.
+    pub fn open_transcript(&mut self) {
+        let Some(episode) = self.music_handle.playing().filter(|_| !self.music_handle.is_live()) else {
+            self.notify(String::from("Nothing's playing to show the transcript of"));
+            return;
+        };
+        self.transcript = Some(TranscriptView {
+            url: episode.EpisodeURL.clone(),
+            lines: StatefulList::with_items(Vec::new()),
+            loading: true,
+            error: None,
+            follow: true,
+        });
+        self.start_transcript();
+    }
+
+    // fetch the transcript of what's playing, for the view open
+    fn start_transcript(&mut self) {
+        if self.data_saver {
+            if let Some(view) = self.transcript.as_mut() {
+                view.loading = false;
+                view.error = Some(String::from("Transcripts aren't fetched with data saver on"));
+            }
+            return;
+        }
+        let Some(episode) = self.music_handle.playing() else {
+            return;
+        };
+        let (url, title) = (episode.EpisodeURL.clone(), episode.EpisodeTitle.clone());
+        let Some(feed_url) = self.feed_url_of(episode) else {
+            if let Some(view) = self.transcript.as_mut() {
+                view.loading = false;
+                view.error = Some(String::from("Only subscribed podcasts' transcripts can be found"));
+            }
+            return;
+        };
+        let cached = self
+            .feeds
+            .get(&feed_url)
+            .map(|feed| feed.item(&url, &title).map(|item| item.transcripts.clone()).unwrap_or_default());
+
+        let (tx, rx) = oneshot::channel();
+        self.transcript_check = Some(rx);
+        tokio::spawn(async move {
+            let found = match cached {
+                Some(found) => Ok(found),
+                None => feed::fetch(&feed_url)
+                    .await
+                    .map(|feed| feed.item(&url, &title).map(|item| item.transcripts.clone()).unwrap_or_default()),
+            };
+            let result = match found {
+                Ok(found) => transcripts::fetch(&found).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = &result {
+                debug!("Unable to fetch the transcript of {}: {:?}", url, e);
+            }
+            let _ = tx.send((url, result.map_err(|e| e.to_string())));
+        });
+    }
+
+    // the transcript coming in, or the next episode's once that starts, and
+    // the line being said picked out
+    fn check_transcript(&mut self) {
+        if let Some(check) = self.transcript_check.as_mut() {
+            match check.try_recv() {
+                Ok((url, result)) => {
+                    self.transcript_check = None;
+                    if let Some(view) = self.transcript.as_mut().filter(|view| view.url == url) {
+                        view.loading = false;
+                        match result {
+                            Ok(lines) => view.lines = StatefulList::with_items(lines),
+                            Err(e) => view.error = Some(e),
+                        }
+                    }
+                }
+                Err(oneshot::error::TryRecvError::Empty) => return,
+                Err(oneshot::error::TryRecvError::Closed) => self.transcript_check = None,
+            }
+        }
+
+        let Some(playing) = self.music_handle.playing().map(|episode| episode.EpisodeURL.clone()) else {
+            return;
+        };
+        let now = f64::from(self.music_handle.time_played());
+        let Some(view) = self.transcript.as_mut() else {
+            return;
+        };
+        if view.url != playing {
+            *view = TranscriptView {
+                url: playing,
+                lines: StatefulList::with_items(Vec::new()),
+                loading: true,
+                error: None,
+                follow: true,
+            };
+            self.start_transcript();
+            return;
+        }
+        if !view.follow {
+            return;
+        }
+        let said = view.lines.items().iter().rposition(|line| line.start <= now);
+        if let Some(i) = said.filter(|i| view.lines.selected() != Some(*i)) {
+            view.lines.select(i);
+        }
+    }
+
+    /// Pick out the line `delta` lines along, which stops following along.
+    pub fn move_in_transcript(&mut self, motion: Motion) {
+        if let Some(view) = self.transcript.as_mut() {
+            view.follow = false;
+            motion.apply(&mut view.lines);
+        }
+    }
+
+    /// Play on from the line picked out, following along again.
+    pub fn play_from_transcript(&mut self) {
+        let Some(view) = self.transcript.as_mut() else {
+            return;
+        };
+        let Some(line) = view.lines.selected().and_then(|i| view.lines.items().get(i)) else {
+            return;
+        };
+        let start = line.start.max(0.0) as u16;
+        view.follow = true;
+        self.music_handle.seek_to(start);
+    }
+
+    pub fn follow_transcript(&mut self) {
+        if let Some(view) = self.transcript.as_mut() {
+            view.follow = true;
+        }
+    }
+
+    // playback that's stopped moving starts again on a fresh audio output,
+    // which only needs mentioning when that doesn't work either
+    fn check_playback(&mut self) {
+        if let Some(Err(e)) = self.music_handle.watchdog() {
+            self.notify(format!("Playback is stuck and the audio output couldn't be opened again: {}", e));
+        }
+    }
+
+    // look the chapters up whenever a different episode starts
+    fn check_chapters(&mut self) {
+        if let Some(check) = self.chapter_check.as_mut() {
+            match check.try_recv() {
+                Ok(chapters) => self.chapters = Some(chapters),
+                Err(oneshot::error::TryRecvError::Empty) => return,
+                Err(oneshot::error::TryRecvError::Closed) => {}
+            }
+            self.chapter_check = None;
+        }
+        // without a chapters file there may be chapters tagged into the file
+        if let Some((url, chapters)) = self.chapters.as_mut() {
+            if chapters.is_empty() {
+                if let Some(embedded) = self.music_handle.embedded_chapters(url) {
+                    *chapters = embedded;
+                }
+            }
+        }
+
+        let Some(episode) = self.music_handle.playing() else {
+            return;
+        };
+        if self.chapters.as_ref().is_some_and(|(url, _)| *url == episode.EpisodeURL) {
+            return;
+        }
+        let url = episode.EpisodeURL.clone();
+        let title = episode.EpisodeTitle.clone();
+        let Some(feed_url) = self.feed_url_of(episode) else {
+            self.chapters = Some((url, Vec::new()));
+            return;
+        };
+        // the feed is usually already here from picking a quality
+        let cached = self
+            .feeds
+            .get(&feed_url)
+            .map(|feed| feed.item(&url, &title).and_then(|item| item.chapters.clone()));
+
+        let (tx, rx) = oneshot::channel();
+        self.chapter_check = Some(rx);
+        tokio::spawn(async move {
+            let chapters_url = match cached {
+                Some(chapters_url) => chapters_url,
+                None => feed::fetch(&feed_url)
+                    .await
+                    .ok()
+                    .and_then(|feed| feed.item(&url, &title)?.chapters.clone()),
+            };
+            let chapters = match chapters_url {
+                Some(chapters_url) => feed::chapters(&chapters_url).await.unwrap_or_else(|e| {
+                    debug!("Unable to fetch chapters: {:?}", e);
+                    Vec::new()
+                }),
+                None => Vec::new(),
+            };
+            let _ = tx.send((url, chapters));
+        });
+    }
+
+    /// Chapters of the episode playing, if it has any.
+    pub fn chapters(&self) -> &[Chapter] {
+        match (&self.chapters, self.music_handle.playing()) {
+            (Some((url, chapters)), Some(episode)) if *url == episode.EpisodeURL => chapters,
+            _ => &[],
+        }
+    }
+
+    pub fn current_chapter(&self) -> Option<&Chapter> {
+        let now = f64::from(self.music_handle.time_played());
+        self.chapters().iter().rev().find(|chapter| chapter.start <= now)
+    }
+
+    /// Jump to the start of the next chapter, or back to the start of this
+    /// one, or of the one before when it's only just started.
+    pub fn jump_chapter(&mut self, forward: bool) {
+        let chapters = self.chapters();
+        if chapters.is_empty() {
+            return;
+        }
+        let now = f64::from(self.music_handle.time_played());
+        let target = match forward {
+            true => chapters.iter().map(|chapter| chapter.start).find(|start| *start > now + 0.5),
+            false => Some(
+                chapters
+                    .iter()
+                    .map(|chapter| chapter.start)
+                    .rev()
+                    .find(|start| *start < now - 3.0)
+                    .unwrap_or(0.0),
+            ),
+        };
+        if let Some(target) = target {
+            self.seek_by((target - now).round() as i64);
+        }
+    }
+
+    pub fn open_chapter_picker(&mut self) {
+        let mut picker = StatefulList::with_items(self.chapters().to_vec());
+        let now = f64::from(self.music_handle.time_played());
+        match picker.items().iter().rposition(|chapter| chapter.start <= now) {
+            Some(i) => picker.select(i),
+            None => picker.next(),
+        }
+        self.chapter_picker = Some(picker);
+    }
+
+    pub fn open_user_picker(&mut self) {
+        let mut picker = StatefulList::with_items(Profiles::load().names());
+        let active = profiles::active();
+        match picker.items().iter().position(|name| *name == active) {
+            Some(i) => picker.select(i),
+            None => picker.next(),
+        }
+        self.user_picker = Some(picker);
+    }
+
+    /// Switch to the user selected in the list, once they've given their PIN
+    /// if they have one.
+    pub fn switch_to_selected_user(&mut self) {
+        let Some(name) = self
+            .user_picker
+            .take()
+            .and_then(|picker| picker.selected().and_then(|i| picker.items().get(i).cloned()))
+        else {
+            return;
+        };
+        if name == profiles::active() {
+            return;
+        }
+        if Profiles::load().has_pin(&name) {
+            self.switching_to = Some(name);
+            self.prompt(Prompt::UserPin, String::new());
+        } else {
+            self.user_switch = Some(name);
+        }
+    }
+
+    pub fn enter_pin(&mut self, pin: &str) {
+        let Some(name) = self.switching_to.take() else {
+            return;
+        };
+        match Profiles::load().unlocks(&name, pin) {
+            true => self.user_switch = Some(name),
+            false => self.notify(format!("That isn't {}'s PIN", name)),
+        }
+    }
+
+    /// Ask for `pin` before switching to the active user, or with an empty one
+    /// stop asking.
+    pub fn set_pin(&mut self, pin: &str) {
+        Profiles::load().set_pin(&profiles::active(), pin);
+        self.notify(String::from(match pin.is_empty() {
+            true => "Switching to you no longer needs a PIN",
+            false => "Switching to you needs this PIN from now on",
+        }));
+    }
+
+    /// Jump to the chapter selected in the list.
+    pub fn play_chapter(&mut self) {
+        let Some(start) = self
+            .chapter_picker
+            .take()
+            .and_then(|picker| picker.selected().and_then(|i| picker.items().get(i).map(|chapter| chapter.start)))
+        else {
+            return;
+        };
+        let now = f64::from(self.music_handle.time_played());
+        self.seek_by((start - now).round() as i64);
+    }
+
+    fn start_stats_refresh(&mut self) {
+        if self.stats_check.is_some() {
+            return;
+        }
+        let pinepods_values = self.pinepods_values.lock().unwrap().clone();
+        let (tx, rx) = oneshot::channel();
+        self.stats_check = Some(rx);
+        tokio::spawn(async move {
+            let stats = match pinepods_values.user_history().await {
+                Ok(history) => Stats::from_history(&history, chrono::Local::now().date_naive()),
+                Err(e) => {
+                    debug!("Unable to fetch listening history: {:?}", e);
+                    None
+                }
+            };
+            let _ = tx.send(stats);
+        });
+    }
+
+    // the history again, as the podcast's plays are only a part of it
+    fn start_podcast_stats(&mut self, podcast: &str) {
+        let podcast = podcast.to_string();
+        let pinepods_values = self.pinepods_values.lock().unwrap().clone();
+        let (tx, rx) = oneshot::channel();
+        self.podcast_stats_check = Some(rx);
+        tokio::spawn(async move {
+            let stats = match pinepods_values.user_history().await {
+                Ok(history) => PodcastStats::from_history(&history, &podcast, chrono::Local::now().date_naive()),
+                Err(e) => {
+                    debug!("Unable to fetch listening history: {:?}", e);
+                    None
+                }
+            };
+            let _ = tx.send((podcast, stats));
+        });
+    }
+
+    fn check_podcast_stats(&mut self) {
+        let Some(check) = self.podcast_stats_check.as_mut() else {
+            return;
+        };
+        match check.try_recv() {
+            Ok((podcast, stats)) => {
+                if let Some(detail) = self.podcast_detail.as_mut().filter(|detail| detail.name == podcast) {
+                    detail.stats = stats;
+                }
+            }
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {}
+        }
+        self.podcast_stats_check = None;
+    }
+
+    // a failed fetch hides the stats rather than showing old ones
+    fn check_stats(&mut self) {
+        let Some(check) = self.stats_check.as_mut() else {
+            return;
+        };
+        match check.try_recv() {
+            Ok(stats) => self.stats = stats,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {}
+        }
+        self.stats_check = None;
+    }
+
+    // pick up the saved searches if they've run, and show the new results if
+    // one of them is open
+    fn check_searches(&mut self) {
+        let Some(check) = self.search_check.as_mut() else {
+            return;
+        };
+        match check.try_recv() {
+            Ok(SearchRefresh {
+                results,
+                unplayed: counts,
+                episodes,
+            }) => {
+                self.search_results = results;
+                self.search_index.rebuild(&episodes);
+                // a search that isn't saved is looked up again in the new index
+                if let Some(term) = self.search_term().map(String::from) {
+                    if !self.search_results.contains_key(&term) {
+                        let found = self.search_index.search(&term);
+                        self.search_results.insert(term, found);
+                    }
+                }
+                // podcasts with nothing left unplayed don't show up in the counts
+                self.unplayed = self
+                    .podcasts
+                    .iter()
+                    .map(|podcast| {
+                        let count = counts.get(&podcast.PodcastName).copied().unwrap_or(0);
+                        (podcast.PodcastName.clone(), count)
+                    })
+                    .collect();
+            }
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => {}
+        }
+        self.search_check = None;
+
+        let ContentState::SearchMode { term } = &self.content_state else {
+            return;
+        };
+        self.loading = None;
+        let items = self.search_items(term);
+        self.replace_browser_items(items);
+    }
+
+    // swap in a finished page refresh, if the page it's for is still showing,
+    // keeping the same item selected
+    fn check_refreshed(&mut self) {
+        let Some(refreshed) = self.refreshed.as_mut() else {
+            return;
+        };
+        let refreshed = match refreshed.try_recv() {
+            Ok(refreshed) => refreshed,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            // it failed, the banner says why
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.refreshed = None;
+                self.loading = None;
+                return;
+            }
+        };
+        self.refreshed = None;
+        let loading = self.loading.take().is_some();
+
+        let items = match (refreshed, &self.content_state) {
+            // an empty list is more likely a hiccup than everything unsubscribed,
+            // unless there was nothing before it either
+            (Refreshed::Podcasts(podcasts), ContentState::PodcastMode { .. }) if !podcasts.is_empty() || loading => {
+                if let Some(changes) = subscription_changes(&podcasts) {
+                    self.note_subscription_changes(changes);
+                }
+                self.subscriptions = subscription_feeds(&podcasts);
+                self.notify_new_episodes(&podcasts);
+                self.cache.set_podcasts(&podcasts);
+                let items = podcast_items(&self.searches, &self.podcast_filter, &podcasts);
+                self.podcasts = podcasts;
+                items
+            }
+            (Refreshed::Episodes(id, mut episodes), ContentState::EpisodeMode { podcast_id, feed_url })
+                if id == *podcast_id =>
+            {
+                let feed_url = feed_url.clone();
+                self.cache.set_episodes(id, &episodes);
+                if let Some(podcast) = self.podcasts.iter().find(|podcast| podcast.PodcastID == id) {
+                    self.unplayed.insert(podcast.PodcastName.clone(), unplayed(&episodes));
+                }
+                let quality = self.quality_of(&feed_url);
+                if quality != Quality::Feed {
+                    self.pick_variants(&feed_url, quality, &mut episodes);
+                }
+                self.order(&mut episodes);
+                episodes.into_iter().map(BrowserItem::Episode).collect()
+            }
+            // for going back to
+            (Refreshed::Podcasts(podcasts), _) if !podcasts.is_empty() => {
+                self.cache.set_podcasts(&podcasts);
+                return;
+            }
+            _ => return,
+        };
+        self.stale = None;
+        self.replace_browser_items(items);
+        if loading && !self.browser_items.empty() {
+            self.browser_items.first();
+        }
+    }
+
+    // for podcasts set to notify immediately, going by the episode counts
+    fn notify_new_episodes(&mut self, podcasts: &[PinepodsPodcasts]) {
+        let new: Vec<String> = podcasts
+            .iter()
+            .filter(|podcast| self.podcast_flags.get(&podcast.PodcastName).notify == Notify::Immediately)
+            .filter_map(|podcast| {
+                let old = self.podcasts.iter().find(|old| old.PodcastID == podcast.PodcastID)?;
+                match podcast.EpisodeCount.checked_sub(old.EpisodeCount) {
+                    Some(0) | None => None,
+                    Some(1) => Some(format!("New from {}", podcast.PodcastName)),
+                    Some(n) => Some(format!("{} new from {}", n, podcast.PodcastName)),
+                }
+            })
+            .collect();
+        if !new.is_empty() {
+            info!("{}", new.join(", "));
+            self.desktop.send(Kind::Episodes, "New episodes", &new.join("\n"));
+            self.notify(new.join(", "));
+        }
+    }
+
+    // keeps the same item selected, wherever it is in the new list
+    fn replace_browser_items(&mut self, items: Vec<BrowserItem>) {
+        let mut list = StatefulList::with_items(items);
+        let selected = self.browser_items.selected().map(|_| self.browser_items.item());
+        let position = list.items().iter().position(|item| match (selected, item) {
+            (Some(BrowserItem::Podcast(a)), BrowserItem::Podcast(b)) => a.PodcastID == b.PodcastID,
+            (Some(BrowserItem::Episode(a)), BrowserItem::Episode(b)) => {
+                a.EpisodeID == b.EpisodeID && a.EpisodeTitle == b.EpisodeTitle
+            }
+            (Some(BrowserItem::Search(a)), BrowserItem::Search(b)) => a == b,
+            (Some(BrowserItem::More(_)), BrowserItem::More(_)) => true,
+            _ => false,
+        });
+        match position {
+            Some(i) => list.select(i),
+            None if selected.is_some() => list.first(),
+            None => {}
+        }
+        self.browser_items = list;
+        self.refresh_palette();
+    }
+
+    // notify about anything that has gone live since the last check
+    fn update_live_shows(&mut self, mut shows: Vec<LiveShow>) {
+        shows.sort_by(|a, b| {
+            (a.item.status != LiveStatus::Live, &a.item.start)
+                .cmp(&(b.item.status != LiveStatus::Live, &b.item.start))
+        });
+
+        let was_live = |show: &LiveShow| {
+            self.live_shows.iter().any(|old| {
+                old.item.status == LiveStatus::Live
+                    && old.podcast == show.podcast
+                    && old.item.title == show.item.title
+            })
+        };
+        let started: Vec<String> = shows
+            .iter()
+            .filter(|show| show.item.status == LiveStatus::Live && !was_live(show))
+            .filter(|show| self.podcast_flags.get(&show.podcast).notify != Notify::Never)
+            .map(|show| format!("{} is live: {}", show.podcast, show.item.title))
+            .collect();
+        for message in started {
+            info!("{}", message);
+            self.desktop.send(Kind::Live, "Live now", &message);
+            self.notify(message);
+        }
+        self.live_shows = shows;
+    }
+
+    /// Listen to the first show that's live right now.
+    pub fn play_live(&mut self) {
+        let show = self
+            .live_shows
+            .iter()
+            .find(|show| show.item.status == LiveStatus::Live && show.item.url.is_some());
+        if let Some(show) = show {
+            let title = format!("{} - {}", show.podcast, show.item.title);
+            let url = show.item.url.clone().unwrap_or_default();
+            self.music_handle.play_live(&title, &url);
+        }
+    }
+
+    pub fn toggle_recording(&mut self) {
+        if self.music_handle.is_recording() {
+            self.music_handle.stop_recording();
+            self.notify(String::from("Recording stopped"));
+            return;
+        }
+        match self.music_handle.start_recording() {
+            Ok(path) => self.notify(format!("Recording to {}", path.display())),
+            Err(e) => self.notify(format!("Not recording: {}", e)),
+        }
+    }
+
+    pub fn notify(&mut self, message: String) {
+        self.notification = Some((message, Instant::now()));
+    }
+
+    // the latest notification, if it hasn't timed out yet
+    pub fn notification(&self) -> Option<&str> {
+        self.notification
+            .as_ref()
+            .filter(|(_, shown)| shown.elapsed() < NOTIFICATION_DURATION)
+            .map(|(message, _)| message.as_str())
+    }
+
+    // fetch the next queue item while the current one plays
+    pub fn precache_next(&mut self, limit_mb: u32) {
+        // wait for the playing episode to finish downloading first
+        if self.data_saver || limit_mb == 0 || self.music_handle.sink_empty() {
+            return;
+        }
+        if let Some(next) = self.queue_items.up_next() {
+            self.music_handle.precache(next, limit_mb);
+        }
+    }
+
+    // if playing and
+    pub fn song_progress(&mut self) -> u16 {
+        // a live stream is always at its end
+        if self.music_handle.is_live() && !self.music_handle.sink_empty() {
+            return 100;
+        }
+        let progress = || {
+            if self.music_handle.song_length() == 0 {
+                return 0;
+            }
+            let percentage =
+                (self.music_handle.time_played() * 100) / self.music_handle.song_length();
+            if percentage >= 100 {
+                100
+            } else {
+                percentage
+            }
+        };
+
+        // edge case if nothing queued or playing
+        if self.music_handle.sink_empty() && self.queue_items.is_empty() {
+            0
+
+            // if something playing, calculate progress
+        } else if !self.music_handle.sink_empty() {
+            progress()
+            // if nothing playing keep rolling
+        } else {
+            self.auto_play();
+            0
+        }
+    }
+
+
+    // get file path
+    pub fn selected_item(&self) -> Option<SelectedItem> {
+        if self.browser_items.empty() {
+            return None;
+        }
         match self.browser_items.item() {
             BrowserItem::Podcast(podcast) => Some(SelectedItem::Podcast(podcast)),
             BrowserItem::Episode(episode) => Some(SelectedItem::Episode(episode)),
+            BrowserItem::Search(_) | BrowserItem::More(_) => None,
         }
     }
 }
\ No newline at end of file
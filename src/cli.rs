@@ -0,0 +1,291 @@
+use clap::{Parser, Subcommand};
+
+/// A light terminal client for Pinepods.
+///
+/// Every option can also be set with the FIREWOOD_* environment variable shown
+/// next to it. Flags win over environment variables, which win over
+/// ~/.config/kronos/config.toml.
+#[derive(Parser, Debug, Default)]
+#[command(version)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub action: Option<Action>,
+
+    /// Pinepods server to use instead of the saved one, e.g. https://pinepods.online
+    #[arg(long, env = "FIREWOOD_SERVER_URL")]
+    pub server_url: Option<String>,
+
+    /// API key to use instead of the saved one
+    #[arg(long, env = "FIREWOOD_API_KEY", hide_env_values = true)]
+    pub api_key: Option<String>,
+
+    /// Start as this user of the computer, setting up a login for them first if
+    /// they're new, each with their own settings, queue and kept episodes
+    #[arg(long, env = "FIREWOOD_USER")]
+    pub user: Option<String>,
+
+    /// Log filter, same syntax as RUST_LOG (e.g. "debug" or "pinepods_firewood=info")
+    #[arg(long, env = "FIREWOOD_LOG_LEVEL")]
+    pub log_level: Option<String>,
+
+    /// Text color: a name, "r, g, b" or "#rrggbb"
+    #[arg(long, env = "FIREWOOD_THEME_FOREGROUND")]
+    pub theme_foreground: Option<String>,
+
+    /// Background color
+    #[arg(long, env = "FIREWOOD_THEME_BACKGROUND")]
+    pub theme_background: Option<String>,
+
+    /// Text color of the selected item
+    #[arg(long, env = "FIREWOOD_THEME_HIGHLIGHT_FOREGROUND")]
+    pub theme_highlight_foreground: Option<String>,
+
+    /// Background color of the selected item
+    #[arg(long, env = "FIREWOOD_THEME_HIGHLIGHT_BACKGROUND")]
+    pub theme_highlight_background: Option<String>,
+
+    /// Colors the terminal can show: auto, truecolor, 256, 16 or none.
+    /// Auto goes by COLORTERM, TERM and NO_COLOR
+    #[arg(long, env = "FIREWOOD_COLOR_DEPTH")]
+    pub color_depth: Option<String>,
+
+    /// Characters to draw with: unicode, or ascii for terminals and fonts
+    /// where borders come out misaligned
+    #[arg(long, env = "FIREWOOD_GLYPHS")]
+    pub glyphs: Option<String>,
+
+    /// How dates are written, a strftime format like %e %b %Y, or auto for
+    /// the locale's usual one
+    #[arg(long, env = "FIREWOOD_DATE_FORMAT")]
+    pub date_format: Option<String>,
+
+    /// Height of the now playing bar, as a percentage of the queue column
+    #[arg(long, env = "FIREWOOD_PROGRESS_BAR")]
+    pub progress_bar: Option<u16>,
+
+    /// Where the now playing bar goes in the queue column: bottom or top
+    #[arg(long, env = "FIREWOOD_PLAYER")]
+    pub player: Option<String>,
+
+    /// Hide the now playing bar while nothing is playing
+    #[arg(long, env = "FIREWOOD_PLAYER_HIDE_IDLE")]
+    pub player_hide_idle: Option<bool>,
+
+    /// Shrink the now playing bar to a single line
+    #[arg(long, env = "FIREWOOD_PLAYER_COMPACT")]
+    pub player_compact: Option<bool>,
+
+    /// Keep the podcast list down the side while looking at a podcast's episodes
+    #[arg(long, env = "FIREWOOD_PODCAST_RAIL")]
+    pub podcast_rail: Option<bool>,
+
+    /// Show the queue under a header for each run of episodes from the same
+    /// podcast, each foldable with za
+    #[arg(long, env = "FIREWOOD_GROUP_QUEUE")]
+    pub group_queue: Option<bool>,
+
+    /// A bar of the keys for what has focus at the bottom of the screen:
+    /// hidden, compact (one line) or full
+    #[arg(long, env = "FIREWOOD_HINTS")]
+    pub hints: Option<String>,
+
+    /// Click on tabs, list rows and the progress bar, and scroll with the
+    /// wheel. The terminal's own selecting of text then needs Shift held
+    #[arg(long, env = "FIREWOOD_MOUSE")]
+    pub mouse: Option<bool>,
+
+    /// How much of the next episode to download ahead of time in MB, the
+    /// beginning of a bigger one, 0 turns it off
+    #[arg(long, env = "FIREWOOD_PRECACHE_MB")]
+    pub precache_mb: Option<u32>,
+
+    /// Start with data saver on or off
+    #[arg(long, env = "FIREWOOD_DATA_SAVER")]
+    pub data_saver: Option<bool>,
+
+    /// Which version of an episode to play when its feed offers several:
+    /// feed, lowest or highest bitrate
+    #[arg(long, env = "FIREWOOD_QUALITY")]
+    pub quality: Option<String>,
+
+    /// Record live streams to disk while they play
+    #[arg(long, env = "FIREWOOD_RECORD_LIVE")]
+    pub record_live: Option<bool>,
+
+    /// Keep the queue items above one played from partway down, passed over,
+    /// instead of removing them
+    #[arg(long, env = "FIREWOOD_KEEP_EARLIER")]
+    pub keep_earlier: Option<bool>,
+
+    /// Start the next in the queue when an episode finishes, and the server's
+    /// queue once that's empty, or stop after each one
+    #[arg(long, env = "FIREWOOD_CONTINUOUS")]
+    pub continuous: Option<bool>,
+
+    /// Playback speed to start at, 0.5 to 3.0
+    #[arg(long, env = "FIREWOOD_SPEED")]
+    pub speed: Option<f32>,
+
+    /// What plays between one episode and the next when the queue carries
+    /// on: off, tone or voice ("Up next" and the title, spoken)
+    #[arg(long, env = "FIREWOOD_ANNOUNCE")]
+    pub announce: Option<String>,
+
+    /// The audio output to play on, by name, instead of the system's default
+    #[arg(long, env = "FIREWOOD_AUDIO_DEVICE")]
+    pub audio_device: Option<String>,
+
+    /// Minutes between background refreshes of the podcast list and live
+    /// shows, 0 for never
+    #[arg(long, env = "FIREWOOD_REFRESH_PODCASTS")]
+    pub refresh_podcasts: Option<u64>,
+
+    /// Minutes between background refreshes of the episode list, 0 for never
+    #[arg(long, env = "FIREWOOD_REFRESH_EPISODES")]
+    pub refresh_episodes: Option<u64>,
+
+    /// Hours between backups of the subscriptions, queue and settings, 0 for
+    /// only before bulk changes
+    #[arg(long, env = "FIREWOOD_BACKUP_HOURS")]
+    pub backup_hours: Option<u64>,
+
+    /// How many backups to keep, the oldest go first
+    #[arg(long, env = "FIREWOOD_BACKUP_KEEP")]
+    pub backup_keep: Option<usize>,
+
+    /// A command to fetch kept episodes with instead, e.g. "curl -sfo {output}
+    /// {url}", with {url}, {output}, {dir} and {file} filled in
+    #[arg(long, env = "FIREWOOD_DOWNLOAD_COMMAND")]
+    pub download_command: Option<String>,
+
+    /// Trim the silence off either end of kept episodes once they're
+    /// downloaded, into a copy beside the original, with ffmpeg
+    #[arg(long, env = "FIREWOOD_TRIM_SILENCE")]
+    pub trim_silence: Option<bool>,
+
+    /// Even out the loudness of kept episodes the same way
+    #[arg(long, env = "FIREWOOD_NORMALIZE_LOUDNESS")]
+    pub normalize_loudness: Option<bool>,
+
+    /// Play the processed copy of a kept episode rather than the original
+    #[arg(long, env = "FIREWOOD_PLAY_PROCESSED")]
+    pub play_processed: Option<bool>,
+
+    /// What to show desktop notifications for: episodes, downloads and live,
+    /// comma separated, or off
+    #[arg(long, env = "FIREWOOD_DESKTOP_NOTIFICATIONS", value_delimiter = ',')]
+    pub desktop_notifications: Vec<String>,
+
+    /// Where to look for new podcasts first: itunes or podcastindex
+    #[arg(long, env = "FIREWOOD_SEARCH_PROVIDER")]
+    pub search_provider: Option<String>,
+
+    /// PodcastIndex API key, searched through Pinepods without one
+    #[arg(long, env = "FIREWOOD_PODCASTINDEX_KEY")]
+    pub podcastindex_key: Option<String>,
+
+    /// The secret that goes with the PodcastIndex API key
+    #[arg(long, env = "FIREWOOD_PODCASTINDEX_SECRET")]
+    pub podcastindex_secret: Option<String>,
+
+    /// How much newer episodes count for in smart sort
+    #[arg(long, env = "FIREWOOD_SMART_RECENCY")]
+    pub smart_recency: Option<f64>,
+
+    /// How much fitting the time there is to listen counts for in smart sort
+    #[arg(long, env = "FIREWOOD_SMART_FIT")]
+    pub smart_fit: Option<f64>,
+
+    /// How much podcast priorities count for in smart sort
+    #[arg(long, env = "FIREWOOD_SMART_PRIORITY")]
+    pub smart_priority: Option<f64>,
+
+    /// Minutes there are to listen, for smart sort and the listening plan
+    #[arg(long, env = "FIREWOOD_LISTENING_MINUTES")]
+    pub listening_minutes: Option<u32>,
+
+    /// Run headless: no terminal ui, only the player driven through the remote
+    /// control server, which is turned on regardless of --remote
+    #[arg(long, env = "FIREWOOD_DAEMON")]
+    pub daemon: bool,
+
+    /// Add the episodes of a shared episode link, playlist link or playlist id
+    /// to the queue on startup
+    #[arg(long)]
+    pub import: Option<String>,
+
+    /// Turn the remote control server on or off
+    #[arg(long, env = "FIREWOOD_REMOTE")]
+    pub remote: Option<bool>,
+
+    /// Turn the local remote control socket (named pipe on Windows) on or off
+    #[arg(long, env = "FIREWOOD_REMOTE_SOCKET")]
+    pub remote_socket: Option<bool>,
+
+    /// Address for the remote control server to listen on
+    #[arg(long, env = "FIREWOOD_REMOTE_BIND")]
+    pub remote_bind: Option<String>,
+
+    /// Port for the remote control server
+    #[arg(long, env = "FIREWOOD_REMOTE_PORT")]
+    pub remote_port: Option<u16>,
+
+    /// Bearer token remote control requests must carry, empty for none
+    #[arg(long, env = "FIREWOOD_REMOTE_TOKEN", hide_env_values = true)]
+    pub remote_token: Option<String>,
+
+    /// URL to POST the player status to whenever playback changes, can be
+    /// given more than once (comma separated in the variable)
+    #[arg(long, env = "FIREWOOD_WEBHOOKS", value_delimiter = ',')]
+    pub webhook: Vec<String>,
+
+    /// Send anonymous crash and error reports (off unless turned on)
+    #[arg(long, env = "FIREWOOD_TELEMETRY")]
+    pub telemetry: Option<bool>,
+
+    /// Where reports are sent, a self-hosted endpoint taking a JSON array by POST
+    #[arg(long, env = "FIREWOOD_TELEMETRY_URL")]
+    pub telemetry_url: Option<String>,
+}
+
+/// Do one thing and exit instead of starting the terminal ui. The player is
+/// the one in the Firewood already running here, if there is one.
+#[derive(Subcommand, Debug, Clone)]
+pub enum Action {
+    /// Play an episode, by its id on the server. With no Firewood running, a
+    /// headless player is started for it, as --daemon would
+    Play { episode_id: i64 },
+
+    /// Pause what's playing
+    Pause,
+
+    /// Carry on with what's paused
+    Resume,
+
+    /// Pause, or carry on if paused
+    Toggle,
+
+    /// Skip to the next in the queue
+    Skip,
+
+    /// What's playing, and how much is queued
+    Status,
+
+    /// The queue on the server
+    #[command(subcommand)]
+    Queue(QueueAction),
+
+    /// Subscribe to the podcast at a feed url
+    Subscribe { url: String },
+
+    /// Queue the episode a link is to: its audio file, its page, or a Pinepods
+    /// share link. It goes on the queue of the Firewood running, or the
+    /// server's if there isn't one
+    Add { url: String },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum QueueAction {
+    /// Every episode in it, in order
+    List,
+}
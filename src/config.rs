@@ -1,26 +1,271 @@
+use std::collections::HashMap;
 use std::fs;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
 
+use log::error;
+use pinepods_firewood::announce::Announce;
+use pinepods_firewood::color_depth::{self, ColorDepth};
+use pinepods_firewood::downloads::Processing;
+use pinepods_firewood::feed::Quality;
+use pinepods_firewood::format;
+use pinepods_firewood::glyphs::Glyphs;
+use pinepods_firewood::music_handler::{MAX_SPEED, MIN_SPEED};
+use pinepods_firewood::notifications;
+use pinepods_firewood::podcast_search::{self, Provider};
+use pinepods_firewood::profiles;
+use pinepods_firewood::requests;
+use pinepods_firewood::smart_sort;
 use serde::{Deserialize, Serialize};
 use ratatui::style::Color;
+use ratatui::symbols::border;
+use ratatui::widgets::BorderType;
 
-#[derive(Serialize, Deserialize, Debug)]
+use crate::cli::Cli;
+use crate::remote::access;
+
+// the theme file's name, see Config::theme_path
+const THEME_FILE: &str = "theme.toml";
+
+#[derive(Serialize, Deserialize, Debug, Default)]
 struct Theme {
     foreground: Option<String>,
     background: Option<String>,
     highlight_foreground: Option<String>,
     highlight_background: Option<String>,
+    // "auto", "truecolor", "256", "16" or "none"
+    color_depth: Option<String>,
+    // "unicode" or "ascii"
+    glyphs: Option<String>,
+    // strftime, e.g. "%e %b %Y", or "auto" for the locale's
+    date_format: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 struct Layout {
     progress_bar: Option<u16>,
+    // "bottom" or "top" of the queue column
+    player: Option<String>,
+    player_hide_idle: Option<bool>,
+    player_compact: Option<bool>,
+    // podcasts down the side of the episode list
+    podcast_rail: Option<bool>,
+    // runs of the same podcast in the queue under a header
+    group_queue: Option<bool>,
+    // "hidden", "compact" or "full"
+    hints: Option<String>,
+    // clicking and scrolling, instead of the terminal selecting text
+    mouse: Option<bool>,
+}
+
+/// Where the now playing bar goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerPosition {
+    Bottom,
+    Top,
+}
+
+/// The bar of keys at the bottom of the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hints {
+    Hidden,
+    /// one line, cut off where it runs out of room
+    Compact,
+    /// two lines in a box
+    Full,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct Playback {
+    precache_mb: Option<u32>,
+    data_saver: Option<bool>,
+    quality: Option<String>,
+    record_live: Option<bool>,
+    // playing from partway down the queue keeps the items above, passed
+    // over, instead of removing them
+    keep_earlier: Option<bool>,
+    // start the next in the queue when one finishes, and the server's queue
+    // once that's empty
+    continuous: Option<bool>,
+    // times normal speed, 0.5 to 3.0
+    speed: Option<f32>,
+    // between one episode and the next the queue carries on with: off, tone
+    // or voice
+    announce: Option<String>,
+    // the audio output to play on by name, the system's default if unset
+    audio_device: Option<String>,
+    // podcast name to quality, over the one above
+    podcast_quality: Option<HashMap<String, String>>,
+}
+
+// minutes between background refreshes of each page, 0 for never
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct Refresh {
+    podcasts: Option<u64>,
+    episodes: Option<u64>,
+}
+
+// copies of the subscriptions, queue and settings, see helpers::backups
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct Backups {
+    // hours between them, 0 for only before bulk changes
+    hours: Option<u64>,
+    keep: Option<usize>,
+}
+
+// how kept episodes are fetched, see helpers::downloads
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct Downloads {
+    // run instead of fetching them directly, e.g. "aria2c -c -o {file} -d {dir} {url}"
+    command: Option<String>,
+    // afterwards, with ffmpeg, into a copy beside the original
+    trim_silence: Option<bool>,
+    normalize: Option<bool>,
+    // the processed copy rather than the original, when there is one
+    play_processed: Option<bool>,
+}
+
+// desktop notifications, see helpers::notifications
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct Notifications {
+    // which to send: episodes, downloads and live, none if empty
+    desktop: Option<Vec<String>>,
+}
+
+// finding new podcasts, see helpers::podcast_search
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct PodcastSearch {
+    // "itunes" or "podcastindex", the one searched first
+    provider: Option<String>,
+    // from api.podcastindex.org, PodcastIndex is searched through Pinepods
+    // without them
+    podcastindex_key: Option<String>,
+    podcastindex_secret: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct SmartSort {
+    recency: Option<f64>,
+    fit: Option<f64>,
+    priority: Option<f64>,
+    listening_minutes: Option<u32>,
+    // podcast name to priority
+    podcasts: Option<HashMap<String, f64>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct Remote {
+    enabled: Option<bool>,
+    socket: Option<bool>,
+    bind: Option<String>,
+    port: Option<u16>,
+    token: Option<String>,
+    webhooks: Option<Vec<String>>,
 }
 
-// for tables
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct Telemetry {
+    enabled: Option<bool>,
+    url: Option<String>,
+}
+
+// for tables, each one is a layer: anything left out falls through to the layer below
+#[derive(Serialize, Deserialize, Debug, Default)]
 struct ConfigToml {
     theme: Option<Theme>,
     layout: Option<Layout>,
+    playback: Option<Playback>,
+    refresh: Option<Refresh>,
+    backups: Option<Backups>,
+    downloads: Option<Downloads>,
+    notifications: Option<Notifications>,
+    podcast_search: Option<PodcastSearch>,
+    smart_sort: Option<SmartSort>,
+    remote: Option<Remote>,
+    telemetry: Option<Telemetry>,
+}
+
+// flags and FIREWOOD_* variables, the topmost layer
+impl From<&Cli> for ConfigToml {
+    fn from(cli: &Cli) -> Self {
+        ConfigToml {
+            theme: Some(Theme {
+                foreground: cli.theme_foreground.clone(),
+                background: cli.theme_background.clone(),
+                highlight_foreground: cli.theme_highlight_foreground.clone(),
+                highlight_background: cli.theme_highlight_background.clone(),
+                color_depth: cli.color_depth.clone(),
+                glyphs: cli.glyphs.clone(),
+                date_format: cli.date_format.clone(),
+            }),
+            layout: Some(Layout {
+                progress_bar: cli.progress_bar,
+                player: cli.player.clone(),
+                player_hide_idle: cli.player_hide_idle,
+                player_compact: cli.player_compact,
+                podcast_rail: cli.podcast_rail,
+                group_queue: cli.group_queue,
+                hints: cli.hints.clone(),
+                mouse: cli.mouse,
+            }),
+            playback: Some(Playback {
+                precache_mb: cli.precache_mb,
+                data_saver: cli.data_saver,
+                quality: cli.quality.clone(),
+                record_live: cli.record_live,
+                keep_earlier: cli.keep_earlier,
+                continuous: cli.continuous,
+                speed: cli.speed,
+                announce: cli.announce.clone(),
+                audio_device: cli.audio_device.clone(),
+                podcast_quality: None,
+            }),
+            refresh: Some(Refresh {
+                podcasts: cli.refresh_podcasts,
+                episodes: cli.refresh_episodes,
+            }),
+            backups: Some(Backups {
+                hours: cli.backup_hours,
+                keep: cli.backup_keep,
+            }),
+            downloads: Some(Downloads {
+                command: cli.download_command.clone(),
+                trim_silence: cli.trim_silence,
+                normalize: cli.normalize_loudness,
+                play_processed: cli.play_processed,
+            }),
+            notifications: Some(Notifications {
+                desktop: (!cli.desktop_notifications.is_empty()).then(|| cli.desktop_notifications.clone()),
+            }),
+            podcast_search: Some(PodcastSearch {
+                provider: cli.search_provider.clone(),
+                podcastindex_key: cli.podcastindex_key.clone(),
+                podcastindex_secret: cli.podcastindex_secret.clone(),
+            }),
+            smart_sort: Some(SmartSort {
+                recency: cli.smart_recency,
+                fit: cli.smart_fit,
+                priority: cli.smart_priority,
+                listening_minutes: cli.listening_minutes,
+                podcasts: None,
+            }),
+            remote: Some(Remote {
+                enabled: cli.remote,
+                socket: cli.remote_socket,
+                bind: cli.remote_bind.clone(),
+                port: cli.remote_port,
+                token: cli.remote_token.clone(),
+                webhooks: (!cli.webhook.is_empty()).then(|| cli.webhook.clone()),
+            }),
+            telemetry: Some(Telemetry {
+                enabled: cli.telemetry,
+                url: cli.telemetry_url.clone(),
+            }),
+        }
+    }
 }
 
 // everything
@@ -30,7 +275,56 @@ pub struct Config {
     background: Color,
     highlight_foreground: Color,
     highlight_background: Color,
+    color_depth: ColorDepth,
+    glyphs: Glyphs,
+    date_format: String,
     progress_bar: u16,
+    player: PlayerPosition,
+    player_hide_idle: bool,
+    player_compact: bool,
+    podcast_rail: bool,
+    group_queue: bool,
+    hints: Hints,
+    mouse: bool,
+    precache_mb: u32,
+    data_saver: bool,
+    quality: Quality,
+    record_live: bool,
+    keep_earlier: bool,
+    continuous: bool,
+    speed: f32,
+    announce: Announce,
+    audio_device: Option<String>,
+    podcast_quality: HashMap<String, Quality>,
+    refresh_podcasts: u64,
+    refresh_episodes: u64,
+    backup_hours: u64,
+    backup_keep: usize,
+    download_command: Option<String>,
+    download_processing: Processing,
+    play_processed: bool,
+    desktop_notifications: Vec<notifications::Kind>,
+    search_provider: Provider,
+    podcastindex_key: Option<String>,
+    podcastindex_secret: Option<String>,
+    smart_sort: smart_sort::SmartSort,
+    remote_enabled: bool,
+    remote_socket: bool,
+    remote_bind: IpAddr,
+    remote_port: u16,
+    remote_token: Option<String>,
+    // remote.token set empty on purpose, for no authentication
+    remote_open: bool,
+    webhooks: Vec<String>,
+    telemetry: bool,
+    telemetry_url: Option<String>,
+    // file the settings were read from
+    path: Option<PathBuf>,
+    // problems found while layering, shown as a banner instead of panicking
+    warnings: Vec<String>,
+    // why the file the settings were read from couldn't be, its settings
+    // left out
+    unreadable: Option<String>,
 }
 
 impl Default for Config {
@@ -40,113 +334,542 @@ impl Default for Config {
 }
 
 impl Config {
+    /// Built in defaults with the config file laid over them.
     pub fn new() -> Self {
-        // may want to add more path options later
-        let config_paths = [home::home_dir()
-            .unwrap()
-            .as_path()
-            .join(".config/kronos/config.toml")];
+        let mut config = Self::defaults();
 
-        // placeholder to store config in
-        let mut content: String = "".to_owned();
+        // a user other than the default one can have settings of their own,
+        // or else shares everyone's
+        let config_paths = [
+            (profiles::active() != profiles::DEFAULT)
+                .then(requests::get_app_path)
+                .flatten()
+                .map(|dir| dir.join("config.toml")),
+            home::home_dir().map(|home| home.join(".config/kronos/config.toml")),
+        ];
 
         // for filepaths in above array, check to see if there is a config
-        for config in config_paths {
-            let result: Result<String, std::io::Error> = fs::read_to_string(config);
-
-            if let Ok(file_content) = result {
-                content = file_content;
-                break;
-            }
-        }
-
-        // convert toml file to serialized data
-        let config_toml: ConfigToml = toml::from_str(&content).unwrap_or_else(|_| {
-            // if config file not found, set defaults
-            eprintln!("FAILED TO CREATE CONFIG OBJECT FROM FILE");
-            ConfigToml {
-                theme: None,
-                layout: None,
-            }
-        });
-
-        // match theme
-        let (foreground, background, highlight_foreground, highlight_background) = match config_toml
-            .theme
-        {
-            // 200, 100, 255
-            Some(theme) => {
-                // item, if error
-                let map = |i: Option<String>, s: String| {
-                    let rgb = i.clone();
-                    match i.unwrap_or(s).to_ascii_lowercase().as_ref() {
-                        "black" => Color::Black,
-                        "blue" => Color::Blue,
-                        "green" => Color::Green,
-                        "red" => Color::Red,
-                        "yellow" => Color::Yellow,
-                        "magenta" => Color::Magenta,
-                        "cyan" => Color::Cyan,
-                        "gray" => Color::Gray,
-                        "dark gray" => Color::DarkGray,
-                        "light red" => Color::LightRed,
-                        "light green" => Color::LightGreen,
-                        "light yellow" => Color::LightYellow,
-                        "light blue" => Color::LightBlue,
-                        "light magenta" => Color::LightMagenta,
-                        "light cyan" => Color::LightCyan,
-                        "white" => Color::White,
-                        _ => {
-                            let colors: Vec<u8> = rgb.unwrap()
-                                .split(|i| i == ',')
-                                .map(|i| i.to_string().trim().parse().expect("Couldn't read RGB Values. Make sure each value is between 0 & 255"))
-                                .collect();
-
-                            if colors.len() == 3 {
-                                Color::Rgb(colors[0], colors[1], colors[2])
-                            } else {
-                                eprintln!("Couldn't read RGB Values. Make sure each value is comma seperated");
-                                Color::Black
-                            }
-                        }
-                    }
-                };
-
-                let foreground = map(theme.foreground, "LightCyan".to_string());
-                let background = map(theme.background, "Black".to_string());
-                let hfg = map(theme.highlight_foreground, "Black".to_string());
-                let hbg = map(theme.highlight_background, "Light Cyan".to_string());
+        for path in config_paths.into_iter().flatten() {
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
 
-                (foreground, background, hfg, hbg)
+            // convert toml file to serialized data
+            match toml::from_str::<ConfigToml>(&content) {
+                Ok(layer) => config.apply(layer),
+                Err(e) => {
+                    let problem = format!("{} is not valid: {}", path.display(), e.message());
+                    config.warnings.push(problem.clone());
+                    config.unreadable = Some(problem);
+                }
             }
+            config.path = Some(path);
+            break;
+        }
 
-            None => (
-                Color::LightCyan,
-                Color::Black,
-                Color::Black,
-                Color::LightCyan,
-            ),
-        };
+        if let Some(theme) = config.read_theme_file() {
+            config.apply_theme(theme);
+        }
+        config
+    }
 
-        let progress_bar = match config_toml.layout {
-            Some(i) => i.progress_bar.unwrap_or(35),
-            None => 35,
-        };
+    /// Defaults, then the config file, then environment variables and flags.
+    pub fn load(cli: &Cli) -> Self {
+        let mut config = Self::new();
+        config.apply(cli.into());
+        config
+    }
 
+    pub fn defaults() -> Self {
         Self {
             // quit: quit, // gathered from above
             // play_pause: play_pause,
             // skip: skip,
             // queue_add: queue_add,
             // queue_remove: queue_remove,
-            foreground,
-            background,
-            highlight_foreground,
-            highlight_background,
-            progress_bar,
+            foreground: Color::LightCyan,
+            background: Color::Black,
+            highlight_foreground: Color::Black,
+            highlight_background: Color::LightCyan,
+            color_depth: ColorDepth::detect(),
+            glyphs: Glyphs::Unicode,
+            date_format: format::locale_date_format().to_string(),
+            progress_bar: 35,
+            player: PlayerPosition::Bottom,
+            player_hide_idle: false,
+            player_compact: false,
+            podcast_rail: false,
+            group_queue: false,
+            // the Controls tab has them all, there's no room taken up unless asked
+            hints: Hints::Hidden,
+            // on, text can only be selected to copy with Shift held in most terminals
+            mouse: false,
+            // 0 turns pre-caching of the next queue item off
+            precache_mb: 64,
+            data_saver: false,
+            quality: Quality::Feed,
+            record_live: false,
+            keep_earlier: true,
+            continuous: true,
+            speed: 1.0,
+            announce: Announce::Off,
+            audio_device: None,
+            podcast_quality: HashMap::new(),
+            // the podcast list and live shows, every 5 minutes as before
+            refresh_podcasts: 5,
+            refresh_episodes: 0,
+            backup_hours: 24,
+            backup_keep: 20,
+            download_command: None,
+            download_processing: Processing::default(),
+            play_processed: true,
+            // the banner in the app is enough unless asked for
+            desktop_notifications: Vec::new(),
+            // nothing to set up before searching it
+            search_provider: Provider::Itunes,
+            podcastindex_key: None,
+            podcastindex_secret: None,
+            smart_sort: smart_sort::SmartSort::default(),
+            remote_enabled: false,
+            // local only, so on unless turned off
+            remote_socket: true,
+            remote_bind: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            remote_port: 8042,
+            remote_token: None,
+            remote_open: false,
+            webhooks: Vec::new(),
+            // strictly opt-in, and there's no endpoint unless one is given
+            telemetry: false,
+            telemetry_url: None,
+            path: None,
+            warnings: Vec::new(),
+            unreadable: None,
+        }
+    }
+
+    // lay `layer` over the current values, keeping them wherever it's invalid
+    fn apply(&mut self, layer: ConfigToml) {
+        if let Some(theme) = layer.theme {
+            self.apply_theme(theme);
+        }
+
+        if let Some(layout) = layer.layout {
+            if let Some(progress_bar) = layout.progress_bar {
+                if progress_bar <= 100 {
+                    self.progress_bar = progress_bar;
+                } else {
+                    self.warnings
+                        .push(format!("layout.progress_bar = {} is not a percentage", progress_bar));
+                }
+            }
+            match layout.player.as_deref() {
+                Some("bottom") => self.player = PlayerPosition::Bottom,
+                Some("top") => self.player = PlayerPosition::Top,
+                Some(player) => self
+                    .warnings
+                    .push(format!("layout.player = \"{}\" is not bottom or top", player)),
+                None => {}
+            }
+            if let Some(hide_idle) = layout.player_hide_idle {
+                self.player_hide_idle = hide_idle;
+            }
+            if let Some(compact) = layout.player_compact {
+                self.player_compact = compact;
+            }
+            if let Some(rail) = layout.podcast_rail {
+                self.podcast_rail = rail;
+            }
+            if let Some(grouped) = layout.group_queue {
+                self.group_queue = grouped;
+            }
+            match layout.hints.as_deref() {
+                Some("hidden") => self.hints = Hints::Hidden,
+                Some("compact") => self.hints = Hints::Compact,
+                Some("full") => self.hints = Hints::Full,
+                Some(hints) => self
+                    .warnings
+                    .push(format!("layout.hints = \"{}\" is not hidden, compact or full", hints)),
+                None => {}
+            }
+            if let Some(mouse) = layout.mouse {
+                self.mouse = mouse;
+            }
+        }
+
+        if let Some(playback) = layer.playback {
+            if let Some(precache_mb) = playback.precache_mb {
+                self.precache_mb = precache_mb;
+            }
+            if let Some(data_saver) = playback.data_saver {
+                self.data_saver = data_saver;
+            }
+            if let Some(quality) = playback.quality {
+                match quality.parse() {
+                    Ok(quality) => self.quality = quality,
+                    Err(()) => self.warnings.push(format!(
+                        "playback.quality = \"{}\" is not feed, lowest or highest",
+                        quality
+                    )),
+                }
+            }
+            if let Some(record_live) = playback.record_live {
+                self.record_live = record_live;
+            }
+            if let Some(keep_earlier) = playback.keep_earlier {
+                self.keep_earlier = keep_earlier;
+            }
+            if let Some(continuous) = playback.continuous {
+                self.continuous = continuous;
+            }
+            if let Some(speed) = playback.speed {
+                match (MIN_SPEED..=MAX_SPEED).contains(&speed) {
+                    true => self.speed = speed,
+                    false => self.warnings.push(format!(
+                        "playback.speed = {} is not between {} and {}",
+                        speed, MIN_SPEED, MAX_SPEED
+                    )),
+                }
+            }
+            if let Some(announce) = playback.announce {
+                match announce.parse() {
+                    Ok(announce) => self.announce = announce,
+                    Err(()) => self.warnings.push(format!(
+                        "playback.announce = \"{}\" is not off, tone or voice",
+                        announce
+                    )),
+                }
+            }
+            if let Some(audio_device) = playback.audio_device {
+                self.audio_device = Some(audio_device).filter(|device| !device.is_empty());
+            }
+            for (podcast, quality) in playback.podcast_quality.unwrap_or_default() {
+                match quality.parse() {
+                    Ok(quality) => {
+                        self.podcast_quality.insert(podcast, quality);
+                    }
+                    Err(()) => self.warnings.push(format!(
+                        "playback.podcast_quality.\"{}\" = \"{}\" is not feed, lowest or highest",
+                        podcast, quality
+                    )),
+                }
+            }
+        }
+
+        if let Some(refresh) = layer.refresh {
+            if let Some(podcasts) = refresh.podcasts {
+                self.refresh_podcasts = podcasts;
+            }
+            if let Some(episodes) = refresh.episodes {
+                self.refresh_episodes = episodes;
+            }
+        }
+
+        if let Some(backups) = layer.backups {
+            if let Some(hours) = backups.hours {
+                self.backup_hours = hours;
+            }
+            if let Some(keep) = backups.keep {
+                // the one just taken is always kept
+                self.backup_keep = keep.max(1);
+            }
+        }
+
+        if let Some(downloads) = layer.downloads {
+            if let Some(command) = downloads.command {
+                // an empty one goes back to fetching them directly
+                match command.trim() {
+                    "" => self.download_command = None,
+                    command if command.contains("{url}") => self.download_command = Some(command.to_string()),
+                    _ => self
+                        .warnings
+                        .push(format!("downloads.command = \"{}\" has no {{url}} to fetch", command)),
+                }
+            }
+            if let Some(trim_silence) = downloads.trim_silence {
+                self.download_processing.trim_silence = trim_silence;
+            }
+            if let Some(normalize) = downloads.normalize {
+                self.download_processing.normalize = normalize;
+            }
+            if let Some(play_processed) = downloads.play_processed {
+                self.play_processed = play_processed;
+            }
+        }
+
+        if let Some(desktop) = layer.notifications.and_then(|notifications| notifications.desktop) {
+            self.desktop_notifications.clear();
+            // "off" on its own is how the flag turns them all off
+            for kind in desktop.iter().filter(|kind| *kind != "off") {
+                match kind.parse() {
+                    Ok(kind) => self.desktop_notifications.push(kind),
+                    Err(_) => self.warnings.push(format!(
+                        "notifications.desktop has \"{}\", which is not episodes, downloads or live",
+                        kind
+                    )),
+                }
+            }
+        }
+
+        if let Some(search) = layer.podcast_search {
+            if let Some(provider) = search.provider {
+                match provider.parse() {
+                    Ok(provider) => self.search_provider = provider,
+                    Err(()) => self.warnings.push(format!(
+                        "podcast_search.provider = \"{}\" is not itunes or podcastindex",
+                        provider
+                    )),
+                }
+            }
+            // empty ones go back to searching through Pinepods
+            if let Some(key) = search.podcastindex_key {
+                self.podcastindex_key = Some(key).filter(|key| !key.is_empty());
+            }
+            if let Some(secret) = search.podcastindex_secret {
+                self.podcastindex_secret = Some(secret).filter(|secret| !secret.is_empty());
+            }
+        }
+
+        if let Some(smart_sort) = layer.smart_sort {
+            let weights = [
+                (smart_sort.recency, &mut self.smart_sort.recency),
+                (smart_sort.fit, &mut self.smart_sort.fit),
+                (smart_sort.priority, &mut self.smart_sort.priority),
+            ];
+            for (value, weight) in weights {
+                if let Some(value) = value {
+                    *weight = value;
+                }
+            }
+            if let Some(minutes) = smart_sort.listening_minutes {
+                self.smart_sort.available = i64::from(minutes) * 60;
+            }
+            self.smart_sort.podcasts.extend(smart_sort.podcasts.unwrap_or_default());
+        }
+
+        if let Some(remote) = layer.remote {
+            if let Some(enabled) = remote.enabled {
+                self.remote_enabled = enabled;
+            }
+            if let Some(socket) = remote.socket {
+                self.remote_socket = socket;
+            }
+            if let Some(bind) = remote.bind {
+                match bind.parse() {
+                    Ok(bind) => self.remote_bind = bind,
+                    Err(_) => self
+                        .warnings
+                        .push(format!("remote.bind = \"{}\" is not an IP address", bind)),
+                }
+            }
+            if let Some(port) = remote.port {
+                self.remote_port = port;
+            }
+            if let Some(token) = remote.token {
+                // an empty token turns authentication off again
+                self.remote_open = token.is_empty();
+                self.remote_token = Some(token).filter(|t| !t.is_empty());
+            }
+            if let Some(webhooks) = remote.webhooks {
+                let (valid, invalid): (Vec<_>, Vec<_>) = webhooks
+                    .into_iter()
+                    .partition(|url| url.starts_with("http://") || url.starts_with("https://"));
+                for url in invalid {
+                    self.warnings
+                        .push(format!("remote.webhooks has \"{}\", which is not an http(s) URL", url));
+                }
+                self.webhooks = valid;
+            }
+        }
+
+        if let Some(telemetry) = layer.telemetry {
+            if let Some(enabled) = telemetry.enabled {
+                self.telemetry = enabled;
+            }
+            if let Some(url) = telemetry.url {
+                match url.starts_with("http://") || url.starts_with("https://") {
+                    true => self.telemetry_url = Some(url),
+                    false => self
+                        .warnings
+                        .push(format!("telemetry.url = \"{}\" is not an http(s) URL", url)),
+                }
+            }
+        }
+    }
+
+    // the [theme] table, or the theme file, which is the same without the header
+    fn apply_theme(&mut self, theme: Theme) {
+        let colors = [
+            ("theme.foreground", theme.foreground, &mut self.foreground),
+            ("theme.background", theme.background, &mut self.background),
+            ("theme.highlight_foreground", theme.highlight_foreground, &mut self.highlight_foreground),
+            ("theme.highlight_background", theme.highlight_background, &mut self.highlight_background),
+        ];
+        for (key, value, color) in colors {
+            let Some(value) = value else {
+                continue;
+            };
+            match parse_color(&value) {
+                Some(parsed) => *color = parsed,
+                None => self.warnings.push(format!(
+                    "{} = \"{}\" is not a color name, \"r, g, b\" or \"#rrggbb\"",
+                    key, value
+                )),
+            }
+        }
+        if let Some(depth) = theme.color_depth {
+            match depth.trim() {
+                "auto" => self.color_depth = ColorDepth::detect(),
+                _ => match ColorDepth::parse(&depth) {
+                    Some(depth) => self.color_depth = depth,
+                    None => self.warnings.push(format!(
+                        "theme.color_depth = \"{}\" is not auto, truecolor, 256, 16 or none",
+                        depth
+                    )),
+                },
+            }
+        }
+        if let Some(date_format) = theme.date_format {
+            match date_format.as_str() {
+                "auto" => self.date_format = format::locale_date_format().to_string(),
+                custom if format::valid_date_format(custom) => self.date_format = date_format,
+                _ => self.warnings.push(format!(
+                    "theme.date_format = \"{}\" is not auto or a strftime format like %Y-%m-%d",
+                    date_format
+                )),
+            }
+        }
+        if let Some(glyphs) = theme.glyphs {
+            match Glyphs::parse(&glyphs) {
+                Some(glyphs) => self.glyphs = glyphs,
+                None => self
+                    .warnings
+                    .push(format!("theme.glyphs = \"{}\" is not unicode or ascii", glyphs)),
+            }
+        }
+    }
+
+    /// Where the theme is kept, beside the config file: the [theme] keys on
+    /// their own, laid over the config's, and what the theme editor saves to.
+    pub fn theme_path(&self) -> Option<PathBuf> {
+        match &self.path {
+            Some(path) => path.parent().map(|dir| dir.join(THEME_FILE)),
+            None => home::home_dir().map(|home| home.join(".config/kronos").join(THEME_FILE)),
+        }
+    }
+
+    /// When the theme file was last changed, None if there isn't one.
+    pub fn theme_modified(&self) -> Option<SystemTime> {
+        fs::metadata(self.theme_path()?).and_then(|meta| meta.modified()).ok()
+    }
+
+    fn read_theme_file(&mut self) -> Option<Theme> {
+        let path = self.theme_path()?;
+        let content = fs::read_to_string(&path).ok()?;
+        match toml::from_str(&content) {
+            Ok(theme) => Some(theme),
+            Err(e) => {
+                self.warnings
+                    .push(format!("{} is not valid: {}", path.display(), e.message()));
+                None
+            }
         }
     }
 
+    /// Read the theme again after the file has changed, through the same
+    /// layers as on startup so flags still win.
+    pub fn reload_theme(&mut self, cli: &Cli) {
+        let defaults = Self::defaults();
+        self.foreground = defaults.foreground;
+        self.background = defaults.background;
+        self.highlight_foreground = defaults.highlight_foreground;
+        self.highlight_background = defaults.highlight_background;
+        self.color_depth = defaults.color_depth;
+        self.glyphs = defaults.glyphs;
+        self.date_format = defaults.date_format;
+
+        let config_theme = self
+            .path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| toml::from_str::<ConfigToml>(&content).ok())
+            .and_then(|layer| layer.theme);
+        let layers = [config_theme, self.read_theme_file(), ConfigToml::from(cli).theme];
+        for theme in layers.into_iter().flatten() {
+            self.apply_theme(theme);
+        }
+    }
+
+    /// Foreground, background, highlight foreground and highlight background.
+    pub fn colors(&self) -> [Color; 4] {
+        [self.foreground, self.background, self.highlight_foreground, self.highlight_background]
+    }
+
+    /// For this run only, see `save_theme` to keep them.
+    pub fn set_colors(&mut self, [foreground, background, highlight_foreground, highlight_background]: [Color; 4]) {
+        self.foreground = foreground;
+        self.background = background;
+        self.highlight_foreground = highlight_foreground;
+        self.highlight_background = highlight_background;
+    }
+
+    /// Write `colors` (as for `colors`) to the theme file, leaving anything
+    /// else in it as it was, and use them from now on.
+    pub fn save_theme(&mut self, colors: [Color; 4]) -> io::Result<PathBuf> {
+        let path = self
+            .theme_path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+        let mut theme: Theme = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| toml::from_str(&content).ok())
+            .unwrap_or_default();
+        let hex = |color: Color| {
+            let (r, g, b) = color_depth::rgb(color);
+            Some(format!("#{:02x}{:02x}{:02x}", r, g, b))
+        };
+        theme.foreground = hex(colors[0]);
+        theme.background = hex(colors[1]);
+        theme.highlight_foreground = hex(colors[2]);
+        theme.highlight_background = hex(colors[3]);
+
+        let toml = toml::to_string(&theme).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, toml)?;
+        self.set_colors(colors);
+        Ok(path)
+    }
+
+    /// Anything that went wrong outside the config itself but should still
+    /// end up in the banner.
+    pub fn warn(&mut self, warning: String) {
+        self.warnings.push(warning);
+    }
+
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Why the config file couldn't be read, if it couldn't.
+    pub fn unreadable(&self) -> Option<&str> {
+        self.unreadable.as_deref()
+    }
+
+    pub fn dismiss_warnings(&mut self) {
+        self.warnings.clear();
+    }
+
+    /// Go back to the built in defaults. The config file is moved aside to
+    /// config.toml.bak so it doesn't come back on the next start.
+    pub fn reset(&mut self) {
+        if let Some(path) = &self.path {
+            if let Err(e) = fs::rename(path, path.with_extension("toml.bak")) {
+                error!("Unable to move {} aside: {}", path.display(), e);
+            }
+        }
+        *self = Self::defaults();
+    }
+
     // pub fn quit_key(&self) -> KeyCode {
 
     //     KeyCode::Char(self.quit)
@@ -169,22 +892,237 @@ impl Config {
     // }
 
     pub fn foreground(&self) -> Color {
-        self.foreground
+        self.color(self.foreground)
     }
 
     pub fn background(&self) -> Color {
-        self.background
+        self.color(self.background)
     }
 
     pub fn highlight_foreground(&self) -> Color {
-        self.highlight_foreground
+        self.color(self.highlight_foreground)
     }
 
     pub fn highlight_background(&self) -> Color {
-        self.highlight_background
+        self.color(self.highlight_background)
+    }
+
+    pub fn glyphs(&self) -> Glyphs {
+        self.glyphs
+    }
+
+    /// The characters for a border of `border_type` in the glyphs in use.
+    pub fn border(&self, border_type: BorderType) -> border::Set {
+        self.glyphs.border(border_type)
+    }
+
+    /// A publication date as the server sends it, in the date format in use.
+    pub fn date(&self, date: &str) -> String {
+        format::date(date, &self.date_format)
+    }
+
+    /// `color`, or the nearest the terminal can show.
+    pub fn color(&self, color: Color) -> Color {
+        self.color_depth.fit(color)
     }
 
     pub fn progress_bar(&self) -> u16 {
         self.progress_bar
     }
+
+    pub fn player(&self) -> PlayerPosition {
+        self.player
+    }
+
+    pub fn player_hide_idle(&self) -> bool {
+        self.player_hide_idle
+    }
+
+    pub fn player_compact(&self) -> bool {
+        self.player_compact
+    }
+
+    pub fn mouse(&self) -> bool {
+        self.mouse
+    }
+
+    pub fn podcast_rail(&self) -> bool {
+        self.podcast_rail
+    }
+
+    pub fn group_queue(&self) -> bool {
+        self.group_queue
+    }
+
+    pub fn hints(&self) -> Hints {
+        self.hints
+    }
+
+    pub fn precache_mb(&self) -> u32 {
+        self.precache_mb
+    }
+
+    pub fn data_saver(&self) -> bool {
+        self.data_saver
+    }
+
+    pub fn quality(&self) -> Quality {
+        self.quality
+    }
+
+    pub fn record_live(&self) -> bool {
+        self.record_live
+    }
+
+    pub fn keep_earlier(&self) -> bool {
+        self.keep_earlier
+    }
+
+    pub fn continuous(&self) -> bool {
+        self.continuous
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    pub fn announce(&self) -> Announce {
+        self.announce
+    }
+
+    pub fn audio_device(&self) -> Option<&str> {
+        self.audio_device.as_deref()
+    }
+
+    pub fn podcast_quality(&self) -> &HashMap<String, Quality> {
+        &self.podcast_quality
+    }
+
+    pub fn refresh_podcasts(&self) -> Option<Duration> {
+        minutes(self.refresh_podcasts)
+    }
+
+    pub fn refresh_episodes(&self) -> Option<Duration> {
+        minutes(self.refresh_episodes)
+    }
+
+    pub fn backup_interval(&self) -> Option<Duration> {
+        minutes(self.backup_hours * 60)
+    }
+
+    pub fn backup_keep(&self) -> usize {
+        self.backup_keep
+    }
+
+    /// What's worth a desktop notification.
+    pub fn desktop_notifications(&self) -> &[notifications::Kind] {
+        &self.desktop_notifications
+    }
+
+    pub fn search_provider(&self) -> Provider {
+        self.search_provider
+    }
+
+    /// The PodcastIndex API key and secret, if both are set.
+    pub fn podcastindex_keys(&self) -> Option<podcast_search::Keys> {
+        Some(podcast_search::Keys {
+            key: self.podcastindex_key.clone()?,
+            secret: self.podcastindex_secret.clone()?,
+        })
+    }
+
+    /// The command kept episodes are fetched with, when it isn't done directly.
+    pub fn download_command(&self) -> Option<&str> {
+        self.download_command.as_deref()
+    }
+
+    /// What's done to kept episodes once they're downloaded.
+    pub fn download_processing(&self) -> Processing {
+        self.download_processing
+    }
+
+    /// Whether kept episodes play from their processed copy, if they have one.
+    pub fn play_processed(&self) -> bool {
+        self.play_processed
+    }
+
+    /// The file the settings were read from, if there was one.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn smart_sort(&self) -> &smart_sort::SmartSort {
+        &self.smart_sort
+    }
+
+    pub fn remote_enabled(&self) -> bool {
+        self.remote_enabled
+    }
+
+    pub fn remote_socket(&self) -> bool {
+        self.remote_socket
+    }
+
+    pub fn remote_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.remote_bind, self.remote_port)
+    }
+
+    /// For this run only, once the remote control server has moved or been
+    /// turned on or off.
+    pub fn set_remote(&mut self, enabled: bool, addr: SocketAddr) {
+        self.remote_enabled = enabled;
+        self.remote_bind = addr.ip();
+        self.remote_port = addr.port();
+    }
+
+    pub fn remote_token(&self) -> Option<&str> {
+        self.remote_token.as_deref()
+    }
+
+    /// The token the remote control takes: the one set, or else the kept one
+    /// (see access::kept_token), since it listens on every address unless
+    /// told otherwise. None only when remote.token was set empty on purpose.
+    pub fn ensure_remote_token(&mut self) -> Option<String> {
+        if self.remote_token.is_none() && !self.remote_open {
+            self.remote_token = Some(access::kept_token());
+        }
+        self.remote_token.clone()
+    }
+
+    /// For this run, the settings file keeps the one it has.
+    pub fn set_remote_token(&mut self, token: String) {
+        self.remote_token = Some(token);
+    }
+
+    pub fn webhooks(&self) -> &[String] {
+        &self.webhooks
+    }
+
+    pub fn telemetry(&self) -> bool {
+        self.telemetry
+    }
+
+    pub fn telemetry_url(&self) -> Option<&str> {
+        self.telemetry_url.as_deref()
+    }
+}
+
+fn minutes(minutes: u64) -> Option<Duration> {
+    (minutes > 0).then(|| Duration::from_secs(minutes * 60))
+}
+
+// "r, g, b" as before, otherwise anything ratatui understands (names, "#rrggbb", 0-255)
+fn parse_color(value: &str) -> Option<Color> {
+    if value.contains(',') {
+        let colors: Vec<u8> = value
+            .split(',')
+            .map(|i| i.trim().parse())
+            .collect::<Result<_, _>>()
+            .ok()?;
+        return match colors[..] {
+            [r, g, b] => Some(Color::Rgb(r, g, b)),
+            _ => None,
+        };
+    }
+    Color::from_str(value).ok()
 }
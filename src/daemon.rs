@@ -0,0 +1,103 @@
+// Headless mode: no terminal ui, only the player and the remote control
+// server, so a spare machine hooked up to speakers can be played to from the
+// Pinepods web ui. Meant to run as a systemd Type=notify service, optionally
+// socket activated.
+
+use std::io;
+use std::time::Duration;
+
+use log::{error, info, warn};
+use pinepods_firewood::systemd;
+
+use crate::app::App;
+use crate::config::Config;
+use crate::remote::Remote;
+
+// how often playback is looked after when no commands come in
+const IDLE_TICK: Duration = Duration::from_millis(250);
+
+pub async fn run(mut app: App<'_>, mut cfg: Config) -> io::Result<()> {
+    let given = cfg.remote_token().is_some();
+    let token = cfg.ensure_remote_token();
+    match &token {
+        None => warn!(
+            "remote.token is empty, so anyone who can reach {} can control playback and have it fetch any URL",
+            cfg.remote_addr()
+        ),
+        Some(token) if !given => warn!("No remote.token set, clients need this one: {}", token),
+        Some(_) => {}
+    }
+    // without the server there's nothing to drive the player, so this one is fatal
+    let mut remote = Remote::new();
+    match systemd::take_listeners().into_iter().next() {
+        Some(listener) => remote.serve_http_on(listener, token)?,
+        None => remote.serve_http(cfg.remote_addr(), token)?,
+    }
+    if let Some(addr) = remote.addr {
+        info!("Remote control listening on {}", addr);
+    }
+    if cfg.remote_socket() {
+        if let Err(e) = remote.serve_socket() {
+            error!("Local remote control unavailable: {}", e);
+        }
+    }
+    if let Some(socket) = &remote.socket {
+        info!("Local remote control on {}", socket.display());
+    }
+    systemd::notify("READY=1");
+
+    app.apply_settings(&cfg);
+    let mut status = String::new();
+    let mut shutdown = std::pin::pin!(shutdown_signal());
+    loop {
+        tokio::select! {
+            Some(request) = remote.requests.recv() => app.handle_remote(request),
+            _ = tokio::time::sleep(IDLE_TICK) => {}
+            _ = &mut shutdown => break,
+        }
+
+        // the tui does all of this from its draw loop
+        app.song_progress();
+        app.precache_next(cfg.precache_mb());
+        app.sync_server();
+        remote.publish(app.status());
+
+        let current = status_line(&app);
+        if current != status {
+            systemd::notify(&format!("STATUS={}", current));
+            status = current;
+        }
+    }
+
+    info!("Shutting down");
+    systemd::notify("STOPPING=1");
+    Ok(())
+}
+
+// what `systemctl status` shows
+fn status_line(app: &App) -> String {
+    if app.music_handle.sink_empty() {
+        String::from("Idle")
+    } else if app.music_handle.is_paused() {
+        format!("Paused: {}", app.music_handle.currently_playing())
+    } else {
+        format!("Playing: {}", app.music_handle.currently_playing())
+    }
+}
+
+// ctrl-c, or SIGTERM from systemd
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = terminate.recv() => {}
+            }
+            return;
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+}
@@ -1,8 +1,53 @@
+pub mod announce;
+pub mod audiobook;
+pub mod backups;
+pub mod cache;
+pub mod changelog;
+pub mod chapters;
+pub mod color_depth;
 pub mod constants;
+pub mod downloads;
+pub mod episode_settings;
+pub mod errors;
+pub mod feed;
+pub mod format;
 pub mod gen_funcs;
+pub mod glyphs;
+pub mod handoff;
+pub mod html;
+pub mod key_sequences;
+pub mod local_files;
+pub mod local_store;
+pub mod logging;
+pub mod media_keys;
 pub mod music_handler;
+pub mod notifications;
+pub mod outbox;
+pub mod palette;
+pub mod paste;
+pub mod play_history;
+pub mod playback_log;
+pub mod playlist_import;
+pub mod podcast_filter;
+pub mod podcast_flags;
+pub mod podcast_search;
+pub mod profiler;
+pub mod profiles;
 pub mod queue;
+pub mod queue_merge;
+pub mod refresh;
+pub mod search_index;
+pub mod searches;
+pub mod server_downloads;
+pub mod sha1;
+pub mod smart_sort;
+pub mod snapshots;
 pub mod stateful_list;
 pub mod stateful_table;
+pub mod stats;
+pub mod subscriptions;
+pub mod systemd;
+pub mod telemetry;
+pub mod transcripts;
 pub mod requests;
 pub mod models;
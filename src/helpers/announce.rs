@@ -0,0 +1,129 @@
+// What's played between one queued episode and the next when the queue
+// carries on by itself, so it's clear from across the room that another one's
+// starting:
+//
+//   off    nothing, the next one starts straight away
+//   tone   two short rising beeps
+//   voice  "Up next:" and the episode's title, spoken by whatever the system
+//          has for it, espeak-ng or espeak on Linux and the BSDs, say on
+//          macOS and PowerShell's speech synthesizer on Windows. Without one
+//          it's the tone instead.
+//
+// The announcement plays on a sink of its own (see music_handler.rs), and
+// the next episode starts once it's done.
+
+use std::fs;
+use std::io::Cursor;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::time::Duration;
+
+use log::error;
+use rodio::source::{SineWave, Zero};
+use rodio::{Decoder, Sink, Source};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Announce {
+    #[default]
+    Off,
+    Tone,
+    Voice,
+}
+
+impl FromStr for Announce {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, ()> {
+        match value {
+            "off" => Ok(Announce::Off),
+            "tone" => Ok(Announce::Tone),
+            "voice" => Ok(Announce::Voice),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Queue `announce` for `title` on `sink`. Speaking it can take a moment, so
+/// this is best called off the ui thread.
+pub fn append(sink: &Sink, announce: Announce, title: &str) {
+    match announce {
+        Announce::Off => {}
+        Announce::Tone => append_tone(sink),
+        Announce::Voice => match speech(&format!("Up next: {}", title)).map(|wav| Decoder::new(Cursor::new(wav))) {
+            Some(Ok(speech)) => sink.append(speech),
+            Some(Err(e)) => {
+                error!("Unable to decode the spoken announcement: {}", e);
+                append_tone(sink);
+            }
+            None => append_tone(sink),
+        },
+    }
+}
+
+fn append_tone(sink: &Sink) {
+    let beep = |frequency: f32, millis: u64| {
+        SineWave::new(frequency)
+            .take_duration(Duration::from_millis(millis))
+            .amplify(0.25)
+    };
+    sink.append(beep(660.0, 150));
+    sink.append(Zero::<f32>::new(1, 48000).take_duration(Duration::from_millis(80)));
+    sink.append(beep(880.0, 220));
+}
+
+// `text` spoken, as a wav file, None if there's nothing here to speak with
+fn speech(text: &str) -> Option<Vec<u8>> {
+    let path = std::env::temp_dir().join(format!("firewood-announce-{}.wav", std::process::id()));
+    for (program, args) in speakers(&path.display().to_string(), text) {
+        let output = Command::new(program)
+            .args(&args)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output();
+        let output = match output {
+            Ok(output) if output.status.success() => output,
+            Ok(_) => continue,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => {
+                error!("Unable to run {} to speak the announcement: {}", program, e);
+                continue;
+            }
+        };
+        // those that can't write to stdout write to the file
+        let wav = match output.stdout.is_empty() {
+            true => fs::read(&path).ok(),
+            false => Some(output.stdout),
+        };
+        let _ = fs::remove_file(&path);
+        if wav.is_some() {
+            return wav;
+        }
+    }
+    None
+}
+
+#[cfg(target_os = "windows")]
+fn speakers(path: &str, text: &str) -> Vec<(&'static str, Vec<String>)> {
+    let quoted = |text: &str| format!("'{}'", text.replace('\'', "''"));
+    let script = format!(
+        "Add-Type -AssemblyName System.Speech; \
+         $speaker = New-Object System.Speech.Synthesis.SpeechSynthesizer; \
+         $speaker.SetOutputToWaveFile({}); $speaker.Speak({}); $speaker.Dispose()",
+        quoted(path),
+        quoted(text)
+    );
+    let args = ["-NoProfile", "-NonInteractive", "-Command"].map(String::from);
+    vec![("powershell", [args.as_slice(), &[script]].concat())]
+}
+
+#[cfg(target_os = "macos")]
+fn speakers(path: &str, text: &str) -> Vec<(&'static str, Vec<String>)> {
+    let args = ["-o", path, "--data-format=LEI16@22050", text].map(String::from);
+    vec![("say", args.to_vec())]
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn speakers(_path: &str, text: &str) -> Vec<(&'static str, Vec<String>)> {
+    let args = vec![String::from("--stdout"), text.to_string()];
+    vec![("espeak-ng", args.clone()), ("espeak", args)]
+}
@@ -0,0 +1,54 @@
+// Podcasts, and folders of local files, marked as audiobooks in their playback
+// settings (see podcast_flags.rs). A local folder is known by its name, the
+// same as the podcast its files play as. Their parts are listened to in
+// order, oldest episode or first file first, so:
+//
+//   the next part is queued when one finishes, when nothing else is up next
+//   picking a part up from where it was left starts a little further back
+//   a part only counts as finished once 99% of it has been listened to
+//   what's left of the whole book shows by the player
+
+use std::path::Path;
+
+use super::gen_funcs;
+use super::local_files;
+use super::requests::PinepodsEpisodes;
+
+/// Seconds gone back over when a part is picked up partway, more than for a
+/// podcast since the story's harder to pick up cold.
+pub const REWIND: u16 = 30;
+
+/// Every part of the book `episode` is in, in order. Episodes come from
+/// `episodes`, as many of the podcast's as are known here.
+pub fn parts(episode: &PinepodsEpisodes, episodes: &[PinepodsEpisodes]) -> Vec<PinepodsEpisodes> {
+    if let Some(path) = local_files::path(&episode.EpisodeURL) {
+        return path.parent().and_then(|dir| local_files::folder(dir).ok()).unwrap_or_default();
+    }
+    let mut parts: Vec<PinepodsEpisodes> = episodes
+        .iter()
+        .filter(|other| other.PodcastName.is_some() && other.PodcastName == episode.PodcastName)
+        .cloned()
+        .collect();
+    // undated ones go first, they're usually the introduction
+    parts.sort_by_key(|part| gen_funcs::pub_date(&part.EpisodePubDate));
+    parts.dedup_by(|a, b| a.EpisodeURL == b.EpisodeURL);
+    parts
+}
+
+/// The parts after the one at `url`, none if it isn't one of them.
+pub fn after<'a>(parts: &'a [PinepodsEpisodes], url: &str) -> &'a [PinepodsEpisodes] {
+    match parts.iter().position(|part| part.EpisodeURL == url) {
+        Some(i) => &parts[i + 1..],
+        None => &[],
+    }
+}
+
+/// Whether `played` seconds of a part `length` long is all of it.
+pub fn finished(played: u16, length: u16) -> bool {
+    length > 0 && u32::from(played) * 100 >= u32::from(length) * 99
+}
+
+/// The name a local folder's audiobook setting is kept under.
+pub fn folder_name(dir: &Path) -> Option<String> {
+    dir.file_name().map(|name| name.to_string_lossy().into_owned())
+}
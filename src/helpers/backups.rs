@@ -0,0 +1,125 @@
+// Copies of the subscriptions, the queue and the settings file, taken on a
+// schedule and before anything that replaces a lot at once, so a mass
+// unsubscribe (here or on the server) or a queue restored over the wrong one
+// can be undone. Each one is a timestamped file of its own in
+// <app_path>/backups, the oldest go once there are more than are kept.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, TimeZone, Utc};
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use super::requests::{self, PinepodsEpisodes, PinepodsPodcasts};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Backup {
+    /// unix seconds
+    pub taken: i64,
+    /// why it was taken, e.g. "scheduled" or "before restoring a queue"
+    pub reason: String,
+    pub podcasts: Vec<PinepodsPodcasts>,
+    pub queue: Vec<PinepodsEpisodes>,
+    /// None when there was no settings file
+    pub settings: Option<Settings>,
+}
+
+/// The settings file as it was, and where it goes back to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Settings {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+impl Settings {
+    pub fn read(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        Some(Self {
+            path: path.to_path_buf(),
+            content,
+        })
+    }
+}
+
+impl Backup {
+    pub fn new(reason: &str, podcasts: &[PinepodsPodcasts], queue: Vec<PinepodsEpisodes>, settings: Option<Settings>) -> Self {
+        Self {
+            taken: Utc::now().timestamp(),
+            reason: reason.to_string(),
+            podcasts: podcasts.to_vec(),
+            queue,
+            settings,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        let taken = Local
+            .timestamp_opt(self.taken, 0)
+            .single()
+            .map_or_else(|| self.taken.to_string(), |taken| taken.format("%Y-%m-%d %H:%M").to_string());
+        format!(
+            "{}  {}, {} podcasts, {} queued",
+            taken,
+            self.reason,
+            self.podcasts.len(),
+            self.queue.len()
+        )
+    }
+}
+
+fn dir() -> Option<PathBuf> {
+    requests::get_app_path().map(|p| p.join("backups"))
+}
+
+/// Write `backup` out, then drop all but the newest `keep`.
+pub fn save(backup: &Backup, keep: usize) -> io::Result<PathBuf> {
+    let dir = dir().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no app directory"))?;
+    fs::create_dir_all(&dir)?;
+    let taken = Local.timestamp_opt(backup.taken, 0).single().unwrap_or_else(Local::now);
+    let path = dir.join(format!("backup-{}.json", taken.format("%Y%m%d-%H%M%S")));
+    let json = serde_json::to_string(backup).map_err(io::Error::from)?;
+    fs::write(&path, json)?;
+
+    for old in files().into_iter().skip(keep) {
+        if let Err(e) = fs::remove_file(&old) {
+            error!("Unable to remove old backup {:?}: {}", old, e);
+        }
+    }
+    Ok(path)
+}
+
+// newest first, the names sort by when they were taken
+fn files() -> Vec<PathBuf> {
+    let Some(entries) = dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("backup-") && name.ends_with(".json"))
+        })
+        .collect();
+    files.sort();
+    files.reverse();
+    files
+}
+
+/// Every backup there is, newest first, skipping any that can't be read.
+pub fn list() -> Vec<Backup> {
+    files()
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect()
+}
+
+/// When the newest backup was taken, in unix seconds.
+pub fn last_taken() -> Option<i64> {
+    let newest = files().into_iter().next()?;
+    let content = fs::read_to_string(newest).ok()?;
+    serde_json::from_str::<Backup>(&content).ok().map(|backup| backup.taken)
+}
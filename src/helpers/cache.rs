@@ -0,0 +1,113 @@
+// The last of everything fetched that a page is made from, the podcast list,
+// each podcast's episodes and the queue, so pages show straight away on the
+// way in and going back to them, from what was fetched last, while the fresh
+// copy is fetched in the background. Until it comes in the browser says how
+// old what it's showing is (see main.rs).
+//
+// Downloads aren't in here, what's been kept on this computer is in
+// local_store.rs already. The queue is kept as it was at the last look, to
+// restore on the way in.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::Utc;
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use super::requests::{self, PinepodsEpisodes, PinepodsPodcasts};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Saved<T> {
+    /// unix seconds
+    pub fetched: i64,
+    pub value: T,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Cached {
+    #[serde(default)]
+    podcasts: Option<Saved<Vec<PinepodsPodcasts>>>,
+    /// by podcast id
+    #[serde(default)]
+    episodes: BTreeMap<i64, Saved<Vec<PinepodsEpisodes>>>,
+    #[serde(default)]
+    queue: Vec<PinepodsEpisodes>,
+}
+
+pub struct Cache {
+    cached: Cached,
+    path: Option<PathBuf>,
+}
+
+fn saved<T>(value: T) -> Saved<T> {
+    Saved {
+        fetched: Utc::now().timestamp(),
+        value,
+    }
+}
+
+impl Cache {
+    pub fn load() -> Self {
+        let path = requests::get_app_path().map(|p| p.join("cache.json"));
+        let cached = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self { cached, path }
+    }
+
+    pub fn podcasts(&self) -> Option<&Saved<Vec<PinepodsPodcasts>>> {
+        self.cached.podcasts.as_ref().filter(|saved| !saved.value.is_empty())
+    }
+
+    /// The episodes of podcasts no longer subscribed to go with them.
+    pub fn set_podcasts(&mut self, podcasts: &[PinepodsPodcasts]) {
+        self.cached
+            .episodes
+            .retain(|id, _| podcasts.iter().any(|podcast| podcast.PodcastID == *id));
+        self.cached.podcasts = Some(saved(podcasts.to_vec()));
+        self.write();
+    }
+
+    pub fn episodes(&self, podcast_id: i64) -> Option<&Saved<Vec<PinepodsEpisodes>>> {
+        self.cached.episodes.get(&podcast_id)
+    }
+
+    pub fn set_episodes(&mut self, podcast_id: i64, episodes: &[PinepodsEpisodes]) {
+        self.cached.episodes.insert(podcast_id, saved(episodes.to_vec()));
+        self.write();
+    }
+
+    pub fn queue(&self) -> &[PinepodsEpisodes] {
+        &self.cached.queue
+    }
+
+    /// Only written when it's changed since, this is called as often as the
+    /// server is synced with.
+    pub fn set_queue(&mut self, queue: &VecDeque<PinepodsEpisodes>) {
+        let same = queue.len() == self.cached.queue.len()
+            && queue.iter().zip(&self.cached.queue).all(|(a, b)| a.EpisodeURL == b.EpisodeURL);
+        if same {
+            return;
+        }
+        self.cached.queue = queue.iter().cloned().collect();
+        self.write();
+    }
+
+    fn write(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let result = serde_json::to_string(&self.cached)
+            .map_err(std::io::Error::from)
+            .and_then(|json| fs::write(path, json));
+        if let Err(e) = result {
+            error!("Unable to save the cache to {:?}: {}", path, e);
+        }
+    }
+}
@@ -0,0 +1,75 @@
+// What changed in each release, for the "what's new" popup shown once after
+// an upgrade. Add an entry here whenever the minor version goes up.
+
+use std::fs;
+
+use log::error;
+
+use super::requests;
+
+pub struct Release {
+    /// major.minor, patch releases don't get a popup
+    pub version: &'static str,
+    pub features: &'static [&'static str],
+    pub keys: &'static [&'static str],
+}
+
+// newest first
+pub const RELEASES: &[Release] = &[Release {
+    version: "0.1",
+    features: &[
+        "Hardware media keys on macOS and Windows",
+        "The next episode in the queue downloads ahead of time, unless data saver is on",
+        "Listen positions are kept and resent if the server can't be reached",
+        "Subscriptions added or removed since last time are listed at startup",
+        "Settings from flags and FIREWOOD_* variables, with problems shown in a banner",
+        "Headless --daemon mode, controlled over HTTP or a local socket",
+        "Opt-in anonymous crash and error reports",
+        "Named queue snapshots",
+    ],
+    keys: &[
+        "+ / - change the volume, [ / ] seek, from any tab",
+        "gg, G, dd, Home, End, Page Up / Down and Ctrl + D / U in every list, with counts like 5j",
+        "v shows subscriptions as a grid",
+        "Shift + L and Shift + F change logging from the Controls tab",
+        "s saves the queue, o restores a saved one",
+    ],
+}];
+
+fn version() -> &'static str {
+    let full = env!("CARGO_PKG_VERSION");
+    match full.rmatch_indices('.').next() {
+        Some((i, _)) => &full[..i],
+        None => full,
+    }
+}
+
+/// Notes for the running version.
+pub fn current() -> Option<&'static Release> {
+    RELEASES.iter().find(|release| release.version == version())
+}
+
+/// Nothing has been recorded yet, so this is a new install. Upgrades from
+/// before the changelog existed look the same.
+pub fn first_run() -> bool {
+    requests::get_app_path().is_some_and(|p| !p.join("last_seen_version").exists())
+}
+
+/// Notes for the running version, unless they've already been shown.
+pub fn unseen() -> Option<&'static Release> {
+    let seen = requests::get_app_path()
+        .and_then(|p| fs::read_to_string(p.join("last_seen_version")).ok());
+    match seen {
+        Some(seen) if seen.trim() == version() => None,
+        _ => current(),
+    }
+}
+
+pub fn mark_seen() {
+    let Some(path) = requests::get_app_path().map(|p| p.join("last_seen_version")) else {
+        return;
+    };
+    if let Err(e) = fs::write(&path, version()) {
+        error!("Unable to save {:?}: {}", path, e);
+    }
+}
@@ -0,0 +1,115 @@
+// Chapters kept in the episode file itself, as ID3v2 CHAP frames at the start
+// of an mp3, for the feeds that don't point at a chapters file. Only what the
+// chapter list needs is read: where each one starts and its TIT2 title. Tags
+// written with unsynchronisation, or in ID3v2.2, are rare enough for
+// podcasts to be left alone, they read as having none.
+
+use super::feed::Chapter;
+
+const HEADER: usize = 10;
+
+/// The chapters tagged into `bytes`, an episode file, in order.
+pub fn embedded(bytes: &[u8]) -> Vec<Chapter> {
+    let Some((version, frames)) = tag(bytes) else {
+        return Vec::new();
+    };
+    let mut chapters: Vec<Chapter> = frames_of(frames, version)
+        .filter(|(id, _)| id == b"CHAP")
+        .filter_map(|(_, body)| chapter(body, version))
+        .collect();
+    chapters.sort_by(|a, b| a.start.total_cmp(&b.start));
+    chapters
+}
+
+// the major version and frames of the tag at the start of bytes
+fn tag(bytes: &[u8]) -> Option<(u8, &[u8])> {
+    if bytes.len() < HEADER || &bytes[..3] != b"ID3" {
+        return None;
+    }
+    let (version, flags) = (bytes[3], bytes[5]);
+    if !(3..=4).contains(&version) || flags & 0x80 != 0 {
+        return None;
+    }
+    let size = syncsafe(&bytes[6..10])?;
+    let frames = bytes.get(HEADER..HEADER + size)?;
+    if flags & 0x40 == 0 {
+        return Some((version, frames));
+    }
+    // an extended header, sized without itself in 2.3 and with it in 2.4
+    let extended = match version {
+        3 => u32_at(frames, 0)? as usize + 4,
+        _ => syncsafe(frames.get(..4)?)?,
+    };
+    Some((version, frames.get(extended..)?))
+}
+
+// (id, body) of each frame, stopping at the padding
+fn frames_of(mut frames: &[u8], version: u8) -> impl Iterator<Item = ([u8; 4], &[u8])> {
+    std::iter::from_fn(move || {
+        let id: [u8; 4] = frames.get(..4)?.try_into().ok()?;
+        if !id.iter().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit()) {
+            return None;
+        }
+        let size = match version {
+            3 => u32_at(frames, 4)? as usize,
+            _ => syncsafe(frames.get(4..8)?)?,
+        };
+        let body = frames.get(HEADER..HEADER + size)?;
+        frames = &frames[HEADER + size..];
+        Some((id, body))
+    })
+}
+
+// element id, start and end in ms, start and end offsets, then its own frames
+fn chapter(body: &[u8], version: u8) -> Option<Chapter> {
+    let id_end = body.iter().position(|b| *b == 0)?;
+    let start = u32_at(body, id_end + 1)?;
+    let title = body
+        .get(id_end + 17..)
+        .and_then(|frames| frames_of(frames, version).find(|(id, _)| id == b"TIT2"))
+        .map(|(_, text)| decode(text))
+        .unwrap_or_default();
+    Some(Chapter {
+        start: f64::from(start) / 1000.0,
+        title,
+    })
+}
+
+// a text frame, its first byte saying how the rest is written
+fn decode(text: &[u8]) -> String {
+    let Some((&encoding, text)) = text.split_first() else {
+        return String::new();
+    };
+    let decoded = match encoding {
+        0 => text.iter().map(|&b| char::from(b)).collect(),
+        1 | 2 => {
+            let little = encoding == 1 && text.starts_with(&[0xff, 0xfe]);
+            let text = match encoding == 1 && text.len() >= 2 {
+                true => &text[2..],
+                false => text,
+            };
+            let units: Vec<u16> = text
+                .chunks_exact(2)
+                .map(|pair| match little {
+                    true => u16::from_le_bytes([pair[0], pair[1]]),
+                    false => u16::from_be_bytes([pair[0], pair[1]]),
+                })
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        _ => String::from_utf8_lossy(text).into_owned(),
+    };
+    decoded.trim_end_matches('\0').trim().to_string()
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+// seven bits to a byte, so no byte of it looks like the start of a frame
+fn syncsafe(bytes: &[u8]) -> Option<usize> {
+    if bytes.iter().any(|b| b & 0x80 != 0) {
+        return None;
+    }
+    Some(bytes.iter().fold(0, |size, &b| size << 7 | usize::from(b)))
+}
@@ -0,0 +1,149 @@
+// How many colors the terminal can show, so theme colors can be brought down
+// to the nearest ones it has instead of being sent as 24 bit and coming out
+// wrong, or not at all, on basic terminals. NO_COLOR (https://no-color.org)
+// turns colors off altogether, leaving bold text and the ">> " marker to show
+// what's selected.
+
+use std::env;
+
+use ratatui::style::Color;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorDepth {
+    TrueColor,
+    Indexed256,
+    Basic16,
+    Monochrome,
+}
+
+impl ColorDepth {
+    /// "truecolor", "256", "16" or "none".
+    pub fn parse(value: &str) -> Option<ColorDepth> {
+        match value.trim().to_lowercase().as_str() {
+            "truecolor" | "24bit" => Some(ColorDepth::TrueColor),
+            "256" => Some(ColorDepth::Indexed256),
+            "16" => Some(ColorDepth::Basic16),
+            "none" | "mono" | "monochrome" => Some(ColorDepth::Monochrome),
+            _ => None,
+        }
+    }
+
+    /// Going by the usual environment variables, as most terminals don't
+    /// answer when asked.
+    pub fn detect() -> ColorDepth {
+        if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return ColorDepth::Monochrome;
+        }
+        let colorterm = env::var("COLORTERM").unwrap_or_default().to_lowercase();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorDepth::TrueColor;
+        }
+        let term = env::var("TERM").unwrap_or_default().to_lowercase();
+        if term == "dumb" {
+            ColorDepth::Monochrome
+        } else if term.contains("256color") {
+            ColorDepth::Indexed256
+        } else if env::var_os("WT_SESSION").is_some() {
+            // Windows Terminal does 24 bit without setting either
+            ColorDepth::TrueColor
+        } else {
+            ColorDepth::Basic16
+        }
+    }
+
+    /// The nearest color to `color` there is at this depth.
+    pub fn fit(self, color: Color) -> Color {
+        match (self, color) {
+            (ColorDepth::Monochrome, _) => Color::Reset,
+            (ColorDepth::TrueColor, _) => color,
+            (ColorDepth::Indexed256, Color::Rgb(r, g, b)) => Color::Indexed(to_256(r, g, b)),
+            (ColorDepth::Basic16, Color::Rgb(r, g, b)) => to_16(r, g, b),
+            (ColorDepth::Basic16, Color::Indexed(i)) => {
+                let (r, g, b) = from_256(i);
+                to_16(r, g, b)
+            }
+            _ => color,
+        }
+    }
+}
+
+/// `color` as r, g, b, going by xterm's defaults for the named ones, and
+/// black for the terminal's own.
+pub fn rgb(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(i) => from_256(i),
+        _ => BASIC
+            .iter()
+            .find(|(basic, _)| *basic == color)
+            .map_or((0, 0, 0), |(_, rgb)| *rgb),
+    }
+}
+
+// the steps of xterm's 6x6x6 color cube
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).unsigned_abs().pow(2);
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+fn nearest_step(value: u8) -> usize {
+    (0..CUBE.len())
+        .min_by_key(|&i| (i32::from(CUBE[i]) - i32::from(value)).abs())
+        .unwrap_or(0)
+}
+
+// the closer of the nearest cube color and the nearest grey
+fn to_256(r: u8, g: u8, b: u8) -> u8 {
+    let (ri, gi, bi) = (nearest_step(r), nearest_step(g), nearest_step(b));
+    let cube = (CUBE[ri], CUBE[gi], CUBE[bi]);
+    let average = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+    let grey_index = (average.saturating_sub(3) / 10).min(23) as u8;
+    let grey = 8 + grey_index * 10;
+    match distance((grey, grey, grey), (r, g, b)) < distance(cube, (r, g, b)) {
+        true => 232 + grey_index,
+        false => 16 + 36 * ri as u8 + 6 * gi as u8 + bi as u8,
+    }
+}
+
+fn from_256(i: u8) -> (u8, u8, u8) {
+    match i {
+        0..=15 => BASIC[usize::from(i)].1,
+        16..=231 => {
+            let i = usize::from(i - 16);
+            (CUBE[i / 36], CUBE[i / 6 % 6], CUBE[i % 6])
+        }
+        _ => {
+            let grey = 8 + (i - 232) * 10;
+            (grey, grey, grey)
+        }
+    }
+}
+
+// xterm's defaults, terminals differ but not by so much the nearest changes
+const BASIC: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+fn to_16(r: u8, g: u8, b: u8) -> Color {
+    BASIC
+        .iter()
+        .min_by_key(|(_, rgb)| distance(*rgb, (r, g, b)))
+        .map_or(Color::Reset, |(color, _)| *color)
+}
@@ -0,0 +1,578 @@
+// Fetching episodes into the local store, a few at a time in the background,
+// with how far along each one is and how fast it's coming. A paused download
+// keeps what it has in a .part file beside where it'll go and carries on from
+// there when resumed, if the server takes range requests, or starts again if
+// it doesn't.
+//
+// For network setups reqwest can't manage, the fetching can be handed to a
+// command instead (downloads.command in the settings), run once per episode
+// with {url}, {output} (the .part file), and {dir} and {file} (the same split
+// in two) filled in. It's split into words as is, quotes keeping spaces in
+// one, and never handed to a shell. It's done when it exits successfully and
+// has left the file where it was told to; progress is read from the size of
+// the .part file as it grows. Pausing stops the command, and resuming runs it
+// again, so tools that can carry on (curl -C -, aria2c -c) should be told to.
+//
+// Once an episode's all there it can be run through ffmpeg to trim the
+// silence off either end and even out its loudness, into a copy beside the
+// original (see local_store::processed_path_for). The original is kept either
+// way, and if ffmpeg isn't there or fails, that's all there is.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use log::error;
+use reqwest::{header, StatusCode};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use super::local_store::{self, Kept};
+use super::music_handler;
+use super::requests::PinepodsEpisodes;
+
+// fetching at once, the rest wait their turn
+const WORKERS: usize = 3;
+// how often the speed is worked out again
+const SPEED_WINDOW: Duration = Duration::from_secs(1);
+// how often a download command's file is looked at
+const POLL: Duration = Duration::from_millis(250);
+// quieter than this is silence, for trimming
+const SILENCE: &str = "-50dB";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum State {
+    Queued,
+    Fetching,
+    /// all there, being trimmed or normalised
+    Processing,
+    Paused,
+    Cancelled,
+    Done,
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Progress {
+    pub episode: PinepodsEpisodes,
+    pub state: State,
+    pub received: u64,
+    /// when the server said how big it is
+    pub total: Option<u64>,
+    /// bytes a second, over the last second or so
+    pub speed: u64,
+    /// where it's going, once it's started
+    pub path: Option<PathBuf>,
+    sample: Option<(Instant, u64)>,
+}
+
+impl Progress {
+    fn new(episode: PinepodsEpisodes) -> Self {
+        Self {
+            episode,
+            state: State::Queued,
+            received: 0,
+            total: None,
+            speed: 0,
+            path: None,
+            sample: None,
+        }
+    }
+
+    pub fn percent(&self) -> Option<u64> {
+        self.total
+            .filter(|total| *total > 0)
+            .map(|total| (self.received * 100 / total).min(100))
+    }
+
+    fn record(&mut self, received: u64) {
+        self.received = received;
+        match self.sample {
+            Some((since, from)) if since.elapsed() >= SPEED_WINDOW => {
+                let secs = since.elapsed().as_secs_f64();
+                self.speed = (received.saturating_sub(from) as f64 / secs) as u64;
+                self.sample = Some((Instant::now(), received));
+            }
+            Some(_) => {}
+            None => self.sample = Some((Instant::now(), received)),
+        }
+    }
+}
+
+/// What's done to an episode once it's downloaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Processing {
+    /// silence off the beginning and end
+    pub trim_silence: bool,
+    /// to the loudness podcasts usually aim for
+    pub normalize: bool,
+}
+
+impl Processing {
+    fn any(&self) -> bool {
+        self.trim_silence || self.normalize
+    }
+
+    // ffmpeg's audio filters for it
+    fn filters(&self) -> String {
+        let mut filters = Vec::new();
+        if self.trim_silence {
+            // the start, then any silence of 5 seconds or more, which catches
+            // the end without reading the whole episode backwards
+            filters.push(format!(
+                "silenceremove=start_periods=1:start_threshold={0}:stop_periods=-1:stop_duration=5:stop_threshold={0}",
+                SILENCE
+            ));
+        }
+        if self.normalize {
+            filters.push(String::from("loudnorm=I=-16:TP=-1.5:LRA=11"));
+        }
+        filters.join(",")
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    jobs: Vec<Progress>,
+    workers: usize,
+    command: Option<String>,
+    processing: Processing,
+}
+
+/// Finished one way or the other, for keeping or reporting.
+pub enum Finished {
+    Kept(Kept),
+    Failed(PinepodsEpisodes, String),
+}
+
+#[derive(Clone, Default)]
+pub struct Downloads {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl Downloads {
+    /// Fetch with `command` from now on, or directly again with None.
+    pub fn set_command(&self, command: Option<String>) {
+        self.shared.lock().unwrap().command = command;
+    }
+
+    /// Process episodes like this once they're downloaded from now on.
+    pub fn set_processing(&self, processing: Processing) {
+        self.shared.lock().unwrap().processing = processing;
+    }
+
+    /// Queue `episode`, false if it's already there.
+    pub fn add(&self, episode: &PinepodsEpisodes) -> bool {
+        {
+            let mut shared = self.shared.lock().unwrap();
+            if shared.jobs.iter().any(|job| job.episode.EpisodeURL == episode.EpisodeURL) {
+                return false;
+            }
+            shared.jobs.push(Progress::new(episode.clone()));
+        }
+        self.spawn_workers();
+        true
+    }
+
+    /// As things stand, for drawing.
+    pub fn jobs(&self) -> Vec<Progress> {
+        self.shared.lock().unwrap().jobs.clone()
+    }
+
+    /// Queued, fetching or being processed.
+    pub fn active(&self) -> usize {
+        self.shared
+            .lock()
+            .unwrap()
+            .jobs
+            .iter()
+            .filter(|job| matches!(job.state, State::Queued | State::Fetching | State::Processing))
+            .count()
+    }
+
+    /// Pause the download of `url`, or carry on with it if it's paused.
+    pub fn toggle_pause(&self, url: &str) {
+        let resumed = {
+            let mut shared = self.shared.lock().unwrap();
+            let Some(job) = shared.jobs.iter_mut().find(|job| job.episode.EpisodeURL == url) else {
+                return;
+            };
+            match job.state {
+                State::Queued | State::Fetching => job.state = State::Paused,
+                State::Paused => job.state = State::Queued,
+                _ => {}
+            }
+            job.speed = 0;
+            job.sample = None;
+            job.state == State::Queued
+        };
+        if resumed {
+            self.spawn_workers();
+        }
+    }
+
+    /// Stop the download of `url` and throw away what it has.
+    pub fn cancel(&self, url: &str) {
+        let mut shared = self.shared.lock().unwrap();
+        let Some(i) = shared.jobs.iter().position(|job| job.episode.EpisodeURL == url) else {
+            return;
+        };
+        if matches!(shared.jobs[i].state, State::Fetching | State::Processing) {
+            // its worker tidies up once it sees
+            shared.jobs[i].state = State::Cancelled;
+            return;
+        }
+        shared.jobs.remove(i);
+        remove_partial(url);
+    }
+
+    /// The downloads that have finished since last asked.
+    pub fn take_finished(&self) -> Vec<Finished> {
+        let mut shared = self.shared.lock().unwrap();
+        let (finished, jobs): (Vec<Progress>, Vec<Progress>) = shared
+            .jobs
+            .drain(..)
+            .partition(|job| matches!(job.state, State::Done | State::Failed(_)));
+        shared.jobs = jobs;
+        finished
+            .into_iter()
+            .map(|job| match job.state {
+                State::Failed(e) => Finished::Failed(job.episode, e),
+                _ => Finished::Kept(Kept {
+                    processed: local_store::processed_for(&job.episode.EpisodeURL),
+                    episode: job.episode,
+                    bytes: job.received,
+                    path: job.path,
+                }),
+            })
+            .collect()
+    }
+
+    fn spawn_workers(&self) {
+        let mut shared = self.shared.lock().unwrap();
+        let queued = shared.jobs.iter().filter(|job| job.state == State::Queued).count();
+        while shared.workers < WORKERS.min(queued) {
+            shared.workers += 1;
+            tokio::spawn(work(self.shared.clone()));
+        }
+    }
+}
+
+// fetch queued episodes until there aren't any
+async fn work(shared: Arc<Mutex<Shared>>) {
+    loop {
+        let url = {
+            let mut shared = shared.lock().unwrap();
+            match shared.jobs.iter_mut().find(|job| job.state == State::Queued) {
+                Some(job) => {
+                    job.state = State::Fetching;
+                    job.episode.EpisodeURL.clone()
+                }
+                None => {
+                    shared.workers -= 1;
+                    return;
+                }
+            }
+        };
+
+        let (command, processing) = {
+            let shared = shared.lock().unwrap();
+            (shared.command.clone(), shared.processing)
+        };
+        let result = match command {
+            Some(command) => fetch_with(&shared, &url, &command).await,
+            None => fetch(&shared, &url).await,
+        };
+        if matches!(result, Ok(true)) && processing.any() && update_state(&shared, &url, State::Processing) {
+            if let Err(e) = process(&url, processing).await {
+                error!("Unable to process {}, keeping it as it is: {}", url, e);
+            }
+        }
+        let mut shared = shared.lock().unwrap();
+        let Some(i) = shared.jobs.iter().position(|job| job.episode.EpisodeURL == url) else {
+            continue;
+        };
+        match (result, &shared.jobs[i].state) {
+            (_, State::Cancelled) => {
+                shared.jobs.remove(i);
+                remove_partial(&url);
+            }
+            // paused part way, the .part file stays for later
+            (Ok(false), _) => {}
+            (Ok(true), _) => shared.jobs[i].state = State::Done,
+            (Err(e), _) => {
+                error!("Unable to download {}: {}", url, e);
+                shared.jobs[i].state = State::Failed(e.to_string());
+            }
+        }
+    }
+}
+
+// true once it's all there, false if it was stopped first
+async fn fetch(shared: &Mutex<Shared>, url: &str) -> Result<bool> {
+    let path = local_store::path_for(url).ok_or_else(|| anyhow!("no data directory to keep episodes in"))?;
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    // under another name until it's all there, so half a file never plays
+    let partial = path.with_extension("part");
+    let have = tokio::fs::metadata(&partial).await.map_or(0, |meta| meta.len());
+
+    let mut request = reqwest::Client::new().get(url);
+    if have > 0 {
+        request = request.header(header::RANGE, format!("bytes={}-", have));
+    }
+    let mut response = request.send().await?.error_for_status()?;
+    let resumed = have > 0 && response.status() == StatusCode::PARTIAL_CONTENT;
+    let mut received = if resumed { have } else { 0 };
+    let total = response.content_length().map(|len| len + received);
+    if !update(shared, url, |job| {
+        job.path = Some(path.clone());
+        job.total = total;
+        job.record(received);
+    }) {
+        return Ok(false);
+    }
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(&partial)
+        .await?;
+    while let Some(chunk) = response.chunk().await? {
+        file.write_all(&chunk).await?;
+        received += chunk.len() as u64;
+        if !update(shared, url, |job| job.record(received)) {
+            file.flush().await?;
+            return Ok(false);
+        }
+    }
+    file.flush().await?;
+    tokio::fs::rename(&partial, &path).await?;
+    Ok(true)
+}
+
+// as fetch, but by running `template`
+async fn fetch_with(shared: &Mutex<Shared>, url: &str, template: &str) -> Result<bool> {
+    let path = local_store::path_for(url).ok_or_else(|| anyhow!("no data directory to keep episodes in"))?;
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    let partial = path.with_extension("part");
+    let words = command_line(template, url, &partial);
+    let (program, args) = words.split_first().ok_or_else(|| anyhow!("the download command is empty"))?;
+
+    let size = || async { tokio::fs::metadata(&partial).await.map_or(0, |meta| meta.len()) };
+    if !update(shared, url, |job| {
+        job.path = Some(path.clone());
+        job.total = None;
+    }) {
+        return Ok(false);
+    }
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| anyhow!("unable to run {}: {}", program, e))?;
+
+    let status = loop {
+        tokio::select! {
+            status = child.wait() => break status?,
+            _ = tokio::time::sleep(POLL) => {
+                let received = size().await;
+                if !update(shared, url, |job| job.record(received)) {
+                    child.kill().await?;
+                    return Ok(false);
+                }
+            }
+        }
+    };
+    if !status.success() {
+        return Err(anyhow!("{} stopped with {}", program, status));
+    }
+    if !tokio::fs::try_exists(&partial).await? {
+        return Err(anyhow!("{} finished without writing {}", program, partial.display()));
+    }
+    let received = size().await;
+    update(shared, url, |job| job.record(received));
+    tokio::fs::rename(&partial, &path).await?;
+    Ok(true)
+}
+
+// a copy of the kept episode at `url` made with ffmpeg, beside it
+async fn process(url: &str, processing: Processing) -> Result<()> {
+    let original = local_store::file_for(url).ok_or_else(|| anyhow!("it isn't kept"))?;
+    let processed = local_store::processed_path_for(url).ok_or_else(|| anyhow!("no data directory to keep it in"))?;
+    // under another name until it's done, ending the same so ffmpeg keeps
+    // the format
+    let partial = processed.with_extension(format!("part.{}", music_handler::extension(url)));
+    let status = Command::new("ffmpeg")
+        .args(["-nostdin", "-loglevel", "error", "-y", "-i"])
+        .arg(&original)
+        .args(["-af", &processing.filters()])
+        .arg(&partial)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .status()
+        .await
+        .map_err(|e| anyhow!("unable to run ffmpeg: {}", e))?;
+    if !status.success() {
+        let _ = tokio::fs::remove_file(&partial).await;
+        return Err(anyhow!("ffmpeg stopped with {}", status));
+    }
+    tokio::fs::rename(&partial, &processed).await?;
+    Ok(())
+}
+
+// the words of `template` with the placeholders filled in afterwards, so
+// nothing in the url can be taken for a quote or a space, nor anything
+// filled in for another placeholder
+fn command_line(template: &str, url: &str, output: &Path) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote = None;
+    for c in template.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => word.get_or_insert_with(String::new).push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            None if c.is_whitespace() => words.extend(word.take()),
+            None => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+
+    let dir = output.parent().map(|dir| dir.to_string_lossy()).unwrap_or_default();
+    let file = output.file_name().map(|file| file.to_string_lossy()).unwrap_or_default();
+    let output = output.to_string_lossy();
+    let values = [("{url}", url), ("{output}", &output), ("{dir}", &dir), ("{file}", &file)];
+    words.into_iter().map(|word| fill(&word, &values)).collect()
+}
+
+// `word` with each placeholder in it swapped for its value, in one go
+fn fill(word: &str, values: &[(&str, &str)]) -> String {
+    let mut filled = String::with_capacity(word.len());
+    let mut rest = word;
+    while let Some(at) = rest.find('{') {
+        filled.push_str(&rest[..at]);
+        rest = &rest[at..];
+        match values.iter().find(|(name, _)| rest.starts_with(name)) {
+            Some((name, value)) => {
+                filled.push_str(value);
+                rest = &rest[name.len()..];
+            }
+            None => {
+                filled.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    filled.push_str(rest);
+    filled
+}
+
+// change the job for `url`, false if it's been paused or cancelled since
+fn update(shared: &Mutex<Shared>, url: &str, change: impl FnOnce(&mut Progress)) -> bool {
+    let mut shared = shared.lock().unwrap();
+    match shared.jobs.iter_mut().find(|job| job.episode.EpisodeURL == url) {
+        Some(job) if job.state == State::Fetching => {
+            change(job);
+            true
+        }
+        _ => false,
+    }
+}
+
+// move the job for `url` on from fetching, false if it's been paused or
+// cancelled since
+fn update_state(shared: &Mutex<Shared>, url: &str, state: State) -> bool {
+    update(shared, url, |job| job.state = state)
+}
+
+fn remove_partial(url: &str) {
+    if let Some(path) = local_store::path_for(url) {
+        let _ = std::fs::remove_file(path.with_extension("part"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(template: &str, url: &str) -> Vec<String> {
+        command_line(template, url, Path::new("/kept/abc.part"))
+    }
+
+    #[test]
+    fn placeholders_filled_in() {
+        assert_eq!(
+            words("aria2c -c -o {file} -d {dir} {url}", "https://a.example/ep.mp3"),
+            ["aria2c", "-c", "-o", "abc.part", "-d", "/kept", "https://a.example/ep.mp3"]
+        );
+        assert_eq!(
+            words("curl -sfo {output} {url}", "https://a.example/ep.mp3"),
+            ["curl", "-sfo", "/kept/abc.part", "https://a.example/ep.mp3"]
+        );
+    }
+
+    #[test]
+    fn quotes_keep_spaces() {
+        assert_eq!(
+            words(r#"fetch --header "User-Agent: Firewood 1.0" '{url}'"#, "https://a.example"),
+            ["fetch", "--header", "User-Agent: Firewood 1.0", "https://a.example"]
+        );
+        // the other kind of quote is just a character inside one
+        assert_eq!(words(r#"say "it's" 'a "b"'"#, ""), ["say", "it's", r#"a "b""#]);
+        assert_eq!(words(r#"empty "" {url}"#, "u"), ["empty", "", "u"]);
+    }
+
+    #[test]
+    fn extra_whitespace_between_words() {
+        assert_eq!(words("  curl\t-o  {output}\n{url} ", "u"), ["curl", "-o", "/kept/abc.part", "u"]);
+    }
+
+    #[test]
+    fn url_stays_one_word() {
+        // spaces and quotes in the url aren't read as the template's
+        let url = r#"https://a.example/my episode's "final".mp3"#;
+        assert_eq!(words("curl {url}", url), ["curl", url]);
+    }
+
+    #[test]
+    fn placeholders_in_the_url_arent_filled() {
+        // filled in once, so what it says is left as it is
+        let url = "https://a.example/{output}/{dir}?f={file}";
+        assert_eq!(words("curl -o {output} {url}", url), ["curl", "-o", "/kept/abc.part", url]);
+    }
+
+    #[test]
+    fn placeholders_in_the_path_arent_filled() {
+        let output = Path::new("/home/{url}/abc.part");
+        assert_eq!(
+            command_line("curl -o {output} {url} {dir}", "u", output),
+            ["curl", "-o", "/home/{url}/abc.part", "u", "/home/{url}"]
+        );
+    }
+
+    #[test]
+    fn other_braces_left_alone() {
+        assert_eq!(words("{curl} {} {url}{url} {{url}}", "u"), ["{curl}", "{}", "uu", "{u}"]);
+    }
+
+    #[test]
+    fn an_unclosed_quote_runs_to_the_end() {
+        assert_eq!(words(r#"curl "{url} -o"#, "u"), ["curl", "u -o"]);
+    }
+}
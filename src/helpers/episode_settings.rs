@@ -0,0 +1,114 @@
+// How each episode left partway through was being played, so going back to it
+// plays it the same way again: the speed it was at, over the podcast's own
+// (see podcast_flags.rs) and whatever the last one played at, and whether its
+// podcast's intro has been skipped already, so it isn't again after going
+// back into it by hand.
+//
+// Kept on disk by episode id, or by url for local files, which don't have one,
+// until the episode's finished. The player has no equalizer, so there's no
+// preset to keep.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::Utc;
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use super::requests::{self, PinepodsEpisodes};
+
+// the most episodes kept, those played longest ago go first
+const KEPT: usize = 500;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Remembered {
+    /// times normal speed, None until it's been changed during the episode
+    #[serde(default)]
+    pub speed: Option<f32>,
+    #[serde(default)]
+    pub intro_skipped: bool,
+    /// unix seconds
+    #[serde(default)]
+    pub updated: i64,
+}
+
+pub struct EpisodeSettings {
+    settings: BTreeMap<String, Remembered>,
+    path: Option<PathBuf>,
+}
+
+fn key(episode: &PinepodsEpisodes) -> String {
+    match episode.EpisodeID {
+        Some(id) => id.to_string(),
+        None => episode.EpisodeURL.clone(),
+    }
+}
+
+impl EpisodeSettings {
+    pub fn load() -> Self {
+        let path = requests::get_app_path().map(|p| p.join("episode_settings.json"));
+        let settings = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self { settings, path }
+    }
+
+    pub fn get(&self, episode: &PinepodsEpisodes) -> Remembered {
+        self.settings.get(&key(episode)).copied().unwrap_or_default()
+    }
+
+    pub fn set_speed(&mut self, episode: &PinepodsEpisodes, speed: f32) {
+        self.change(episode, |remembered| remembered.speed = Some(speed));
+    }
+
+    pub fn skipped_intro(&mut self, episode: &PinepodsEpisodes) {
+        self.change(episode, |remembered| remembered.intro_skipped = true);
+    }
+
+    /// Nothing more to go back to once it's been played to the end.
+    pub fn forget(&mut self, episode_id: Option<i64>, url: &str) {
+        let key = episode_id.map_or_else(|| url.to_string(), |id| id.to_string());
+        if self.settings.remove(&key).is_some() {
+            self.write();
+        }
+    }
+
+    fn change(&mut self, episode: &PinepodsEpisodes, change: impl FnOnce(&mut Remembered)) {
+        let mut remembered = self.get(episode);
+        let before = remembered;
+        change(&mut remembered);
+        if remembered == before {
+            return;
+        }
+        remembered.updated = Utc::now().timestamp();
+        self.settings.insert(key(episode), remembered);
+        if self.settings.len() > KEPT {
+            let oldest = self
+                .settings
+                .iter()
+                .min_by_key(|(_, remembered)| remembered.updated)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.settings.remove(&oldest);
+            }
+        }
+        self.write();
+    }
+
+    fn write(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let result = serde_json::to_string(&self.settings)
+            .map_err(std::io::Error::from)
+            .and_then(|json| fs::write(path, json));
+        if let Err(e) = result {
+            error!("Unable to save episode settings to {:?}: {}", path, e);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+// Failed server requests, for the banner under the tabs. While the server is
+// down every refresh fails the same way, so identical errors are counted
+// rather than shown again, and the banner goes away by itself as soon as a
+// request gets through. Kept here rather than in the app since most
+// requests run in the background.
+
+use std::sync::Mutex;
+
+use super::telemetry;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Banner {
+    /// the latest error
+    pub message: String,
+    /// how many times in a row it's happened
+    pub count: u32,
+    /// other errors since the last request that got through
+    pub others: u32,
+}
+
+static BANNER: Mutex<Option<Banner>> = Mutex::new(None);
+
+/// Note a failed request to `endpoint`, for the banner and, if it's on,
+/// telemetry.
+pub fn api_error(endpoint: &str, error: &dyn std::fmt::Display) {
+    telemetry::api_error(endpoint, error);
+    report(error.to_string());
+}
+
+fn report(message: String) {
+    let mut banner = BANNER.lock().unwrap();
+    match banner.as_mut() {
+        Some(banner) if banner.message == message => banner.count += 1,
+        Some(banner) => {
+            banner.others += banner.count;
+            banner.message = message;
+            banner.count = 1;
+        }
+        None => {
+            *banner = Some(Banner {
+                message,
+                count: 1,
+                others: 0,
+            })
+        }
+    }
+}
+
+/// A request got through, so whatever was wrong isn't any more.
+pub fn api_ok() {
+    *BANNER.lock().unwrap() = None;
+}
+
+pub fn banner() -> Option<Banner> {
+    BANNER.lock().unwrap().clone()
+}
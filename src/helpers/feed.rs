@@ -0,0 +1,486 @@
+// Podcasting 2.0 tags read straight from a show's RSS feed, for the things the
+// Pinepods server doesn't pass along. See https://podcastindex.org/namespace/1.0
+//
+// Only the tags used somewhere in the app are picked out, everything else in
+// the feed is skipped. The podcast's own title, author and so on are read too,
+// for subscribing to a feed the server hasn't seen yet.
+
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::Deserialize;
+
+use super::requests::PinepodsPodcasts;
+
+/// A `<podcast:funding>` link.
+#[derive(Debug, Clone)]
+pub struct Funding {
+    pub url: String,
+    pub label: String,
+}
+
+/// A `<podcast:soundbite>`, a short part of an episode picked out as a preview.
+#[derive(Debug, Clone)]
+pub struct Soundbite {
+    /// seconds into the episode
+    pub start: f64,
+    pub duration: f64,
+    pub title: String,
+}
+
+/// A chapter, from the JSON file a `<podcast:chapters>` tag points at.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Chapter {
+    /// seconds into the episode
+    #[serde(rename = "startTime")]
+    pub start: f64,
+    #[serde(default)]
+    pub title: String,
+}
+
+#[derive(Deserialize)]
+struct Chapters {
+    chapters: Vec<Chapter>,
+}
+
+/// A `<podcast:transcript>`, see transcripts.rs for the ones understood.
+#[derive(Debug, Clone)]
+pub struct Transcript {
+    pub url: String,
+    pub mime: String,
+}
+
+/// A `<podcast:alternateEnclosure>`, the same episode at another bitrate or
+/// in another format. Only ones with an http(s) source are kept.
+#[derive(Debug, Clone)]
+pub struct Enclosure {
+    pub url: String,
+    pub mime: String,
+    /// bits per second
+    pub bitrate: Option<f64>,
+    pub title: Option<String>,
+}
+
+impl Enclosure {
+    pub fn label(&self) -> String {
+        let mut label = self.title.clone().unwrap_or_else(|| self.mime.clone());
+        if let Some(bitrate) = self.bitrate {
+            label.push_str(&format!(" {} kbps", (bitrate / 1000.0).round()));
+        }
+        label
+    }
+}
+
+/// Which enclosure to play when a feed offers more than one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Quality {
+    /// the regular enclosure, the same one Pinepods has
+    #[default]
+    Feed,
+    Lowest,
+    Highest,
+}
+
+impl Quality {
+    pub fn name(self) -> &'static str {
+        match self {
+            Quality::Feed => "feed",
+            Quality::Lowest => "lowest",
+            Quality::Highest => "highest",
+        }
+    }
+}
+
+impl FromStr for Quality {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, ()> {
+        match value {
+            "feed" => Ok(Quality::Feed),
+            "lowest" => Ok(Quality::Lowest),
+            "highest" => Ok(Quality::Highest),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Item {
+    pub title: String,
+    /// the enclosure url, which is what Pinepods has as the episode url
+    pub enclosure: Option<String>,
+    /// the episode's own page
+    pub link: Option<String>,
+    pub alternates: Vec<Enclosure>,
+    pub soundbites: Vec<Soundbite>,
+    /// where the chapters are, only JSON chapters are understood
+    pub chapters: Option<String>,
+    pub transcripts: Vec<Transcript>,
+}
+
+impl Item {
+    /// The alternate to play for `quality`, None for the regular enclosure.
+    /// Only audio alternates with a bitrate can be compared.
+    pub fn variant(&self, quality: Quality) -> Option<&Enclosure> {
+        let audio = self
+            .alternates
+            .iter()
+            .filter(|alternate| alternate.mime.starts_with("audio/"))
+            .filter_map(|alternate| Some((alternate.bitrate?, alternate)));
+        let pick = match quality {
+            Quality::Feed => None,
+            Quality::Lowest => audio.min_by(|(a, _), (b, _)| a.total_cmp(b)),
+            Quality::Highest => audio.max_by(|(a, _), (b, _)| a.total_cmp(b)),
+        };
+        pick.map(|(_, alternate)| alternate)
+    }
+
+    /// What `url` is, for showing which variant is playing.
+    pub fn label(&self, url: &str) -> String {
+        match self.alternates.iter().find(|alternate| alternate.url == url) {
+            Some(alternate) => alternate.label(),
+            None => String::from("Feed default"),
+        }
+    }
+
+    fn has_url(&self, url: &str) -> bool {
+        self.enclosure.as_deref() == Some(url)
+            || self.alternates.iter().any(|alternate| alternate.url == url)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiveStatus {
+    Pending,
+    Live,
+    Ended,
+}
+
+/// A `<podcast:liveItem>`, a scheduled or running live stream.
+#[derive(Debug, Clone)]
+pub struct LiveItem {
+    pub title: String,
+    pub status: LiveStatus,
+    /// ISO 8601, as given in the feed
+    pub start: String,
+    pub end: Option<String>,
+    pub url: Option<String>,
+}
+
+/// What the feed says about the podcast itself, the first of each tag.
+#[derive(Debug, Default)]
+pub struct Channel {
+    pub title: String,
+    pub author: String,
+    pub description: String,
+    pub artwork: String,
+    pub website: String,
+}
+
+// which of the channel's tags is waiting for its text
+#[derive(Clone, Copy)]
+enum ChannelField {
+    Title,
+    Author,
+    Description,
+    Artwork,
+    Website,
+}
+
+impl Channel {
+    fn field(&mut self, field: ChannelField) -> &mut String {
+        match field {
+            ChannelField::Title => &mut self.title,
+            ChannelField::Author => &mut self.author,
+            ChannelField::Description => &mut self.description,
+            ChannelField::Artwork => &mut self.artwork,
+            ChannelField::Website => &mut self.website,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Feed {
+    pub channel: Channel,
+    pub funding: Vec<Funding>,
+    pub items: Vec<Item>,
+    pub live: Vec<LiveItem>,
+}
+
+impl Feed {
+    /// The item for an episode, by enclosure url, or by title for feeds
+    /// that have moved their files since Pinepods saw them.
+    pub fn item(&self, url: &str, title: &str) -> Option<&Item> {
+        self.items
+            .iter()
+            .find(|item| item.has_url(url))
+            .or_else(|| self.items.iter().find(|item| item.title == title))
+    }
+
+    /// The podcast to subscribe to, for the feed at `url`.
+    pub fn podcast(&self, url: &str) -> PinepodsPodcasts {
+        PinepodsPodcasts {
+            PodcastID: 0,
+            PodcastName: self.channel.title.clone(),
+            ArtworkURL: self.channel.artwork.clone(),
+            Author: self.channel.author.clone(),
+            Categories: String::new(),
+            EpisodeCount: self.items.len() as u32,
+            FeedURL: url.to_string(),
+            WebsiteURL: self.channel.website.clone(),
+            Description: self.channel.description.clone(),
+        }
+    }
+}
+
+pub async fn fetch(url: &str) -> Result<Feed> {
+    let body = reqwest::get(url)
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    parse(&body).with_context(|| format!("unable to read the feed at {}", url))
+}
+
+/// The chapters at `url`, in order.
+pub async fn chapters(url: &str) -> Result<Vec<Chapter>> {
+    let file: Chapters = reqwest::get(url)
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+        .with_context(|| format!("unable to read the chapters at {}", url))?;
+    let mut chapters = file.chapters;
+    chapters.sort_by(|a, b| a.start.total_cmp(&b.start));
+    Ok(chapters)
+}
+
+pub fn parse(xml: &str) -> Result<Feed> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut feed = Feed::default();
+    let mut item: Option<Item> = None;
+    // tags still waiting for their text
+    let mut funding: Option<Funding> = None;
+    let mut soundbite: Option<Soundbite> = None;
+    // an alternate enclosure, until one of its sources gives it a url
+    let mut alternate: Option<Enclosure> = None;
+    // a live item is read like any other item, these are the extra bits
+    let mut live: Option<(LiveStatus, String, Option<String>)> = None;
+    let mut in_title = false;
+    let mut in_link = false;
+    let mut channel_field: Option<ChannelField> = None;
+    // <image> has a <title> and <link> of its own
+    let mut in_image = false;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) if item.is_none() && channel_tag(e.name().as_ref(), in_image).is_some() => {
+                let field = channel_tag(e.name().as_ref(), in_image);
+                channel_field = field.filter(|field| feed.channel.field(*field).is_empty());
+                if e.name().as_ref() == b"itunes:image" {
+                    set_artwork(&mut feed.channel, &e);
+                }
+            }
+            Event::Start(e) => match e.name().as_ref() {
+                b"image" => in_image = true,
+                b"item" => item = Some(Item::default()),
+                b"podcast:liveItem" => {
+                    let status = match attribute(&e, "status").as_deref() {
+                        Some("live") => LiveStatus::Live,
+                        Some("ended") => LiveStatus::Ended,
+                        _ => LiveStatus::Pending,
+                    };
+                    let start = attribute(&e, "start").unwrap_or_default();
+                    live = Some((status, start, attribute(&e, "end")));
+                    item = Some(Item::default());
+                }
+                b"title" => in_title = item.is_some(),
+                b"link" => in_link = item.is_some(),
+                b"podcast:funding" if item.is_none() => {
+                    funding = attribute(&e, "url").map(|url| Funding {
+                        url,
+                        label: String::new(),
+                    })
+                }
+                b"podcast:soundbite" if item.is_some() => soundbite = soundbite_from(&e),
+                b"podcast:alternateEnclosure" if item.is_some() => {
+                    alternate = Some(Enclosure {
+                        url: String::new(),
+                        mime: attribute(&e, "type").unwrap_or_default(),
+                        bitrate: attribute(&e, "bitrate").and_then(|b| b.parse().ok()),
+                        title: attribute(&e, "title"),
+                    })
+                }
+                b"podcast:source" => add_source(&e, &mut alternate),
+                b"podcast:transcript" => add_transcript(&e, &mut item),
+                _ => {}
+            },
+            Event::Empty(e) => match (e.name().as_ref(), item.as_mut()) {
+                (b"itunes:image", None) => set_artwork(&mut feed.channel, &e),
+                (b"podcast:funding", None) => {
+                    if let Some(url) = attribute(&e, "url") {
+                        feed.funding.push(Funding {
+                            label: url.clone(),
+                            url,
+                        });
+                    }
+                }
+                (b"podcast:soundbite", Some(item)) => item.soundbites.extend(soundbite_from(&e)),
+                (b"enclosure", Some(item)) => item.enclosure = attribute(&e, "url"),
+                (b"podcast:chapters", Some(item))
+                    if attribute(&e, "type").is_some_and(|t| t.starts_with("application/json")) =>
+                {
+                    item.chapters = attribute(&e, "url")
+                }
+                (b"podcast:source", Some(_)) => add_source(&e, &mut alternate),
+                (b"podcast:transcript", Some(_)) => add_transcript(&e, &mut item),
+                _ => {}
+            },
+            Event::Text(text) => match channel_field {
+                Some(field) => feed.channel.field(field).push_str(&text.unescape()?),
+                None if in_link => push_link(&text.unescape()?, &mut item),
+                None => push_text(&text.unescape()?, in_title, &mut item, &mut funding, &mut soundbite),
+            },
+            Event::CData(text) => match channel_field {
+                Some(field) => feed.channel.field(field).push_str(&String::from_utf8_lossy(&text)),
+                None if in_link => push_link(&String::from_utf8_lossy(&text), &mut item),
+                None => push_text(
+                    &String::from_utf8_lossy(&text),
+                    in_title,
+                    &mut item,
+                    &mut funding,
+                    &mut soundbite,
+                ),
+            },
+            Event::End(_) if channel_field.is_some() => channel_field = None,
+            Event::End(e) => match e.name().as_ref() {
+                b"image" => in_image = false,
+                b"item" => feed.items.extend(item.take()),
+                b"podcast:liveItem" => {
+                    if let (Some(item), Some((status, start, end))) = (item.take(), live.take()) {
+                        feed.live.push(LiveItem {
+                            title: item.title,
+                            status,
+                            start,
+                            end,
+                            url: item.enclosure,
+                        });
+                    }
+                }
+                b"title" => in_title = false,
+                b"link" => in_link = false,
+                b"podcast:alternateEnclosure" => {
+                    let alternate = alternate.take().filter(|a| !a.url.is_empty());
+                    if let (Some(item), Some(alternate)) = (item.as_mut(), alternate) {
+                        item.alternates.push(alternate);
+                    }
+                }
+                b"podcast:soundbite" => {
+                    if let (Some(item), Some(soundbite)) = (item.as_mut(), soundbite.take()) {
+                        item.soundbites.push(soundbite);
+                    }
+                }
+                b"podcast:funding" => {
+                    if let Some(mut funding) = funding.take() {
+                        if funding.label.is_empty() {
+                            funding.label = funding.url.clone();
+                        }
+                        feed.funding.push(funding);
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(feed)
+}
+
+// text goes to whichever open tag is waiting for it
+fn push_text(
+    text: &str,
+    in_title: bool,
+    item: &mut Option<Item>,
+    funding: &mut Option<Funding>,
+    soundbite: &mut Option<Soundbite>,
+) {
+    if let Some(soundbite) = soundbite.as_mut() {
+        soundbite.title.push_str(text);
+    } else if let Some(funding) = funding.as_mut() {
+        funding.label.push_str(text);
+    } else if let Some(item) = item.as_mut().filter(|_| in_title) {
+        item.title.push_str(text);
+    }
+}
+
+fn push_link(text: &str, item: &mut Option<Item>) {
+    if let Some(item) = item.as_mut() {
+        item.link.get_or_insert_with(String::new).push_str(text);
+    }
+}
+
+// the channel field a tag outside any item is for
+fn channel_tag(name: &[u8], in_image: bool) -> Option<ChannelField> {
+    match (name, in_image) {
+        (b"url", true) => Some(ChannelField::Artwork),
+        (_, true) => None,
+        (b"title", _) => Some(ChannelField::Title),
+        (b"itunes:author", _) => Some(ChannelField::Author),
+        (b"description" | b"itunes:summary", _) => Some(ChannelField::Description),
+        (b"link", _) => Some(ChannelField::Website),
+        (b"itunes:image", _) => Some(ChannelField::Artwork),
+        _ => None,
+    }
+}
+
+// <itunes:image href="...">, which wins over <image><url>
+fn set_artwork(channel: &mut Channel, tag: &BytesStart) {
+    if let Some(href) = attribute(tag, "href") {
+        channel.artwork = href;
+    }
+}
+
+// the first source that can be streamed, torrents and ipfs are skipped
+fn add_source(tag: &BytesStart, alternate: &mut Option<Enclosure>) {
+    let Some(alternate) = alternate.as_mut().filter(|a| a.url.is_empty()) else {
+        return;
+    };
+    if let Some(uri) = attribute(tag, "uri")
+        .filter(|uri| uri.starts_with("http://") || uri.starts_with("https://"))
+    {
+        alternate.url = uri;
+    }
+}
+
+fn add_transcript(tag: &BytesStart, item: &mut Option<Item>) {
+    if let (Some(item), Some(url)) = (item.as_mut(), attribute(tag, "url")) {
+        item.transcripts.push(Transcript {
+            url,
+            mime: attribute(tag, "type").unwrap_or_default(),
+        });
+    }
+}
+
+// startTime and duration are required, the title isn't
+fn soundbite_from(tag: &BytesStart) -> Option<Soundbite> {
+    let seconds = |name| attribute(tag, name).and_then(|value| value.parse::<f64>().ok());
+    Some(Soundbite {
+        start: seconds("startTime")?.max(0.0),
+        duration: seconds("duration").filter(|duration| *duration > 0.0)?,
+        title: String::new(),
+    })
+}
+
+fn attribute(tag: &BytesStart, name: &str) -> Option<String> {
+    tag.try_get_attribute(name)
+        .ok()
+        .flatten()
+        .and_then(|attr| attr.unescape_value().ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
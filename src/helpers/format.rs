@@ -0,0 +1,197 @@
+// Durations and dates the way the ui shows them, kept in one place so every
+// page agrees: clocks for positions and lengths, "1h 25m" for listening
+// totals, and publication dates in the format the settings ask for, or else
+// the one usual where the locale says the user is.
+
+use std::env;
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::Local;
+
+use super::gen_funcs;
+
+/// Shown for a length that isn't known.
+pub const UNKNOWN: &str = "--:--";
+
+const MINUTE: i64 = 60;
+const HOUR: i64 = 60 * MINUTE;
+const DAY: i64 = 24 * HOUR;
+
+/// Seconds as m:ss, h:mm:ss once there are hours and 1d 02:03:04 once there
+/// are days. Negative seconds can't be right, they show as unknown.
+pub fn clock(seconds: i64) -> String {
+    if seconds < 0 {
+        return String::from(UNKNOWN);
+    }
+    let (d, h, m, s) = (seconds / DAY, seconds % DAY / HOUR, seconds % HOUR / MINUTE, seconds % MINUTE);
+    match (d, h) {
+        (0, 0) => format!("{}:{:02}", m, s),
+        (0, _) => format!("{}:{:02}:{:02}", h, m, s),
+        _ => format!("{}d {:02}:{:02}:{:02}", d, h, m, s),
+    }
+}
+
+/// An episode's length, which the server sends as 0 when it doesn't know it.
+pub fn length(seconds: i64) -> String {
+    match seconds {
+        ..=0 => String::from(UNKNOWN),
+        _ => clock(seconds),
+    }
+}
+
+/// Minutes as "45m", "1h 25m", or "2d 3h" once there are days.
+pub fn minutes(minutes: u64) -> String {
+    let (days, hours) = (minutes / (24 * 60), minutes / 60 % 24);
+    match (days, hours) {
+        (0, 0) => format!("{}m", minutes),
+        (0, _) => format!("{}h {}m", hours, minutes % 60),
+        _ => format!("{}d {}h", days, hours),
+    }
+}
+
+/// The usual way of writing a date in `locale`, e.g. en_US.UTF-8.
+pub fn date_format_for(locale: &str) -> &'static str {
+    let locale = locale.split(['.', '@']).next().unwrap_or_default();
+    let language = locale.split('_').next().unwrap_or_default();
+    match (locale, language) {
+        ("" | "C" | "POSIX", _) => "%Y-%m-%d",
+        ("en_US" | "en_PH", _) => "%m/%d/%Y",
+        (_, "ja" | "zh" | "ko") => "%Y/%m/%d",
+        (_, "sv" | "lt" | "hu" | "mn") => "%Y-%m-%d",
+        (_, "de" | "ru" | "pl" | "cs" | "sk" | "fi" | "nb" | "nn" | "da" | "tr" | "uk" | "ro") => "%d.%m.%Y",
+        _ => "%d/%m/%Y",
+    }
+}
+
+/// The date format usual for the locale set in the environment.
+pub fn locale_date_format() -> &'static str {
+    let locale = ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+    date_format_for(&locale)
+}
+
+/// Whether `format` is one chrono can write dates in.
+pub fn valid_date_format(format: &str) -> bool {
+    !StrftimeItems::new(format).any(|item| matches!(item, Item::Error))
+}
+
+/// `date`, a publication date as the server sends it, in `format` and local
+/// time. Dates that can't be read are shown as they came.
+pub fn date(date: &str, format: &str) -> String {
+    match gen_funcs::pub_date(date) {
+        Some(parsed) => parsed.with_timezone(&Local).format(format).to_string(),
+        None => date.trim().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // back to seconds, from any of the shapes clock writes
+    fn unclock(clock: &str) -> i64 {
+        let (days, rest) = match clock.split_once("d ") {
+            Some((days, rest)) => (days.parse::<i64>().unwrap(), rest),
+            None => (0, clock),
+        };
+        days * DAY + rest.split(':').fold(0, |total, part| total * 60 + part.parse::<i64>().unwrap())
+    }
+
+    #[test]
+    fn clock_shapes() {
+        assert_eq!(clock(0), "0:00");
+        assert_eq!(clock(59), "0:59");
+        assert_eq!(clock(61), "1:01");
+        assert_eq!(clock(HOUR), "1:00:00");
+        assert_eq!(clock(DAY - 1), "23:59:59");
+        assert_eq!(clock(DAY), "1d 00:00:00");
+        assert_eq!(clock(2 * DAY + 3 * HOUR + 4 * MINUTE + 5), "2d 03:04:05");
+        assert_eq!(clock(-1), UNKNOWN);
+    }
+
+    #[test]
+    fn clock_round_trips() {
+        for seconds in (0..3 * DAY).step_by(7).chain([i64::from(u16::MAX), 400 * DAY]) {
+            assert_eq!(unclock(&clock(seconds)), seconds, "{}", clock(seconds));
+        }
+    }
+
+    #[test]
+    fn clock_sorts_like_seconds_within_a_unit() {
+        // same shape, so the same width, means longer is later as text too
+        for seconds in (HOUR..10 * HOUR).step_by(13) {
+            let (a, b) = (clock(seconds), clock(seconds + 1));
+            if a.len() == b.len() {
+                assert!(a < b, "{} {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn negative_and_unknown() {
+        for seconds in [i64::MIN, -DAY, -1] {
+            assert_eq!(clock(seconds), UNKNOWN);
+            assert_eq!(length(seconds), UNKNOWN);
+        }
+        assert_eq!(length(0), UNKNOWN);
+        assert_eq!(length(90), "1:30");
+    }
+
+    #[test]
+    fn minutes_shapes() {
+        assert_eq!(minutes(0), "0m");
+        assert_eq!(minutes(45), "45m");
+        assert_eq!(minutes(85), "1h 25m");
+        assert_eq!(minutes(24 * 60), "1d 0h");
+        assert_eq!(minutes(2 * 24 * 60 + 3 * 60 + 59), "2d 3h");
+    }
+
+    #[test]
+    fn minutes_never_overstate() {
+        // days and hours, or hours and minutes, add up to no more than given
+        for total in 0..5 * 24 * 60 {
+            let shown: u64 = minutes(total)
+                .split(' ')
+                .map(|part| {
+                    let (number, unit) = part.split_at(part.len() - 1);
+                    let number: u64 = number.parse().unwrap();
+                    match unit {
+                        "d" => number * 24 * 60,
+                        "h" => number * 60,
+                        _ => number,
+                    }
+                })
+                .sum();
+            assert!(shown <= total && total - shown < 60, "{} {}", total, minutes(total));
+        }
+    }
+
+    #[test]
+    fn locale_formats() {
+        assert_eq!(date_format_for(""), "%Y-%m-%d");
+        assert_eq!(date_format_for("C.UTF-8"), "%Y-%m-%d");
+        assert_eq!(date_format_for("en_US.UTF-8"), "%m/%d/%Y");
+        assert_eq!(date_format_for("en_GB.UTF-8"), "%d/%m/%Y");
+        assert_eq!(date_format_for("de_DE.UTF-8@euro"), "%d.%m.%Y");
+        assert_eq!(date_format_for("ja_JP.UTF-8"), "%Y/%m/%d");
+        assert_eq!(date_format_for("sv_SE"), "%Y-%m-%d");
+        for locale in ["", "en_US", "fr_FR", "de_DE", "ja_JP", "pt_BR"] {
+            assert!(valid_date_format(date_format_for(locale)));
+        }
+    }
+
+    #[test]
+    fn dates() {
+        // midday, so it's the same day in any time zone
+        assert_eq!(date("2024-03-05T12:00:00Z", "%Y-%m-%d"), "2024-03-05");
+        assert_eq!(date("2024-03-05T12:00:00", "%d.%m.%Y"), "05.03.2024");
+        assert_eq!(date("Tue, 05 Mar 2024 12:00:00 +0000", "%m/%d/%Y"), "03/05/2024");
+        assert_eq!(date(" sometime last spring ", "%Y"), "sometime last spring");
+        assert_eq!(date("", "%Y"), "");
+        assert!(valid_date_format("%e %b %Y"));
+        assert!(!valid_date_format("%Q"));
+    }
+}
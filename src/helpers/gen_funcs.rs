@@ -9,6 +9,7 @@ use std::sync::{Arc, Mutex};
 use glob::{glob_with, MatchOptions};
 use lofty::{Accessor, Probe, TaggedFileExt};
 
+use chrono::{DateTime, NaiveDateTime, Utc};
 use log::error;
 use crate::requests::{PinepodsEpisodes, PinepodsPodcasts};
 
@@ -17,6 +18,153 @@ pub fn audio_display(episode: &PinepodsEpisodes) -> String {
     return format!("{:?} - {}", episode.PodcastName, episode.EpisodeTitle);
 }
 
+// a different order every time, nothing here needs a good random number
+pub fn shuffle<T>(items: &mut [T]) {
+    use std::collections::hash_map::RandomState;
+    use std::hash::BuildHasher;
+
+    let random = RandomState::new();
+    for i in (1..items.len()).rev() {
+        let j = random.hash_one(i) as usize % (i + 1);
+        items.swap(i, j);
+    }
+}
+
+// publication dates as Pinepods sends them, an ISO 8601 timestamp with or
+// without a zone, or whatever the feed had
+pub fn pub_date(date: &str) -> Option<DateTime<Utc>> {
+    let date = date.trim();
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(date) {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    if let Ok(parsed) = DateTime::parse_from_rfc2822(date) {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S%.f"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(date, format).ok())
+        .map(|naive| naive.and_utc())
+}
+
+/// Bytes free for this user on the disk holding `path`, None when it can't
+/// be told.
+#[cfg(unix)]
+pub fn free_space(path: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    // SAFETY: statvfs is plain old data, all zeroes is a valid value
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: path is nul terminated and stat lives for the whole call
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    // the field types differ between platforms
+    #[allow(clippy::unnecessary_cast)]
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(windows)]
+pub fn free_space(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let path: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut free = 0u64;
+    // SAFETY: path is nul terminated, the totals that aren't wanted may be null
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(path.as_ptr(), &mut free, std::ptr::null_mut(), std::ptr::null_mut())
+    };
+    (ok != 0).then_some(free)
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn free_space(_path: &Path) -> Option<u64> {
+    None
+}
+
+// in the default browser. Only web links, the urls come from feeds, and the
+// system's opener would launch a file: or any other scheme's handler just
+// the same. Not through cmd on Windows, which would run what follows a & in
+// the url.
+pub fn open_link(url: &str) -> std::io::Result<()> {
+    let scheme = url.split_once("://").map(|(scheme, _)| scheme.to_ascii_lowercase());
+    if !matches!(scheme.as_deref(), Some("http" | "https")) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "only http and https links are opened",
+        ));
+    }
+
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = std::process::Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = std::process::Command::new("open");
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut command = std::process::Command::new("xdg-open");
+
+    command
+        .arg(url)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+// `path` picked out in the file manager, or on Linux and the like, where
+// there's no one way of asking for that, the folder it's in opened
+pub fn reveal_file(path: &Path) -> std::io::Result<()> {
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = std::process::Command::new("explorer");
+        command.arg(format!("/select,{}", path.display()));
+        command
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut command = std::process::Command::new("open");
+        command.arg("-R").arg(path);
+        command
+    };
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut command = {
+        let mut command = std::process::Command::new("xdg-open");
+        command.arg(path.parent().unwrap_or(path));
+        command
+    };
+
+    command
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+// over ssh a file manager would open on the other computer, if at all
+pub fn remote_session() -> bool {
+    ["SSH_CONNECTION", "SSH_TTY"]
+        .iter()
+        .any(|name| std::env::var_os(name).is_some_and(|value| !value.is_empty()))
+}
+
+// through the terminal (OSC 52), which also works over ssh; terminals that
+// don't support it ignore it
+pub fn copy_to_clipboard(text: &str) -> std::io::Result<()> {
+    use base64::Engine;
+    use std::io::Write;
+
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", encoded)?;
+    stdout.flush()
+}
+
 // scans folder for valid files, returns matches
 pub async fn scan_folder(pinepods_values: &Arc<Mutex<super::requests::ReqwestValues>>) -> Vec<PinepodsPodcasts> {
 
@@ -27,9 +175,11 @@ pub async fn scan_folder(pinepods_values: &Arc<Mutex<super::requests::ReqwestVal
 
     match result {
         Ok(podcasts) => {
+            super::errors::api_ok();
             podcasts
         },
         Err(e) => {
+            super::errors::api_error("return_pods", &e);
             Vec::new() // return empty list on error
         }
     }
@@ -0,0 +1,107 @@
+// Which characters the ui draws with. The default box drawing and block
+// characters are missing from, or the wrong width in, some terminal fonts,
+// which throws the borders out of line, so there's a plain ASCII set too.
+// Only borders, the tab divider, the listening stats' bars, the queue's fold
+// markers, the bullets in show notes and the spinner while a page loads use
+// anything else, the rest of the ui is ASCII text already.
+
+use std::time::Duration;
+
+use ratatui::symbols::{bar, border, line};
+use ratatui::widgets::BorderType;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Glyphs {
+    #[default]
+    Unicode,
+    Ascii,
+}
+
+const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+// from full down to empty
+const ASCII_BARS: bar::Set = bar::Set {
+    full: "#",
+    seven_eighths: "#",
+    three_quarters: "=",
+    five_eighths: "=",
+    half: "-",
+    three_eighths: "-",
+    one_quarter: ".",
+    one_eighth: ".",
+    empty: " ",
+};
+
+impl Glyphs {
+    /// "unicode" or "ascii".
+    pub fn parse(value: &str) -> Option<Glyphs> {
+        match value.trim().to_lowercase().as_str() {
+            "unicode" => Some(Glyphs::Unicode),
+            "ascii" => Some(Glyphs::Ascii),
+            _ => None,
+        }
+    }
+
+    /// The characters for a border of `border_type`, every type looks the
+    /// same in ASCII.
+    pub fn border(self, border_type: BorderType) -> border::Set {
+        match self {
+            Glyphs::Unicode => border_type.to_border_set(),
+            Glyphs::Ascii => ASCII_BORDER,
+        }
+    }
+
+    pub fn bars(self) -> bar::Set {
+        match self {
+            Glyphs::Unicode => bar::NINE_LEVELS,
+            Glyphs::Ascii => ASCII_BARS,
+        }
+    }
+
+    /// Between tab titles.
+    pub fn divider(self) -> &'static str {
+        match self {
+            Glyphs::Unicode => line::VERTICAL,
+            Glyphs::Ascii => "|",
+        }
+    }
+
+    /// Before a group's header in the queue, pointing right when it's folded.
+    pub fn fold(self, folded: bool) -> &'static str {
+        match (self, folded) {
+            (Glyphs::Unicode, true) => "▸",
+            (Glyphs::Unicode, false) => "▾",
+            (Glyphs::Ascii, true) => "+",
+            (Glyphs::Ascii, false) => "-",
+        }
+    }
+
+    /// The spinner's look `elapsed` into loading, it turns ten times a
+    /// second.
+    pub fn spinner(self, elapsed: Duration) -> &'static str {
+        const BRAILLE: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+        const ASCII: [&str; 4] = ["|", "/", "-", "\\"];
+        let frame = (elapsed.as_millis() / 100) as usize;
+        match self {
+            Glyphs::Unicode => BRAILLE[frame % BRAILLE.len()],
+            Glyphs::Ascii => ASCII[frame % ASCII.len()],
+        }
+    }
+
+    /// Before each item of a list in show notes.
+    pub fn bullet(self) -> &'static str {
+        match self {
+            Glyphs::Unicode => "•",
+            Glyphs::Ascii => "*",
+        }
+    }
+}
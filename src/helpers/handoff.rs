@@ -0,0 +1,53 @@
+// Carrying on here with what was playing on another device, and the other way
+// round. The Pinepods server doesn't know about devices or sessions, only the
+// account's listening history and where each episode was left off, so "what's
+// playing elsewhere" is the most recently listened episode that isn't
+// finished. Handing off is the reverse: save the position now rather than at
+// the next report, and stop, so the web or mobile app picks up from there.
+
+use anyhow::Result;
+
+use super::gen_funcs;
+use super::requests::{HistoryEntry, PinepodsEpisodes, ReqwestValues};
+
+// this close to the end counts as finished, outros and ads included
+const FINISHED_WITHIN: i64 = 30;
+
+/// The episode to carry on with, ListenDuration being where it was left off.
+pub async fn elsewhere(values: &ReqwestValues, playing_here: Option<&str>) -> Result<Option<PinepodsEpisodes>> {
+    let history = values.user_history().await?;
+    Ok(latest(&history, playing_here))
+}
+
+// newest first, skipping what this device is playing itself
+fn latest(history: &[HistoryEntry], playing_here: Option<&str>) -> Option<PinepodsEpisodes> {
+    history
+        .iter()
+        .filter(|entry| entry.episode_url.is_some() && entry.episode_url.as_deref() != playing_here)
+        .filter(|entry| !finished(entry))
+        .max_by_key(|entry| entry.listen_date.as_deref().and_then(gen_funcs::pub_date))
+        .map(episode)
+}
+
+fn finished(entry: &HistoryEntry) -> bool {
+    match (entry.listen_duration, entry.episode_duration) {
+        (Some(position), Some(duration)) if duration > 0 => position >= duration - FINISHED_WITHIN,
+        _ => false,
+    }
+}
+
+/// The episode `entry` is for, with as much as the history has on it.
+pub fn episode(entry: &HistoryEntry) -> PinepodsEpisodes {
+    PinepodsEpisodes {
+        PodcastName: entry.podcast_name.clone(),
+        EpisodeTitle: entry.episode_title.clone(),
+        EpisodePubDate: String::new(),
+        EpisodeDescription: String::new(),
+        EpisodeArtwork: entry.episode_artwork.clone().unwrap_or_default(),
+        EpisodeURL: entry.episode_url.clone().unwrap_or_default(),
+        EpisodeDuration: entry.episode_duration.unwrap_or(0),
+        ListenDuration: entry.listen_duration,
+        EpisodeID: entry.episode_id,
+        PodcastID: None,
+    }
+}
@@ -0,0 +1,391 @@
+// Show notes, and the descriptions feeds write in HTML, made into styled text
+// for the terminal: paragraphs and line breaks, bold and italic, headings and
+// bullet or numbered lists. Anything else, images, tables and the like, is
+// left as just its text, and scripts and styles are left out altogether.
+//
+// Links are numbered, [1], [2] and so on, where they are in the text, and
+// kept aside in that order so they can be opened by number. Bare urls in
+// the text count as links too. Descriptions without any tags are taken as
+// plain text, keeping their line breaks.
+
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+
+pub struct Rendered {
+    pub lines: Vec<Line<'static>>,
+    /// by their number, from 1
+    pub links: Vec<String>,
+}
+
+/// `html` as lines, with list items marked by `bullet`.
+pub fn render(html: &str, bullet: &str) -> Rendered {
+    let mut renderer = Renderer {
+        bullet: bullet.to_string(),
+        plain: !html.contains('<'),
+        ..Renderer::default()
+    };
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(at) = rest.find('<') else {
+            renderer.text(rest);
+            break;
+        };
+        renderer.text(&rest[..at]);
+        rest = &rest[at..];
+        // a < that doesn't start a tag is just text
+        if !rest[1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!') {
+            renderer.text("<");
+            rest = &rest[1..];
+            continue;
+        }
+        let (tag, after) = match rest.strip_prefix("<!--") {
+            Some(comment) => ("", comment.find("-->").map_or("", |end| &comment[end + 3..])),
+            None => match rest.find('>') {
+                Some(end) => (&rest[1..end], &rest[end + 1..]),
+                None => (&rest[1..], ""),
+            },
+        };
+        renderer.tag(tag);
+        rest = after;
+    }
+    renderer.finish()
+}
+
+#[derive(Default)]
+struct Renderer {
+    bullet: String,
+    plain: bool,
+    lines: Vec<Line<'static>>,
+    spans: Vec<Span<'static>>,
+    // the word being read, and whether a space goes before it
+    word: String,
+    space: bool,
+    bold: usize,
+    italic: usize,
+    // the lists inside one another, numbered ones with the next item's number
+    lists: Vec<Option<usize>>,
+    // where the link being read goes
+    link: Option<String>,
+    links: Vec<String>,
+    // inside a script or style
+    hidden: usize,
+}
+
+impl Renderer {
+    fn text(&mut self, text: &str) {
+        if self.hidden > 0 {
+            return;
+        }
+        for c in decode(text).chars() {
+            match c {
+                '\n' if self.plain => self.break_line(true),
+                c if c.is_whitespace() => {
+                    self.end_word();
+                    self.space = true;
+                }
+                c => self.word.push(c),
+            }
+        }
+    }
+
+    fn tag(&mut self, tag: &str) {
+        self.end_word();
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match (name.as_str(), closing) {
+            ("script" | "style", false) => self.hidden += 1,
+            ("script" | "style", true) => self.hidden = self.hidden.saturating_sub(1),
+            ("b" | "strong", false) => self.bold += 1,
+            ("b" | "strong", true) => self.bold = self.bold.saturating_sub(1),
+            ("i" | "em", false) => self.italic += 1,
+            ("i" | "em", true) => self.italic = self.italic.saturating_sub(1),
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", false) => {
+                self.paragraph();
+                self.bold += 1;
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", true) => {
+                self.bold = self.bold.saturating_sub(1);
+                self.paragraph();
+            }
+            ("p" | "div" | "blockquote" | "pre" | "table" | "section", _) => self.paragraph(),
+            ("br", _) => self.break_line(true),
+            ("tr", _) => self.break_line(false),
+            ("ul" | "ol", false) => {
+                if self.lists.is_empty() {
+                    self.paragraph();
+                }
+                self.lists.push((name == "ol").then_some(1));
+            }
+            ("ul" | "ol", true) => {
+                self.lists.pop();
+                match self.lists.is_empty() {
+                    true => self.paragraph(),
+                    false => self.break_line(false),
+                }
+            }
+            ("li", false) => {
+                self.break_line(false);
+                let indent = "  ".repeat(self.lists.len().saturating_sub(1));
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => format!("{} ", self.bullet),
+                };
+                self.spans.push(Span::raw(format!("{}{}", indent, marker)));
+            }
+            ("li", true) => self.break_line(false),
+            ("a", false) => {
+                let lower = tag.to_ascii_lowercase();
+                self.link = attribute(tag, &lower, "href")
+                    .map(decode)
+                    .filter(|href| web_link(href));
+            }
+            ("a", true) => {
+                if let Some(url) = self.link.take() {
+                    self.number(url);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn style(&self) -> Style {
+        let mut style = Style::default();
+        if self.bold > 0 {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        if self.italic > 0 {
+            style = style.add_modifier(Modifier::ITALIC);
+        }
+        if self.link.is_some() {
+            style = style.add_modifier(Modifier::UNDERLINED);
+        }
+        style
+    }
+
+    fn end_word(&mut self) {
+        if self.word.is_empty() {
+            return;
+        }
+        let word = std::mem::take(&mut self.word);
+        let spaced = self.space && self.spans.last().is_some_and(|span| !span.content.ends_with(' '));
+        self.space = false;
+        let text = match spaced {
+            true => format!(" {}", word),
+            false => word.clone(),
+        };
+        self.spans.push(Span::styled(text, self.style()));
+        if self.link.is_none() && web_link(&word) {
+            let url = word.trim_end_matches(['.', ',', ';', ':', '!', '?', ')']);
+            self.number(url.to_string());
+        }
+    }
+
+    // the link's number after its text, the same number for the same link
+    fn number(&mut self, url: String) {
+        let number = match self.links.iter().position(|link| *link == url) {
+            Some(i) => i + 1,
+            None => {
+                self.links.push(url);
+                self.links.len()
+            }
+        };
+        self.spans.push(Span::styled(
+            format!("[{}]", number),
+            Style::default().add_modifier(Modifier::DIM),
+        ));
+    }
+
+    // onto a new line, a blank one too if `blank` and the line is empty
+    fn break_line(&mut self, blank: bool) {
+        self.end_word();
+        self.space = false;
+        if !self.spans.is_empty() {
+            self.lines.push(Line::from(std::mem::take(&mut self.spans)));
+        } else if blank && self.lines.last().is_some_and(|line| !line.spans.is_empty()) {
+            self.lines.push(Line::default());
+        }
+    }
+
+    // a blank line between this and whatever's next
+    fn paragraph(&mut self) {
+        self.break_line(false);
+        if self.lines.last().is_some_and(|line| !line.spans.is_empty()) {
+            self.lines.push(Line::default());
+        }
+    }
+
+    fn finish(mut self) -> Rendered {
+        self.break_line(false);
+        while self.lines.last().is_some_and(|line| line.spans.is_empty()) {
+            self.lines.pop();
+        }
+        Rendered {
+            lines: self.lines,
+            links: self.links,
+        }
+    }
+}
+
+// only web links are numbered, not javascript:, file: or the page's own #anchors
+fn web_link(url: &str) -> bool {
+    let lower = url.get(..8).unwrap_or(url).to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// The value of `name` in a tag, quoted or not, found in its lowercase copy.
+pub fn attribute<'a>(tag: &'a str, lower: &str, name: &str) -> Option<&'a str> {
+    let at = lower.match_indices(name).find_map(|(at, _)| {
+        let before = lower[..at].chars().last()?;
+        let rest = lower[at + name.len()..].trim_start();
+        (before.is_whitespace() && rest.starts_with('=')).then(|| at + name.len())
+    })?;
+    let rest = tag[at..].trim_start().strip_prefix('=')?.trim_start();
+    match rest.chars().next()? {
+        quote @ ('"' | '\'') => rest[1..].split(quote).next(),
+        _ => rest.split(char::is_whitespace).next(),
+    }
+}
+
+/// `text` with its entities, &amp;, &#8217; and the like, made characters.
+pub fn decode(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find('&') {
+        decoded.push_str(&rest[..at]);
+        rest = &rest[at..];
+        let entity = rest[1..].find(';').filter(|end| *end <= 10).and_then(|end| {
+            let c = match &rest[1..end + 1] {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                "hellip" => Some('…'),
+                "mdash" => Some('—'),
+                "ndash" => Some('–'),
+                "rsquo" => Some('’'),
+                "lsquo" => Some('‘'),
+                "rdquo" => Some('”'),
+                "ldquo" => Some('“'),
+                code => {
+                    let number = code.strip_prefix('#')?;
+                    let number = match number.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => number.parse().ok()?,
+                    };
+                    char::from_u32(number)
+                }
+            };
+            c.map(|c| (c, end + 2))
+        });
+        match entity {
+            Some((c, length)) => {
+                decoded.push(c);
+                rest = &rest[length..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // each line's text, the styling left out
+    fn text(rendered: &Rendered) -> Vec<String> {
+        rendered
+            .lines
+            .iter()
+            .map(|line| line.spans.iter().map(|span| span.content.as_ref()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn links_numbered_in_order() {
+        let rendered = render(
+            r#"<p>See <a href="https://one.example">the first</a> and <a href='http://two.example/page'>the second</a>.</p>"#,
+            "-",
+        );
+        assert_eq!(rendered.links, ["https://one.example", "http://two.example/page"]);
+        assert_eq!(text(&rendered), ["See the first[1] and the second[2]."]);
+    }
+
+    #[test]
+    fn same_link_same_number() {
+        let rendered = render(
+            r#"<a href="https://a.example">a</a> <a href="https://b.example">b</a> <a href="https://a.example">a again</a>"#,
+            "-",
+        );
+        assert_eq!(rendered.links, ["https://a.example", "https://b.example"]);
+        assert_eq!(text(&rendered), ["a[1] b[2] a again[1]"]);
+    }
+
+    #[test]
+    fn bare_urls_are_links() {
+        let rendered = render("<p>Sponsor: https://sponsor.example/code, and more.</p>", "-");
+        assert_eq!(rendered.links, ["https://sponsor.example/code"]);
+        assert_eq!(text(&rendered), ["Sponsor: https://sponsor.example/code,[1] and more."]);
+    }
+
+    #[test]
+    fn only_web_links() {
+        let rendered = render(
+            r##"<a href="javascript:alert(1)">x</a> <a href="#notes">y</a> <a href="file:///etc/passwd">z</a> <a href="HTTPS://UPPER.example">w</a>"##,
+            "-",
+        );
+        assert_eq!(rendered.links, ["HTTPS://UPPER.example"]);
+        assert_eq!(text(&rendered), ["x y z w[1]"]);
+    }
+
+    #[test]
+    fn entities_in_links() {
+        let rendered = render(r#"<a href="https://a.example/?x=1&amp;y=2">a</a>"#, "-");
+        assert_eq!(rendered.links, ["https://a.example/?x=1&y=2"]);
+    }
+
+    #[test]
+    fn plain_text_keeps_line_breaks() {
+        let rendered = render("First line\nSecond line\n\nAfter a gap https://plain.example", "-");
+        assert_eq!(
+            text(&rendered),
+            ["First line", "Second line", "", "After a gap https://plain.example[1]"]
+        );
+        assert_eq!(rendered.links, ["https://plain.example"]);
+    }
+
+    #[test]
+    fn html_ignores_line_breaks() {
+        let rendered = render("<p>One\ntwo</p><p>Three</p>", "-");
+        assert_eq!(text(&rendered), ["One two", "", "Three"]);
+    }
+
+    #[test]
+    fn lists_and_hidden_text() {
+        let rendered = render(
+            "<style>p { color: red }</style><ul><li>a</li><li>b</li></ul><ol><li>first</li><li>second</li></ol>",
+            "*",
+        );
+        assert_eq!(text(&rendered), ["* a", "* b", "", "1. first", "2. second"]);
+    }
+
+    #[test]
+    fn a_lone_angle_bracket_is_text() {
+        let rendered = render("<p>3 < 4 &amp; 5 > 2</p>", "-");
+        assert_eq!(text(&rendered), ["3 < 4 & 5 > 2"]);
+    }
+}
@@ -0,0 +1,182 @@
+// Multi-key shortcuts (gg, dd, za, 5j) on top of single key presses.
+//
+// A key that starts a sequence is held back until the next key arrives or it
+// times out, vim style. If no sequence was finished it's handled on its own.
+// Digits typed first are a count for the key that follows.
+//
+// How the keys move the selection of a list is here too, so every list moves
+// the same way: j / k and the arrows, gg / G, Home / End, Page Up / Page Down
+// and Ctrl + D / U for half a page, with counts.
+
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyModifiers};
+
+use super::queue::Queue;
+use super::stateful_list::StatefulList;
+use super::stateful_table::StatefulTable;
+
+const SEQUENCES: [&str; 6] = ["gg", "dd", "za", "zi", "zM", "zR"];
+const TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// A single key, with the count typed before it (1 without one).
+    Code(KeyCode, usize),
+    /// A letter with Ctrl held, and the count typed before it.
+    Ctrl(char, usize),
+    Sequence(&'static str),
+}
+
+impl Key {
+    /// A key press on its own, without counts or sequences, for where those
+    /// aren't taken (popups, which have digits and d of their own).
+    pub fn single(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => Key::Ctrl(c, 1),
+            _ => Key::Code(code, 1),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct KeySequences {
+    pending: Option<(char, Instant)>,
+    count: Option<usize>,
+}
+
+impl KeySequences {
+    /// Feed in a key press, getting back whatever is ready to be handled.
+    pub fn press(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Vec<Key> {
+        let mut keys = Vec::new();
+        let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+
+        if let Some((first, _)) = self.pending.take() {
+            if let (KeyCode::Char(second), false) = (code, ctrl) {
+                if let Some(sequence) = SEQUENCES.iter().find(|s| s.chars().eq([first, second])) {
+                    keys.push(Key::Sequence(sequence));
+                    return keys;
+                }
+            }
+            keys.push(Key::Code(KeyCode::Char(first), 1));
+        }
+
+        match code {
+            KeyCode::Char(c) if ctrl => keys.push(Key::Ctrl(c, self.count.take().unwrap_or(1))),
+            // a leading 0 isn't a count
+            KeyCode::Char(c @ '0'..='9') if c != '0' || self.count.is_some() => {
+                let digit = c as usize - '0' as usize;
+                let count = self.count.unwrap_or(0).saturating_mul(10).saturating_add(digit);
+                self.count = Some(count);
+            }
+            KeyCode::Char(c) if SEQUENCES.iter().any(|s| s.starts_with(c)) => {
+                self.count = None;
+                self.pending = Some((c, Instant::now()));
+            }
+            _ => keys.push(Key::Code(code, self.count.take().unwrap_or(1))),
+        }
+        keys
+    }
+
+    /// The held back key, once it's waited too long for the rest of a sequence.
+    pub fn expired(&mut self) -> Option<Key> {
+        match self.pending {
+            Some((c, since)) if since.elapsed() >= TIMEOUT => {
+                self.pending = None;
+                Some(Key::Code(KeyCode::Char(c), 1))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    /// j / k and the arrows, that many rows, or rows of tiles in the grid
+    Step(isize),
+    /// pages and half pages, that many rows
+    Jump(isize),
+    First,
+    Last,
+}
+
+/// Where `key` moves a list's selection to, if it's one that moves it, for a
+/// list showing `page` rows.
+pub fn motion(key: Key, page: usize) -> Option<Motion> {
+    let page = page.max(1) as isize;
+    let half = (page / 2).max(1);
+    match key {
+        Key::Code(KeyCode::Down | KeyCode::Char('j'), count) => Some(Motion::Step(count as isize)),
+        Key::Code(KeyCode::Up | KeyCode::Char('k'), count) => Some(Motion::Step(-(count as isize))),
+        Key::Code(KeyCode::PageDown, count) => Some(Motion::Jump(count as isize * page)),
+        Key::Code(KeyCode::PageUp, count) => Some(Motion::Jump(-(count as isize) * page)),
+        Key::Ctrl('d', count) => Some(Motion::Jump(count as isize * half)),
+        Key::Ctrl('u', count) => Some(Motion::Jump(-(count as isize) * half)),
+        Key::Code(KeyCode::Home, _) | Key::Sequence("gg") => Some(Motion::First),
+        Key::Code(KeyCode::End | KeyCode::Char('G'), _) => Some(Motion::Last),
+        _ => None,
+    }
+}
+
+/// A list whose selection a Motion moves.
+pub trait Navigable {
+    fn move_by(&mut self, delta: isize);
+    fn first(&mut self);
+    fn last(&mut self);
+}
+
+impl Motion {
+    pub fn apply(self, list: &mut impl Navigable) {
+        match self {
+            Motion::Step(delta) | Motion::Jump(delta) => list.move_by(delta),
+            Motion::First => list.first(),
+            Motion::Last => list.last(),
+        }
+    }
+}
+
+impl<T> Navigable for StatefulList<T> {
+    fn move_by(&mut self, delta: isize) {
+        StatefulList::move_by(self, delta)
+    }
+
+    fn first(&mut self) {
+        StatefulList::first(self)
+    }
+
+    fn last(&mut self) {
+        StatefulList::last(self)
+    }
+}
+
+impl Navigable for Queue {
+    fn move_by(&mut self, delta: isize) {
+        Queue::move_by(self, delta)
+    }
+
+    fn first(&mut self) {
+        Queue::first(self)
+    }
+
+    fn last(&mut self) {
+        Queue::last(self)
+    }
+}
+
+impl Navigable for StatefulTable<'_> {
+    fn move_by(&mut self, delta: isize) {
+        StatefulTable::move_by(self, delta)
+    }
+
+    fn first(&mut self) {
+        StatefulTable::first(self)
+    }
+
+    fn last(&mut self) {
+        StatefulTable::last(self)
+    }
+}
@@ -0,0 +1,216 @@
+// Audio files on this computer that aren't from the server at all,
+// audiobooks, archives of shows and the like, played by the same player as
+// everything else. They're made into episodes with a file:// url, named
+// after the file and with the folder they're in as the podcast, so the queue
+// and the player don't need to know the difference.
+//
+// The server never hears of them, so how far into each one it got is kept
+// here instead, in <data dir>/pinepods/local_progress.json (per user, see
+// profiles.rs), and it carries on from there the next time it's played.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use lofty::AudioFile;
+use log::error;
+use reqwest::Url;
+
+use super::profiles;
+use super::requests::PinepodsEpisodes;
+
+pub const AUDIO: [&str; 8] = ["mp3", "m4a", "aac", "ogg", "oga", "opus", "wav", "flac"];
+
+/// A folder, or an audio file, in the one being browsed.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub path: PathBuf,
+    pub folder: bool,
+    /// how far into it it got, if it was left partway
+    pub position: Option<u16>,
+}
+
+impl Entry {
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.path.display().to_string())
+    }
+}
+
+pub fn is_audio(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| AUDIO.contains(&extension.to_ascii_lowercase().as_str()))
+}
+
+/// The folders and audio files in `dir`, folders first, each in the order
+/// they'd be read in (2 before 10). Hidden ones are left out.
+pub fn list(dir: &Path) -> io::Result<Vec<Entry>> {
+    let progress = load_progress();
+    let mut entries: Vec<Entry> = fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|entry| {
+            let path = entry.path();
+            let folder = path.is_dir();
+            let position = progress.get(&path.display().to_string()).copied().filter(|_| !folder);
+            (folder || is_audio(&path)).then_some(Entry { path, folder, position })
+        })
+        .collect();
+    entries.sort_by(|a, b| b.folder.cmp(&a.folder).then_with(|| natural(&a.name(), &b.name())));
+    Ok(entries)
+}
+
+/// Every audio file in `dir`, in order, as episodes. Folders in it aren't
+/// gone into, an audiobook's parts are usually all in the one.
+pub fn folder(dir: &Path) -> io::Result<Vec<PinepodsEpisodes>> {
+    Ok(list(dir)?
+        .iter()
+        .filter(|entry| !entry.folder)
+        .map(|entry| episode(&entry.path))
+        .collect())
+}
+
+/// The file at `path` as an episode.
+pub fn episode(path: &Path) -> PinepodsEpisodes {
+    let title = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string());
+    let podcast = path
+        .parent()
+        .and_then(Path::file_name)
+        .map(|name| name.to_string_lossy().into_owned());
+    let url = Url::from_file_path(path).map(String::from).unwrap_or_else(|_| path.display().to_string());
+    PinepodsEpisodes {
+        PodcastName: podcast,
+        EpisodeTitle: title,
+        EpisodePubDate: String::new(),
+        EpisodeDescription: path.display().to_string(),
+        EpisodeArtwork: String::new(),
+        EpisodeDuration: duration(path).into(),
+        ListenDuration: position(&url).map(i64::from),
+        EpisodeURL: url,
+        EpisodeID: None,
+        PodcastID: None,
+    }
+}
+
+// from its headers, 0 when they don't say
+fn duration(path: &Path) -> u16 {
+    lofty::read_from_path(path)
+        .map_or(0, |file| file.properties().duration().as_secs().min(u16::MAX.into()) as u16)
+}
+
+/// The file a file:// episode url is for, if it's there.
+pub fn path(url: &str) -> Option<PathBuf> {
+    Url::parse(url)
+        .ok()
+        .filter(|url| url.scheme() == "file")
+        .and_then(|url| url.to_file_path().ok())
+        .filter(|path| path.is_file())
+}
+
+pub fn is_local(url: &str) -> bool {
+    url.starts_with("file:")
+}
+
+fn progress_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| profiles::scoped(dir.join("pinepods")).join("local_progress.json"))
+}
+
+// seconds in, by the file's path
+fn load_progress() -> HashMap<String, u16> {
+    progress_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_progress(progress: &HashMap<String, u16>) {
+    let Some(path) = progress_path() else {
+        return;
+    };
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| serde_json::to_string(progress).map_err(io::Error::from))
+        .and_then(|json| fs::write(&path, json));
+    if let Err(e) = result {
+        error!("Unable to save how far into local files it got to {:?}: {}", path, e);
+    }
+}
+
+// progress is kept by path, the url's percent encoding could change
+fn key(url: &str) -> Option<String> {
+    Url::parse(url)
+        .ok()
+        .filter(|url| url.scheme() == "file")
+        .and_then(|url| url.to_file_path().ok())
+        .map(|path| path.display().to_string())
+}
+
+/// How far into the file at `url` it got last time, if it was left partway.
+pub fn position(url: &str) -> Option<u16> {
+    let key = key(url)?;
+    load_progress().get(&key).copied().filter(|position| *position > 0)
+}
+
+pub fn remember(url: &str, position: u16) {
+    let Some(key) = key(url) else {
+        return;
+    };
+    let mut progress = load_progress();
+    progress.insert(key, position);
+    save_progress(&progress);
+}
+
+/// Played to the end, so the next time starts at the beginning.
+pub fn forget(url: &str) {
+    let Some(key) = key(url) else {
+        return;
+    };
+    let mut progress = load_progress();
+    if progress.remove(&key).is_some() {
+        save_progress(&progress);
+    }
+}
+
+// runs of digits compared as numbers, so Part 2 comes before Part 10
+fn natural(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a.chars().peekable(), b.chars().peekable());
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let number = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+                    let mut digits = String::new();
+                    while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                        digits.push(digit);
+                    }
+                    digits
+                };
+                let (x, y) = (number(&mut a), number(&mut b));
+                let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                let ordering = x.len().cmp(&y.len()).then_with(|| x.cmp(y));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+// Episodes kept on this computer, so they play without the server or the
+// network. The server's downloads stay on the server, these are fetched (see
+// downloads.rs) from the episode's url into <data dir>/pinepods/downloads/
+// (~/.local/share on Linux), or <data dir>/pinepods/users/<name>/downloads/
+// for users other than the default one (see profiles.rs), with index.json
+// beside them holding each episode's details, so the list of what's kept can
+// be shown with nothing else to go on.
+//
+// Files are named after their url, so the player can find one from the
+// episode it's given without the index. A trimmed or normalised copy (see
+// downloads.rs) goes beside the original with .processed before the
+// extension, and is what plays when there is one, unless asked otherwise.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use super::music_handler;
+use super::profiles;
+use super::requests::PinepodsEpisodes;
+
+// the processed copy played over the original, when there is one
+static PLAY_PROCESSED: AtomicBool = AtomicBool::new(true);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Kept {
+    pub episode: PinepodsEpisodes,
+    pub bytes: u64,
+    /// where it was saved, None for episodes kept before that was noted
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    /// the trimmed or normalised copy, if one was made
+    #[serde(default)]
+    pub processed: Option<PathBuf>,
+}
+
+impl Kept {
+    pub fn label(&self) -> String {
+        format!(
+            "{} - {} ({} MB{})",
+            self.episode.PodcastName.as_deref().unwrap_or("Unknown Podcast"),
+            self.episode.EpisodeTitle,
+            self.bytes / (1024 * 1024),
+            if self.processed.is_some() { ", processed" } else { "" }
+        )
+    }
+
+    /// The file it's kept in, if it's still there.
+    pub fn file(&self) -> Option<PathBuf> {
+        match &self.path {
+            Some(path) => Some(path.clone()).filter(|path| path.is_file()),
+            None => file_for(&self.episode.EpisodeURL),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct LocalStore {
+    kept: Vec<Kept>,
+}
+
+pub fn dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| profiles::scoped(dir.join("pinepods")).join("downloads"))
+}
+
+fn index_path() -> Option<PathBuf> {
+    dir().map(|dir| dir.join("index.json"))
+}
+
+fn file_name(url: &str) -> String {
+    format!("{:016x}.{}", name_hash(url), music_handler::extension(url))
+}
+
+// fnv-1a, the std hashers aren't the same from one run to the next
+fn name_hash(url: &str) -> u64 {
+    url.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Where the episode at `url` is, or would be, kept.
+pub fn path_for(url: &str) -> Option<PathBuf> {
+    dir().map(|dir| dir.join(file_name(url)))
+}
+
+/// Where the episode at `url` is kept, if it is.
+pub fn file_for(url: &str) -> Option<PathBuf> {
+    path_for(url).filter(|path| path.is_file())
+}
+
+/// Where the processed copy of the episode at `url` is, or would be, kept.
+pub fn processed_path_for(url: &str) -> Option<PathBuf> {
+    let name = format!("{:016x}.processed.{}", name_hash(url), music_handler::extension(url));
+    dir().map(|dir| dir.join(name))
+}
+
+/// The processed copy of the episode at `url`, if there is one.
+pub fn processed_for(url: &str) -> Option<PathBuf> {
+    processed_path_for(url).filter(|path| path.is_file())
+}
+
+/// Play processed copies over the originals from now on, or not.
+pub fn set_play_processed(play: bool) {
+    PLAY_PROCESSED.store(play, Ordering::Relaxed);
+}
+
+/// The file to play for the episode at `url`, if it's kept.
+pub fn playable_for(url: &str) -> Option<PathBuf> {
+    let processed = processed_for(url).filter(|_| PLAY_PROCESSED.load(Ordering::Relaxed));
+    processed.or_else(|| file_for(url))
+}
+
+impl LocalStore {
+    /// What's kept, less anything whose file has gone.
+    pub fn load() -> Self {
+        let kept: Vec<Kept> = index_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            kept: kept
+                .into_iter()
+                .filter(|kept| kept.file().is_some())
+                .collect(),
+        }
+    }
+
+    /// Newest kept first.
+    pub fn kept(&self) -> &[Kept] {
+        &self.kept
+    }
+
+    pub fn has(&self, url: &str) -> bool {
+        self.kept.iter().any(|kept| kept.episode.EpisodeURL == url)
+    }
+
+    pub fn add(&mut self, kept: Kept) {
+        self.kept.retain(|other| other.episode.EpisodeURL != kept.episode.EpisodeURL);
+        self.kept.insert(0, kept);
+        self.write();
+    }
+
+    /// Delete the kept file for `url`.
+    pub fn remove(&mut self, url: &str) -> io::Result<()> {
+        let kept = self.kept.iter().find(|kept| kept.episode.EpisodeURL == url);
+        if let Some(path) = kept.and_then(Kept::file).or_else(|| file_for(url)) {
+            fs::remove_file(path)?;
+        }
+        if let Some(path) = processed_for(url) {
+            fs::remove_file(path)?;
+        }
+        self.kept.retain(|kept| kept.episode.EpisodeURL != url);
+        self.write();
+        Ok(())
+    }
+
+    fn write(&self) {
+        let Some(path) = index_path() else {
+            return;
+        };
+
+        let result = serde_json::to_string(&self.kept)
+            .map_err(io::Error::from)
+            .and_then(|json| fs::write(&path, json));
+        if let Err(e) = result {
+            error!("Unable to save the kept episodes to {:?}: {}", path, e);
+        }
+    }
+}
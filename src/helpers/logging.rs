@@ -0,0 +1,102 @@
+// Logger whose filters can be swapped while the app runs, so debugging
+// doesn't need a restart with RUST_LOG. Filters use the RUST_LOG syntax,
+// e.g. "warn,pinepods_firewood::remote=debug".
+
+use std::sync::{OnceLock, RwLock};
+
+use log::{Log, Metadata, Record};
+
+/// Overall levels, quietest first.
+pub const LEVELS: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
+
+struct Reloadable {
+    current: RwLock<(String, env_logger::Logger)>,
+}
+
+static LOGGER: OnceLock<Reloadable> = OnceLock::new();
+
+impl Log for Reloadable {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.current.read().unwrap().1.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        self.current.read().unwrap().1.log(record)
+    }
+
+    fn flush(&self) {
+        self.current.read().unwrap().1.flush()
+    }
+}
+
+fn build(filters: &str) -> env_logger::Logger {
+    let mut builder = env_logger::Builder::new();
+    builder.parse_filters(filters);
+    if let Ok(style) = std::env::var("RUST_LOG_STYLE") {
+        builder.parse_write_style(&style);
+    }
+    builder.build()
+}
+
+/// Install the logger, starting from RUST_LOG with `filters` laid over it.
+pub fn init(filters: Option<&str>) {
+    let mut initial = std::env::var("RUST_LOG").unwrap_or_default();
+    if let Some(filters) = filters {
+        if !initial.is_empty() {
+            initial.push(',');
+        }
+        initial.push_str(filters);
+    }
+    // env_logger's own default, spelled out so it shows up in the ui
+    if initial.is_empty() {
+        initial.push_str("error");
+    }
+
+    let logger = build(&initial);
+    let max_level = logger.filter();
+    let reloadable = LOGGER.get_or_init(|| Reloadable {
+        current: RwLock::new((initial, logger)),
+    });
+    if log::set_logger(reloadable).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+/// The filters in use right now.
+pub fn filters() -> String {
+    LOGGER
+        .get()
+        .map(|reloadable| reloadable.current.read().unwrap().0.clone())
+        .unwrap_or_default()
+}
+
+/// Replace the filters for everything logged from here on.
+pub fn set_filters(filters: &str) {
+    let Some(reloadable) = LOGGER.get() else {
+        return;
+    };
+    let logger = build(filters);
+    log::set_max_level(logger.filter());
+    *reloadable.current.write().unwrap() = (filters.to_string(), logger);
+}
+
+/// Step the overall level towards trace, wrapping back to error, leaving
+/// module filters alone.
+pub fn cycle_level() {
+    let current = filters();
+    let mut directives: Vec<&str> = current.split(',').filter(|d| !d.is_empty()).collect();
+
+    let position = directives
+        .iter()
+        .position(|d| LEVELS.iter().any(|level| level.eq_ignore_ascii_case(d)));
+    let index = position
+        .and_then(|p| LEVELS.iter().position(|level| level.eq_ignore_ascii_case(directives[p])))
+        .unwrap_or(0);
+    let next = LEVELS[(index + 1) % LEVELS.len()];
+
+    match position {
+        Some(p) => directives[p] = next,
+        None => directives.insert(0, next),
+    }
+    set_filters(&directives.join(","));
+}
@@ -0,0 +1,298 @@
+// Hardware media keys on platforms without MPRIS.
+//
+// On macOS and Windows the play/pause keys and the system media overlay talk
+// to the OS media session (MPNowPlayingInfoCenter / SMTC) rather than to the
+// focused terminal, so we register with it and forward the button presses,
+// and tell it what's playing so the overlay and lock screen can show it.
+// Everywhere else `MediaKeys::new()` returns `None` and nothing is registered.
+
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
+
+// the overlay works the position out for itself between updates, it's only
+// sent again once it's this far from where the overlay would have it
+const POSITION_SLACK: Duration = Duration::from_secs(2);
+
+/// Playback commands coming from the OS media session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKeyEvent {
+    Play,
+    Pause,
+    Toggle,
+    Next,
+    Previous,
+    Stop,
+    /// Seconds forwards, or backwards when negative, `None` for however far
+    /// the player usually goes.
+    Seek(bool, Option<u64>),
+    /// Seconds into the episode.
+    SetPosition(u64),
+}
+
+/// What we last told the OS media session about playback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKeyStatus {
+    Stopped,
+    Paused,
+    Playing,
+}
+
+/// What the OS media session shows as playing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NowPlaying {
+    pub title: String,
+    pub podcast: Option<String>,
+    pub artwork: Option<String>,
+    /// unknown for live streams
+    pub length: Option<Duration>,
+}
+
+pub struct MediaKeys {
+    session: platform::Session,
+    events: Receiver<MediaKeyEvent>,
+    // what was last sent, with the position then and when
+    status: Option<(MediaKeyStatus, Duration, Instant)>,
+    now_playing: Option<Option<NowPlaying>>,
+}
+
+impl MediaKeys {
+    /// Register with the OS media session, `None` if it isn't available.
+    pub fn new() -> Option<Self> {
+        let (tx, events) = std::sync::mpsc::channel();
+        let session = platform::Session::attach(tx)?;
+
+        Some(Self {
+            session,
+            events,
+            status: None,
+            now_playing: None,
+        })
+    }
+
+    /// Drain every key press received since the last call.
+    pub fn poll(&mut self) -> Vec<MediaKeyEvent> {
+        self.session.pump();
+        self.events.try_iter().collect()
+    }
+
+    /// Keep the system overlay in sync with the player, `position` seconds
+    /// in. Only sends on a change, or once the position has moved from where
+    /// the overlay would have it, after a seek say.
+    pub fn set_status(&mut self, status: MediaKeyStatus, position: Duration) {
+        let unchanged = self.status.is_some_and(|(last, at, sent)| {
+            let expected = match last {
+                MediaKeyStatus::Playing => at + sent.elapsed(),
+                _ => at,
+            };
+            last == status && expected.abs_diff(position) < POSITION_SLACK
+        });
+        if !unchanged {
+            self.session.set_status(status, position);
+            self.status = Some((status, position, Instant::now()));
+        }
+    }
+
+    /// Show `now_playing` in the overlay, only sends on change.
+    pub fn set_now_playing(&mut self, now_playing: Option<NowPlaying>) {
+        if self.now_playing.as_ref() != Some(&now_playing) {
+            self.session.set_now_playing(now_playing.as_ref());
+            self.now_playing = Some(now_playing);
+        }
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+mod platform {
+    use std::sync::mpsc::Sender;
+    use std::time::Duration;
+
+    use log::error;
+    use souvlaki::{
+        MediaControlEvent, MediaControls, MediaMetadata, MediaPlayback, MediaPosition, PlatformConfig, SeekDirection,
+    };
+
+    use super::{MediaKeyEvent, MediaKeyStatus, NowPlaying};
+
+    pub struct Session {
+        controls: MediaControls,
+        // owns the hwnd the controls are bound to, dropped after them
+        #[cfg(target_os = "windows")]
+        _window: window::HiddenWindow,
+    }
+
+    impl Session {
+        pub fn attach(tx: Sender<MediaKeyEvent>) -> Option<Self> {
+            // SMTC hands out controls per window, a terminal app doesn't own one
+            #[cfg(target_os = "windows")]
+            let window = window::HiddenWindow::new()?;
+            #[cfg(target_os = "windows")]
+            let hwnd = Some(window.hwnd());
+            #[cfg(not(target_os = "windows"))]
+            let hwnd = None;
+
+            let config = PlatformConfig {
+                dbus_name: "pinepods_firewood",
+                display_name: "Pinepods Firewood",
+                hwnd,
+            };
+
+            let mut controls = match MediaControls::new(config) {
+                Ok(controls) => controls,
+                Err(e) => {
+                    error!("Unable to create media controls: {:?}", e);
+                    return None;
+                }
+            };
+
+            let attached = controls.attach(move |event| {
+                let key = match event {
+                    MediaControlEvent::Play => MediaKeyEvent::Play,
+                    MediaControlEvent::Pause => MediaKeyEvent::Pause,
+                    MediaControlEvent::Toggle => MediaKeyEvent::Toggle,
+                    MediaControlEvent::Next => MediaKeyEvent::Next,
+                    MediaControlEvent::Previous => MediaKeyEvent::Previous,
+                    MediaControlEvent::Stop => MediaKeyEvent::Stop,
+                    MediaControlEvent::Seek(direction) => MediaKeyEvent::Seek(direction == SeekDirection::Forward, None),
+                    MediaControlEvent::SeekBy(direction, by) => {
+                        MediaKeyEvent::Seek(direction == SeekDirection::Forward, Some(by.as_secs()))
+                    }
+                    MediaControlEvent::SetPosition(MediaPosition(at)) => MediaKeyEvent::SetPosition(at.as_secs()),
+                    _ => return,
+                };
+                let _ = tx.send(key);
+            });
+            if let Err(e) = attached {
+                error!("Unable to attach media key handler: {:?}", e);
+                return None;
+            }
+
+            Some(Self {
+                controls,
+                #[cfg(target_os = "windows")]
+                _window: window,
+            })
+        }
+
+        pub fn set_status(&mut self, status: MediaKeyStatus, position: Duration) {
+            let progress = Some(MediaPosition(position));
+            let playback = match status {
+                MediaKeyStatus::Stopped => MediaPlayback::Stopped,
+                MediaKeyStatus::Paused => MediaPlayback::Paused { progress },
+                MediaKeyStatus::Playing => MediaPlayback::Playing { progress },
+            };
+            if let Err(e) = self.controls.set_playback(playback) {
+                error!("Unable to update media session: {:?}", e);
+            }
+        }
+
+        pub fn set_now_playing(&mut self, now_playing: Option<&NowPlaying>) {
+            let metadata = match now_playing {
+                Some(now_playing) => MediaMetadata {
+                    title: Some(&now_playing.title),
+                    artist: now_playing.podcast.as_deref(),
+                    album: now_playing.podcast.as_deref(),
+                    cover_url: now_playing.artwork.as_deref(),
+                    duration: now_playing.length,
+                },
+                None => MediaMetadata::default(),
+            };
+            if let Err(e) = self.controls.set_metadata(metadata) {
+                error!("Unable to update media session: {:?}", e);
+            }
+        }
+
+        // remote command callbacks are dispatched on the main run loop, which
+        // nothing else services while the terminal ui owns the main thread
+        #[cfg(target_os = "macos")]
+        pub fn pump(&mut self) {
+            use core_foundation::runloop::{kCFRunLoopDefaultMode, CFRunLoop};
+            use std::time::Duration;
+
+            unsafe {
+                CFRunLoop::run_in_mode(kCFRunLoopDefaultMode, Duration::from_millis(0), true);
+            }
+        }
+
+        #[cfg(target_os = "windows")]
+        pub fn pump(&mut self) {
+            // SMTC delivers button presses on its own thread pool
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    mod window {
+        use std::ffi::c_void;
+        use std::ptr;
+
+        use log::error;
+        use windows_sys::Win32::Foundation::HWND;
+        use windows_sys::Win32::System::LibraryLoader::GetModuleHandleW;
+        use windows_sys::Win32::UI::WindowsAndMessaging::{
+            CreateWindowExW, DestroyWindow, WS_OVERLAPPEDWINDOW,
+        };
+
+        /// A never-shown window that the media session can be bound to.
+        pub struct HiddenWindow(HWND);
+
+        impl HiddenWindow {
+            pub fn new() -> Option<Self> {
+                let class: Vec<u16> = "STATIC\0".encode_utf16().collect();
+                let hwnd = unsafe {
+                    CreateWindowExW(
+                        0,
+                        class.as_ptr(),
+                        ptr::null(),
+                        WS_OVERLAPPEDWINDOW,
+                        0,
+                        0,
+                        0,
+                        0,
+                        0,
+                        0,
+                        GetModuleHandleW(ptr::null()),
+                        ptr::null(),
+                    )
+                };
+                if hwnd == 0 {
+                    error!("Unable to create window for media controls");
+                    return None;
+                }
+                Some(Self(hwnd))
+            }
+
+            pub fn hwnd(&self) -> *mut c_void {
+                self.0 as *mut c_void
+            }
+        }
+
+        impl Drop for HiddenWindow {
+            fn drop(&mut self) {
+                unsafe {
+                    DestroyWindow(self.0);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use std::sync::mpsc::Sender;
+    use std::time::Duration;
+
+    use super::{MediaKeyEvent, MediaKeyStatus, NowPlaying};
+
+    pub struct Session;
+
+    impl Session {
+        pub fn attach(_tx: Sender<MediaKeyEvent>) -> Option<Self> {
+            None
+        }
+
+        pub fn set_status(&mut self, _status: MediaKeyStatus, _position: Duration) {}
+
+        pub fn set_now_playing(&mut self, _now_playing: Option<&NowPlaying>) {}
+
+        pub fn pump(&mut self) {}
+    }
+}
@@ -1,25 +1,134 @@
 use std::{
     fs::File,
-    io::{BufReader, Cursor},
+    io::{BufReader, Cursor, Read},
     path::{Path, PathBuf},
-    sync::{Arc, Mutex},
+    sync::{Arc, Condvar, Mutex},
     thread,
     time::Duration,
 };
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
 
 use lofty::{AudioFile, Probe};
-use log::error;
-use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+use log::{error, info};
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
+use rodio::cpal::traits::HostTrait;
+use rodio::{DeviceTrait, StreamError};
+use reqwest::{header, StatusCode};
 use crate::requests::PinepodsEpisodes;
 
+use super::announce::{self, Announce};
+
+use super::chapters;
+use super::feed::Chapter;
 use super::gen_funcs;
+use super::local_files;
+use super::local_store;
+use super::playback_log;
+
+// the audio output to play on by name, the system's default for None
+static OUTPUT_DEVICE: Mutex<Option<String>> = Mutex::new(None);
+
+// recording stops before the disk gets fuller than this
+const RECORDING_MIN_FREE: u64 = 256 * 1024 * 1024;
+// written between free space checks
+const RECORDING_CHECK_EVERY: u64 = 8 * 1024 * 1024;
+// a live stream read taking this long goes in the playback log
+const LIVE_SLOW_READ: Duration = Duration::from_secs(1);
+// playback speeds there are, times normal
+pub const MIN_SPEED: f32 = 0.5;
+pub const MAX_SPEED: f32 = 3.0;
+// as loud as it plays while ducked, for a word with someone in the room
+pub const DUCKED_VOLUME: f32 = 0.2;
+// stuck this long while playing and the audio output is rebuilt
+const STALL_LIMIT: Duration = Duration::from_secs(8);
+// after a rebuild that didn't work, before trying again
+const RECOVERY_RETRY: Duration = Duration::from_secs(30);
+
+// next queue item fetched ahead of time
+enum Precache {
+    Fetching,
+    // all of it, or just its beginning when it's bigger than the limit
+    Ready { bytes: Vec<u8>, whole: bool },
+    Failed,
+}
+
+// the next queue item's url and how far fetching it has got, shared with
+// the thread fetching it and with playback waiting on it
+#[derive(Default)]
+struct Precached {
+    slot: Mutex<Option<(String, Precache)>>,
+    // signalled whenever the slot changes from fetching
+    done: Condvar,
+}
+
+impl Precached {
+    // hand over what's been fetched of url, waiting for it if it's still on
+    // its way rather than fetching it all a second time
+    fn take(&self, url: &str) -> Option<(Vec<u8>, bool)> {
+        let slot = self.slot.lock().unwrap();
+        let mut slot = self
+            .done
+            .wait_while(slot, |slot| matches!(slot, Some((cached_url, Precache::Fetching)) if cached_url == url))
+            .unwrap();
+        match slot.take() {
+            Some((cached_url, Precache::Ready { bytes, whole })) if cached_url == url => Some((bytes, whole)),
+            other => {
+                *slot = other;
+                None
+            }
+        }
+    }
+}
+
+// an episode's url and the chapters in its file
+type TaggedChapters = (String, Vec<Chapter>);
+
+// how the playback going on is getting along, kept up to date by the thread
+// counting its seconds
+#[derive(Default)]
+struct Watch {
+    // since when it hasn't moved while playing
+    stalled: Mutex<Option<Instant>>,
+    // given up on, so its thread stops counting
+    abandoned: AtomicBool,
+}
 
 pub struct MusicHandle {
-    music_output: Arc<(OutputStream, OutputStreamHandle)>,
+    music_output: (OutputStream, OutputStreamHandle),
     sink: Arc<Sink>,
     song_length: u16,
     time_played: Arc<Mutex<u16>>,
     currently_playing: String,
+    currently_playing_id: Option<i64>,
+    // the episode itself, None for live streams
+    playing: Option<PinepodsEpisodes>,
+    // kept here since every episode gets a fresh sink
+    volume: f32,
+    // muted or ducked, the volume's kept as it was to go back to after
+    muted: bool,
+    ducked: bool,
+    speed: f32,
+    // a live stream, which has no length and can't seek
+    live: bool,
+    live_url: String,
+    // where the live stream is being saved, shared with the thread reading it
+    recording: Arc<Mutex<Option<Recording>>>,
+    /// Start recording live streams as soon as they start playing.
+    pub record_live: bool,
+    precached: Arc<Precached>,
+    // chapters tagged into the file playing, by url, once it's been fetched
+    embedded_chapters: Arc<Mutex<Option<TaggedChapters>>>,
+    watch: Arc<Watch>,
+    // where a segment being played stops, to start it again the same
+    segment_end: Option<u16>,
+    // no rebuilding the output again before this, the last one failed
+    recovery_retry: Option<Instant>,
+    // while one's playing between episodes
+    announcing: Arc<AtomicBool>,
 }
 
 impl Default for MusicHandle {
@@ -31,11 +140,27 @@ impl Default for MusicHandle {
 impl MusicHandle {
     pub fn new() -> Self {
         Self {
-            music_output: Arc::new(OutputStream::try_default().unwrap()),
+            music_output: open_output().unwrap(),
             sink: Arc::new(Sink::new_idle().0), // more efficient way, shouldnt have to do twice?
             song_length: 0,
             time_played: Arc::new(Mutex::new(0)),
             currently_playing: "CURRENT SONG".to_string(),
+            currently_playing_id: None,
+            playing: None,
+            volume: 1.0,
+            muted: false,
+            ducked: false,
+            speed: 1.0,
+            live: false,
+            live_url: String::new(),
+            recording: Arc::new(Mutex::new(None)),
+            record_live: false,
+            precached: Arc::new(Precached::default()),
+            embedded_chapters: Arc::new(Mutex::new(None)),
+            watch: Arc::new(Watch::default()),
+            segment_end: None,
+            recovery_retry: None,
+            announcing: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -43,6 +168,14 @@ impl MusicHandle {
         self.currently_playing.clone()
     }
 
+    pub fn currently_playing_id(&self) -> Option<i64> {
+        self.currently_playing_id
+    }
+
+    pub fn playing(&self) -> Option<&PinepodsEpisodes> {
+        self.playing.as_ref().filter(|_| !self.sink.empty())
+    }
+
     pub fn song_length(&self) -> u16 {
         self.song_length
     }
@@ -51,10 +184,84 @@ impl MusicHandle {
         *self.time_played.lock().unwrap()
     }
 
+    /// Play `announce` for the episode `title`, on a sink of its own so
+    /// it's no part of either episode. See `announcing` for when it's done.
+    pub fn announce(&mut self, announce: Announce, title: String) {
+        let sink = match Sink::try_new(&self.music_output.1) {
+            Ok(sink) => sink,
+            Err(e) => {
+                error!("Unable to play the announcement: {}", e);
+                return;
+            }
+        };
+        sink.set_volume(self.output_volume());
+        let announcing = self.announcing.clone();
+        announcing.store(true, Ordering::SeqCst);
+        thread::spawn(move || {
+            announce::append(&sink, announce, &title);
+            sink.sleep_until_end();
+            announcing.store(false, Ordering::SeqCst);
+        });
+    }
+
+    pub fn announcing(&self) -> bool {
+        self.announcing.load(Ordering::SeqCst)
+    }
+
     pub fn sink_empty(&self) -> bool {
         self.sink.empty()
     }
 
+    pub fn is_live(&self) -> bool {
+        self.live
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.lock().unwrap().is_some()
+    }
+
+    /// Save the live stream to a file from here on, returning where.
+    pub fn start_recording(&mut self) -> anyhow::Result<PathBuf> {
+        if !self.live {
+            anyhow::bail!("only live streams can be recorded");
+        }
+        let dir = recordings_dir().context("no folder to keep recordings in")?;
+        std::fs::create_dir_all(&dir)?;
+        if gen_funcs::free_space(&dir).is_some_and(|free| free < RECORDING_MIN_FREE) {
+            anyhow::bail!("less than {} MB free in {}", RECORDING_MIN_FREE / 1024 / 1024, dir.display());
+        }
+
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        let path = dir.join(format!(
+            "{}-{}.{}",
+            started,
+            file_name(self.currently_playing.trim_end_matches(" (LIVE)")),
+            extension(&self.live_url)
+        ));
+        let file = BufWriter::new(File::create(&path)?);
+        *self.recording.lock().unwrap() = Some(Recording {
+            file,
+            path: path.clone(),
+            since_check: 0,
+        });
+        Ok(path)
+    }
+
+    pub fn stop_recording(&mut self) {
+        if let Some(mut recording) = self.recording.lock().unwrap().take() {
+            if let Err(e) = recording.file.flush() {
+                error!("Unable to finish recording {:?}: {}", recording.path, e);
+            }
+            info!("Recorded to {:?}", recording.path);
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.sink.is_paused()
+    }
+
     pub fn set_time_played(&mut self, t: u16) {
         *self.time_played.lock().unwrap() = t;
     }
@@ -63,52 +270,158 @@ impl MusicHandle {
         self.currently_playing = gen_funcs::audio_display(path);
     }
 
-    // update current song and play
+    // update current song and play, local files from where they were left
     pub fn play(&mut self, episode: &PinepodsEpisodes) {
+        match local_files::position(&episode.EpisodeURL) {
+            Some(position) => self.play_from(episode, position),
+            None => self.start(episode, 0, None),
+        }
+    }
+
+    /// Play `episode` from `position` seconds in, e.g. where it was left off
+    /// on another device.
+    pub fn play_from(&mut self, episode: &PinepodsEpisodes, position: u16) {
+        self.start(episode, position, None);
+        *self.time_played.lock().unwrap() = position;
+    }
+
+    /// Play only seconds `start` to `end` of `episode`, e.g. as a preview. The
+    /// server doesn't hear about the position, it isn't really listened to.
+    pub fn play_segment(&mut self, episode: &PinepodsEpisodes, start: u16, end: u16) {
+        self.start(episode, start, Some(end));
+        self.currently_playing_id = None;
+        *self.time_played.lock().unwrap() = start;
+    }
+
+    fn start(&mut self, episode: &PinepodsEpisodes, from: u16, until: Option<u16>) {
         // if song already playing, need to be able to restart tho
         // println!("Playing: {}", episode.EpisodeURL.clone());
         error!("Playing: {}", episode.EpisodeURL.clone());
         self.sink.stop();
         *self.time_played.lock().unwrap() = 0;
+        self.stop_recording();
+        self.live = false;
+        self.segment_end = until;
 
         // set currently playing
         self.currently_playing = episode.EpisodeTitle.clone();
+        self.currently_playing_id = episode.EpisodeID;
+        self.playing = Some(episode.clone());
         self.set_currently_playing(episode);
         self.update_song_length(episode);
 
         // reinitialize due to rodio crate
         self.sink = Arc::new(Sink::try_new(&self.music_output.1).unwrap());
+        self.sink.set_volume(self.output_volume());
+        self.sink.set_speed(self.speed);
 
         // clone sink for thread
         let sclone = self.sink.clone();
 
         let tpclone = self.time_played.clone();
+        let watch = self.new_watch();
 
         let episode_url = episode.EpisodeURL.clone();
         let episode_title = episode.EpisodeTitle.clone();
+        let precached = self.precached.clone();
+        let kept = on_disk(&episode_url);
+        let chapters_url = episode_url.clone();
+        let chapters_slot = self.embedded_chapters.clone();
 
+        playback_log::event(format!("Playing {} from {}", episode_title, episode_url));
         let _t1 = thread::spawn(move || {
 
             // can send in through function
-            // get file
-            let resp = reqwest::blocking::get(episode_url).unwrap();
-            let mut cursor = Cursor::new(resp.bytes().unwrap()); // Adds Read and Seek to the bytes via Cursor
+            // get file, unless it's kept here or was already fetched while
+            // the last one played
+            let kept = kept.and_then(|path| match std::fs::read(&path) {
+                Ok(bytes) => Some(bytes),
+                Err(e) => {
+                    error!("Unable to read {:?}, streaming instead: {}", path, e);
+                    None
+                }
+            });
+            let cached = match kept {
+                Some(_) => None,
+                None => precached.take(&episode_url),
+            };
+            let bytes: Vec<u8> = match (kept, cached) {
+                (Some(bytes), _) => {
+                    playback_log::event(format!("Kept locally, {} KB", bytes.len() / 1024));
+                    bytes
+                }
+                (None, Some((bytes, true))) => {
+                    playback_log::event(format!("Pre-cached, {} KB", bytes.len() / 1024));
+                    bytes
+                }
+                (None, Some((head, false))) => {
+                    let fetching = Instant::now();
+                    let had = head.len();
+                    let bytes = fetch_rest(&episode_url, head).unwrap();
+                    playback_log::event(format!(
+                        "Pre-cached {} KB, fetched the other {} KB in {} ms",
+                        had / 1024,
+                        bytes.len().saturating_sub(had) / 1024,
+                        fetching.elapsed().as_millis()
+                    ));
+                    bytes
+                }
+                (None, None) => {
+                    let fetching = Instant::now();
+                    let resp = reqwest::blocking::get(episode_url).unwrap();
+                    let bytes: Vec<u8> = resp.bytes().unwrap().into();
+                    playback_log::event(format!(
+                        "Fetched {} KB in {} ms",
+                        bytes.len() / 1024,
+                        fetching.elapsed().as_millis()
+                    ));
+                    bytes
+                }
+            };
+            *chapters_slot.lock().unwrap() = Some((chapters_url, chapters::embedded(&bytes)));
+            let cursor = Cursor::new(bytes); // Adds Read and Seek to the bytes via Cursor
             // let file = BufReader::new(File::open(episode).unwrap());
             let source = Decoder::new(cursor).unwrap();
+            playback_log::event(format!(
+                "Source: {} Hz, {} channels",
+                source.sample_rate(),
+                source.channels()
+            ));
 
             // Arc inside a thread inside a thread. BOOM, INCEPTION
             let sink_clone_2 = sclone.clone();
             let tpclone2 = tpclone.clone();
 
             sclone.append(source);
+            if from > 0 {
+                if let Err(e) = sclone.try_seek(Duration::from_secs(from.into())) {
+                    error!("Unable to seek to {}s: {}", from, e);
+                    playback_log::event(format!("Seek to {}s failed: {}", from, e));
+                }
+            }
 
             let _ = thread::spawn(move || {
+                let mut stalls = Stalls::default();
+                // episode seconds played, faster or slower than real ones
+                let mut played = 0.0;
                 // sleep for 1 second then increment count
-                while sink_clone_2.len() == 1 {
+                while sink_clone_2.len() == 1 && !watch.abandoned.load(Ordering::Relaxed) {
                     thread::sleep(Duration::from_secs(1));
+                    stalls.check(&sink_clone_2);
+                    *watch.stalled.lock().unwrap() = stalls.since;
 
-                    if !sink_clone_2.is_paused() {
-                        *tpclone2.lock().unwrap() += 1;
+                    // the clock stays put while the audio does
+                    if !sink_clone_2.is_paused() && stalls.since.is_none() {
+                        let mut time_played = tpclone2.lock().unwrap();
+                        played += sink_clone_2.speed();
+                        while played >= 1.0 {
+                            *time_played += 1;
+                            played -= 1.0;
+                        }
+                        // end of the segment, same as the end of the episode
+                        if until.is_some_and(|end| *time_played >= end) {
+                            sink_clone_2.stop();
+                        }
                     }
                 }
             });
@@ -117,21 +430,505 @@ impl MusicHandle {
         });
     }
 
+    /// Play a live stream as it comes in rather than downloading it first,
+    /// which would never finish.
+    pub fn play_live(&mut self, title: &str, url: &str) {
+        self.sink.stop();
+        *self.time_played.lock().unwrap() = 0;
+        self.currently_playing = format!("{} (LIVE)", title);
+        self.currently_playing_id = None;
+        self.playing = None;
+        self.song_length = 0;
+        self.stop_recording();
+        self.live = true;
+        self.live_url = url.to_string();
+        // a fresh one, so nothing still reading the last stream writes to it
+        self.recording = Arc::new(Mutex::new(None));
+        if self.record_live {
+            if let Err(e) = self.start_recording() {
+                error!("Not recording {}: {}", title, e);
+            }
+        }
+        let recording = self.recording.clone();
+
+        self.sink = Arc::new(Sink::try_new(&self.music_output.1).unwrap());
+        self.sink.set_volume(self.output_volume());
+        let sink = self.sink.clone();
+        let time_played = self.time_played.clone();
+        let watch = self.new_watch();
+        let url = url.to_string();
+        playback_log::event(format!("Live stream {} from {}", title, url));
+
+        thread::spawn(move || {
+            // the default client gives up after 30 seconds, a stream doesn't end
+            let response = reqwest::blocking::Client::builder()
+                .timeout(None)
+                .build()
+                .and_then(|client| client.get(&url).send())
+                .and_then(|response| response.error_for_status());
+            let source = match response {
+                Ok(response) => Decoder::new(LiveStream {
+                    response,
+                    position: 0,
+                    recording,
+                }),
+                Err(e) => {
+                    error!("Unable to open live stream {}: {}", url, e);
+                    return;
+                }
+            };
+            let source = match source {
+                Ok(source) => source,
+                Err(e) => {
+                    error!("Unable to decode live stream {}: {}", url, e);
+                    return;
+                }
+            };
+
+            playback_log::event(format!(
+                "Live source: {} Hz, {} channels",
+                source.sample_rate(),
+                source.channels()
+            ));
+            sink.append(source);
+            let counter = sink.clone();
+            thread::spawn(move || {
+                let mut stalls = Stalls::default();
+                while counter.len() == 1 && !watch.abandoned.load(Ordering::Relaxed) {
+                    thread::sleep(Duration::from_secs(1));
+                    stalls.check(&counter);
+                    *watch.stalled.lock().unwrap() = stalls.since;
+                    if !counter.is_paused() && stalls.since.is_none() {
+                        *time_played.lock().unwrap() += 1;
+                    }
+                }
+            });
+            sink.sleep_until_end();
+        });
+    }
+
+    /// Fetch `episode` in the background so playing it next doesn't wait on
+    /// the network. Of episodes bigger than `limit_mb` only that much of the
+    /// beginning is fetched, the rest once it plays.
+    pub fn precache(&mut self, episode: &PinepodsEpisodes, limit_mb: u32) {
+        let url = episode.EpisodeURL.clone();
+        if on_disk(&url).is_some() {
+            return;
+        }
+        {
+            let mut slot = self.precached.slot.lock().unwrap();
+            if matches!(&*slot, Some((cached_url, _)) if *cached_url == url) {
+                return;
+            }
+            *slot = Some((url.clone(), Precache::Fetching));
+            // anything waiting on the one before stops waiting
+            self.precached.done.notify_all();
+        }
+
+        let precached = self.precached.clone();
+        let limit = limit_mb as u64 * 1024 * 1024;
+        thread::spawn(move || {
+            let result = fetch_limited(&url, limit);
+            let mut slot = precached.slot.lock().unwrap();
+            // queue may have moved on while we were downloading
+            if let Some((cached_url, state)) = slot.as_mut() {
+                if *cached_url == url {
+                    *state = match result {
+                        Ok((bytes, whole)) => Precache::Ready { bytes, whole },
+                        Err(e) => {
+                            error!("Not pre-caching {}: {}", url, e);
+                            Precache::Failed
+                        }
+                    };
+                    precached.done.notify_all();
+                }
+            }
+        });
+    }
+
+    /// The chapters tagged into the episode at `url`, once it's been fetched,
+    /// if there are any.
+    pub fn embedded_chapters(&self, url: &str) -> Option<Vec<Chapter>> {
+        match &*self.embedded_chapters.lock().unwrap() {
+            Some((playing, chapters)) if playing == url && !chapters.is_empty() => Some(chapters.clone()),
+            _ => None,
+        }
+    }
+
+    // a fresh watch for playback that's starting, the last one's thread stops
+    fn new_watch(&mut self) -> Arc<Watch> {
+        self.watch.abandoned.store(true, Ordering::Relaxed);
+        self.watch = Arc::new(Watch::default());
+        self.watch.clone()
+    }
+
+    /// When playback has been stuck for a while though it isn't paused, the
+    /// output device having wedged or its stream died, open the output again
+    /// and carry on from where it got to. Called every so often, it's
+    /// Some(position carried on from) or Some(what went wrong) once it's
+    /// tried, None otherwise.
+    pub fn watchdog(&mut self) -> Option<anyhow::Result<u16>> {
+        let stalled = (*self.watch.stalled.lock().unwrap())?;
+        if stalled.elapsed() < STALL_LIMIT
+            || self.sink.is_paused()
+            || self.recovery_retry.is_some_and(|retry| Instant::now() < retry)
+        {
+            return None;
+        }
+
+        let position = self.time_played();
+        error!(
+            "Playback stuck at {}s for {}s, opening the audio output again",
+            position,
+            stalled.elapsed().as_secs()
+        );
+        playback_log::event(format!("Stuck at {}s, rebuilding the audio output", position));
+        let output = match open_output() {
+            Ok(output) => output,
+            Err(e) => {
+                error!("Unable to open the audio output again: {}", e);
+                playback_log::event(format!("Rebuilding the audio output failed: {}", e));
+                self.recovery_retry = Some(Instant::now() + RECOVERY_RETRY);
+                return Some(Err(e.into()));
+            }
+        };
+        self.recovery_retry = None;
+        // the old stream goes once the sink on it has stopped
+        self.sink.stop();
+        self.music_output = output;
+
+        match (self.live, self.playing.clone()) {
+            (true, _) => {
+                let recording = self.is_recording();
+                let title = self.currently_playing.trim_end_matches(" (LIVE)").to_string();
+                let url = self.live_url.clone();
+                self.play_live(&title, &url);
+                if recording && !self.is_recording() {
+                    if let Err(e) = self.start_recording() {
+                        error!("Not recording {} after rebuilding the audio output: {}", title, e);
+                    }
+                }
+            }
+            (false, Some(episode)) => {
+                let id = self.currently_playing_id;
+                self.start(&episode, position, self.segment_end);
+                self.currently_playing_id = id;
+            }
+            (false, None) => {}
+        }
+        *self.time_played.lock().unwrap() = position;
+        playback_log::event(format!("Carrying on from {}s", position));
+        info!("Audio output rebuilt, carrying on from {}s", position);
+        Some(Ok(position))
+    }
+
     pub fn play_pause(&mut self) {
         if self.sink.is_paused() {
+            playback_log::event(format!("Resumed at {}s", self.time_played()));
             self.sink.play()
         } else {
+            playback_log::event(format!("Paused at {}s", self.time_played()));
             self.sink.pause()
         }
     }
 
     pub fn skip(&self) {
+        playback_log::event(format!("Skipped at {}s", self.time_played()));
         self.sink.stop();
     }
 
+    pub fn volume(&self) -> f32 {
+        self.volume
+    }
+
+    /// Raise or lower the volume by `delta`, staying between 0.0 and 1.0.
+    pub fn change_volume(&mut self, delta: f32) {
+        self.set_volume(self.volume + delta);
+    }
+
+    /// Play at `volume`, between 0.0 and 1.0, unmuted and no longer ducked
+    /// if it was.
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, 1.0);
+        self.muted = false;
+        self.ducked = false;
+        self.sink.set_volume(self.output_volume());
+    }
+
+    pub fn is_muted(&self) -> bool {
+        self.muted
+    }
+
+    /// Silence it, or go back to the volume it was at, true once muted.
+    pub fn toggle_mute(&mut self) -> bool {
+        self.muted = !self.muted;
+        self.sink.set_volume(self.output_volume());
+        self.muted
+    }
+
+    pub fn is_ducked(&self) -> bool {
+        self.ducked
+    }
+
+    /// Drop to DUCKED_VOLUME, or go back to the volume it was at, true once
+    /// ducked. Already as quiet as that, it stays as it is.
+    pub fn toggle_duck(&mut self) -> bool {
+        self.ducked = !self.ducked;
+        self.sink.set_volume(self.output_volume());
+        self.ducked
+    }
+
+    // what's actually played at, the volume less muting and ducking
+    fn output_volume(&self) -> f32 {
+        match (self.muted, self.ducked) {
+            (true, _) => 0.0,
+            (false, true) => self.volume.min(DUCKED_VOLUME),
+            (false, false) => self.volume,
+        }
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Play episodes at `speed` times, between MIN_SPEED and MAX_SPEED in
+    /// steps of a tenth. Live streams always play as they come.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = ((speed * 10.0).round() / 10.0).clamp(MIN_SPEED, MAX_SPEED);
+        if !self.live {
+            self.sink.set_speed(self.speed);
+        }
+        playback_log::event(format!("Speed {:.1}x", self.speed));
+    }
+
+    /// Jump `seconds` forwards (or backwards when negative) in the current episode.
+    pub fn seek_by(&mut self, seconds: i64) {
+        let target = (i64::from(self.time_played()) + seconds).clamp(0, i64::from(u16::MAX));
+        self.seek_to(target as u16);
+    }
+
+    /// Jump to `position` seconds into the current episode, or its end if
+    /// that's sooner.
+    pub fn seek_to(&mut self, position: u16) {
+        if self.sink.empty() || self.live {
+            return;
+        }
+        let mut target = position;
+        if self.song_length > 0 {
+            target = target.min(self.song_length);
+        }
+
+        let from = self.time_played();
+        match self.sink.try_seek(Duration::from_secs(u64::from(target))) {
+            Ok(()) => {
+                playback_log::event(format!("Seek {}s to {}s", from, target));
+                self.set_time_played(target)
+            }
+            Err(e) => {
+                error!("Unable to seek to {}s: {}", target, e);
+                playback_log::event(format!("Seek {}s to {}s failed: {}", from, target, e));
+            }
+        }
+    }
+
     /// Update `self.song_length` with the provided file.
     pub fn update_song_length(&mut self, episode: &PinepodsEpisodes) {
         // update song length, currently playing
         self.song_length = episode.EpisodeDuration as u16;
     }
 }
+
+// kept here, or not from the server at all
+fn on_disk(url: &str) -> Option<PathBuf> {
+    local_store::playable_for(url).or_else(|| local_files::path(url))
+}
+
+// playback not moving on while not paused, checked once a second
+#[derive(Default)]
+struct Stalls {
+    last: Option<Duration>,
+    since: Option<Instant>,
+}
+
+impl Stalls {
+    fn check(&mut self, sink: &Sink) {
+        let position = sink.get_pos();
+        let stuck = !sink.is_paused() && self.last == Some(position);
+        // a resume since the last check would look like a stall otherwise
+        self.last = Some(position).filter(|_| !sink.is_paused());
+        match (stuck, self.since) {
+            (true, None) => {
+                self.since = Some(Instant::now());
+                playback_log::event(format!("Stalled at {}s", position.as_secs()));
+            }
+            (false, Some(since)) => {
+                self.since = None;
+                playback_log::event(format!(
+                    "Moving again after about {}s",
+                    since.elapsed().as_secs().max(1)
+                ));
+            }
+            _ => {}
+        }
+    }
+}
+
+struct Recording {
+    file: BufWriter<File>,
+    path: PathBuf,
+    since_check: u64,
+}
+
+impl Recording {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.file.write_all(bytes)?;
+        self.since_check += bytes.len() as u64;
+        if self.since_check >= RECORDING_CHECK_EVERY {
+            self.since_check = 0;
+            if gen_funcs::free_space(&self.path).is_some_and(|free| free < RECORDING_MIN_FREE) {
+                self.file.flush()?;
+                return Err(io::Error::other("the disk is almost full"));
+            }
+        }
+        Ok(())
+    }
+}
+
+// a response body for the decoder, which wants to be able to seek even
+// though a live stream only ever goes forward. Whatever is read is also
+// written to the recording, when there is one.
+struct LiveStream {
+    response: reqwest::blocking::Response,
+    position: u64,
+    recording: Arc<Mutex<Option<Recording>>>,
+}
+
+impl Read for LiveStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let waiting = Instant::now();
+        let read = self.response.read(buf)?;
+        // the decoder, and so the speakers, had nothing while this waited
+        if waiting.elapsed() >= LIVE_SLOW_READ {
+            playback_log::event(format!(
+                "Live stream waited {} ms for data",
+                waiting.elapsed().as_millis()
+            ));
+        }
+        self.position += read as u64;
+
+        let mut recording = self.recording.lock().unwrap();
+        if let Some(active) = recording.as_mut() {
+            if let Err(e) = active.write(&buf[..read]) {
+                error!("Recording to {:?} stopped: {}", active.path, e);
+                *recording = None;
+            }
+        }
+        Ok(read)
+    }
+}
+
+impl Seek for LiveStream {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match pos {
+            SeekFrom::Current(0) => Ok(self.position),
+            _ => Err(io::Error::new(io::ErrorKind::Unsupported, "live streams can't seek")),
+        }
+    }
+}
+
+fn recordings_dir() -> Option<PathBuf> {
+    dirs::audio_dir()
+        .or_else(dirs::home_dir)
+        .map(|dir| dir.join("Pinepods Recordings"))
+}
+
+// something every filesystem accepts
+fn file_name(title: &str) -> String {
+    let name: String = title
+        .chars()
+        .map(|c| match c.is_alphanumeric() || c == '-' || c == ' ' {
+            true => c,
+            false => '_',
+        })
+        .take(80)
+        .collect();
+    name.trim().to_string()
+}
+
+// from the url, since most live streams are mp3 and don't say so
+pub(crate) fn extension(url: &str) -> &'static str {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    match path.rsplit('.').next().map(str::to_ascii_lowercase).as_deref() {
+        Some("aac") => "aac",
+        Some("ogg") | Some("oga") => "ogg",
+        Some("opus") => "opus",
+        Some("m4a") => "m4a",
+        _ => "mp3",
+    }
+}
+
+// download url into memory, no more than its first limit bytes, and
+// whether that was all of it
+fn fetch_limited(url: &str, limit: u64) -> anyhow::Result<(Vec<u8>, bool)> {
+    let resp = reqwest::blocking::get(url)?.error_for_status()?;
+    let mut bytes = Vec::new();
+    resp.take(limit + 1).read_to_end(&mut bytes)?;
+    let whole = bytes.len() as u64 <= limit;
+    bytes.truncate(limit as usize);
+    Ok((bytes, whole))
+}
+
+// the rest of url after the head already fetched, or all of it again from a
+// server that doesn't do ranges
+fn fetch_rest(url: &str, mut head: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+    let resp = reqwest::blocking::Client::new()
+        .get(url)
+        .header(header::RANGE, format!("bytes={}-", head.len()))
+        .send()?
+        .error_for_status()?;
+    let resumed = resp.status() == StatusCode::PARTIAL_CONTENT;
+    let rest: Vec<u8> = resp.bytes()?.into();
+    if !resumed {
+        return Ok(rest);
+    }
+    head.extend(rest);
+    Ok(head)
+}
+
+/// The names of the audio outputs there are to play on.
+pub fn output_devices() -> Vec<String> {
+    match rodio::cpal::default_host().output_devices() {
+        Ok(devices) => devices.filter_map(|device| device.name().ok()).collect(),
+        Err(e) => {
+            error!("Unable to list the audio outputs: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Play on the output named `device` from now on, or the system's default
+/// for None, as players made after this will.
+pub fn set_output_device(device: Option<String>) {
+    *OUTPUT_DEVICE.lock().unwrap() = device;
+}
+
+/// Whether there's an output to play on, and if not, why.
+pub fn check_output() -> Result<(), StreamError> {
+    open_output().map(|_| ())
+}
+
+// the output picked, or the default one if it's gone
+fn open_output() -> Result<(OutputStream, OutputStreamHandle), StreamError> {
+    let picked = OUTPUT_DEVICE.lock().unwrap().clone();
+    if let Some(name) = picked {
+        let device = rodio::cpal::default_host()
+            .output_devices()
+            .ok()
+            .and_then(|mut devices| devices.find(|device| device.name().is_ok_and(|n| n == name)));
+        match device {
+            Some(device) => return OutputStream::try_from_device(&device),
+            None => error!("There's no audio output called {}, playing on the default one", name),
+        }
+    }
+    OutputStream::try_default()
+}
@@ -0,0 +1,110 @@
+// Notifications on the desktop as well as in the app's own banner, for when
+// Firewood is in a terminal that's out of sight or running as the daemon.
+// They go through whatever the system has for it: notify-send on Linux and
+// the BSDs, osascript on macOS and a toast through PowerShell on Windows.
+// Which sort are sent is set in the settings, and per podcast the notify
+// flag (see podcast_flags.rs) still decides whether its new episodes and
+// live shows are worth telling about at all.
+
+use std::process::Stdio;
+use std::str::FromStr;
+
+use log::warn;
+use tokio::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// a refresh found new episodes
+    Episodes,
+    /// a kept episode finished downloading, or couldn't be
+    Downloads,
+    /// a podcast went live
+    Live,
+}
+
+impl FromStr for Kind {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, ()> {
+        match value {
+            "episodes" => Ok(Kind::Episodes),
+            "downloads" => Ok(Kind::Downloads),
+            "live" => Ok(Kind::Live),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Desktop {
+    kinds: Vec<Kind>,
+    // there's nothing to send them with, noted once rather than every time
+    unavailable: bool,
+}
+
+impl Desktop {
+    pub fn new(kinds: Vec<Kind>) -> Self {
+        Self {
+            kinds,
+            unavailable: false,
+        }
+    }
+
+    /// Show `body` under `title` on the desktop, if `kind` is one to send.
+    pub fn send(&mut self, kind: Kind, title: &str, body: &str) {
+        if self.unavailable || !self.kinds.contains(&kind) {
+            return;
+        }
+        // the child is left to the runtime, which reaps it once it exits
+        if let Err(e) = command(title, body)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            warn!("Desktop notifications are off, there's nothing to show them with: {}", e);
+            self.unavailable = true;
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn command(title: &str, body: &str) -> Command {
+    // the text goes in through the environment, so none of it is read as script
+    const TOAST: &str = "$m=[Windows.UI.Notifications.ToastNotificationManager,Windows.UI.Notifications,ContentType=WindowsRuntime];\
+        $t=$m::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02);\
+        $x=$t.GetElementsByTagName('text');\
+        $x.Item(0).AppendChild($t.CreateTextNode($env:FIREWOOD_TITLE))>$null;\
+        $x.Item(1).AppendChild($t.CreateTextNode($env:FIREWOOD_BODY))>$null;\
+        $m::CreateToastNotifier('{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\\WindowsPowerShell\\v1.0\\powershell.exe').Show([Windows.UI.Notifications.ToastNotification]::new($t))";
+    let mut command = Command::new("powershell");
+    command
+        .args(["-NoProfile", "-NonInteractive", "-Command", TOAST])
+        .env("FIREWOOD_TITLE", title)
+        .env("FIREWOOD_BODY", body);
+    command
+}
+
+#[cfg(target_os = "macos")]
+fn command(title: &str, body: &str) -> Command {
+    // as arguments to the script, so none of the text is read as AppleScript
+    let mut command = Command::new("osascript");
+    command
+        .args([
+            "-e",
+            "on run argv",
+            "-e",
+            "display notification (item 2 of argv) with title (item 1 of argv)",
+            "-e",
+            "end run",
+        ])
+        .args([title, body]);
+    command
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn command(title: &str, body: &str) -> Command {
+    let mut command = Command::new("notify-send");
+    command.args(["--app-name=Firewood", "--", title, body]);
+    command
+}
@@ -0,0 +1,282 @@
+// Server actions that haven't been delivered yet.
+//
+// Everything that changes state on the server goes through here, in order, so
+// an action taken while the server is unreachable is replayed once it's back
+// instead of being lost. Pending actions are kept on disk between runs.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use super::requests::{self, PinepodsError, ReqwestValues};
+use super::errors;
+
+// most actions sent in one go
+const BATCH: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Action {
+    /// Listen position of an episode, in seconds.
+    Position { episode_id: i64, position: i64 },
+    /// Have the server download an episode.
+    Download { episode_id: i64 },
+    /// The order of the queue, top first.
+    QueueOrder { episode_ids: Vec<i64> },
+    /// Played to the end, so done with and off the server's queue.
+    Completed { episode_id: i64 },
+}
+
+impl Action {
+    // a newer action that makes sending this one pointless
+    fn superseded_by(&self, newer: &Action) -> bool {
+        match (self, newer) {
+            (Action::Position { episode_id: a, .. }, Action::Position { episode_id: b, .. }) => {
+                a == b
+            }
+            (Action::Download { episode_id: a }, Action::Download { episode_id: b }) => a == b,
+            (Action::QueueOrder { .. }, Action::QueueOrder { .. }) => true,
+            (Action::Completed { episode_id: a }, Action::Completed { episode_id: b }) => a == b,
+            _ => false,
+        }
+    }
+
+    // for error reports
+    fn endpoint(&self) -> &'static str {
+        match self {
+            Action::Position { .. } => "record_podcast_history",
+            Action::Download { .. } => "download_podcast",
+            Action::QueueOrder { .. } => "reorder_queue",
+            Action::Completed { .. } => "mark_episode_completed",
+        }
+    }
+
+    async fn send(&self, values: &ReqwestValues) -> Result<(), PinepodsError> {
+        match self {
+            Action::Position {
+                episode_id,
+                position,
+            } => values.record_listen_position(*episode_id, *position).await,
+            Action::Download { episode_id } => values.download_episode(*episode_id).await,
+            Action::QueueOrder { episode_ids } => values.reorder_queue(episode_ids).await,
+            // the server may not take it off the queue itself, nor say more
+            // than no when it wasn't queued there
+            Action::Completed { episode_id } => {
+                values.mark_completed(*episode_id).await?;
+                match values.remove_queued(*episode_id).await {
+                    Err(PinepodsError::Status(status)) if status.is_client_error() => Ok(()),
+                    removed => removed,
+                }
+            }
+        }
+    }
+}
+
+/// What came of sending a batch, for `Outbox::sent`.
+pub struct Flushed {
+    // delivered, or refused by the server so not worth sending again
+    done: Vec<Action>,
+    stalled: bool,
+}
+
+pub struct Outbox {
+    actions: VecDeque<Action>,
+    path: Option<PathBuf>,
+}
+
+impl Outbox {
+    /// Pick up whatever was still pending when the app last closed.
+    pub fn load() -> Self {
+        let path = requests::get_app_path().map(|p| p.join("outbox.json"));
+        let actions = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self { actions, path }
+    }
+
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    pub fn downloads(&self) -> usize {
+        self.actions
+            .iter()
+            .filter(|action| matches!(action, Action::Download { .. }))
+            .count()
+    }
+
+    pub fn push(&mut self, action: Action) {
+        self.actions.retain(|queued| !queued.superseded_by(&action));
+        self.actions.push_back(action);
+        self.save();
+    }
+
+    /// The oldest pending actions, up to `BATCH` so a long queue doesn't
+    /// take too long to get through, for `flush` to send.
+    pub fn batch(&self) -> Vec<Action> {
+        self.actions.iter().take(BATCH).cloned().collect()
+    }
+
+    /// Send `batch` oldest first, stopping at the first one that couldn't be
+    /// delivered so nothing overtakes it. Nothing is taken off the outbox
+    /// until the result is handed to `sent`, so this can run away from it.
+    pub async fn flush(batch: Vec<Action>, values: &ReqwestValues) -> Flushed {
+        let mut done = Vec::new();
+        let mut stalled = false;
+        for action in batch {
+            match action.send(values).await {
+                Ok(()) => errors::api_ok(),
+                // the server got it and said no, retrying won't change that
+                Err(PinepodsError::Status(status)) if status.is_client_error() => {
+                    error!("Dropping {:?}, server answered {}", action, status);
+                    errors::api_error(action.endpoint(), &status);
+                }
+                Err(e) => {
+                    error!("Server unreachable, keeping {:?} and what's after it: {:?}", action, e);
+                    stalled = true;
+                    break;
+                }
+            }
+            done.push(action);
+        }
+        Flushed { done, stalled }
+    }
+
+    /// Take off what `flush` got through. Anything pushed since stays, and an
+    /// action superseded meanwhile is already gone. True when that left some
+    /// to send.
+    pub fn sent(&mut self, flushed: Flushed) -> bool {
+        let before = self.actions.len();
+        for action in &flushed.done {
+            if let Some(i) = self.actions.iter().position(|queued| queued == action) {
+                self.actions.remove(i);
+            }
+        }
+
+        if self.actions.len() != before {
+            self.save();
+        }
+        !flushed.stalled && !self.actions.is_empty()
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let result = serde_json::to_string(&self.actions)
+            .map_err(std::io::Error::from)
+            .and_then(|json| fs::write(path, json));
+        if let Err(e) = result {
+            error!("Unable to save outbox to {:?}: {}", path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(episode_id: i64, position: i64) -> Action {
+        Action::Position { episode_id, position }
+    }
+
+    // kept in memory only
+    fn outbox(actions: &[Action]) -> Outbox {
+        Outbox {
+            actions: actions.iter().cloned().collect(),
+            path: None,
+        }
+    }
+
+    #[test]
+    fn superseded_by_the_same_kind_for_the_same_episode() {
+        assert!(position(1, 10).superseded_by(&position(1, 20)));
+        assert!(!position(1, 10).superseded_by(&position(2, 20)));
+        assert!(Action::Download { episode_id: 1 }.superseded_by(&Action::Download { episode_id: 1 }));
+        assert!(!Action::Download { episode_id: 1 }.superseded_by(&Action::Download { episode_id: 2 }));
+        assert!(Action::Completed { episode_id: 3 }.superseded_by(&Action::Completed { episode_id: 3 }));
+        assert!(!Action::Completed { episode_id: 3 }.superseded_by(&Action::Completed { episode_id: 4 }));
+    }
+
+    #[test]
+    fn any_queue_order_supersedes_another() {
+        let old = Action::QueueOrder { episode_ids: vec![1, 2] };
+        let new = Action::QueueOrder { episode_ids: vec![3] };
+        assert!(old.superseded_by(&new));
+        assert!(new.superseded_by(&old));
+    }
+
+    #[test]
+    fn different_kinds_dont_supersede() {
+        // finishing an episode doesn't make where it got to pointless to send
+        let actions = [
+            position(1, 10),
+            Action::Download { episode_id: 1 },
+            Action::QueueOrder { episode_ids: vec![1] },
+            Action::Completed { episode_id: 1 },
+        ];
+        for (i, older) in actions.iter().enumerate() {
+            for (j, newer) in actions.iter().enumerate() {
+                assert_eq!(older.superseded_by(newer), i == j, "{:?} {:?}", older, newer);
+            }
+        }
+    }
+
+    #[test]
+    fn push_drops_what_it_supersedes() {
+        let mut outbox = outbox(&[position(1, 10), position(2, 5), Action::Completed { episode_id: 2 }]);
+        outbox.push(position(1, 30));
+        assert_eq!(
+            outbox.batch(),
+            [position(2, 5), Action::Completed { episode_id: 2 }, position(1, 30)]
+        );
+    }
+
+    #[test]
+    fn batches_are_the_oldest() {
+        let actions: Vec<Action> = (0..BATCH as i64 + 5).map(|id| position(id, 1)).collect();
+        let outbox = outbox(&actions);
+        assert_eq!(outbox.batch(), actions[..BATCH]);
+    }
+
+    #[test]
+    fn sent_takes_off_only_what_got_through() {
+        let mut outbox = outbox(&[position(1, 10), position(2, 10), position(3, 10)]);
+        let flushed = Flushed {
+            done: vec![position(1, 10)],
+            stalled: true,
+        };
+        // stalled, so there's no point trying again straight away
+        assert!(!outbox.sent(flushed));
+        assert_eq!(outbox.batch(), [position(2, 10), position(3, 10)]);
+    }
+
+    #[test]
+    fn sent_keeps_what_was_pushed_meanwhile() {
+        let mut outbox = outbox(&[position(1, 10), Action::Download { episode_id: 2 }]);
+        let batch = outbox.batch();
+        // while the batch is on its way, a newer position replaces the one in it
+        outbox.push(position(1, 40));
+        let flushed = Flushed {
+            done: batch,
+            stalled: false,
+        };
+        assert!(outbox.sent(flushed));
+        assert_eq!(outbox.batch(), [position(1, 40)]);
+        assert!(!outbox.sent(Flushed {
+            done: vec![position(1, 40)],
+            stalled: false,
+        }));
+        assert!(outbox.is_empty());
+    }
+}
@@ -0,0 +1,165 @@
+// The palette Ctrl+K opens: one search over everything there is to go to, the
+// subscriptions, episodes, the queue, what's kept on this computer and every
+// command in the Controls tab, to jump to or play without going through the
+// tabs to find it.
+//
+// Matching is fuzzy, the letters typed have to come in order but not next to
+// one another, so "tdn" finds "The Daily News". Runs of letters and ones at
+// the start of words count for more, and of those that match as well, the
+// first listed wins, which is why the entries go in the order they do.
+
+use crossterm::event::KeyCode;
+use ratatui::widgets::ListState;
+
+use super::stateful_list::StatefulList;
+
+// listed at most, more than a screen's worth of the best
+const SHOWN: usize = 100;
+
+pub struct Entry<T> {
+    /// what it is, "Podcast", "Queue", "Command" and the like
+    pub kind: &'static str,
+    pub label: String,
+    /// shown after the label but not searched, a command's key for one
+    pub detail: String,
+    pub target: T,
+}
+
+pub struct Palette<T> {
+    entries: Vec<Entry<T>>,
+    query: String,
+    // the entries matching, best first
+    found: StatefulList<usize>,
+}
+
+impl<T> Palette<T> {
+    pub fn new(entries: Vec<Entry<T>>) -> Self {
+        let mut palette = Self {
+            entries,
+            query: String::new(),
+            found: StatefulList::with_items(Vec::new()),
+        };
+        palette.search();
+        palette
+    }
+
+    /// Start over from `entries`, as of what's loaded now, keeping what's
+    /// been typed.
+    pub fn set_entries(&mut self, entries: Vec<Entry<T>>) {
+        self.entries = entries;
+        self.search();
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.search();
+    }
+
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.search();
+    }
+
+    fn search(&mut self) {
+        let mut found: Vec<(i64, usize)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, entry)| Some((score(&self.query, &entry.label)?, i)))
+            .collect();
+        found.sort_by_key(|&(score, i)| (std::cmp::Reverse(score), i));
+        found.truncate(SHOWN);
+        self.found = StatefulList::with_items(found.into_iter().map(|(_, i)| i).collect());
+        self.found.next();
+    }
+
+    /// The entries matching, best first.
+    pub fn found(&self) -> impl Iterator<Item = &Entry<T>> {
+        self.found.items().iter().map(|&i| &self.entries[i])
+    }
+
+    pub fn state(&self) -> ListState {
+        self.found.state()
+    }
+
+    pub fn next(&mut self) {
+        self.found.next();
+    }
+
+    pub fn previous(&mut self) {
+        self.found.previous();
+    }
+
+    /// What the entry picked out goes to.
+    pub fn chosen(mut self) -> Option<T> {
+        let i = *self.found.items().get(self.found.selected()?)?;
+        Some(self.entries.swap_remove(i).target)
+    }
+}
+
+/// How well `query` matches `text`, None if its letters aren't all in there
+/// in order. Anything matches an empty query, equally.
+pub fn score(query: &str, text: &str) -> Option<i64> {
+    let query: Vec<char> = query.to_lowercase().chars().filter(|c| !c.is_whitespace()).collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    // the best of starting from each place the first letter is
+    text.iter()
+        .enumerate()
+        .filter(|(_, c)| **c == query[0])
+        .filter_map(|(start, _)| score_from(&query, &text, start))
+        .max()
+}
+
+// matching each letter as soon as it comes, from `start` on
+fn score_from(query: &[char], text: &[char], start: usize) -> Option<i64> {
+    let mut score = 0;
+    let mut last: Option<usize> = None;
+    let mut at = start;
+    for c in query {
+        let i = at + text[at..].iter().position(|t| t == c)?;
+        score += 10;
+        if i == 0 || !text[i - 1].is_alphanumeric() {
+            score += 8;
+        }
+        match last {
+            Some(last) if last + 1 == i => score += 6,
+            Some(last) => score -= (i - last - 1).min(5) as i64,
+            None => {}
+        }
+        last = Some(i);
+        at = i + 1;
+    }
+    // shorter texts are the closer match
+    Some(score - (text.len() as i64 / 20))
+}
+
+/// The key a row of the Controls tab is under, the first of them where there
+/// are a few, None for ones that take Ctrl or a sequence of keys, which a
+/// command in the palette can't stand in for.
+pub fn key(label: &str) -> Option<KeyCode> {
+    let first = label.split(" / ").next()?.trim();
+    if let Some(letter) = first.strip_prefix("Shift + ") {
+        let mut chars = letter.chars();
+        return match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphabetic() => Some(KeyCode::Char(c.to_ascii_uppercase())),
+            _ => None,
+        };
+    }
+    if let Some(number) = first.strip_prefix('F').and_then(|number| number.parse().ok()) {
+        return Some(KeyCode::F(number));
+    }
+    let mut chars = first.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphabetic() => Some(KeyCode::Char(c.to_ascii_lowercase())),
+        (Some(c), None) if c.is_ascii_punctuation() => Some(KeyCode::Char(c)),
+        _ if first == "Space" => Some(KeyCode::Char(' ')),
+        _ => None,
+    }
+}
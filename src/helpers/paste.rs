@@ -0,0 +1,206 @@
+// Links pasted in, from the system clipboard (Ctrl + V) or the terminal's own
+// paste, worked out into something to do with them:
+//
+//   a Pinepods share link, or a playlist on this server   import it (see
+//                                                          playlist_import.rs)
+//   an audio file                                         queue it
+//   a podcast feed                                        subscribe to it
+//   an episode's page                                     queue the episode,
+//                                                          found through the
+//                                                          feed the page links
+//
+// Nothing is done until the user has seen what it came to and said yes. The
+// clipboard is read through whatever the system has for it, wl-paste, xclip
+// or xsel on Linux and the BSDs, pbpaste on macOS and PowerShell on Windows.
+
+use std::io;
+use std::process::Stdio;
+
+use anyhow::{anyhow, Result};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Url;
+use tokio::process::Command;
+
+use super::feed::{self, Feed};
+use super::html;
+use super::local_files::AUDIO;
+use super::playlist_import::{self, Import, Link};
+use super::requests::{PinepodsEpisodes, PinepodsPodcasts, ReqwestValues};
+
+/// What a pasted link came to.
+#[derive(Debug)]
+pub enum Pasted {
+    Subscribe(PinepodsPodcasts),
+    Queue(PinepodsEpisodes),
+    Import(Import),
+}
+
+/// What's on the system clipboard.
+pub async fn clipboard() -> io::Result<String> {
+    let mut tried = Vec::new();
+    for (program, args) in readers() {
+        let output = Command::new(program)
+            .args(*args)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .await;
+        match output {
+            Ok(output) if output.status.success() => return Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+            // there, but nothing it could read, e.g. xclip without an X display
+            Ok(_) => tried.push(*program),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Err(match tried.is_empty() {
+        true => io::Error::new(io::ErrorKind::NotFound, "there's nothing to read the clipboard with"),
+        false => io::Error::other(format!("{} couldn't read the clipboard", tried.join(", "))),
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn readers() -> &'static [(&'static str, &'static [&'static str])] {
+    &[("powershell", &["-NoProfile", "-NonInteractive", "-Command", "Get-Clipboard -Raw"])]
+}
+
+#[cfg(target_os = "macos")]
+fn readers() -> &'static [(&'static str, &'static [&'static str])] {
+    &[("pbpaste", &[])]
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn readers() -> &'static [(&'static str, &'static [&'static str])] {
+    &[
+        ("wl-paste", &["--no-newline"]),
+        ("xclip", &["-out", "-selection", "clipboard"]),
+        ("xsel", &["--clipboard", "--output"]),
+    ]
+}
+
+/// Work out what `text`, the first line of it, is a link to.
+pub async fn resolve(values: &ReqwestValues, text: &str) -> Result<Pasted> {
+    let link = text.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or_default();
+    let url = Url::parse(link)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .ok_or_else(|| anyhow!("not a link: {}", shorten(link)))?;
+
+    let server = values.url.trim_end_matches('/');
+    let import = match Link::parse(link) {
+        Some(shared @ Link::SharedEpisode(_)) => Some(shared),
+        Some(playlist @ Link::Playlist(_)) if !server.is_empty() && link.starts_with(server) => Some(playlist),
+        _ => None,
+    };
+    if let Some(import) = import {
+        return Ok(Pasted::Import(playlist_import::resolve(values, &import).await?));
+    }
+    if is_audio_file(&url) {
+        return Ok(Pasted::Queue(bare_episode(&url)));
+    }
+
+    let response = reqwest::get(url.clone()).await?.error_for_status()?;
+    let mime = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    // left unread, it could be a whole episode
+    if mime.starts_with("audio/") {
+        return Ok(Pasted::Queue(bare_episode(&url)));
+    }
+    let body = response.text().await?;
+    if let Some(feed) = as_feed(&body) {
+        return Ok(Pasted::Subscribe(feed.podcast(link)));
+    }
+
+    // a page, episode or show, with its feed linked from it
+    let feed_url = feed_link(&body, &url).ok_or_else(|| anyhow!("no feed or episode found at {}", shorten(link)))?;
+    let feed = feed::fetch(&feed_url).await?;
+    let item = feed
+        .items
+        .iter()
+        .find(|item| item.link.as_deref().is_some_and(|page| same_page(page, link)));
+    match item.and_then(|item| Some((item, item.enclosure.as_ref()?))) {
+        Some((item, enclosure)) => Ok(Pasted::Queue(PinepodsEpisodes {
+            PodcastName: Some(feed.channel.title.clone()),
+            EpisodeTitle: item.title.clone(),
+            EpisodePubDate: String::new(),
+            EpisodeDescription: String::new(),
+            EpisodeArtwork: feed.channel.artwork.clone(),
+            EpisodeURL: enclosure.clone(),
+            EpisodeDuration: 0,
+            ListenDuration: None,
+            EpisodeID: None,
+            PodcastID: None,
+        })),
+        None => Ok(Pasted::Subscribe(feed.podcast(&feed_url))),
+    }
+}
+
+fn is_audio_file(url: &Url) -> bool {
+    let extension = url.path().rsplit_once('.').map(|(_, extension)| extension.to_ascii_lowercase());
+    extension.is_some_and(|extension| AUDIO.contains(&extension.as_str()))
+}
+
+// an episode known only by its file, named after it
+fn bare_episode(url: &Url) -> PinepodsEpisodes {
+    let file = url
+        .path_segments()
+        .and_then(|mut segments| segments.rfind(|segment| !segment.is_empty()))
+        .unwrap_or(url.as_str());
+    PinepodsEpisodes {
+        PodcastName: None,
+        EpisodeTitle: file.to_string(),
+        EpisodePubDate: String::new(),
+        EpisodeDescription: String::new(),
+        EpisodeArtwork: String::new(),
+        EpisodeURL: url.to_string(),
+        EpisodeDuration: 0,
+        ListenDuration: None,
+        EpisodeID: None,
+        PodcastID: None,
+    }
+}
+
+// pages have a <title> too, only a feed has an <rss> around it
+fn as_feed(body: &str) -> Option<Feed> {
+    let start = body.as_bytes()[..body.len().min(1024)].to_ascii_lowercase();
+    if !start.windows(4).any(|window| window == b"<rss") {
+        return None;
+    }
+    feed::parse(body).ok().filter(|feed| !feed.channel.title.is_empty())
+}
+
+// the <link rel="alternate" type="application/rss+xml" href="..."> of a page
+fn feed_link(body: &str, page: &Url) -> Option<String> {
+    // the same length as body, so positions in one are positions in the other
+    let lower = body.to_ascii_lowercase();
+    lower.match_indices("<link").find_map(|(at, _)| {
+        let end = at + lower[at..].find('>')?;
+        let tag = &lower[at..end];
+        if !tag.contains("application/rss+xml") {
+            return None;
+        }
+        let href = html::attribute(&body[at..end], &lower[at..end], "href")?;
+        page.join(&html::decode(href)).ok().map(String::from)
+    })
+}
+
+// the same page whichever of http and https, or a trailing /, it's given with
+fn same_page(a: &str, b: &str) -> bool {
+    let bare = |url: &str| {
+        let url = url.trim();
+        let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+        url.trim_end_matches('/').to_string()
+    };
+    bare(a) == bare(b)
+}
+
+fn shorten(text: &str) -> String {
+    match text.char_indices().nth(60) {
+        Some((at, _)) => format!("{}...", &text[..at]),
+        None => text.to_string(),
+    }
+}
@@ -0,0 +1,229 @@
+// What this device has played, as opposed to the server's history for the
+// whole account: one entry per time an episode was played, with how long it
+// actually played for. Kept in <app_path>/play_history.json for the remote
+// control's /api/history and /api/stats, so a dashboard can see what each
+// room's player has been up to without asking the Pinepods server.
+//
+// Times are unix seconds. Only time spent playing counts, pausing stops the
+// clock, and so does anything long enough between updates to look like the
+// machine was asleep.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{Local, TimeZone, Utc};
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use super::requests::{self, PinepodsEpisodes};
+
+// oldest entries go once there are more than this
+const MAX_PLAYS: usize = 1000;
+// longer than this between updates isn't counted as listening
+const MAX_GAP: Duration = Duration::from_secs(30);
+// how much listening can go unsaved
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Play {
+    pub episode_id: Option<i64>,
+    pub episode: String,
+    pub url: String,
+    pub podcast: Option<String>,
+    pub started: i64,
+    pub last_played: i64,
+    /// seconds actually played
+    pub listened: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PodcastStats {
+    pub podcast: String,
+    pub listened: u64,
+    pub plays: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Summary {
+    /// seconds, across everything recorded
+    pub listened: u64,
+    pub plays: usize,
+    /// different episodes played
+    pub episodes: usize,
+    /// seconds since local midnight
+    pub today: u64,
+    /// seconds over the last 7 days
+    pub week: u64,
+    /// most listened first
+    pub podcasts: Vec<PodcastStats>,
+}
+
+struct State {
+    plays: Vec<Play>,
+    path: Option<PathBuf>,
+    loaded: bool,
+    // url of the episode being added to, and when it was last added to
+    current: Option<(String, Instant)>,
+    unsaved: Duration,
+}
+
+static STATE: Mutex<State> = Mutex::new(State {
+    plays: Vec::new(),
+    path: None,
+    loaded: false,
+    current: None,
+    unsaved: Duration::ZERO,
+});
+
+// the file's read the first time it's needed
+fn load(state: &mut State) {
+    if state.loaded {
+        return;
+    }
+    state.loaded = true;
+    state.path = requests::get_app_path().map(|p| p.join("play_history.json"));
+    state.plays = state
+        .path
+        .as_ref()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+}
+
+/// Note what's playing right now, None if nothing is or it's paused. Meant to
+/// be called every time round the loop that owns the player.
+pub fn update(playing: Option<&PinepodsEpisodes>) {
+    let mut state = STATE.lock().unwrap();
+    load(&mut state);
+
+    let Some(episode) = playing else {
+        // a resume carries on with the same play
+        if let Some((_, since)) = state.current.as_mut() {
+            *since = Instant::now();
+        }
+        if !state.unsaved.is_zero() {
+            save(&mut state);
+        }
+        return;
+    };
+
+    let now = Utc::now().timestamp();
+    match state.current.clone() {
+        Some((url, since)) if url == episode.EpisodeURL && !state.plays.is_empty() => {
+            let elapsed = since.elapsed();
+            if elapsed > MAX_GAP {
+                state.current = Some((url, Instant::now()));
+                return;
+            }
+            // whole seconds only, the rest carries over to the next update
+            let elapsed = Duration::from_secs(elapsed.as_secs());
+            if elapsed.is_zero() {
+                return;
+            }
+            state.current = Some((url, since + elapsed));
+            if let Some(play) = state.plays.last_mut() {
+                play.listened += elapsed.as_secs();
+                play.last_played = now;
+            }
+            state.unsaved += elapsed;
+            if state.unsaved >= SAVE_INTERVAL {
+                save(&mut state);
+            }
+        }
+        _ => {
+            state.current = Some((episode.EpisodeURL.clone(), Instant::now()));
+            state.plays.push(Play {
+                episode_id: episode.EpisodeID,
+                episode: episode.EpisodeTitle.clone(),
+                url: episode.EpisodeURL.clone(),
+                podcast: episode.PodcastName.clone(),
+                started: now,
+                last_played: now,
+                listened: 0,
+            });
+            let excess = state.plays.len().saturating_sub(MAX_PLAYS);
+            state.plays.drain(..excess);
+            save(&mut state);
+        }
+    }
+}
+
+/// The most recent `limit` plays, newest first.
+pub fn history(limit: usize) -> Vec<Play> {
+    let mut state = STATE.lock().unwrap();
+    load(&mut state);
+    state.plays.iter().rev().take(limit).cloned().collect()
+}
+
+pub fn summary() -> Summary {
+    let mut state = STATE.lock().unwrap();
+    load(&mut state);
+
+    let now = Utc::now().timestamp();
+    let midnight = Local::now()
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .and_then(|midnight| Local.from_local_datetime(&midnight).earliest())
+        .map_or(now, |midnight| midnight.timestamp());
+    let week_ago = now - 7 * 24 * 60 * 60;
+
+    let mut podcasts: HashMap<&str, PodcastStats> = HashMap::new();
+    for play in &state.plays {
+        let podcast = play.podcast.as_deref().unwrap_or("Unknown");
+        let stats = podcasts.entry(podcast).or_insert_with(|| PodcastStats {
+            podcast: podcast.to_string(),
+            listened: 0,
+            plays: 0,
+        });
+        stats.listened += play.listened;
+        stats.plays += 1;
+    }
+    let mut podcasts: Vec<PodcastStats> = podcasts.into_values().collect();
+    podcasts.sort_by(|a, b| b.listened.cmp(&a.listened).then_with(|| a.podcast.cmp(&b.podcast)));
+
+    // a play that ran over midnight counts for the day it was last played
+    let since = |start: i64| -> u64 {
+        state
+            .plays
+            .iter()
+            .filter(|play| play.last_played >= start)
+            .map(|play| play.listened)
+            .sum()
+    };
+    Summary {
+        listened: state.plays.iter().map(|play| play.listened).sum(),
+        plays: state.plays.len(),
+        episodes: state.plays.iter().map(|play| &play.url).collect::<HashSet<_>>().len(),
+        today: since(midnight),
+        week: since(week_ago),
+        podcasts,
+    }
+}
+
+/// Save what's unsaved and start over from the file, for a different user's
+/// history once they're the active one.
+pub fn reload() {
+    let mut state = STATE.lock().unwrap();
+    if !state.unsaved.is_zero() {
+        save(&mut state);
+    }
+    state.loaded = false;
+    state.current = None;
+    state.plays.clear();
+}
+
+fn save(state: &mut State) {
+    state.unsaved = Duration::ZERO;
+    let Some(path) = &state.path else {
+        return;
+    };
+    let result = serde_json::to_string(&state.plays)
+        .map_err(std::io::Error::from)
+        .and_then(|json| fs::write(path, json));
+    if let Err(e) = result {
+        error!("Unable to save play history to {:?}: {}", path, e);
+    }
+}
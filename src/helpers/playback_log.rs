@@ -0,0 +1,111 @@
+// A log of this session's playback, kept apart from the main log so it can
+// be attached to an audio bug report on its own: the output device and its
+// format, each episode's source format and how long it took to fetch, seeks,
+// and stalls where playback stopped moving without being paused.
+//
+// Written to <app_path>/playback.log as it happens, the last session's is
+// kept as playback.previous.log, since that's the one wanted after a crash.
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use chrono::Local;
+use rodio::cpal::traits::{DeviceTrait, HostTrait};
+
+use super::requests;
+
+struct Session {
+    started: Option<Instant>,
+    file: Option<File>,
+    path: Option<PathBuf>,
+}
+
+static SESSION: Mutex<Session> = Mutex::new(Session {
+    started: None,
+    file: None,
+    path: None,
+});
+
+/// Start this session's log with what it's playing on.
+pub fn init() {
+    let path = requests::base_app_path().map(|p| p.join("playback.log"));
+    let file = path.as_ref().and_then(|path| {
+        let _ = fs::rename(path, path.with_file_name("playback.previous.log"));
+        File::create(path).ok()
+    });
+    {
+        let mut session = SESSION.lock().unwrap();
+        session.started = Some(Instant::now());
+        session.file = file;
+        session.path = path;
+    }
+
+    event(format!(
+        "pinepods-firewood {} on {}, started {}",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        Local::now().to_rfc3339()
+    ));
+    event(output_device());
+}
+
+// what rodio picks, its default output device
+fn output_device() -> String {
+    let host = rodio::cpal::default_host();
+    let Some(device) = host.default_output_device() else {
+        return format!("Output: no default device on {}", host.id().name());
+    };
+    let name = device.name().unwrap_or_else(|_| String::from("unnamed"));
+    match device.default_output_config() {
+        Ok(config) => format!(
+            "Output: {} via {}, {} Hz, {} channels, {:?}",
+            name,
+            host.id().name(),
+            config.sample_rate().0,
+            config.channels(),
+            config.sample_format()
+        ),
+        Err(e) => format!("Output: {} via {}, no default format: {}", name, host.id().name(), e),
+    }
+}
+
+/// Add a line, stamped with the time since the session started.
+pub fn event(message: impl AsRef<str>) {
+    let mut session = SESSION.lock().unwrap();
+    let elapsed = session.started.map(|started| started.elapsed()).unwrap_or_default();
+    let Some(file) = session.file.as_mut() else {
+        return;
+    };
+    let line = format!(
+        "[{:>4}:{:02}.{:03}] {}\n",
+        elapsed.as_secs() / 60,
+        elapsed.as_secs() % 60,
+        elapsed.subsec_millis(),
+        message.as_ref()
+    );
+    // a log that can't be written isn't worth stopping playback for
+    if file.write_all(line.as_bytes()).is_err() {
+        session.file = None;
+    }
+}
+
+/// Copy this session's log somewhere easy to find, returning where.
+pub fn export() -> io::Result<PathBuf> {
+    let session = SESSION.lock().unwrap();
+    let path = session
+        .path
+        .as_ref()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no playback log this session"))?;
+    let dir = dirs::download_dir()
+        .or_else(dirs::home_dir)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no downloads or home directory"))?;
+    let target = dir.join(format!(
+        "firewood-playback-{}.log",
+        Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    fs::copy(path, &target)?;
+    Ok(target)
+}
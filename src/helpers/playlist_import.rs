@@ -0,0 +1,124 @@
+// Filling the queue from something shared on a Pinepods server: a shared
+// episode link (.../shared_episode/<key>), a playlist link ending in its id,
+// or just the id. The server's answers use whichever key case it stores
+// things in, so episodes are matched up field by field rather than trusted
+// to be in the usual shape, and anything without a title and url to play is
+// reported instead.
+
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+
+use super::requests::{PinepodsEpisodes, ReqwestValues};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Link {
+    SharedEpisode(String),
+    Playlist(i64),
+}
+
+impl Link {
+    pub fn parse(link: &str) -> Option<Link> {
+        let link = link.trim().trim_end_matches('/');
+        if let Some((_, key)) = link.rsplit_once("/shared_episode/") {
+            let key = key.split(['?', '#', '/']).next()?;
+            return (!key.is_empty()).then(|| Link::SharedEpisode(key.to_string()));
+        }
+        // a bare id, or whatever the link ends with, ?playlist_id=12 included
+        let id = link.rsplit(['/', '=']).next()?;
+        id.parse().ok().map(Link::Playlist)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Import {
+    pub episodes: Vec<PinepodsEpisodes>,
+    /// titles, or what there was instead, of entries that couldn't be played
+    pub unmatched: Vec<String>,
+}
+
+pub async fn resolve(values: &ReqwestValues, link: &Link) -> Result<Import> {
+    let answer = match link {
+        Link::SharedEpisode(key) => values.shared_episode(key).await?,
+        Link::Playlist(id) => values.playlist_episodes(*id).await?,
+    };
+
+    let mut import = Import::default();
+    for entry in entries(answer)? {
+        match episode(&entry) {
+            Some(episode) => import.episodes.push(episode),
+            None => import.unmatched.push(label(&entry)),
+        }
+    }
+    Ok(import)
+}
+
+// the list, whether it came bare or wrapped in an object
+fn entries(answer: Value) -> Result<Vec<Value>> {
+    match answer {
+        Value::Array(entries) => Ok(entries),
+        Value::Object(mut object) => {
+            for key in ["episodes", "episode", "data"] {
+                match object.remove(key) {
+                    Some(Value::Array(entries)) => return Ok(entries),
+                    Some(entry @ Value::Object(_)) => return Ok(vec![entry]),
+                    _ => {}
+                }
+            }
+            Ok(vec![Value::Object(object)])
+        }
+        _ => Err(anyhow!("The server's answer had no episodes in it")),
+    }
+}
+
+// an entry's fields under the names PinepodsEpisodes uses, whatever their
+// case or underscores on the way in
+fn episode(entry: &Value) -> Option<PinepodsEpisodes> {
+    const FIELDS: [&str; 10] = [
+        "PodcastName",
+        "EpisodeTitle",
+        "EpisodePubDate",
+        "EpisodeDescription",
+        "EpisodeArtwork",
+        "EpisodeURL",
+        "EpisodeDuration",
+        "ListenDuration",
+        "EpisodeID",
+        "PodcastID",
+    ];
+    let mut fields = Map::new();
+    for (key, value) in entry.as_object()? {
+        if let Some(field) = FIELDS.iter().find(|field| simple(field) == simple(key)) {
+            fields.insert(field.to_string(), value.clone());
+        }
+    }
+    // has to have something to play, the rest can be blank
+    for required in ["EpisodeTitle", "EpisodeURL"] {
+        fields.get(required)?.as_str().filter(|value| !value.is_empty())?;
+    }
+    for optional in ["EpisodePubDate", "EpisodeDescription", "EpisodeArtwork"] {
+        if !fields.get(optional).is_some_and(Value::is_string) {
+            fields.insert(optional.to_string(), Value::String(String::new()));
+        }
+    }
+    if !fields.get("EpisodeDuration").is_some_and(Value::is_i64) {
+        fields.insert(String::from("EpisodeDuration"), Value::from(0));
+    }
+    serde_json::from_value(Value::Object(fields)).ok()
+}
+
+fn simple(key: &str) -> String {
+    key.replace('_', "").to_lowercase()
+}
+
+fn label(entry: &Value) -> String {
+    entry
+        .as_object()
+        .and_then(|object| {
+            object
+                .iter()
+                .find(|(key, _)| simple(key) == "episodetitle")
+        })
+        .and_then(|(_, title)| title.as_str())
+        .map(String::from)
+        .unwrap_or_else(|| entry.to_string().chars().take(60).collect())
+}
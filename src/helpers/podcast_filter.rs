@@ -0,0 +1,110 @@
+// Which subscriptions the podcast list and saved searches show. Either the
+// picked podcasts are hidden, or they're the only ones shown. The picks are
+// kept on disk, and the filter can be turned off without losing them.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use super::requests;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FilterMode {
+    /// hide the picked podcasts
+    #[default]
+    Exclude,
+    /// show only the picked podcasts
+    Include,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Saved {
+    enabled: bool,
+    mode: FilterMode,
+    // by podcast name
+    podcasts: BTreeSet<String>,
+}
+
+pub struct PodcastFilter {
+    saved: Saved,
+    path: Option<PathBuf>,
+}
+
+impl PodcastFilter {
+    pub fn load() -> Self {
+        let path = requests::get_app_path().map(|p| p.join("podcast_filter.json"));
+        let saved = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self { saved, path }
+    }
+
+    /// Whether the filter is on and has anything picked.
+    pub fn active(&self) -> bool {
+        self.saved.enabled && !self.saved.podcasts.is_empty()
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.saved.enabled
+    }
+
+    pub fn mode(&self) -> FilterMode {
+        self.saved.mode
+    }
+
+    pub fn picked(&self, podcast: &str) -> bool {
+        self.saved.podcasts.contains(podcast)
+    }
+
+    /// Whether `podcast` gets through, always true while the filter is off.
+    pub fn shows(&self, podcast: &str) -> bool {
+        if !self.active() {
+            return true;
+        }
+        match self.saved.mode {
+            FilterMode::Exclude => !self.picked(podcast),
+            FilterMode::Include => self.picked(podcast),
+        }
+    }
+
+    /// Pick or unpick `podcast`. Picking turns the filter on.
+    pub fn toggle(&mut self, podcast: &str) {
+        if !self.saved.podcasts.remove(podcast) {
+            self.saved.podcasts.insert(podcast.to_string());
+            self.saved.enabled = true;
+        }
+        self.write();
+    }
+
+    pub fn switch_mode(&mut self) {
+        self.saved.mode = match self.saved.mode {
+            FilterMode::Exclude => FilterMode::Include,
+            FilterMode::Include => FilterMode::Exclude,
+        };
+        self.write();
+    }
+
+    pub fn toggle_enabled(&mut self) {
+        self.saved.enabled = !self.saved.enabled;
+        self.write();
+    }
+
+    fn write(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let result = serde_json::to_string(&self.saved)
+            .map_err(std::io::Error::from)
+            .and_then(|json| fs::write(path, json));
+        if let Err(e) = result {
+            error!("Unable to save the podcast filter to {:?}: {}", path, e);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+// Per podcast preferences set from the podcast's details: whether it notifies,
+// whether the smart order favours it, and how its episodes play. Kept on disk
+// by podcast name, only for podcasts that have anything other than the
+// defaults.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use super::requests;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Notify {
+    /// when it goes live
+    #[default]
+    Default,
+    Never,
+    /// when it goes live, and as soon as a refresh finds new episodes
+    Immediately,
+}
+
+impl Notify {
+    pub fn label(self) -> &'static str {
+        match self {
+            Notify::Default => "When Live",
+            Notify::Never => "Never",
+            Notify::Immediately => "New Episodes Too",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Flags {
+    #[serde(default)]
+    pub notify: Notify,
+    #[serde(default)]
+    pub high_priority: bool,
+    /// times normal speed, None for the speed everything else plays at
+    #[serde(default)]
+    pub speed: Option<f32>,
+    /// seconds skipped at the start of each episode, and at the end
+    #[serde(default)]
+    pub skip_intro: u16,
+    #[serde(default)]
+    pub skip_outro: u16,
+    /// its episodes, or a folder's files, are the parts of a book, played in
+    /// order (see audiobook.rs)
+    #[serde(default)]
+    pub audiobook: bool,
+}
+
+pub struct PodcastFlags {
+    flags: BTreeMap<String, Flags>,
+    path: Option<PathBuf>,
+}
+
+impl PodcastFlags {
+    pub fn load() -> Self {
+        let path = requests::get_app_path().map(|p| p.join("podcast_flags.json"));
+        let flags = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self { flags, path }
+    }
+
+    pub fn get(&self, podcast: &str) -> Flags {
+        self.flags.get(podcast).copied().unwrap_or_default()
+    }
+
+    /// Default, then never, then immediately, then back round.
+    pub fn cycle_notify(&mut self, podcast: &str) -> Flags {
+        self.change(podcast, |flags| {
+            flags.notify = match flags.notify {
+                Notify::Default => Notify::Never,
+                Notify::Never => Notify::Immediately,
+                Notify::Immediately => Notify::Default,
+            }
+        })
+    }
+
+    pub fn toggle_high_priority(&mut self, podcast: &str) -> Flags {
+        self.change(podcast, |flags| flags.high_priority = !flags.high_priority)
+    }
+
+    pub fn set_speed(&mut self, podcast: &str, speed: Option<f32>) -> Flags {
+        self.change(podcast, |flags| flags.speed = speed)
+    }
+
+    pub fn set_skips(&mut self, podcast: &str, intro: u16, outro: u16) -> Flags {
+        self.change(podcast, |flags| {
+            flags.skip_intro = intro;
+            flags.skip_outro = outro;
+        })
+    }
+
+    pub fn toggle_audiobook(&mut self, podcast: &str) -> Flags {
+        self.change(podcast, |flags| flags.audiobook = !flags.audiobook)
+    }
+
+    pub fn high_priority(&self) -> HashSet<String> {
+        self.flags
+            .iter()
+            .filter(|(_, flags)| flags.high_priority)
+            .map(|(podcast, _)| podcast.clone())
+            .collect()
+    }
+
+    fn change(&mut self, podcast: &str, change: impl FnOnce(&mut Flags)) -> Flags {
+        let mut flags = self.get(podcast);
+        change(&mut flags);
+        if flags == Flags::default() {
+            self.flags.remove(podcast);
+        } else {
+            self.flags.insert(podcast.to_string(), flags);
+        }
+        self.write();
+        flags
+    }
+
+    fn write(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let result = serde_json::to_string(&self.flags)
+            .map_err(std::io::Error::from)
+            .and_then(|json| fs::write(path, json));
+        if let Err(e) = result {
+            error!("Unable to save podcast flags to {:?}: {}", path, e);
+        }
+    }
+}
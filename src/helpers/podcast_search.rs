@@ -0,0 +1,219 @@
+// Finding podcasts that aren't subscribed to yet, from a directory rather
+// than the server, which only knows about those it already has:
+//
+//   iTunes        Apple's podcast directory, nothing needed to search it
+//   PodcastIndex  the open index, searched with the API key and secret from
+//                 https://api.podcastindex.org if they're set, or through the
+//                 Pinepods search service otherwise, as the web app does
+//
+// Results come back as podcasts ready to subscribe to, except that iTunes
+// doesn't say what a podcast is about; its feed has that (see feed.rs).
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+use super::requests::PinepodsPodcasts;
+use super::sha1;
+
+const RESULTS: usize = 30;
+const PINEPODS_SEARCH: &str = "https://search.pinepods.online/api/search";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Provider {
+    #[default]
+    Itunes,
+    PodcastIndex,
+}
+
+impl FromStr for Provider {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, ()> {
+        match value {
+            "itunes" => Ok(Provider::Itunes),
+            "podcastindex" => Ok(Provider::PodcastIndex),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Provider {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Provider::Itunes => "iTunes",
+            Provider::PodcastIndex => "PodcastIndex",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Provider::Itunes => Provider::PodcastIndex,
+            Provider::PodcastIndex => Provider::Itunes,
+        }
+    }
+}
+
+/// A PodcastIndex API key and its secret.
+#[derive(Debug, Clone)]
+pub struct Keys {
+    pub key: String,
+    pub secret: String,
+}
+
+#[derive(Deserialize)]
+struct ItunesResults {
+    results: Vec<ItunesPodcast>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ItunesPodcast {
+    collection_name: Option<String>,
+    artist_name: Option<String>,
+    // missing for the few that are Apple's only
+    feed_url: Option<String>,
+    artwork_url600: Option<String>,
+    artwork_url100: Option<String>,
+    track_count: Option<u32>,
+    #[serde(default)]
+    genres: Vec<String>,
+    collection_view_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct IndexResults {
+    #[serde(default)]
+    feeds: Vec<IndexPodcast>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexPodcast {
+    title: Option<String>,
+    url: Option<String>,
+    link: Option<String>,
+    description: Option<String>,
+    author: Option<String>,
+    image: Option<String>,
+    artwork: Option<String>,
+    episode_count: Option<u32>,
+    // by PodcastIndex's id for them, null when there are none
+    categories: Option<HashMap<String, String>>,
+}
+
+/// Podcasts in `provider`'s directory matching `query`, best matches first.
+pub async fn search(provider: Provider, query: &str, keys: Option<&Keys>) -> Result<Vec<PinepodsPodcasts>> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(15))
+        .user_agent(concat!("Firewood/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    let podcasts = match provider {
+        Provider::Itunes => {
+            let results: ItunesResults = client
+                .get("https://itunes.apple.com/search")
+                .query(&[("media", "podcast"), ("entity", "podcast"), ("term", query)])
+                .query(&[("limit", RESULTS)])
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            results.results.into_iter().filter_map(from_itunes).collect()
+        }
+        Provider::PodcastIndex => {
+            let request = match keys {
+                Some(keys) => signed(
+                    client
+                        .get("https://api.podcastindex.org/api/1.0/search/byterm")
+                        .query(&[("q", query)])
+                        .query(&[("max", RESULTS)]),
+                    keys,
+                ),
+                None => client
+                    .get(PINEPODS_SEARCH)
+                    .query(&[("query", query), ("index", "podcastindex")]),
+            };
+            let results: IndexResults = request.send().await?.error_for_status()?.json().await?;
+            results.feeds.into_iter().filter_map(from_index).take(RESULTS).collect()
+        }
+    };
+    Ok(podcasts)
+}
+
+// PodcastIndex wants the key, the time and a hash of both with the secret
+fn signed(request: reqwest::RequestBuilder, keys: &Keys) -> reqwest::RequestBuilder {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs())
+        .unwrap_or_default()
+        .to_string();
+    let hash: String = sha1::digest(format!("{}{}{}", keys.key, keys.secret, now).as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    request
+        .header("X-Auth-Key", &keys.key)
+        .header("X-Auth-Date", now)
+        .header("Authorization", hash)
+}
+
+fn from_itunes(podcast: ItunesPodcast) -> Option<PinepodsPodcasts> {
+    Some(PinepodsPodcasts {
+        PodcastID: 0,
+        PodcastName: podcast.collection_name?,
+        ArtworkURL: podcast.artwork_url600.or(podcast.artwork_url100).unwrap_or_default(),
+        Author: podcast.artist_name.unwrap_or_default(),
+        // the first is always just "Podcasts"
+        Categories: podcast
+            .genres
+            .into_iter()
+            .filter(|genre| genre != "Podcasts")
+            .collect::<Vec<_>>()
+            .join(", "),
+        EpisodeCount: podcast.track_count.unwrap_or(0),
+        FeedURL: podcast.feed_url.filter(|url| !url.is_empty())?,
+        WebsiteURL: podcast.collection_view_url.unwrap_or_default(),
+        Description: String::new(),
+    })
+}
+
+fn from_index(podcast: IndexPodcast) -> Option<PinepodsPodcasts> {
+    let mut categories: Vec<String> = podcast.categories.unwrap_or_default().into_values().collect();
+    categories.sort();
+    Some(PinepodsPodcasts {
+        PodcastID: 0,
+        PodcastName: podcast.title.filter(|title| !title.is_empty())?,
+        ArtworkURL: podcast.artwork.or(podcast.image).unwrap_or_default(),
+        Author: podcast.author.unwrap_or_default(),
+        Categories: categories.join(", "),
+        EpisodeCount: podcast.episode_count.unwrap_or(0),
+        FeedURL: podcast.url.filter(|url| !url.is_empty())?,
+        WebsiteURL: podcast.link.unwrap_or_default(),
+        Description: podcast.description.unwrap_or_default(),
+    })
+}
+
+/// `podcast` filled in from its feed, for what the directory left out.
+pub async fn preview(podcast: &PinepodsPodcasts) -> Result<PinepodsPodcasts> {
+    let feed = super::feed::fetch(&podcast.FeedURL)
+        .await
+        .map_err(|e| anyhow!("couldn't read {}'s feed: {}", podcast.PodcastName, e))?;
+    let from_feed = feed.podcast(&podcast.FeedURL);
+    let or = |ours: &str, theirs: String| if ours.is_empty() { theirs } else { ours.to_string() };
+    Ok(PinepodsPodcasts {
+        PodcastID: 0,
+        PodcastName: podcast.PodcastName.clone(),
+        ArtworkURL: or(&podcast.ArtworkURL, from_feed.ArtworkURL),
+        Author: or(&podcast.Author, from_feed.Author),
+        Categories: podcast.Categories.clone(),
+        // the feed has every episode, directories can lag behind
+        EpisodeCount: from_feed.EpisodeCount.max(podcast.EpisodeCount),
+        FeedURL: podcast.FeedURL.clone(),
+        WebsiteURL: or(&from_feed.WebsiteURL, podcast.WebsiteURL.clone()),
+        Description: or(&podcast.Description, from_feed.Description),
+    })
+}
@@ -0,0 +1,64 @@
+// Timings for the F12 debug overlay, for working out where the time goes
+// when firewood keeps a core busy. Only collected while the overlay is open.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Timing {
+    pub last: Duration,
+    /// moving average, recent runs count the most
+    pub average: Duration,
+    /// since the overlay was opened
+    pub max: Duration,
+}
+
+impl Timing {
+    pub fn record(&mut self, took: Duration) {
+        self.average = match self.average.is_zero() {
+            true => took,
+            false => (self.average * 7 + took) / 8,
+        };
+        self.last = took;
+        self.max = self.max.max(took);
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Profile {
+    /// drawing a whole frame, flushing it to the terminal included
+    pub frame: Timing,
+    /// the work between frames, background refreshes and server syncing
+    pub tick: Timing,
+    /// drawing each tab, by name
+    pub pages: BTreeMap<&'static str, Timing>,
+    /// frames drawn in the last full second
+    pub fps: u32,
+    counted: u32,
+    since: Option<Instant>,
+}
+
+impl Profile {
+    pub fn frame_drawn(&mut self, took: Duration) {
+        self.frame.record(took);
+        self.counted += 1;
+        let since = *self.since.get_or_insert_with(Instant::now);
+        if since.elapsed() >= Duration::from_secs(1) {
+            self.fps = self.counted;
+            self.counted = 0;
+            self.since = Some(Instant::now());
+        }
+    }
+
+    pub fn page(&mut self, page: &'static str, took: Duration) {
+        self.pages.entry(page).or_default().record(took);
+    }
+}
+
+/// Resident memory in bytes, where the OS makes it easy to find out.
+pub fn memory() -> Option<u64> {
+    // statm counts pages, nearly always 4 KiB ones
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    Some(pages * 4096)
+}
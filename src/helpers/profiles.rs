@@ -0,0 +1,179 @@
+// Everyone using Firewood on this computer, each with their own login,
+// settings, queue and kept episodes, so a shared machine can be handed over
+// without logging out and in again. The first login set up belongs to the
+// default user, who keeps the app directory as it always was; anyone added
+// with --user gets <app dir>/users/<name>/ instead, and everything saved
+// through requests::get_app_path lands there while they're the active user.
+// Who's set up, and their PINs, are in users.json in the app directory itself.
+//
+// A PIN is kept as a salted hash, iterated fnv-1a as there's nothing stronger
+// to hand, which keeps the household out of each other's queues but won't
+// stand up to anyone who can read the file.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use super::requests;
+
+/// The user whose login was set up first, and who has no directory of their own.
+pub const DEFAULT: &str = "default";
+
+// enough to make trying every four digit PIN take a moment
+const ROUNDS: u32 = 10_000;
+
+static ACTIVE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Who's using Firewood right now.
+pub fn active() -> String {
+    ACTIVE.lock().unwrap().clone().unwrap_or_else(|| String::from(DEFAULT))
+}
+
+/// Make `name` the active user, from here on their files are the ones read and
+/// written.
+pub fn activate(name: &str) {
+    *ACTIVE.lock().unwrap() = (name != DEFAULT).then(|| name.to_string());
+}
+
+/// `dir` for the active user, the same for the default user and
+/// dir/users/<name> for anyone else.
+pub fn scoped(dir: PathBuf) -> PathBuf {
+    match ACTIVE.lock().unwrap().as_deref() {
+        Some(name) => dir.join("users").join(name),
+        None => dir,
+    }
+}
+
+/// Letters, digits, - and _, as it's part of a path.
+pub fn valid_name(name: &str) -> bool {
+    (1..=32).contains(&name.len()) && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Pin {
+    salt: u64,
+    hash: u64,
+}
+
+impl Pin {
+    fn new(pin: &str) -> Self {
+        let salt = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_nanos() as u64)
+            ^ u64::from(std::process::id()).rotate_left(32);
+        Self {
+            salt,
+            hash: hash(salt, pin),
+        }
+    }
+
+    fn matches(&self, pin: &str) -> bool {
+        hash(self.salt, pin) == self.hash
+    }
+}
+
+fn hash(salt: u64, pin: &str) -> u64 {
+    let fnv = |hash: u64, bytes: &[u8]| {
+        bytes
+            .iter()
+            .fold(hash, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3))
+    };
+    (0..ROUNDS).fold(fnv(0xcbf29ce484222325, &salt.to_le_bytes()), |hash, _| {
+        fnv(fnv(hash, &salt.to_le_bytes()), pin.as_bytes())
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct User {
+    name: String,
+    #[serde(default)]
+    pin: Option<Pin>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct Profiles {
+    // the default user is only here once they've set a PIN
+    #[serde(default)]
+    users: Vec<User>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl Profiles {
+    pub fn load() -> Self {
+        let path = requests::base_app_path().map(|p| p.join("users.json"));
+        let mut profiles: Profiles = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        profiles.path = path;
+        profiles
+    }
+
+    /// Everyone set up here, the default user first.
+    pub fn names(&self) -> Vec<String> {
+        std::iter::once(String::from(DEFAULT))
+            .chain(self.users.iter().map(|user| user.name.clone()).filter(|name| name != DEFAULT))
+            .collect()
+    }
+
+    /// Note `name` as set up here, if they aren't already.
+    pub fn add(&mut self, name: &str) {
+        if name == DEFAULT || self.users.iter().any(|user| user.name == name) {
+            return;
+        }
+        self.users.push(User {
+            name: name.to_string(),
+            pin: None,
+        });
+        self.write();
+    }
+
+    pub fn has_pin(&self, name: &str) -> bool {
+        self.users.iter().any(|user| user.name == name && user.pin.is_some())
+    }
+
+    /// Whether `pin` is right for `name`, always so for a user without one.
+    pub fn unlocks(&self, name: &str, pin: &str) -> bool {
+        match self.users.iter().find(|user| user.name == name).and_then(|user| user.pin.as_ref()) {
+            Some(kept) => kept.matches(pin),
+            None => true,
+        }
+    }
+
+    /// Ask for `pin` before switching to `name`, or stop asking with an empty one.
+    pub fn set_pin(&mut self, name: &str, pin: &str) {
+        let pin = (!pin.is_empty()).then(|| Pin::new(pin));
+        match self.users.iter_mut().find(|user| user.name == name) {
+            Some(user) => user.pin = pin,
+            None => self.users.push(User {
+                name: name.to_string(),
+                pin,
+            }),
+        }
+        self.write();
+    }
+
+    fn write(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let result = serde_json::to_string(self)
+            .map_err(io::Error::from)
+            .and_then(|json| {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                fs::write(path, json)
+            });
+        if let Err(e) = result {
+            error!("Unable to save the users set up here to {:?}: {}", path, e);
+        }
+    }
+}
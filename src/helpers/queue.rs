@@ -1,5 +1,5 @@
 use std::{
-    collections::VecDeque,
+    collections::{HashMap, HashSet, VecDeque},
     path::{Path, PathBuf},
 };
 
@@ -10,11 +10,37 @@ use crate::requests::PinepodsEpisodes;
 use super::gen_funcs::bulk_add;
 use super::constants::{SECONDS_PER_DAY, SECONDS_PER_HOUR, SECONDS_PER_MINUTE};
 
+/// A run of consecutive items from the same podcast, as the grouped view
+/// shows them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    pub podcast: String,
+    pub start: usize,
+    pub len: usize,
+}
+
+/// One line of the queue as it's shown, a group's header or an item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Entry {
+    Header(Group),
+    Item(usize),
+}
+
 pub struct Queue {
     state: ListState,
     items: VecDeque<PinepodsEpisodes>,
     curr: usize,
     total_time: u32,
+    // items at the top passed over by playing from further down, kept where
+    // they are without being played
+    passed: usize,
+    // urls of the items marked for removing together
+    marked: HashSet<String>,
+    // shown under a header per run of the same podcast, still in the order
+    // they'll play
+    grouped: bool,
+    // podcasts whose runs are folded down to their header
+    folded: HashSet<String>,
 }
 
 impl Queue {
@@ -24,6 +50,10 @@ impl Queue {
             items: VecDeque::new(),
             curr: 0,
             total_time: 0,
+            passed: 0,
+            marked: HashSet::new(),
+            grouped: false,
+            folded: HashSet::new(),
         }
     }
 
@@ -96,12 +126,245 @@ impl Queue {
     }
 
     pub fn pop(&mut self) -> PinepodsEpisodes {
-        self.decrement_total_time();
-        self.items.pop_front().unwrap()
+        self.take(self.passed)
+    }
+
+    /// What plays when the current episode finishes.
+    pub fn up_next(&self) -> Option<&PinepodsEpisodes> {
+        self.items.get(self.passed)
+    }
+
+    /// How many items at the top were passed over by playing from further
+    /// down, and won't play.
+    pub fn passed(&self) -> usize {
+        self.passed
+    }
+
+    /// Take the selected item out to play it, carrying on down the queue
+    /// after it. The items above it are kept but passed over with
+    /// `keep_earlier`, and removed without.
+    pub fn play_from_here(&mut self, keep_earlier: bool) -> Option<PinepodsEpisodes> {
+        let i = self.state.selected().filter(|i| *i < self.items.len())?;
+        let episode = self.take(i);
+        match keep_earlier {
+            true => self.passed = i,
+            false => {
+                for _ in 0..i {
+                    self.take(0);
+                }
+                self.passed = 0;
+            }
+        }
+        Some(episode)
+    }
+
+    /// Move the selected item `delta` places up or down, false if it's
+    /// already as far as it goes.
+    pub fn move_selected(&mut self, delta: isize) -> bool {
+        let Some(i) = self.state.selected().filter(|i| *i < self.items.len()) else {
+            return false;
+        };
+        let target = (i as isize + delta).clamp(0, self.items.len() as isize - 1) as usize;
+        if target == i {
+            return false;
+        }
+        let episode = self.items.remove(i).unwrap();
+        self.items.insert(target, episode);
+        self.select(target);
+        true
+    }
+
+    /// Move the item at `from` to `to`, both counted from the top, keeping
+    /// the selection on the same episode. False if either is past the end.
+    pub fn move_item(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.items.len() || to >= self.items.len() {
+            return false;
+        }
+        let episode = self.items.remove(from).unwrap();
+        self.items.insert(to, episode);
+        if let Some(selected) = self.state.selected() {
+            let selected = match selected {
+                _ if selected == from => to,
+                _ if from < selected && selected <= to => selected - 1,
+                _ if to <= selected && selected < from => selected + 1,
+                _ => selected,
+            };
+            self.select(selected);
+        }
+        true
+    }
+
+    /// Move the selected item to the top of what's still to play, so it
+    /// plays next.
+    pub fn move_to_next(&mut self) -> bool {
+        let Some(i) = self.state.selected() else {
+            return false;
+        };
+        let passed = self.passed.min(i);
+        self.move_selected(passed as isize - i as isize)
+    }
+
+    /// Add `episode` above the rest of what's still to play.
+    pub fn play_next(&mut self, episode: PinepodsEpisodes, episode_duration: i64) {
+        self.items.insert(self.passed, episode);
+        self.total_time += episode_duration as u32;
+        if let Some(selected) = self.state.selected().filter(|selected| *selected >= self.passed) {
+            self.select(selected + 1);
+        }
+    }
+
+    /// Mark the selected item for removing, or unmark it.
+    pub fn toggle_mark(&mut self) {
+        let Some(url) = self.item().map(|episode| episode.EpisodeURL.clone()) else {
+            return;
+        };
+        if !self.marked.remove(&url) {
+            self.marked.insert(url);
+        }
+    }
+
+    pub fn is_marked(&self, episode: &PinepodsEpisodes) -> bool {
+        self.marked.contains(&episode.EpisodeURL)
+    }
+
+    /// Remove every marked item, how many there were.
+    pub fn remove_marked(&mut self) -> usize {
+        let marked: Vec<usize> = (0..self.items.len())
+            .filter(|i| self.marked.contains(&self.items[*i].EpisodeURL))
+            .collect();
+        for i in marked.iter().rev() {
+            self.take(*i);
+        }
+        self.marked.clear();
+        marked.len()
+    }
+
+    pub fn is_grouped(&self) -> bool {
+        self.grouped
+    }
+
+    pub fn set_grouped(&mut self, grouped: bool) {
+        self.grouped = grouped;
+    }
+
+    /// The runs of consecutive items from the same podcast, top down.
+    pub fn groups(&self) -> Vec<Group> {
+        let mut groups: Vec<Group> = Vec::new();
+        for (i, episode) in self.items.iter().enumerate() {
+            let podcast = episode.PodcastName.as_deref().unwrap_or("Unknown Podcast");
+            match groups.last_mut() {
+                Some(group) if group.podcast == podcast => group.len += 1,
+                _ => groups.push(Group {
+                    podcast: podcast.to_string(),
+                    start: i,
+                    len: 1,
+                }),
+            }
+        }
+        groups
     }
 
+    pub fn is_folded(&self, group: &Group) -> bool {
+        self.grouped && self.folded.contains(&group.podcast)
+    }
+
+    /// Fold or unfold the selected item's group, leaving the selection on
+    /// its header.
+    pub fn toggle_fold(&mut self) {
+        let Some(selected) = self.state.selected() else {
+            return;
+        };
+        let Some(group) = self.groups().into_iter().find(|group| group.start + group.len > selected) else {
+            return;
+        };
+        if !self.folded.remove(&group.podcast) {
+            self.folded.insert(group.podcast);
+        }
+        self.select(group.start);
+    }
+
+    /// Fold every group, or unfold them all.
+    pub fn fold_all(&mut self, folded: bool) {
+        self.folded = match folded {
+            true => self.groups().into_iter().map(|group| group.podcast).collect(),
+            false => HashSet::new(),
+        };
+        if let Some(selected) = self.state.selected() {
+            self.select(self.visible_at(selected));
+        }
+    }
+
+    /// What's shown, headers and all when grouped, top down.
+    pub fn entries(&self) -> Vec<Entry> {
+        if !self.grouped {
+            return (0..self.items.len()).map(Entry::Item).collect();
+        }
+        let mut entries = Vec::new();
+        for group in self.groups() {
+            let folded = self.is_folded(&group);
+            let items = group.start..group.start + group.len;
+            entries.push(Entry::Header(group));
+            if !folded {
+                entries.extend(items.map(Entry::Item));
+            }
+        }
+        entries
+    }
+
+    // the items that can be selected, a folded group's first for all of it
+    fn visible(&self) -> Vec<usize> {
+        if !self.grouped {
+            return (0..self.items.len()).collect();
+        }
+        self.groups()
+            .into_iter()
+            .flat_map(|group| match self.is_folded(&group) {
+                true => group.start..group.start + 1,
+                false => group.start..group.start + group.len,
+            })
+            .collect()
+    }
+
+    // the item selected in place of i, the first of its group if that's folded
+    fn visible_at(&self, i: usize) -> usize {
+        self.visible().into_iter().take_while(|visible| *visible <= i).last().unwrap_or(0)
+    }
+
+    /// Episode ids from the top down, as the server knows the queue.
+    pub fn episode_ids(&self) -> Vec<i64> {
+        self.items.iter().filter_map(|episode| episode.EpisodeID).collect()
+    }
+
+    // remove the item at i, keeping the selection on the same one
+    fn take(&mut self, i: usize) -> PinepodsEpisodes {
+        let episode = self.items.remove(i).unwrap();
+        self.marked.remove(&episode.EpisodeURL);
+        self.total_time -= self.item_length(&episode);
+        if i < self.passed {
+            self.passed -= 1;
+        }
+        match self.state.selected() {
+            _ if self.items.is_empty() => self.unselect(),
+            Some(selected) if i < selected => self.select(selected - 1),
+            Some(selected) => self.select(selected),
+            None => self.curr = self.curr.min(self.items.len() - 1),
+        }
+        episode
+    }
+
+    /// The selection as a row of `entries`, the header of a folded group for
+    /// any item in it.
     pub fn state(&self) -> ListState {
-        self.state.clone()
+        if !self.grouped {
+            return self.state.clone();
+        }
+        let row = self.state.selected().and_then(|selected| {
+            self.entries().iter().position(|row| match row {
+                Entry::Header(group) => self.is_folded(group) && group.start + group.len > selected,
+                Entry::Item(i) => *i == selected,
+            })
+        });
+        self.state.clone().with_selected(row)
     }
 
     fn decrement_total_time(&mut self) {
@@ -117,46 +380,121 @@ impl Queue {
     }
 
     pub fn next(&mut self) {
-        // check if empty
+        self.move_by(1);
+    }
+
+    pub fn previous(&mut self) {
+        self.move_by(-1);
+    }
+
+    /// Move the selection `delta` rows, over folded groups as one. Single
+    /// steps wrap around at the ends, longer jumps stop there.
+    pub fn move_by(&mut self, delta: isize) {
         if self.items.is_empty() {
             return;
+        }
+        let Some(current) = self.state.selected() else {
+            self.select(0);
+            return;
         };
 
-        let i = match self.state.selected() {
-            Some(i) => {
-                if i >= self.items.len() - 1 {
-                    0
-                } else {
-                    i + 1
-                }
-            }
-            None => 0,
+        let visible = self.visible();
+        let current = visible.iter().rposition(|i| *i <= current).unwrap_or(0);
+        let last = visible.len() as isize - 1;
+        let target = current as isize + delta;
+        let i = match delta {
+            1 | -1 if target > last => 0,
+            1 | -1 if target < 0 => last,
+            _ => target.clamp(0, last),
         };
-        self.curr = i;
-        self.state.select(Some(i));
+        self.select(visible[i as usize]);
     }
 
-    pub fn previous(&mut self) {
-        // check if empty
+    pub fn unselect(&mut self) {
+        self.state.select(None);
+    }
+
+    pub fn first(&mut self) {
+        self.select(0);
+    }
+
+    pub fn last(&mut self) {
+        self.select(self.visible_at(self.items.len().saturating_sub(1)));
+    }
+
+    /// Select the item at `i`, or the header of its group if that's folded.
+    pub fn select_item(&mut self, i: usize) {
+        self.select(self.visible_at(i));
+    }
+
+    fn select(&mut self, i: usize) {
         if self.items.is_empty() {
             return;
-        };
-        let i = match self.state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    self.items.len() - 1
-                } else {
-                    i - 1
-                }
-            }
-            None => 0,
-        };
+        }
+        let i = i.min(self.items.len() - 1);
         self.curr = i;
         self.state.select(Some(i));
     }
 
-    pub fn unselect(&mut self) {
-        self.state.select(None);
+    /// Put what the server knows of in `order`, by episode id, with those in
+    /// `new` where it has them and those it leaves out gone, see
+    /// queue_merge.rs. Local files stay after what they came after. What's
+    /// selected, marked and passed over stays with the same episodes.
+    pub fn reorder(&mut self, order: &[i64], new: Vec<PinepodsEpisodes>) {
+        let url = |episode: &PinepodsEpisodes| episode.EpisodeURL.clone();
+        let selected = self.state.selected().and_then(|i| self.items.get(i)).map(url);
+        let passed: HashSet<String> = self.items.iter().take(self.passed).map(url).collect();
+
+        // local files by what's kept that they came after, None for the top
+        let mut files: Vec<(Option<i64>, PinepodsEpisodes)> = Vec::new();
+        let mut by_id: HashMap<i64, PinepodsEpisodes> = HashMap::new();
+        let mut last = None;
+        for episode in self.items.drain(..) {
+            match episode.EpisodeID {
+                Some(id) => {
+                    if order.contains(&id) {
+                        last = Some(id);
+                    }
+                    by_id.insert(id, episode);
+                }
+                None => files.push((last, episode)),
+            }
+        }
+        for episode in new {
+            if let Some(id) = episode.EpisodeID {
+                by_id.entry(id).or_insert(episode);
+            }
+        }
+
+        let after = |id: Option<i64>| files.iter().filter(move |(after, _)| *after == id).map(|(_, file)| file.clone());
+        self.items.extend(after(None));
+        for id in order {
+            if let Some(episode) = by_id.remove(id) {
+                self.items.push_back(episode);
+                self.items.extend(after(Some(*id)));
+            }
+        }
+
+        self.passed = self.items.iter().take_while(|episode| passed.contains(&episode.EpisodeURL)).count();
+        self.total_time = self.items.iter().map(|episode| self.item_length(episode)).sum();
+        let urls: HashSet<String> = self.items.iter().map(url).collect();
+        self.marked.retain(|marked| urls.contains(marked));
+        self.curr = self.curr.min(self.items.len().saturating_sub(1));
+        match selected.and_then(|selected| self.items.iter().position(|episode| episode.EpisodeURL == selected)) {
+            Some(i) => self.select(i),
+            None if self.state.selected().is_some() && !self.items.is_empty() => self.select(self.curr),
+            None => self.unselect(),
+        }
+    }
+
+    /// Put back the queue as it was last run. Its items start out passed
+    /// over, so nothing starts playing by itself on the way in until one's
+    /// played from.
+    pub fn restore(&mut self, episodes: &[PinepodsEpisodes]) {
+        for episode in episodes {
+            self.add(episode.clone(), episode.EpisodeDuration);
+        }
+        self.passed = self.items.len();
     }
 
     pub fn add(&mut self, pinepods_episodes: PinepodsEpisodes, episode_duration: i64) {
@@ -167,10 +505,23 @@ impl Queue {
         self.total_time += episode_duration as u32;
     }
 
-
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.total_time = 0;
+        self.curr = 0;
+        self.passed = 0;
+        self.marked.clear();
+        self.unselect();
+    }
 
     // remove item from items vector
     pub fn remove(&mut self) {
+        if self.curr < self.passed {
+            self.passed -= 1;
+        }
+        if let Some(episode) = self.items.get(self.curr) {
+            self.marked.remove(&episode.EpisodeURL);
+        }
         if self.items.is_empty() {
             // top of queue
         } else if self.items.len() == 1 {
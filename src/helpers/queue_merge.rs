@@ -0,0 +1,218 @@
+// Merging the queue here with the server's when both have changed since they
+// last agreed, from the web app or a phone while this was offline, say, rather
+// than the order here being sent over the top of the server's.
+//
+// The merge is three way, against the order both last had, kept on disk: what
+// either side added stays, where it was added, and what either side removed
+// goes. Only the order is sent to the server, which can go on listing an
+// episode taken out here, so those are kept as tombstones until the server's
+// queue is seen without them. The order of what's on both goes by whichever
+// side changed it, and when both did, each their own way, it's a conflict for
+// the user to settle (see main.rs).
+//
+// Only episodes with ids take part, the server knows nothing of local files.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use super::requests;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Merge {
+    /// episode ids, top down
+    pub order: Vec<i64>,
+    /// added on the server since they last agreed
+    pub added: Vec<i64>,
+    /// removed on the server since, and still queued here
+    pub removed: Vec<i64>,
+    /// both reordered what they have in common, differently
+    pub conflict: bool,
+}
+
+/// `local` and `server` merged, by how each has changed since `base_order`, with
+/// `tombstones` removed here but not yet there.
+pub fn merge(base_order: &[i64], local: &[i64], server: &[i64], tombstones: &[i64]) -> Merge {
+    let base: HashSet<i64> = base_order.iter().copied().collect();
+    let local_ids: HashSet<i64> = local.iter().copied().collect();
+    let server_ids: HashSet<i64> = server.iter().copied().collect();
+    let removed_here = |id: &i64| tombstones.contains(id) || (base.contains(id) && !local_ids.contains(id));
+    let removed_there = |id: &i64| base.contains(id) && !server_ids.contains(id);
+
+    let (mut order, removed): (Vec<i64>, Vec<i64>) = local.iter().copied().partition(|id| !removed_there(id));
+    let added: Vec<i64> = server
+        .iter()
+        .copied()
+        .filter(|id| !base.contains(id) && !local_ids.contains(id) && !removed_here(id))
+        .collect();
+
+    // what both had when they last agreed, and still have, in each one's order
+    let shared = |ids: &[i64]| -> Vec<i64> {
+        ids.iter()
+            .copied()
+            .filter(|id| base.contains(id) && local_ids.contains(id) && server_ids.contains(id))
+            .collect()
+    };
+    let (here, there, before) = (shared(&order), shared(server), shared(base_order));
+    let moved_here = here != before;
+    let moved_there = there != before;
+    let conflict = moved_here && moved_there && here != there;
+
+    // the shared ones reordered in their places, those only here staying put
+    if moved_there && !moved_here {
+        let mut there = there.into_iter();
+        for id in order.iter_mut().filter(|id| here.contains(id)) {
+            *id = there.next().unwrap_or(*id);
+        }
+    }
+
+    // each added one after what it comes after on the server
+    for id in &added {
+        let before = server.iter().position(|other| other == id).unwrap_or(0);
+        let at = server[..before]
+            .iter()
+            .rev()
+            .find_map(|other| order.iter().position(|kept| kept == other))
+            .map_or(0, |at| at + 1);
+        order.insert(at, *id);
+    }
+
+    Merge {
+        order,
+        added,
+        removed,
+        conflict,
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Synced {
+    /// the order both last had, None until they've been merged once
+    #[serde(default)]
+    base: Option<Vec<i64>>,
+    #[serde(default)]
+    tombstones: Vec<i64>,
+}
+
+pub struct QueueSync {
+    synced: Synced,
+    path: Option<PathBuf>,
+}
+
+impl QueueSync {
+    pub fn load() -> Self {
+        let path = requests::get_app_path().map(|p| p.join("queue_sync.json"));
+        let synced = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self { synced, path }
+    }
+
+    /// The queue here and the server's merged. The first time there's no
+    /// last agreed order, the server's is taken as it, so what's here wins
+    /// as it did before there was merging.
+    pub fn merge(&self, local: &[i64], server: &[i64]) -> Merge {
+        let base = self.synced.base.as_deref().unwrap_or(server);
+        merge(base, local, server, &self.synced.tombstones)
+    }
+
+    /// Both have `order` now, the server once it's been sent. What `server`
+    /// has that it doesn't was removed here.
+    pub fn agreed(&mut self, order: &[i64], server: &[i64]) {
+        self.synced.tombstones = server.iter().copied().filter(|id| !order.contains(id)).collect();
+        self.synced.base = Some(order.to_vec());
+        self.write();
+    }
+
+    fn write(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let result = serde_json::to_string(&self.synced)
+            .map_err(std::io::Error::from)
+            .and_then(|json| fs::write(path, json));
+        if let Err(e) = result {
+            error!("Unable to save the queue's last sync to {:?}: {}", path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds_on_each_side() {
+        // added here at the end, there at the front and after 1
+        let merged = merge(&[1, 2, 3], &[1, 2, 3, 7], &[9, 1, 8, 2, 3], &[]);
+        assert_eq!(merged.order, [9, 1, 8, 2, 3, 7]);
+        assert_eq!(merged.added, [9, 8]);
+        assert!(merged.removed.is_empty());
+        assert!(!merged.conflict);
+    }
+
+    #[test]
+    fn removals_on_each_side() {
+        let merged = merge(&[1, 2, 3, 4], &[1, 3, 4], &[1, 2, 3], &[]);
+        assert_eq!(merged.order, [1, 3]);
+        assert_eq!(merged.removed, [4]);
+        assert!(merged.added.is_empty());
+        assert!(!merged.conflict);
+    }
+
+    #[test]
+    fn removed_here_and_reordered_there() {
+        let merged = merge(&[1, 2, 3, 4], &[1, 3, 4], &[4, 3, 2, 1], &[]);
+        assert_eq!(merged.order, [4, 3, 1]);
+        assert!(merged.added.is_empty());
+        assert!(!merged.conflict);
+    }
+
+    #[test]
+    fn removed_there_and_reordered_here() {
+        let merged = merge(&[1, 2, 3, 4], &[4, 3, 2, 1], &[1, 3, 4], &[]);
+        assert_eq!(merged.order, [4, 3, 1]);
+        assert_eq!(merged.removed, [2]);
+        assert!(!merged.conflict);
+    }
+
+    #[test]
+    fn tombstones_stay_removed() {
+        // 2 was taken out here and agreed on, but the server still lists it
+        let merged = merge(&[1, 3], &[1, 3], &[1, 2, 3], &[2]);
+        assert_eq!(merged.order, [1, 3]);
+        assert!(merged.added.is_empty());
+    }
+
+    #[test]
+    fn reordered_here_only() {
+        let merged = merge(&[1, 2, 3], &[3, 1, 2], &[1, 2, 3], &[]);
+        assert_eq!(merged.order, [3, 1, 2]);
+        assert!(!merged.conflict);
+    }
+
+    #[test]
+    fn reordered_there_only() {
+        // 7, only here, keeps its place among the rest
+        let merged = merge(&[1, 2, 3], &[1, 7, 2, 3], &[3, 2, 1], &[]);
+        assert_eq!(merged.order, [3, 7, 2, 1]);
+        assert!(!merged.conflict);
+    }
+
+    #[test]
+    fn reordered_on_both_sides() {
+        let merged = merge(&[1, 2, 3], &[3, 1, 2], &[2, 3, 1], &[]);
+        assert!(merged.conflict);
+        // the same way on both is no conflict
+        let merged = merge(&[1, 2, 3], &[3, 2, 1], &[3, 2, 1], &[]);
+        assert_eq!(merged.order, [3, 2, 1]);
+        assert!(!merged.conflict);
+    }
+}
@@ -0,0 +1,69 @@
+// Background refreshes, each on its own interval. Every wait is stretched or
+// shrunk by up to a tenth so refreshes that share an interval, or several
+// copies of the app against one server, drift apart instead of all asking
+// at once. At most one refresh is handed out per call, for the same reason.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant, SystemTime};
+
+struct Entry<T> {
+    task: T,
+    interval: Duration,
+    next: Instant,
+}
+
+pub struct Scheduler<T> {
+    entries: Vec<Entry<T>>,
+}
+
+impl<T> Default for Scheduler<T> {
+    fn default() -> Self {
+        Self { entries: Vec::new() }
+    }
+}
+
+impl<T: Copy + PartialEq> Scheduler<T> {
+    /// Refresh `task` every `interval`, or never for None. The first one is
+    /// due right away if `now`, otherwise after a full interval.
+    pub fn set(&mut self, task: T, interval: Option<Duration>, now: bool) {
+        self.entries.retain(|entry| entry.task != task);
+        let Some(interval) = interval.filter(|interval| !interval.is_zero()) else {
+            return;
+        };
+        let next = match now {
+            true => Instant::now(),
+            false => Instant::now() + jitter(interval),
+        };
+        self.entries.push(Entry { task, interval, next });
+    }
+
+    /// The most overdue task, if any is due, which is then scheduled again.
+    pub fn due(&mut self) -> Option<T> {
+        let now = Instant::now();
+        let entry = self
+            .entries
+            .iter_mut()
+            .filter(|entry| entry.next <= now)
+            .min_by_key(|entry| entry.next)?;
+        entry.next = now + jitter(entry.interval);
+        Some(entry.task)
+    }
+
+    /// Start `task`'s wait over, after it was refreshed some other way.
+    pub fn postpone(&mut self, task: T) {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.task == task) {
+            entry.next = Instant::now() + jitter(entry.interval);
+        }
+    }
+}
+
+// `interval`, give or take up to 10%. Nothing here needs a good random
+// number, the clock's nanoseconds hashed are plenty.
+fn jitter(interval: Duration) -> Duration {
+    let mut hasher = DefaultHasher::new();
+    SystemTime::now().hash(&mut hasher);
+    // between 0.9 and 1.1
+    let factor = 0.9 + (hasher.finish() % 2001) as f64 / 10000.0;
+    interval.mul_f64(factor)
+}
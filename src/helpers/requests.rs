@@ -14,13 +14,16 @@ use serde_derive::Serialize;
 use tokio::fs::File;
 use tokio::io::AsyncWriteExt;
 use super::models;
+use super::profiles;
 use log::error;
 use std::error::Error;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub enum PinepodsError {
     Reqwest(reqwest::Error),
     Serde(serde_json::Error),
+    Status(reqwest::StatusCode),
 }
 
 impl From<reqwest::Error> for PinepodsError {
@@ -48,6 +51,44 @@ pub struct EpisodeRequest {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+pub struct HistoryRequest {
+    pub episode_id: i64,
+    pub user_id: i64,
+    pub episode_pos: f64
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DownloadRequest {
+    pub episode_id: i64,
+    pub user_id: i64,
+}
+
+// the server's queue, top first
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReorderQueueRequest {
+    pub episode_ids: Vec<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PodcastValues {
+    pub pod_title: String,
+    pub pod_artwork: String,
+    pub pod_author: String,
+    pub categories: String,
+    pub pod_description: String,
+    pub pod_episode_count: u32,
+    pub pod_feed_url: String,
+    pub pod_website: String,
+    pub pod_explicit: bool,
+    pub user_id: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddPodcastRequest {
+    pub podcast_values: PodcastValues,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PinepodsPodcasts {
     pub PodcastID: i64,  // Assuming integers, change to i32 if the range is smaller
     pub PodcastName: String,
@@ -76,6 +117,29 @@ pub struct PinepodsEpisodes {
     pub PodcastID: Option<i64>,
 }
 
+// one episode in the listening history, as of the last time it was played
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct HistoryEntry {
+    #[serde(default)]
+    pub episode_title: String,
+    #[serde(default)]
+    pub podcast_name: Option<String>,
+    #[serde(default)]
+    pub listen_date: Option<String>,
+    // where it was left off, in seconds
+    #[serde(default)]
+    pub listen_duration: Option<i64>,
+    #[serde(default, rename = "EpisodeURL")]
+    pub episode_url: Option<String>,
+    #[serde(default, rename = "EpisodeID")]
+    pub episode_id: Option<i64>,
+    #[serde(default)]
+    pub episode_duration: Option<i64>,
+    #[serde(default)]
+    pub episode_artwork: Option<String>,
+}
+
 // Temporary struct to match the JSON response
 #[derive(Debug, Deserialize)]
 struct TempPodcast {
@@ -111,7 +175,13 @@ async fn verify_existing_key(hostname: &String, api_key: &String) -> Result<mode
     Ok(parsed_data)
 }
 
-fn get_app_path() -> Option<PathBuf> {
+/// Where the active user's settings and state are kept, see profiles.rs.
+pub fn get_app_path() -> Option<PathBuf> {
+    base_app_path().map(profiles::scoped)
+}
+
+/// The app directory itself, for what's shared by everyone using this computer.
+pub fn base_app_path() -> Option<PathBuf> {
     if let Some(proj_dirs) = ProjectDirs::from("org", "Gooseberry Development",  "Pinepods") {
         Some(proj_dirs.config_dir().to_path_buf())
     } else {
@@ -124,6 +194,14 @@ fn create_app_path(app_path: &Display) -> std::io::Result<()> {
     Ok(())
 }
 
+/// The login saved for the active user, without checking it still works.
+pub fn saved_login() -> std::io::Result<PinepodsConfig> {
+    let app_path = get_app_path()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "App Path not found"))?;
+    let content = fs::read_to_string(app_path.join("pinepods_config.json"))?;
+    serde_json::from_str(&content).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
 pub async fn test_existing_config () -> std::io::Result<PinepodsConfig> {
     return if let Some(app_path) = get_app_path() {
         let mut config_path = app_path.join("pinepods_config.json");
@@ -145,6 +223,7 @@ pub async fn test_existing_config () -> std::io::Result<PinepodsConfig> {
     }
 }
 
+#[derive(Clone)]
 pub struct ReqwestValues {
     pub url: String,
     pub api_key: String,
@@ -206,6 +285,8 @@ impl ReqwestValues {
             } else {
                 println!("Could not determine the home directory");
             }
+            // users other than the default one are in a directory of their own
+            create_dir_all(&app_path)?;
             let config_path = app_path.join("pinepods_config.json");
             println!("{:?}", &config_path);
 
@@ -278,10 +359,264 @@ impl ReqwestValues {
     }
 
 
+    pub async fn user_history(&self) -> Result<Vec<HistoryEntry>> {
+        let client = reqwest::Client::new();
+        let response = client
+            .get(format!("{}/api/data/user_history/{}", &self.url, &self.user_id))
+            .header("Api-Key", &self.api_key.trim().to_string())
+            .send()
+            .await
+            .context("Failed to send request to the server")?;
+
+        if response.status().is_success() {
+            let mut json: HashMap<String, Vec<HistoryEntry>> = response
+                .json()
+                .await
+                .context("Failed to deserialize JSON response")?;
+            Ok(json.remove("data").unwrap_or_default())
+        } else {
+            Err(anyhow!("Error fetching history: {}", response.status()))
+        }
+    }
+
+    // a shared episode link's key, answered as the server stores the episode
+    pub async fn shared_episode(&self, key: &str) -> Result<Value> {
+        let client = reqwest::Client::new();
+        let response = client
+            .get(format!("{}/api/data/episode_by_url/{}", &self.url, key))
+            .header("Api-Key", self.api_key.trim())
+            .send()
+            .await
+            .context("Failed to send request to the server")?;
+
+        if response.status().is_success() {
+            response.json().await.context("Failed to deserialize JSON response")
+        } else {
+            Err(anyhow!("Error fetching shared episode: {}", response.status()))
+        }
+    }
+
+    pub async fn playlist_episodes(&self, playlist_id: i64) -> Result<Value> {
+        let client = reqwest::Client::new();
+        let response = client
+            .get(format!("{}/api/data/get_playlist_episodes", &self.url))
+            .query(&[("user_id", self.user_id), ("playlist_id", playlist_id)])
+            .header("Api-Key", self.api_key.trim())
+            .send()
+            .await
+            .context("Failed to send request to the server")?;
+
+        if response.status().is_success() {
+            response.json().await.context("Failed to deserialize JSON response")
+        } else {
+            Err(anyhow!("Error fetching playlist {}: {}", playlist_id, response.status()))
+        }
+    }
+
+    pub async fn record_listen_position(&self, episode_id: i64, position: i64) -> Result<(), PinepodsError> {
+        // don't hang the ui for the full tcp timeout when the server is gone
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()?;
+        let request_body = HistoryRequest {
+            episode_id,
+            user_id: self.user_id,
+            episode_pos: position as f64,
+        };
+
+        let response = client
+            .post(format!("{}/api/data/record_podcast_history", &self.url))
+            .header("Api-Key", self.api_key.trim())
+            .json(&request_body)
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(PinepodsError::Status(response.status()))
+        }
+    }
+
+    // subscribes to a podcast again, as it was when it was backed up
+    pub async fn add_podcast(&self, podcast: &PinepodsPodcasts) -> Result<(), PinepodsError> {
+        let client = reqwest::Client::new();
+        let request_body = AddPodcastRequest {
+            podcast_values: PodcastValues {
+                pod_title: podcast.PodcastName.clone(),
+                pod_artwork: podcast.ArtworkURL.clone(),
+                pod_author: podcast.Author.clone(),
+                categories: podcast.Categories.clone(),
+                pod_description: podcast.Description.clone(),
+                pod_episode_count: podcast.EpisodeCount,
+                pod_feed_url: podcast.FeedURL.clone(),
+                pod_website: podcast.WebsiteURL.clone(),
+                pod_explicit: false,
+                user_id: self.user_id,
+            },
+        };
+
+        let response = client
+            .post(format!("{}/api/data/add_podcast", &self.url))
+            .header("Api-Key", self.api_key.trim())
+            .json(&request_body)
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(PinepodsError::Status(response.status()))
+        }
+    }
+
+    // has the server download an episode for the user, it does it in its own time
+    pub async fn download_episode(&self, episode_id: i64) -> Result<(), PinepodsError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()?;
+        let request_body = DownloadRequest {
+            episode_id,
+            user_id: self.user_id,
+        };
+
+        let response = client
+            .post(format!("{}/api/data/download_podcast", &self.url))
+            .header("Api-Key", self.api_key.trim())
+            .json(&request_body)
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(PinepodsError::Status(response.status()))
+        }
+    }
+
+    // played to the end, as far as the server's concerned
+    pub async fn mark_completed(&self, episode_id: i64) -> Result<(), PinepodsError> {
+        self.post_episode("mark_episode_completed", episode_id).await
+    }
+
+    // onto the end of the server's queue
+    pub async fn queue_episode(&self, episode_id: i64) -> Result<(), PinepodsError> {
+        self.post_episode("queue_pod", episode_id).await
+    }
+
+    // off the server's queue, leaving the rest of it as it was
+    pub async fn remove_queued(&self, episode_id: i64) -> Result<(), PinepodsError> {
+        self.post_episode("remove_queued_pod", episode_id).await
+    }
+
+    async fn post_episode(&self, endpoint: &str, episode_id: i64) -> Result<(), PinepodsError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()?;
+        let request_body = DownloadRequest {
+            episode_id,
+            user_id: self.user_id,
+        };
+
+        let response = client
+            .post(format!("{}/api/data/{}", &self.url, endpoint))
+            .header("Api-Key", self.api_key.trim())
+            .json(&request_body)
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(PinepodsError::Status(response.status()))
+        }
+    }
+
+    // the server's queue, top first
+    pub async fn queued_episodes(&self) -> Result<Vec<PinepodsEpisodes>> {
+        let client = reqwest::Client::new();
+        let response = client
+            .get(format!("{}/api/data/get_queued_episodes", &self.url))
+            .query(&[("user_id", self.user_id)])
+            .header("Api-Key", self.api_key.trim())
+            .send()
+            .await
+            .context("Failed to send request to the server")?;
+
+        if response.status().is_success() {
+            let mut json: HashMap<String, Vec<PinepodsEpisodes>> = response
+                .json()
+                .await
+                .context("Failed to deserialize JSON response")?;
+            Ok(json.remove("data").unwrap_or_default())
+        } else {
+            Err(anyhow!("Error fetching the queue: {}", response.status()))
+        }
+    }
+
+    // what the server has downloaded for the user, by episode id
+    pub async fn downloaded_episodes(&self) -> Result<Vec<i64>> {
+        let client = reqwest::Client::new();
+        let response = client
+            .get(format!("{}/api/data/download_episode_list", &self.url))
+            .query(&[("user_id", self.user_id)])
+            .header("Api-Key", self.api_key.trim())
+            .send()
+            .await
+            .context("Failed to send request to the server")?;
+
+        if response.status().is_success() {
+            let mut json: HashMap<String, Vec<Value>> = response
+                .json()
+                .await
+                .context("Failed to deserialize JSON response")?;
+            // capitalised or not, depending on the server's database
+            let ids = json
+                .remove("downloaded_episodes")
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|episode| episode.get("EpisodeID").or_else(|| episode.get("episodeid"))?.as_i64())
+                .collect();
+            Ok(ids)
+        } else {
+            Err(anyhow!("Error fetching the downloaded episodes: {}", response.status()))
+        }
+    }
+
+    // puts the server's queue in this order, episodes that aren't queued
+    // there are left out of it
+    pub async fn reorder_queue(&self, episode_ids: &[i64]) -> Result<(), PinepodsError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()?;
+        let request_body = ReorderQueueRequest {
+            episode_ids: episode_ids.to_vec(),
+        };
+
+        let response = client
+            .post(format!("{}/api/data/reorder_queue", &self.url))
+            .query(&[("user_id", self.user_id)])
+            .header("Api-Key", self.api_key.trim())
+            .json(&request_body)
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(PinepodsError::Status(response.status()))
+        }
+    }
+
     pub async fn return_eps(&self, podcast_data: &PinepodsPodcasts) -> Result<Vec<PinepodsEpisodes>> {
+        self.podcast_episodes(podcast_data.PodcastID).await
+    }
+
+    // the same, for when only the id is at hand
+    pub async fn podcast_episodes(&self, podcast_id: i64) -> Result<Vec<PinepodsEpisodes>> {
         let client = reqwest::Client::new();
         let request_body = EpisodeRequest {
-            podcast_id: podcast_data.PodcastID,
+            podcast_id,
             user_id: self.user_id,
         };
 
@@ -306,5 +641,26 @@ impl ReqwestValues {
         }
     }
 
+    // the latest episodes across every subscription, as the web app's home
+    pub async fn recent_episodes(&self) -> Result<Vec<PinepodsEpisodes>> {
+        let client = reqwest::Client::new();
+        let response = client
+            .get(format!("{}/api/data/return_episodes/{}", &self.url, &self.user_id))
+            .header("Api-Key", self.api_key.trim())
+            .send()
+            .await
+            .context("Failed to send request to the server")?;
+
+        if response.status().is_success() {
+            let mut json: HashMap<String, Vec<PinepodsEpisodes>> = response
+                .json()
+                .await
+                .context("Failed to deserialize JSON response")?;
+            Ok(json.remove("episodes").unwrap_or_default())
+        } else {
+            Err(anyhow!("Error fetching recent episodes: {}", response.status()))
+        }
+    }
+
 
 }
@@ -0,0 +1,237 @@
+// A local index over the titles and descriptions of every subscribed
+// podcast's episodes, so a search can answer straight away, offline too,
+// before the server is asked again. The episodes are kept on disk in
+// <app_path>/search_index.json and the word index is rebuilt from them on
+// load, it's quick enough not to be worth storing.
+//
+// Matching is by word: every word searched for has to start a word in the
+// episode, or, for words of five letters or more, be one typo away from one.
+// The same episode listed under two feeds, or twice by a feed, shows up once.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::ops::Range;
+use std::path::PathBuf;
+
+use chrono::Utc;
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use super::gen_funcs;
+use super::requests::{self, PinepodsEpisodes};
+
+// shortest word a typo is forgiven in
+const FUZZY_LENGTH: usize = 5;
+// older than this and the next refresh fetches every episode again
+const MAX_AGE: i64 = 60 * 60;
+
+#[derive(Default, Serialize, Deserialize)]
+struct Stored {
+    /// unix seconds
+    built: i64,
+    episodes: Vec<PinepodsEpisodes>,
+}
+
+pub struct SearchIndex {
+    stored: Stored,
+    // word to the episodes with it, in the title if the flag's set
+    words: BTreeMap<String, Vec<(usize, bool)>>,
+    path: Option<PathBuf>,
+}
+
+impl SearchIndex {
+    pub fn load() -> Self {
+        let path = requests::get_app_path().map(|p| p.join("search_index.json"));
+        let stored = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        let mut index = Self {
+            stored,
+            words: BTreeMap::new(),
+            path,
+        };
+        index.index_words();
+        index
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stored.episodes.is_empty()
+    }
+
+    /// Every episode indexed, each once.
+    pub fn episodes(&self) -> &[PinepodsEpisodes] {
+        &self.stored.episodes
+    }
+
+    /// Old enough that it's time every episode was fetched again.
+    pub fn stale(&self) -> bool {
+        Utc::now().timestamp() - self.stored.built >= MAX_AGE
+    }
+
+    /// Start again from `episodes`, every subscription's, and save them.
+    pub fn rebuild(&mut self, episodes: &[PinepodsEpisodes]) {
+        let mut seen = HashSet::new();
+        self.stored = Stored {
+            built: Utc::now().timestamp(),
+            episodes: episodes
+                .iter()
+                .filter(|episode| seen.insert(key(episode)))
+                .cloned()
+                .collect(),
+        };
+        self.index_words();
+        self.write();
+    }
+
+    fn index_words(&mut self) {
+        self.words.clear();
+        for (i, episode) in self.stored.episodes.iter().enumerate() {
+            let title: BTreeSet<String> = words(&episode.EpisodeTitle).collect();
+            let description: BTreeSet<String> = words(&without_tags(&episode.EpisodeDescription)).collect();
+            for word in title.union(&description) {
+                self.words
+                    .entry(word.clone())
+                    .or_default()
+                    .push((i, title.contains(word)));
+            }
+        }
+    }
+
+    /// Episodes with every word of `query`, those matching in the title
+    /// first, then newest first.
+    pub fn search(&self, query: &str) -> Vec<PinepodsEpisodes> {
+        let mut found: Option<BTreeMap<usize, usize>> = None;
+        for term in words(query) {
+            // each episode once per query word, counting title matches
+            let mut hits: BTreeMap<usize, usize> = BTreeMap::new();
+            for (word, episodes) in &self.words {
+                if !matches(&term, word) {
+                    continue;
+                }
+                for &(i, in_title) in episodes {
+                    let title_hits = hits.entry(i).or_default();
+                    *title_hits = (*title_hits).max(usize::from(in_title));
+                }
+            }
+            found = Some(match found {
+                None => hits,
+                Some(found) => found
+                    .into_iter()
+                    .filter_map(|(i, title_hits)| hits.get(&i).map(|hit| (i, title_hits + hit)))
+                    .collect(),
+            });
+        }
+
+        let mut found: Vec<(usize, usize)> = found.unwrap_or_default().into_iter().collect();
+        let episodes = &self.stored.episodes;
+        found.sort_by_key(|&(i, title_hits)| {
+            (
+                std::cmp::Reverse(title_hits),
+                std::cmp::Reverse(gen_funcs::pub_date(&episodes[i].EpisodePubDate)),
+            )
+        });
+        found.into_iter().map(|(i, _)| episodes[i].clone()).collect()
+    }
+
+    fn write(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let result = serde_json::to_string(&self.stored)
+            .map_err(std::io::Error::from)
+            .and_then(|json| fs::write(path, json));
+        if let Err(e) = result {
+            error!("Unable to save the search index to {:?}: {}", path, e);
+        }
+    }
+}
+
+// the same episode, whichever feed it came through
+fn key(episode: &PinepodsEpisodes) -> (String, String) {
+    (
+        episode.PodcastName.clone().unwrap_or_default().to_lowercase(),
+        episode.EpisodeTitle.trim().to_lowercase(),
+    )
+}
+
+// where the runs of letters and digits in `text` are
+fn word_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(from)) => {
+                ranges.push(from..i);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(from) = start {
+        ranges.push(from..text.len());
+    }
+    ranges
+}
+
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    word_ranges(text).into_iter().map(|range| text[range].to_lowercase())
+}
+
+// descriptions are often html, whose tags shouldn't match
+fn without_tags(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                plain.push(' ');
+            }
+            _ if !in_tag => plain.push(c),
+            _ => {}
+        }
+    }
+    plain
+}
+
+fn matches(term: &str, word: &str) -> bool {
+    word.starts_with(term) || (term.chars().count() >= FUZZY_LENGTH && one_typo(term, word))
+}
+
+// at most one letter added, dropped or changed
+fn one_typo(a: &str, b: &str) -> bool {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let (short, long) = match a.len() <= b.len() {
+        true => (&a, &b),
+        false => (&b, &a),
+    };
+    if long.len() - short.len() > 1 {
+        return false;
+    }
+    let same = short.iter().zip(long.iter()).take_while(|(x, y)| x == y).count();
+    if same == short.len() {
+        return true;
+    }
+    match short.len() == long.len() {
+        true => short[same + 1..] == long[same + 1..],
+        false => short[same..] == long[same + 1..],
+    }
+}
+
+/// Where the words of `text` that `query` matched are, for highlighting.
+pub fn highlights(text: &str, query: &str) -> Vec<Range<usize>> {
+    let terms: Vec<String> = words(query).collect();
+    word_ranges(text)
+        .into_iter()
+        .filter(|range| {
+            let word = text[range.clone()].to_lowercase();
+            terms.iter().any(|term| matches(term, &word))
+        })
+        .collect()
+}
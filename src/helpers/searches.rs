@@ -0,0 +1,113 @@
+// Saved searches, shown on the podcast list as podcasts of their own: each
+// one is every episode across the subscriptions with its term in the title
+// or description. Kept on disk between runs, the episodes are looked up
+// again whenever the podcast list is refreshed.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use futures::StreamExt;
+use log::{debug, error};
+
+use super::requests::{self, PinepodsEpisodes, ReqwestValues};
+
+// podcasts asked for their episodes at once
+const CONCURRENCY: usize = 8;
+
+pub struct SavedSearches {
+    terms: Vec<String>,
+    path: Option<PathBuf>,
+}
+
+impl SavedSearches {
+    pub fn load() -> Self {
+        let path = requests::get_app_path().map(|p| p.join("saved_searches.json"));
+        let terms = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self { terms, path }
+    }
+
+    // in the order they were saved
+    pub fn terms(&self) -> &[String] {
+        &self.terms
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Save `term`, unless it's already saved under any capitalisation.
+    pub fn add(&mut self, term: &str) {
+        if self.terms.iter().any(|saved| saved.eq_ignore_ascii_case(term)) {
+            return;
+        }
+        self.terms.push(term.to_string());
+        self.write();
+    }
+
+    pub fn remove(&mut self, term: &str) {
+        let before = self.terms.len();
+        self.terms.retain(|saved| saved != term);
+        if self.terms.len() != before {
+            self.write();
+        }
+    }
+
+    fn write(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let result = serde_json::to_string(&self.terms)
+            .map_err(std::io::Error::from)
+            .and_then(|json| fs::write(path, json));
+        if let Err(e) = result {
+            error!("Unable to save searches to {:?}: {}", path, e);
+        }
+    }
+}
+
+/// Every episode of every subscription, newest first. Podcasts whose
+/// episodes can't be fetched are left out rather than failing the lot.
+pub async fn all_episodes(pinepods_values: &ReqwestValues) -> Result<Vec<PinepodsEpisodes>> {
+    let podcasts = pinepods_values.return_pods().await?;
+    let fetched = futures::stream::iter(podcasts)
+        .map(|podcast| async move {
+            let episodes = pinepods_values.return_eps(&podcast).await;
+            (podcast, episodes)
+        })
+        .buffer_unordered(CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+
+    let mut episodes = Vec::new();
+    for (podcast, result) in fetched {
+        match result {
+            Ok(found) => episodes.extend(found.into_iter().map(|mut episode| {
+                episode.PodcastName.get_or_insert_with(|| podcast.PodcastName.clone());
+                episode
+            })),
+            Err(e) => debug!("Unable to search {}: {:?}", podcast.PodcastName, e),
+        }
+    }
+    episodes.sort_by(|a, b| b.EpisodePubDate.cmp(&a.EpisodePubDate));
+    Ok(episodes)
+}
+
+/// The episodes with `term` in their title or description, ignoring case.
+pub fn matching(term: &str, episodes: &[PinepodsEpisodes]) -> Vec<PinepodsEpisodes> {
+    let term = term.to_lowercase();
+    episodes
+        .iter()
+        .filter(|episode| {
+            episode.EpisodeTitle.to_lowercase().contains(&term)
+                || episode.EpisodeDescription.to_lowercase().contains(&term)
+        })
+        .cloned()
+        .collect()
+}
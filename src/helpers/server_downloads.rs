@@ -0,0 +1,91 @@
+// Episodes the server's been asked to download, watched until they turn up
+// in its list of downloads.
+//
+// The server doesn't say how far along a download is, only whether it's
+// done, so one that hasn't turned up after a while is asked for again, and
+// given up on after a few tries.
+
+use std::time::{Duration, Instant};
+
+// waited for a download before it's asked for again
+const PATIENCE: Duration = Duration::from_secs(15 * 60);
+// times it's asked for before it's given up on
+const TRIES: u32 = 3;
+
+struct Waiting {
+    episode_id: i64,
+    since: Instant,
+    tries: u32,
+}
+
+/// What came of looking at the server's downloads.
+#[derive(Debug, Default, PartialEq)]
+pub struct Checked {
+    pub done: usize,
+    /// to be asked for again
+    pub retry: Vec<i64>,
+    pub failed: usize,
+}
+
+#[derive(Default)]
+pub struct ServerDownloads {
+    waiting: Vec<Waiting>,
+    failed: Vec<i64>,
+}
+
+impl ServerDownloads {
+    /// Asked for, or asked for again after it failed.
+    pub fn requested(&mut self, episode_id: i64) {
+        self.failed.retain(|failed| *failed != episode_id);
+        if !self.waiting.iter().any(|waiting| waiting.episode_id == episode_id) {
+            self.waiting.push(Waiting {
+                episode_id,
+                since: Instant::now(),
+                tries: 1,
+            });
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.waiting.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.waiting.len()
+    }
+
+    pub fn waiting_for(&self, episode_id: i64) -> bool {
+        self.waiting.iter().any(|waiting| waiting.episode_id == episode_id)
+    }
+
+    /// Given up on, until they're asked for again.
+    pub fn failed(&self) -> &[i64] {
+        &self.failed
+    }
+
+    /// Take the ones in `downloaded` off, and ask again for those that have
+    /// been waited on too long.
+    pub fn check(&mut self, downloaded: &[i64]) -> Checked {
+        let mut checked = Checked::default();
+        let now = Instant::now();
+        self.waiting.retain_mut(|waiting| {
+            if downloaded.contains(&waiting.episode_id) {
+                checked.done += 1;
+                return false;
+            }
+            if now.duration_since(waiting.since) < PATIENCE {
+                return true;
+            }
+            if waiting.tries >= TRIES {
+                checked.failed += 1;
+                self.failed.push(waiting.episode_id);
+                return false;
+            }
+            waiting.tries += 1;
+            waiting.since = now;
+            checked.retry.push(waiting.episode_id);
+            true
+        });
+        checked
+    }
+}
@@ -0,0 +1,136 @@
+// The "smart" episode order. Each episode gets a score from how new it is,
+// how well what's left of it fits the time there is to listen, and how much
+// its podcast is liked, each part with its own weight:
+//
+//   recency   halves every week, 1 for something out today
+//   fit       1 if the rest of it fits, less the further over it goes
+//   priority  per podcast, 0 unless set, plus 1 if flagged high priority
+//
+// Finished episodes always go last.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use chrono::Utc;
+
+use super::gen_funcs;
+use super::requests::PinepodsEpisodes;
+
+// anything with less than this left counts as listened to
+const FINISHED_SECONDS: i64 = 60;
+// added to the priority of podcasts flagged high priority
+const HIGH_PRIORITY: f64 = 1.0;
+// how far a regenerated plan can move a score either way, as a fraction
+const VARIATION: f64 = 0.5;
+
+#[derive(Debug, Clone)]
+pub struct SmartSort {
+    pub recency: f64,
+    pub fit: f64,
+    pub priority: f64,
+    /// seconds there are to listen, for the fit and the listening plan
+    pub available: i64,
+    /// podcast name to priority
+    pub podcasts: HashMap<String, f64>,
+    /// names of podcasts flagged high priority
+    pub high_priority: HashSet<String>,
+}
+
+impl Default for SmartSort {
+    fn default() -> Self {
+        Self {
+            recency: 1.0,
+            fit: 1.0,
+            priority: 1.0,
+            available: 45 * 60,
+            podcasts: HashMap::new(),
+            high_priority: HashSet::new(),
+        }
+    }
+}
+
+fn remaining(episode: &PinepodsEpisodes) -> i64 {
+    (episode.EpisodeDuration - episode.ListenDuration.unwrap_or(0)).max(0)
+}
+
+fn finished(episode: &PinepodsEpisodes) -> bool {
+    remaining(episode) < FINISHED_SECONDS
+}
+
+impl SmartSort {
+    pub fn score(&self, episode: &PinepodsEpisodes) -> f64 {
+        // undated episodes are treated as old
+        let recency = gen_funcs::pub_date(&episode.EpisodePubDate)
+            .map(|date| (Utc::now() - date).num_hours().max(0) as f64 / 24.0)
+            .map_or(0.0, |days| 0.5f64.powf(days / 7.0));
+        let left = remaining(episode);
+        let fit = match left <= self.available {
+            true => 1.0,
+            false => self.available as f64 / left as f64,
+        };
+        let priority = episode.PodcastName.as_ref().map_or(0.0, |name| {
+            let flagged = match self.high_priority.contains(name) {
+                true => HIGH_PRIORITY,
+                false => 0.0,
+            };
+            self.podcasts.get(name).copied().unwrap_or(0.0) + flagged
+        });
+
+        self.recency * recency + self.fit * fit + self.priority * priority
+    }
+
+    /// Best first, finished episodes last in the order they were.
+    pub fn sort(&self, episodes: &mut [PinepodsEpisodes]) {
+        episodes.sort_by(|a, b| match (finished(a), finished(b)) {
+            (false, false) => self.score(b).total_cmp(&self.score(a)),
+            (a, b) => a.cmp(&b),
+        });
+    }
+
+    /// The best episodes that together fit in the time available, in the
+    /// order to play them.
+    pub fn plan(&self, episodes: &[PinepodsEpisodes]) -> Vec<PinepodsEpisodes> {
+        let mut candidates: Vec<PinepodsEpisodes> =
+            episodes.iter().filter(|episode| !finished(episode)).cloned().collect();
+        self.sort(&mut candidates);
+        self.fill(candidates)
+    }
+
+    /// Another plan for the same time, picked with each score moved a little
+    /// at random so lower scored episodes get a look in. A different `seed`
+    /// gives a different plan, the same one the same plan.
+    pub fn varied_plan(&self, episodes: &[PinepodsEpisodes], seed: u64) -> Vec<PinepodsEpisodes> {
+        let mut candidates: Vec<(f64, PinepodsEpisodes)> = episodes
+            .iter()
+            .filter(|episode| !finished(episode))
+            .map(|episode| {
+                let mut hasher = DefaultHasher::new();
+                (seed, &episode.EpisodeURL).hash(&mut hasher);
+                // between 1 - VARIATION and 1 + VARIATION
+                let factor = 1.0 + VARIATION * ((hasher.finish() % 2001) as f64 / 1000.0 - 1.0);
+                (self.score(episode) * factor, episode.clone())
+            })
+            .collect();
+        candidates.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+        let mut plan = self.fill(candidates.into_iter().map(|(_, episode)| episode).collect());
+        self.sort(&mut plan);
+        plan
+    }
+
+    // as many as fit, in the order given
+    fn fill(&self, candidates: Vec<PinepodsEpisodes>) -> Vec<PinepodsEpisodes> {
+        let mut left = self.available;
+        candidates
+            .into_iter()
+            .filter(|episode| {
+                let fits = remaining(episode) <= left;
+                if fits {
+                    left -= remaining(episode);
+                }
+                fits
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,62 @@
+// Named copies of the queue, so it can be put back the way it was after
+// something else has reshuffled it. Kept on disk between runs.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use log::error;
+
+use super::requests::{self, PinepodsEpisodes};
+
+pub struct Snapshots {
+    snapshots: BTreeMap<String, Vec<PinepodsEpisodes>>,
+    path: Option<PathBuf>,
+}
+
+impl Snapshots {
+    pub fn load() -> Self {
+        let path = requests::get_app_path().map(|p| p.join("queue_snapshots.json"));
+        let snapshots = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self { snapshots, path }
+    }
+
+    // sorted by name
+    pub fn names(&self) -> Vec<String> {
+        self.snapshots.keys().cloned().collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&[PinepodsEpisodes]> {
+        self.snapshots.get(name).map(Vec::as_slice)
+    }
+
+    /// Save `episodes` as `name`, replacing any snapshot already called that.
+    pub fn save(&mut self, name: &str, episodes: Vec<PinepodsEpisodes>) {
+        self.snapshots.insert(name.to_string(), episodes);
+        self.write();
+    }
+
+    pub fn delete(&mut self, name: &str) {
+        if self.snapshots.remove(name).is_some() {
+            self.write();
+        }
+    }
+
+    fn write(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let result = serde_json::to_string(&self.snapshots)
+            .map_err(std::io::Error::from)
+            .and_then(|json| fs::write(path, json));
+        if let Err(e) = result {
+            error!("Unable to save queue snapshots to {:?}: {}", path, e);
+        }
+    }
+}
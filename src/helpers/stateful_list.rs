@@ -1,5 +1,37 @@
+use std::ops::Range;
+
 use ratatui::widgets::ListState;
 
+// rows made past either end of those showing
+const OVERSCAN: usize = 5;
+
+/// The rows of a list `len` long to make into ListItems for it to show in
+/// `height` rows with `selected` in view, scrolled as ratatui's List would
+/// have it, and a few to spare either side. With the state to render just
+/// those with, which looks the same as rendering them all, only without
+/// making thousands of rows a frame for a long podcast.
+pub fn window(len: usize, selected: Option<usize>, height: u16) -> (Range<usize>, ListState) {
+    let rows = (height as usize).max(1);
+    let selected = selected.filter(|selected| *selected < len);
+    let top = top(len, selected, height);
+    let start = top.saturating_sub(OVERSCAN);
+    let end = (top + rows + OVERSCAN).min(len);
+    let state = ListState::default()
+        .with_offset(top - start)
+        .with_selected(selected.map(|selected| selected - start));
+    (start..end, state)
+}
+
+/// The row of a list `len` long at the top of the `height` rows it shows in,
+/// as ratatui's List scrolls it to keep `selected` in view.
+pub fn top(len: usize, selected: Option<usize>, height: u16) -> usize {
+    let rows = (height as usize).max(1);
+    match selected.filter(|selected| *selected < len) {
+        Some(selected) if selected >= rows => selected + 1 - rows,
+        _ => 0,
+    }
+}
+
 // TODO encapsulation
 pub struct StatefulList<T> {
     state: ListState,
@@ -30,51 +62,66 @@ impl<T> StatefulList<T> {
         self.state.clone()
     }
 
+    /// See `window`, for a list inside a bordered block `height` rows tall.
+    pub fn window(&self, height: u16) -> (Range<usize>, ListState) {
+        window(self.items.len(), self.state.selected(), height.saturating_sub(2))
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.state.selected()
+    }
+
     pub fn empty(&self) -> bool {
         self.items.is_empty()
     }
 
     pub fn next(&mut self) {
-        // check if empty
-        if self.items.is_empty() {
-            return;
-        };
-
-        let i = match self.state.selected() {
-            Some(i) => {
-                if i >= self.items.len() - 1 {
-                    0
-                } else {
-                    i + 1
-                }
-            }
-            None => 0,
-        };
-        self.curr = i;
-        self.state.select(Some(i));
+        self.move_by(1);
     }
 
     pub fn previous(&mut self) {
-        // check if empty
+        self.move_by(-1);
+    }
+
+    /// Move the selection `delta` rows. Single steps wrap around at the ends,
+    /// longer jumps stop there.
+    pub fn move_by(&mut self, delta: isize) {
         if self.items.is_empty() {
             return;
+        }
+        let Some(current) = self.state.selected() else {
+            self.select(0);
+            return;
         };
 
-        let i = match self.state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    self.items.len() - 1
-                } else {
-                    i - 1
-                }
-            }
-            None => 0,
+        let last = self.items.len() as isize - 1;
+        let target = current as isize + delta;
+        let i = match delta {
+            1 | -1 if target > last => 0,
+            1 | -1 if target < 0 => last,
+            _ => target.clamp(0, last),
         };
-        self.curr = i;
-        self.state.select(Some(i));
+        self.select(i as usize);
     }
 
     pub fn unselect(&mut self) {
         self.state.select(None);
     }
+
+    pub fn first(&mut self) {
+        self.select(0);
+    }
+
+    pub fn last(&mut self) {
+        self.select(self.items.len().saturating_sub(1));
+    }
+
+    pub fn select(&mut self, i: usize) {
+        if self.items.is_empty() {
+            return;
+        }
+        let i = i.min(self.items.len() - 1);
+        self.curr = i;
+        self.state.select(Some(i));
+    }
 }
@@ -19,20 +19,102 @@ impl<'a> StatefulTable<'a> {
             state: TableState::default(),
             items: vec![
                 vec!["Q", "Quit"],
-                vec!["P", "Play / Pause"],
+                vec!["P / Space", "Play / Pause"],
                 vec!["G", "Skip To Next Podcast"],
+                vec!["N / Shift + N", "Next / Previous Episode Of The Podcast Playing From (Not The Queue)"],
+                vec!["+ / -", "Volume Up / Down"],
+                vec!["Shift + M / M", "Mute / Unmute, M Everywhere But The Queue"],
+                vec!["Shift + V", "Duck To 20% To Talk Over It / Back Up"],
+                vec!["[ / ]", "Seek Back / Forward 15s"],
+                vec!["< / >", "Slower / Faster, 0.5x To 3x"],
+                vec!["Ctrl + Left / Right", "Previous / Next Chapter"],
+                vec!["Shift + C", "List The Chapters To Jump To One"],
+                vec!["Shift + W", "Follow The Transcript Of What's Playing, Enter Plays From A Line"],
                 vec!["A", "Add To Queue"],
-                vec!["R", "Remove From Queue"],
+                vec!["Shift + A", "Play Next, Above The Rest Of The Queue"],
+                vec!["R / DD", "Remove From Queue, Or Everything Marked"],
+                vec!["M", "Mark For Removing (Queue)"],
+                vec!["Shift + J / K", "Move Down / Up (Queue)"],
+                vec!["T", "Move Up To Play Next (Queue)"],
+                vec!["Shift + P", "Play From Here, Then On Down The Queue (Queue)"],
+                vec!["A", "Continuous Playback On / Off (Queue)"],
+                vec!["ZI", "Group By Podcast On / Off (Queue)"],
+                vec!["ZA", "Fold / Unfold A Podcast's Group (Queue)"],
+                vec!["ZM / ZR", "Fold / Unfold Every Group (Queue)"],
                 vec!["Enter", "Enter Podcast / Play Episode"],
                 vec!["Backspace", "Back To Podcast"],
                 vec!["Down", "Next Item"],
                 vec!["Up", "Previous Item"],
+                vec!["GG / Shift + G", "First / Last Item"],
+                vec!["Home / End", "First / Last Item"],
+                vec!["Page Up / Page Down", "Move A Page"],
+                vec!["Ctrl + D / U", "Move Half A Page Down / Up"],
+                vec!["Number, Then J / K", "Move That Many Items, Or Pages With Page Up / Down And Ctrl + D / U"],
                 vec!["Right / Left", "Enter Queue / Browser"],
                 vec!["Tab", "Change Tabs"],
+                vec!["Shift + S", "Toggle Data Saver"],
+                vec!["S", "Save Queue As (Queue)"],
+                vec!["O", "Restore A Saved Queue (Queue)"],
+                vec!["Shift + I", "Import A Shared Episode Or Playlist (Queue)"],
+                vec!["Ctrl + V", "Paste A Feed, Episode Or Share Link To Subscribe Or Queue"],
+                vec!["V", "Toggle Podcast Grid"],
+                vec!["B", "Toggle Podcast Rail Beside Episodes"],
+                vec!["Shift + J / K", "Next / Previous Podcast (Podcast Rail)"],
+                vec!["I", "Podcast Or Episode Details, Queued Episodes' Too"],
+                vec!["1 - 9", "Open A Link From The Show Notes (Details)"],
+                vec!["N / H", "Podcast Notifications / High Priority (Details)"],
+                vec!["P", "Podcast Speed, Intro / Outro Skips And Audiobook Mode (Details)"],
+                vec!["Shift + L", "Listen To A Live Show (Podcasts)"],
+                vec!["S", "Search Every Podcast's Episodes (Browser)"],
+                vec!["/", "Save A Search As A Podcast (Podcasts)"],
+                vec!["X", "Delete A Saved Search (Podcasts)"],
+                vec!["E", "Find New Podcasts To Subscribe To On iTunes Or PodcastIndex (Browser)"],
+                vec!["F", "Pick Podcasts To Hide Or Show (Podcasts)"],
+                vec!["Shift + F", "Podcast Filter On / Off (Podcasts)"],
+                vec!["O", "Smart Sort On / Off (Episodes)"],
+                vec!["Shift + P", "Plan What Fits The Time There Is (Podcasts / Episodes)"],
+                vec!["S / R", "Shuffle / Regenerate The Plan (Listening Plan)"],
+                vec!["Shift + D", "Download A Podcast's Unplayed Episodes On The Server (Podcasts)"],
+                vec!["D", "Keep An Episode On This Computer To Play Offline (Episodes)"],
+                vec!["Shift + O", "Episodes Kept On This Computer And Their Downloads"],
+                vec!["Ctrl + K", "Go To A Podcast, Episode, Download Or Command By Typing Part Of Its Name"],
+                vec!["Ctrl + O", "Play Or Queue Audio Files On This Computer, Picking Up Where They Were Left"],
+                vec!["P", "Pause / Resume A Download (Kept On This Computer)"],
+                vec!["C", "Record The Live Stream"],
+                vec!["Shift + H", "Carry On Here With What Was Playing Elsewhere"],
+                vec!["Shift + U", "Pause And Hand Off To The Web Or Mobile App"],
+                vec!["Shift + X", "Cast What's Playing To Another Firewood On The Network"],
+                vec!["Shift + L", "Change Log Level (Controls Tab)"],
+                vec!["Shift + F", "Edit Log Filters (Controls Tab)"],
+                vec!["T", "Toggle Telemetry (Controls Tab)"],
+                vec!["Shift + T", "Preview Telemetry Reports (Controls Tab)"],
+                vec!["Shift + E", "Export This Session's Playback Log (Controls Tab)"],
+                vec!["B", "Back Up Subscriptions, Queue And Settings (Controls Tab)"],
+                vec!["Shift + B", "Restore From A Backup (Controls Tab)"],
+                vec!["R", "Remote Control On / Off (Controls Tab)"],
+                vec!["A", "Who's Using The Remote Control, To Revoke A Client Or The Token (Controls Tab)"],
+                vec!["Shift + A", "Move The Remote Control To Another Port Or Address (Controls Tab)"],
+                vec!["U", "Switch User, Or P There To Set Your PIN (Controls Tab)"],
+                vec!["E", "Edit The Theme's Colors, Saved To theme.toml And Reloaded When It Changes (Controls Tab)"],
+                vec!["W", "What's New (Controls Tab)"],
+                vec!["?", "Take The Tour (Controls Tab)"],
+                vec!["F12", "Render And Memory Profile"],
             ],
         }
     }
 
+    /// What `key` does, going by the row for it in one of `scopes` (e.g.
+    /// "Queue", as in the "(Queue)" after a description) or else the one for
+    /// everywhere, without the scope.
+    pub fn describe(&self, key: &str, scopes: &[&str]) -> Option<&'a str> {
+        let rows = || self.items.iter().filter(|row| row[0] == key).map(|row| row[1]);
+        let scoped = rows().find_map(|description| {
+            let (description, scope) = description.rsplit_once(" (")?;
+            scopes.contains(&scope.trim_end_matches(')')).then_some(description)
+        });
+        scoped.or_else(|| rows().find(|description| !description.ends_with(')')))
+    }
+
     // pub fn header(&self) -> Vec<&'a str> {
     //     self.header
     // }
@@ -42,30 +124,36 @@ impl<'a> StatefulTable<'a> {
     // }
 
     pub fn next(&mut self) {
-        let i = match self.state.selected() {
-            Some(i) => {
-                if i >= self.items.len() - 1 {
-                    0
-                } else {
-                    i + 1
-                }
-            }
-            None => 0,
-        };
-        self.state.select(Some(i));
+        self.move_by(1);
     }
 
     pub fn previous(&mut self) {
-        let i = match self.state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    self.items.len() - 1
-                } else {
-                    i - 1
-                }
-            }
-            None => 0,
+        self.move_by(-1);
+    }
+
+    /// Move the selection `delta` rows. Single steps wrap around at the ends,
+    /// longer jumps stop there.
+    pub fn move_by(&mut self, delta: isize) {
+        let Some(current) = self.state.selected() else {
+            self.state.select(Some(0));
+            return;
         };
-        self.state.select(Some(i));
+
+        let last = self.items.len() as isize - 1;
+        let target = current as isize + delta;
+        let i = match delta {
+            1 | -1 if target > last => 0,
+            1 | -1 if target < 0 => last,
+            _ => target.clamp(0, last),
+        };
+        self.state.select(Some(i as usize));
+    }
+
+    pub fn first(&mut self) {
+        self.state.select(Some(0));
+    }
+
+    pub fn last(&mut self) {
+        self.state.select(Some(self.items.len().saturating_sub(1)));
     }
 }
@@ -0,0 +1,151 @@
+// Listening stats for the podcast list, and for one podcast in its detail,
+// worked out from the server's listening history. The history only has where
+// each episode was left off and when, so an episode counts for the day it was
+// last played, for as far as it got. Close enough for a streak and a rough
+// idea of the week.
+
+use chrono::{DateTime, Local, Months, NaiveDate, Utc};
+
+use super::gen_funcs;
+use super::requests::HistoryEntry;
+
+/// Days the daily minutes, and the weekly average, go back.
+pub const DAYS: usize = 56;
+/// Weeks a podcast's weekly minutes go back.
+pub const WEEKS: usize = 12;
+
+#[derive(Debug, Clone)]
+pub struct Stats {
+    /// minutes each day, oldest first, today last
+    pub daily: Vec<u64>,
+    /// days in a row with some listening, up to today or yesterday
+    pub streak: u32,
+    pub today: u64,
+    /// the last 7 days, today included
+    pub week: u64,
+    /// minutes a week, over as much of the last `DAYS` as the history covers
+    pub weekly_average: u64,
+    /// "podcast - episode" for whatever was played this day a year ago
+    pub year_ago: Vec<String>,
+}
+
+impl Stats {
+    /// None when there's no history to go on.
+    pub fn from_history(history: &[HistoryEntry], today: NaiveDate) -> Option<Stats> {
+        let played: Vec<(NaiveDate, &HistoryEntry)> = history
+            .iter()
+            .filter_map(|entry| {
+                let date = gen_funcs::pub_date(entry.listen_date.as_deref()?)?;
+                Some((local_date(date), entry))
+            })
+            .collect();
+        if played.is_empty() {
+            return None;
+        }
+
+        let mut daily = vec![0u64; DAYS];
+        for (date, entry) in &played {
+            let days_ago = (today - *date).num_days();
+            if (0..DAYS as i64).contains(&days_ago) {
+                let minutes = entry.listen_duration.unwrap_or(0).max(0) as u64 / 60;
+                daily[DAYS - 1 - days_ago as usize] += minutes;
+            }
+        }
+
+        // a day off so far today doesn't end the streak yet
+        let mut days = daily.iter().rev().peekable();
+        if days.peek() == Some(&&0) {
+            days.next();
+        }
+        let streak = days.take_while(|minutes| **minutes > 0).count() as u32;
+
+        let earliest = played.iter().map(|(date, _)| *date).min().unwrap_or(today);
+        let covered = ((today - earliest).num_days() + 1).clamp(1, DAYS as i64) as u64;
+        let total: u64 = daily.iter().sum();
+        let year_ago_date = today.checked_sub_months(Months::new(12));
+        let year_ago = played
+            .iter()
+            .filter(|(date, _)| Some(*date) == year_ago_date)
+            .map(|(_, entry)| match &entry.podcast_name {
+                Some(podcast) => format!("{} - {}", podcast, entry.episode_title),
+                None => entry.episode_title.clone(),
+            })
+            .collect();
+
+        Some(Stats {
+            today: daily[DAYS - 1],
+            week: daily[DAYS - 7..].iter().sum(),
+            weekly_average: total * 7 / covered,
+            daily,
+            streak,
+            year_ago,
+        })
+    }
+}
+
+/// One podcast's part of the listening history.
+#[derive(Debug, Clone)]
+pub struct PodcastStats {
+    pub episodes: usize,
+    pub minutes: u64,
+    /// how far through, on average, the episodes with a known length got
+    pub completion: Option<u8>,
+    /// as the server wrote it
+    pub last_played: Option<String>,
+    /// minutes each week, oldest first, this week last
+    pub weekly: Vec<u64>,
+}
+
+impl PodcastStats {
+    /// None when nothing of `podcast` has been played.
+    pub fn from_history(history: &[HistoryEntry], podcast: &str, today: NaiveDate) -> Option<PodcastStats> {
+        let played: Vec<&HistoryEntry> = history
+            .iter()
+            .filter(|entry| entry.podcast_name.as_deref() == Some(podcast))
+            .collect();
+        if played.is_empty() {
+            return None;
+        }
+
+        let listened = |entry: &HistoryEntry| entry.listen_duration.unwrap_or(0).max(0) as u64;
+        let completions: Vec<f64> = played
+            .iter()
+            .filter_map(|entry| {
+                let length = entry.episode_duration.filter(|length| *length > 0)?;
+                Some((listened(entry) as f64 / length as f64).min(1.0))
+            })
+            .collect();
+        let completion = (!completions.is_empty())
+            .then(|| (completions.iter().sum::<f64>() / completions.len() as f64 * 100.0).round() as u8);
+
+        let mut weekly = vec![0u64; WEEKS];
+        let mut last_played: Option<(DateTime<Utc>, &str)> = None;
+        for entry in &played {
+            let Some(raw) = entry.listen_date.as_deref() else {
+                continue;
+            };
+            let Some(date) = gen_funcs::pub_date(raw) else {
+                continue;
+            };
+            let weeks_ago = (today - local_date(date)).num_days().div_euclid(7);
+            if (0..WEEKS as i64).contains(&weeks_ago) {
+                weekly[WEEKS - 1 - weeks_ago as usize] += listened(entry) / 60;
+            }
+            if last_played.is_none_or(|(latest, _)| date > latest) {
+                last_played = Some((date, raw));
+            }
+        }
+
+        Some(PodcastStats {
+            episodes: played.len(),
+            minutes: played.iter().map(|entry| listened(entry)).sum::<u64>() / 60,
+            completion,
+            last_played: last_played.map(|(_, raw)| raw.to_string()),
+            weekly,
+        })
+    }
+}
+
+fn local_date(date: DateTime<Utc>) -> NaiveDate {
+    date.with_timezone(&Local).date_naive()
+}
@@ -0,0 +1,63 @@
+// Subscription changes made from other devices.
+//
+// The podcast list seen on each fetch is remembered on disk, so the next
+// fetch (this session or the next one) can tell which shows were added or
+// removed elsewhere.
+
+use std::collections::BTreeMap;
+use std::fs;
+
+use log::error;
+
+use crate::requests::{self, PinepodsPodcasts};
+
+#[derive(Debug, Default)]
+pub struct SubscriptionChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl SubscriptionChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Compare `podcasts` with the list seen last time and remember it for the
+/// next call. The very first call reports nothing.
+pub fn detect_changes(podcasts: &[PinepodsPodcasts]) -> SubscriptionChanges {
+    let Some(path) = requests::get_app_path().map(|p| p.join("subscriptions.json")) else {
+        return SubscriptionChanges::default();
+    };
+
+    let current: BTreeMap<i64, String> = podcasts
+        .iter()
+        .map(|p| (p.PodcastID, p.PodcastName.clone()))
+        .collect();
+    let previous: Option<BTreeMap<i64, String>> = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok());
+
+    let result = serde_json::to_string(&current)
+        .map_err(std::io::Error::from)
+        .and_then(|json| fs::write(&path, json));
+    if let Err(e) = result {
+        error!("Unable to save subscriptions to {:?}: {}", path, e);
+    }
+
+    let Some(previous) = previous else {
+        return SubscriptionChanges::default();
+    };
+    SubscriptionChanges {
+        added: current
+            .iter()
+            .filter(|(id, _)| !previous.contains_key(id))
+            .map(|(_, name)| name.clone())
+            .collect(),
+        removed: previous
+            .iter()
+            .filter(|(id, _)| !current.contains_key(id))
+            .map(|(_, name)| name.clone())
+            .collect(),
+    }
+}
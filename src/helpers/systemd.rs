@@ -0,0 +1,81 @@
+// The bits of systemd integration used by daemon mode, without linking
+// libsystemd: sd_notify(3) is a datagram sent to $NOTIFY_SOCKET, and socket
+// activation passes listeners in as file descriptors from 3 up (sd_listen_fds(3)).
+
+use std::net::TcpListener;
+
+use log::error;
+
+/// Tell systemd about a state change, e.g. "READY=1". Does nothing unless
+/// started by systemd as a Type=notify service.
+#[cfg(unix)]
+pub fn notify(state: &str) {
+    use std::os::unix::net::UnixDatagram;
+
+    let Some(path) = std::env::var_os("NOTIFY_SOCKET") else {
+        return;
+    };
+
+    let send = || -> std::io::Result<()> {
+        let socket = UnixDatagram::unbound()?;
+
+        // abstract namespace sockets are given with a leading '@'
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::linux::net::SocketAddrExt;
+            use std::os::unix::ffi::OsStrExt;
+            use std::os::unix::net::SocketAddr;
+
+            if let Some(name) = path.as_bytes().strip_prefix(b"@") {
+                let addr = SocketAddr::from_abstract_name(name)?;
+                socket.send_to_addr(state.as_bytes(), &addr)?;
+                return Ok(());
+            }
+        }
+
+        socket.send_to(state.as_bytes(), &path)?;
+        Ok(())
+    };
+    if let Err(e) = send() {
+        error!("Unable to notify systemd of {:?}: {}", state, e);
+    }
+}
+
+#[cfg(not(unix))]
+pub fn notify(_state: &str) {}
+
+/// Listeners passed in by socket activation, empty when not socket activated.
+/// Only the first call gets them.
+#[cfg(unix)]
+pub fn take_listeners() -> Vec<TcpListener> {
+    use std::os::unix::io::FromRawFd;
+
+    const LISTEN_FDS_START: i32 = 3;
+
+    // LISTEN_PID guards against picking up variables meant for a parent
+    let ours = std::env::var("LISTEN_PID")
+        .ok()
+        .and_then(|pid| pid.parse::<u32>().ok())
+        == Some(std::process::id());
+    let count: i32 = std::env::var("LISTEN_FDS")
+        .ok()
+        .and_then(|count| count.parse().ok())
+        .unwrap_or(0);
+    for var in ["LISTEN_PID", "LISTEN_FDS", "LISTEN_FDNAMES"] {
+        std::env::remove_var(var);
+    }
+    if !ours {
+        return Vec::new();
+    }
+
+    (LISTEN_FDS_START..LISTEN_FDS_START + count)
+        // SAFETY: systemd handed these descriptors to this process and nothing
+        // else can claim them now that the variables are gone
+        .map(|fd| unsafe { TcpListener::from_raw_fd(fd) })
+        .collect()
+}
+
+#[cfg(not(unix))]
+pub fn take_listeners() -> Vec<TcpListener> {
+    Vec::new()
+}
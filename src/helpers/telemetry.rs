@@ -0,0 +1,203 @@
+// Opt-in crash and error reports.
+//
+// Nothing is collected unless telemetry is turned on, and turning it off
+// throws away whatever hadn't been sent. Reports stay anonymous: panics carry
+// their message and source location, API errors the endpoint and error, and
+// in both server addresses and the home directory are scrubbed out. Pending
+// reports live in <app_path>/telemetry.json until they're POSTed, as a JSON
+// array, to the configured endpoint, which anyone can host themselves.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use crate::requests;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReportKind {
+    Panic,
+    ApiError,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Report {
+    pub kind: ReportKind,
+    /// Same problem, same fingerprint, so reports can be grouped.
+    pub fingerprint: String,
+    pub detail: String,
+    pub version: String,
+    pub os: String,
+}
+
+struct State {
+    enabled: bool,
+    url: Option<String>,
+    pending: Vec<Report>,
+    path: Option<PathBuf>,
+}
+
+static STATE: Mutex<State> = Mutex::new(State {
+    enabled: false,
+    url: None,
+    pending: Vec::new(),
+    path: None,
+});
+
+/// Pick up reports left over from the last run and start catching panics.
+pub fn init(enabled: bool, url: Option<String>) {
+    let path = requests::base_app_path().map(|p| p.join("telemetry.json"));
+    let pending = path
+        .as_ref()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    {
+        let mut state = STATE.lock().unwrap();
+        state.url = url;
+        state.path = path;
+        state.pending = pending;
+    }
+    set_enabled(enabled);
+
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let detail = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| String::from("panic"));
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}", l.file(), l.line()))
+            .unwrap_or_default();
+        record(ReportKind::Panic, &location, &format!("{} at {}", detail, location));
+        previous(info);
+    }));
+}
+
+pub fn enabled() -> bool {
+    STATE.lock().unwrap().enabled
+}
+
+pub fn url() -> Option<String> {
+    STATE.lock().unwrap().url.clone()
+}
+
+pub fn set_enabled(enabled: bool) {
+    let mut state = STATE.lock().unwrap();
+    state.enabled = enabled;
+    if !enabled {
+        state.pending.clear();
+        if let Some(path) = &state.path {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Note a failed request to `endpoint`, e.g. "return_pods".
+pub fn api_error(endpoint: &str, error: &dyn std::fmt::Display) {
+    record(ReportKind::ApiError, endpoint, &format!("{}: {}", endpoint, error));
+}
+
+/// Exactly what the next upload would send.
+pub fn preview() -> Vec<Report> {
+    STATE.lock().unwrap().pending.clone()
+}
+
+/// Send pending reports. They're kept for next time if that fails.
+pub async fn upload() {
+    let (url, reports) = {
+        let state = STATE.lock().unwrap();
+        match (&state.url, state.enabled && !state.pending.is_empty()) {
+            (Some(url), true) => (url.clone(), state.pending.clone()),
+            _ => return,
+        }
+    };
+
+    let sent = async {
+        reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()?
+            .post(&url)
+            .json(&reports)
+            .send()
+            .await?
+            .error_for_status()
+    };
+    if let Err(e) = sent.await {
+        error!("Unable to upload {} telemetry reports: {}", reports.len(), e);
+        return;
+    }
+
+    let mut state = STATE.lock().unwrap();
+    // anything recorded during the upload stays queued
+    let sent = reports.len().min(state.pending.len());
+    state.pending.drain(..sent);
+    save(&state);
+}
+
+fn record(kind: ReportKind, source: &str, detail: &str) {
+    // a panic while the lock is held must not deadlock the hook
+    let Ok(mut state) = STATE.try_lock() else {
+        return;
+    };
+    if !state.enabled {
+        return;
+    }
+
+    let detail = scrub(detail);
+    // numbers are ids, ports and sizes that would split the same problem up
+    let fingerprint = fnv1a(
+        kind,
+        &format!("{}{}", source, detail.replace(|c: char| c.is_ascii_digit(), "")),
+    );
+    state.pending.push(Report {
+        kind,
+        fingerprint,
+        detail,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+    });
+    save(&state);
+}
+
+fn save(state: &State) {
+    let Some(path) = &state.path else {
+        return;
+    };
+    let result = serde_json::to_string(&state.pending)
+        .map_err(std::io::Error::from)
+        .and_then(|json| fs::write(path, json));
+    if let Err(e) = result {
+        error!("Unable to save telemetry to {:?}: {}", path, e);
+    }
+}
+
+// take out anything that points at a particular server or person
+fn scrub(text: &str) -> String {
+    let mut scrubbed: String = text
+        .split(' ')
+        .map(|word| match word.find("://") {
+            Some(_) => "<url>",
+            None => word,
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    if let Some(home) = home::home_dir().and_then(|h| h.to_str().map(String::from)) {
+        scrubbed = scrubbed.replace(&home, "~");
+    }
+    scrubbed
+}
+
+fn fnv1a(kind: ReportKind, text: &str) -> String {
+    let hash = format!("{:?}", kind)
+        .bytes()
+        .chain(text.bytes())
+        .fold(0xcbf29ce484222325u64, |hash, b| (hash ^ u64::from(b)).wrapping_mul(0x100000001b3));
+    format!("{:016x}", hash)
+}
@@ -0,0 +1,192 @@
+// Transcripts a feed's `<podcast:transcript>` tags point at, read into lines
+// timed against the episode. Of the formats the namespace allows, the timed
+// ones are understood: the JSON format it defines, WebVTT and SubRip. Plain
+// text and HTML ones have no times to follow along with, so they're passed
+// over.
+//
+// JSON transcripts are often a segment a word, those are joined back up into
+// lines, as are SubRip's short cues, up to a sentence or so by one speaker.
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+
+use super::feed::Transcript;
+
+// about as long as a line is let grow when joining segments
+const LINE_LENGTH: usize = 100;
+
+#[derive(Debug, Clone)]
+pub struct Segment {
+    /// seconds into the episode
+    pub start: f64,
+    pub end: f64,
+    pub speaker: Option<String>,
+    pub text: String,
+}
+
+#[derive(Deserialize)]
+struct JsonTranscript {
+    segments: Vec<JsonSegment>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonSegment {
+    start_time: f64,
+    end_time: Option<f64>,
+    #[serde(default)]
+    body: String,
+    speaker: Option<String>,
+}
+
+#[derive(PartialEq)]
+enum Format {
+    Json,
+    Vtt,
+    Srt,
+}
+
+fn format(mime: &str) -> Option<Format> {
+    match mime.split(';').next().unwrap_or_default().trim() {
+        "application/json" => Some(Format::Json),
+        "text/vtt" => Some(Format::Vtt),
+        "application/srt" | "application/x-subrip" | "text/srt" => Some(Format::Srt),
+        _ => None,
+    }
+}
+
+/// The lines of the best of `transcripts` with times to them, JSON first,
+/// in order.
+pub async fn fetch(transcripts: &[Transcript]) -> Result<Vec<Segment>> {
+    let mut timed: Vec<(Format, &Transcript)> = transcripts
+        .iter()
+        .filter_map(|transcript| Some((format(&transcript.mime)?, transcript)))
+        .collect();
+    timed.sort_by_key(|(format, _)| match format {
+        Format::Json => 0,
+        Format::Vtt => 1,
+        Format::Srt => 2,
+    });
+    let (format, transcript) = timed
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("there's no timed transcript, only {}", mimes(transcripts)))?;
+
+    let body = reqwest::get(&transcript.url)
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let segments = match format {
+        Format::Json => serde_json::from_str::<JsonTranscript>(&body)
+            .map(from_json)
+            .with_context(|| format!("unable to read the transcript at {}", transcript.url))?,
+        Format::Vtt | Format::Srt => cues(&body),
+    };
+    Ok(joined(segments))
+}
+
+fn mimes(transcripts: &[Transcript]) -> String {
+    match transcripts.is_empty() {
+        true => String::from("none at all"),
+        false => transcripts
+            .iter()
+            .map(|transcript| transcript.mime.as_str())
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+fn from_json(transcript: JsonTranscript) -> Vec<Segment> {
+    let mut segments: Vec<Segment> = transcript
+        .segments
+        .into_iter()
+        .map(|segment| Segment {
+            start: segment.start_time,
+            end: segment.end_time.unwrap_or(segment.start_time),
+            speaker: segment.speaker.filter(|speaker| !speaker.is_empty()),
+            text: segment.body.trim().to_string(),
+        })
+        .filter(|segment| !segment.text.is_empty())
+        .collect();
+    segments.sort_by(|a, b| a.start.total_cmp(&b.start));
+    segments
+}
+
+// the cues of a WebVTT or SubRip file, which differ only in the header, the
+// comma before the milliseconds and VTT's voice tags
+fn cues(body: &str) -> Vec<Segment> {
+    let body = body.replace("\r\n", "\n");
+    body.split("\n\n")
+        .filter_map(|block| {
+            let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+            let (start, end) = lines.next()?.split_once("-->")?;
+            let (start, end) = (timestamp(start)?, timestamp(end.split_whitespace().next()?)?);
+            let mut speaker = None;
+            let text: Vec<String> = lines
+                .map(|line| {
+                    // <v Speaker Name>what they said</v>
+                    if let Some(voice) = line.trim().strip_prefix("<v ") {
+                        if let Some((name, _)) = voice.split_once('>') {
+                            speaker = Some(name.trim().to_string());
+                        }
+                    }
+                    untagged(line)
+                })
+                .filter(|line| !line.is_empty())
+                .collect();
+            Some(Segment {
+                start,
+                end,
+                speaker,
+                text: text.join(" "),
+            })
+            .filter(|segment| !segment.text.is_empty())
+        })
+        .collect()
+}
+
+// 01:02:03.456, 01:02:03,456 or 02:03.456, in seconds
+fn timestamp(text: &str) -> Option<f64> {
+    let text = text.trim().replace(',', ".");
+    let mut seconds = 0.0;
+    for part in text.split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(seconds)
+}
+
+// a cue's text without its <b>, <i>, <v> and timestamp tags
+fn untagged(line: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in line.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.trim().to_string()
+}
+
+// segments run together into lines, each by one speaker and a sentence or so
+fn joined(segments: Vec<Segment>) -> Vec<Segment> {
+    let mut lines: Vec<Segment> = Vec::new();
+    for segment in segments {
+        match lines.last_mut() {
+            Some(line)
+                if line.speaker == segment.speaker
+                    && !line.text.ends_with(['.', '?', '!'])
+                    && line.text.len() + segment.text.len() < LINE_LENGTH =>
+            {
+                line.text.push(' ');
+                line.text.push_str(&segment.text);
+                line.end = segment.end;
+            }
+            _ => lines.push(segment),
+        }
+    }
+    lines
+}
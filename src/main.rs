@@ -1,5 +1,11 @@
 mod app;
+mod cli;
 mod config;
+mod daemon;
+mod remote;
+mod subcommands;
+mod startup;
+mod tutorial;
 
 use std::{
     error::Error,
@@ -7,7 +13,16 @@ use std::{
     time,
     time::{Duration, Instant},
 };
-use app::{App, AppTab, InputMode, SelectedItem, BrowserItem};
+use app::{App, AppTab, ContentState, DownloadConfirm, EpisodeDetail, LiveShow, Focus, InputMode, Osd, PodcastDetail, Prompt, SelectedItem, TextInput, BrowserItem, SEEK_STEP, VOLUME_STEP};
+use app::PodcastSettings;
+use app::{ThemeChange, ThemeEditor};
+use app::FileBrowser;
+use app::PodcastSearch;
+use app::TranscriptView;
+use app::Jump;
+use pinepods_firewood::palette::Palette;
+use clap::Parser;
+use cli::Cli;
 use std::fmt::format;
 use std::thread::sleep;
 use serde::Deserialize;
@@ -17,18 +32,64 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand
 };
+use crossterm::event::KeyModifiers;
+use crossterm::event::{DisableBracketedPaste, EnableBracketedPaste};
+use ratatui::widgets::ListState;
 use ratatui::{
     prelude::{CrosstermBackend, Stylize, Terminal, Backend},
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Modifier, Style},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
+    style::{Color, Modifier, Style},
     text::{Span, Line, Text},
-    widgets::{Block, BorderType, Borders, Cell, Gauge, List, ListItem, Row, Table, Tabs, Paragraph},
+    widgets::{Block, BorderType, Borders, Cell, Clear, Gauge, List, ListItem, Row, Sparkline, Table, Tabs, Paragraph, Wrap},
+    widgets::block::{Position, Title},
     Frame
 };
 // use app::{App, AppTab, InputMode};
-use config::Config;
+use app::Restore;
+use app::KeptItem;
+use app::RemoteChange;
+use app::SPEED_STEP;
+use pinepods_firewood::music_handler::{MAX_SPEED, MIN_SPEED};
+use pinepods_firewood::music_handler::DUCKED_VOLUME;
+use config::{Config, Hints, PlayerPosition};
+use remote::discovery::Peer;
+use remote::Remote;
+use remote::access;
+use app::RemoteClients;
+use tutorial::{Step, Target};
+use startup::{Action as GateAction, Field, Gate};
+use pinepods_firewood::music_handler;
+use pinepods_firewood::errors;
 use pinepods_firewood::gen_funcs;
+use pinepods_firewood::format;
+use pinepods_firewood::key_sequences::{self, Key, KeySequences, Motion, Navigable};
+use pinepods_firewood::backups::Backup;
+use pinepods_firewood::downloads::{Progress, State as DownloadState};
+use pinepods_firewood::changelog::{self, Release};
+use pinepods_firewood::feed::{Chapter, LiveStatus};
+use pinepods_firewood::html;
+use pinepods_firewood::logging;
+use pinepods_firewood::paste::Pasted;
+use pinepods_firewood::playback_log;
+use pinepods_firewood::profiler::{self, Profile, Timing};
+use pinepods_firewood::profiles::{self, Profiles};
+use pinepods_firewood::play_history;
+use pinepods_firewood::queue::{Entry, Queue};
+use pinepods_firewood::requests::ReqwestValues;
+use std::collections::HashMap;
+use pinepods_firewood::podcast_filter::{FilterMode, PodcastFilter};
+use pinepods_firewood::requests::PinepodsEpisodes;
+use std::collections::VecDeque;
+use pinepods_firewood::search_index;
+use pinepods_firewood::stats::{self, PodcastStats, Stats};
+use pinepods_firewood::stateful_list::StatefulList;
+use pinepods_firewood::stateful_list;
+use app::{QueueConflict, Settle};
+use crossterm::event::{EnableMouseCapture, MouseButton, MouseEvent, MouseEventKind};
+use pinepods_firewood::telemetry;
+use pinepods_firewood::subscriptions::SubscriptionChanges;
 use std::ops::Not;
+use std::net::SocketAddr;
 use std::io::{Write, stderr, Result};
 use serde_derive::Serialize;
 use serde_json::to_string;
@@ -50,7 +111,37 @@ struct PinepodsConfig {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    env_logger::init();
+    let cli = Cli::parse();
+
+    // filters can be changed later from the Controls tab
+    logging::init(cli.log_level.as_deref());
+
+    // everything from here on is read as, and saved for, whoever this is
+    if let Some(name) = cli.user.as_deref() {
+        if name != profiles::DEFAULT && !profiles::valid_name(name) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("\"{}\" can't be a user name, only letters, digits, - and _ can", name),
+            ));
+        }
+        profiles::activate(name);
+        Profiles::load().add(name);
+    }
+
+    // pausing and the like are for the player already running, and shouldn't
+    // wait on logging in
+    if let Some(action) = &cli.action {
+        if subcommands::forward(action).await.unwrap_or_else(|e| subcommands::fail(e)) {
+            return Ok(());
+        }
+    }
+
+    let mut cfg = Config::load(&cli);
+    music_handler::set_output_device(cfg.audio_device().map(String::from));
+    // first thing, so panics from here on are caught
+    telemetry::init(cfg.telemetry(), cfg.telemetry_url().map(String::from));
+    playback_log::init();
+
     let mut shared_values = Arc::new(Mutex::new(pinepods_firewood::helpers::requests::ReqwestValues {
         url: String::new(),
         api_key: String::new(),
@@ -66,19 +157,40 @@ async fn main() -> Result<()> {
 
     {
         let mut pinepods_values = shared_values.lock().unwrap();
-        let config_test = pinepods_firewood::helpers::requests::test_existing_config();
-        match config_test.await {
+        // flags and FIREWOOD_* variables win over the saved login, with both
+        // given there's nothing to read or set up
+        let config_test = match (&cli.server_url, &cli.api_key) {
+            (Some(url), Some(api_key)) => Ok(pinepods_firewood::helpers::requests::PinepodsConfig {
+                url: url.clone(),
+                api_key: api_key.clone(),
+            }),
+            _ => pinepods_firewood::helpers::requests::test_existing_config()
+                .await
+                .map(|mut data| {
+                    if let Some(url) = &cli.server_url {
+                        data.url = url.clone();
+                    }
+                    if let Some(api_key) = &cli.api_key {
+                        data.api_key = api_key.clone();
+                    }
+                    data
+                }),
+        };
+        match config_test {
             Ok(data) => {
-                println!("Heres the url {}", data.url);
+                debug!("Heres the url {}", data.url);
                 pinepods_values.url = String::from(data.url);
                 pinepods_values.api_key = data.api_key;
 
                 match pinepods_values.get_userid().await {
                     Ok(id) => {
-                        println!("Podcasts: {:?}", &id);
+                        debug!("User id: {:?}", &id);
                         pinepods_values.user_id = id;
                     }
-                    Err(e) => eprintln!("Request failed: {:?}", e),
+                    Err(e) => {
+                        errors::api_error("get_user", &e);
+                        eprintln!("Request failed: {:?}", e)
+                    }
                 }
             }
             Err(data) => {
@@ -159,7 +271,10 @@ async fn main() -> Result<()> {
                     Ok(id) => {
                         pinepods_values.user_id = id;
                     }
-                    Err(e) => eprintln!("Request failed: {:?}", e),
+                    Err(e) => {
+                        errors::api_error("get_user", &e);
+                        eprintln!("Request failed: {:?}", e)
+                    }
                 }
             }
         }
@@ -167,26 +282,72 @@ async fn main() -> Result<()> {
     {
     let mut pinepods_values = shared_values.lock().unwrap();
     match pinepods_values.return_pods().await {
-        Ok(pods) => println!("Podcasts: {:?}", pods),
+        Ok(pods) => debug!("Podcasts: {:?}", pods),
         Err(e) => eprintln!("Request failed: {:?}", e),
     }
         }
+
+    if let Some(action) = &cli.action {
+        let values = shared_values.lock().unwrap().clone();
+        let Some(episode) = subcommands::run(action, &values).await.unwrap_or_else(|e| subcommands::fail(e)) else {
+            return Ok(());
+        };
+        // nothing running to play it, so this is the player now
+        let mut app = App::new(shared_values.clone()).await;
+        app.music_handle.play(&episode);
+        return daemon::run(app, cfg).await;
+    }
+
+    if cli.daemon {
+        let mut app = App::new(shared_values.clone()).await;
+        if let Some(link) = cli.import.as_deref() {
+            app.start_import(link);
+        }
+        return daemon::run(app, cfg).await;
+    }
+
+    // a taken port shouldn't keep the ui from starting
+    let mut remote = Remote::new();
+    if cfg.remote_enabled() {
+        let token = remote_token(&mut cfg);
+        if let Err(e) = remote.serve_http(cfg.remote_addr(), token) {
+            cfg.warn(format!("Remote control unavailable on {}: {}", cfg.remote_addr(), e));
+            cfg.set_remote(false, cfg.remote_addr());
+        }
+    }
+    if cfg.remote_socket() {
+        if let Err(e) = remote.serve_socket() {
+            cfg.warn(format!("Local remote control unavailable: {}", e));
+        }
+    }
+    let remote = (remote.addr.is_some() || remote.socket.is_some()).then_some(remote);
+
     error!("Setting up terminal...");
     // setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, DisableMouseCapture)?;
+    // pasted text comes in as one event, rather than a key press per character
+    execute!(stdout, EnterAlternateScreen, DisableMouseCapture, EnableBracketedPaste)?;
+    capture_mouse(cfg.mouse())?;
 
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // create app and run it
-    error!("creating app...");
-    let tick_rate = Duration::from_secs(1);
-    let app = App::new(shared_values.clone());
-    let cfg = Config::new();
-    error!("running app...");
-    let res = run_app(&mut terminal, app.await, cfg, tick_rate).await;
+    // what would keep it from working has a screen of its own before anything starts
+    let res = match startup_gate(&mut terminal, &mut cfg, &shared_values).await {
+        Ok(true) => {
+            // create app and run it
+            error!("creating app...");
+            let tick_rate = Duration::from_secs(1);
+            let mut app = App::new(shared_values.clone()).await;
+            if let Some(link) = cli.import.as_deref() {
+                app.start_import(link);
+            }
+            error!("running app...");
+            run_app(&mut terminal, app, cfg, &cli, tick_rate, remote).await
+        }
+        gate => gate.map(|_| ()),
+    };
 
     // restore terminal
     error!("shutdown app...");
@@ -194,7 +355,8 @@ async fn main() -> Result<()> {
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableBracketedPaste
     )?;
     terminal.show_cursor()?;
 
@@ -206,257 +368,3604 @@ async fn main() -> Result<()> {
 }
 
 
+// the startup problems, each on a screen of its own until it's fixed or
+// carried on past, false to quit instead
+async fn startup_gate<B: Backend>(
+    terminal: &mut Terminal<B>,
+    cfg: &mut Config,
+    values: &Arc<Mutex<ReqwestValues>>,
+) -> io::Result<bool> {
+    let mut gate = Gate::default();
+    // a login typed in here is saved once the server takes it
+    let mut login_changed = false;
+    loop {
+        gate.checking = true;
+        terminal.draw(|f| startup_screen(f, &gate, cfg))?;
+        let current = values.lock().unwrap().clone();
+        gate.set_problems(startup::check(&current, cfg).await);
+        gate.checking = false;
+        gate.message = None;
+        match (login_changed, gate.login_failing()) {
+            (true, false) => {
+                login_changed = false;
+                save_login(values).await;
+                // saving prints where to, over the screen
+                terminal.clear()?;
+            }
+            (true, true) => gate.message = Some(String::from("That didn't do it, the saved login is left as it was")),
+            (false, _) => {}
+        }
+
+        loop {
+            if gate.problem().is_none() {
+                return Ok(true);
+            }
+            terminal.draw(|f| startup_screen(f, &gate, cfg))?;
+            let key = match event::read()? {
+                Event::Key(key) => key,
+                Event::Paste(text) => {
+                    gate.paste(&text);
+                    continue;
+                }
+                _ => continue,
+            };
+            match gate.handle_key(key.code) {
+                None => {}
+                Some(GateAction::Quit) => return Ok(false),
+                Some(GateAction::Retry) => break,
+                Some(GateAction::ResetSettings) => {
+                    cfg.reset();
+                    break;
+                }
+                Some(GateAction::SetUrl(url)) => {
+                    values.lock().unwrap().url = url;
+                    login_changed = true;
+                    break;
+                }
+                Some(GateAction::SetApiKey(api_key)) => {
+                    values.lock().unwrap().api_key = api_key;
+                    login_changed = true;
+                    break;
+                }
+                Some(GateAction::Device(device)) => {
+                    music_handler::set_output_device(Some(device));
+                    break;
+                }
+            }
+        }
+    }
+}
+
+// the login fixed on the way in, for next time, and who it's for
+async fn save_login(values: &Arc<Mutex<ReqwestValues>>) {
+    let mut current = values.lock().unwrap().clone();
+    if let Err(e) = current.store_pinepods_info().await {
+        error!("Unable to save the login: {}", e);
+    }
+    match current.get_userid().await {
+        Ok(id) => current.user_id = id,
+        Err(e) => errors::api_error("get_user", &e),
+    }
+    *values.lock().unwrap() = current;
+}
 
 async fn run_app<B: Backend>(
     terminal: &mut Terminal<B>,
     mut app: App<'_>,
-    cfg: Config,
+    mut cfg: Config,
+    cli: &Cli,
     tick_rate: Duration,
+    mut remote: Option<Remote>,
 ) -> io::Result<()> {
     let mut last_tick = Instant::now();
+    let mut sequences = KeySequences::default();
+    // the queues of everyone else who's used it this run, by user
+    let mut queues: HashMap<String, Queue> = HashMap::new();
+    let mut theme_modified = cfg.theme_modified();
+    app.apply_settings(&cfg);
+    app.remote_access = remote.as_ref().map(|remote| remote.access.clone());
     loop {
+        let drawing = Instant::now();
         terminal.draw(|f| ui::<B>(f, &mut app, &cfg))?;
+        if let Some(profile) = app.profile.as_mut() {
+            profile.frame_drawn(drawing.elapsed());
+        }
+        let ticking = Instant::now();
 
         let timeout = tick_rate
             .checked_sub(last_tick.elapsed())
             .unwrap_or_else(|| Duration::from_secs(0));
+
+        // media keys and remote commands don't come through crossterm, don't
+        // sit on them for a whole tick, nor on an overlay or held back key that
+        // needs to time out
+        app.handle_media_keys();
+        if let Some(remote) = remote.as_mut() {
+            while let Ok(request) = remote.requests.try_recv() {
+                app.handle_remote(request);
+            }
+        }
+        app.precache_next(cfg.precache_mb());
+        app.sync_server();
+        if let Some(remote) = remote.as_mut() {
+            remote.publish(app.status());
+        }
+        if let Some(profile) = app.profile.as_mut() {
+            profile.tick.record(ticking.elapsed());
+        }
+        let timeout = if app.media_keys.is_some()
+            || remote.is_some()
+            || app.osd().is_some()
+            || sequences.is_pending()
+            || app.loading.is_some()
+            || app.podcast_detail.as_ref().is_some_and(|detail| detail.loading.is_some())
+            || app.episode_detail.as_ref().is_some_and(|detail| detail.loading.is_some())
+        {
+            timeout.min(Duration::from_millis(100))
+        } else {
+            timeout
+        };
         if crossterm::event::poll(timeout)? {
             // different keys depending on which browser tab
-            if let Event::Key(key) = event::read()? {
-                match app.input_mode() {
-                    // error!("setting key press...");
-                    InputMode::Browser => match key.code {
-                        KeyCode::Char('q') => return Ok(()),
-                        KeyCode::Char('p') | KeyCode::Char(' ') => app.music_handle.play_pause(),
-                        KeyCode::Char('g') => app.music_handle.skip(),
-                        KeyCode::Char('a') => {
-                            if let Some(SelectedItem::Episode(episode)) = app.selected_item() {
-                                app.queue_items.add(episode.clone(), episode.EpisodeDuration);
-                            }
-                        }
-                        KeyCode::Enter => app.evaluate().await,
-                        KeyCode::Backspace => app.backpedal().await,
-                        KeyCode::Down | KeyCode::Char('j') => app.browser_items.next(),
-                        KeyCode::Up | KeyCode::Char('k') => app.browser_items.previous(),
-                        KeyCode::Right | KeyCode::Char('l') => {
-                            app.browser_items.unselect();
-                            app.set_input_mode(InputMode::Queue);
-                            app.queue_items.next();
+            let event = event::read()?;
+            if let Event::Paste(text) = event {
+                match app.focus() {
+                    Focus::TextInput => {
+                        if let Some(input) = app.text_input.as_mut() {
+                            input.value.extend(text.chars().filter(|c| !c.is_control()));
                         }
-                        KeyCode::Tab => {
-                            app.next();
-                            match app.input_mode() {
-                                InputMode::Controls => app.set_input_mode(InputMode::Browser),
-                                _ => app.set_input_mode(InputMode::Controls),
-                            };
+                    }
+                    Focus::Main => app.paste(text),
+                    Focus::Popup => {}
+                }
+                continue;
+            }
+            if let Event::Mouse(mouse) = event {
+                if !handle_mouse(&mut app, &mut cfg, mouse).await {
+                    return Ok(());
+                }
+                continue;
+            }
+            if let Event::Key(key) = event {
+                // the debug overlay, over anything
+                if key.code == KeyCode::F(12) {
+                    app.toggle_profile();
+                    continue;
+                }
+                // the palette's typed into, and its commands need the config
+                if app.palette.is_some() {
+                    if !handle_palette_key(&mut app, &mut cfg, key.code).await {
+                        return Ok(());
+                    }
+                    continue;
+                }
+                // popups take every key until they're dismissed
+                if app.focus() == Focus::Popup {
+                    handle_popup_key(&mut app, Key::single(key.code, key.modifiers));
+                    continue;
+                }
+                if app.focus() == Focus::TextInput {
+                    handle_text_input(&mut app, key.code);
+                    continue;
+                }
+
+                // chapters, from any tab
+                if key.modifiers.contains(KeyModifiers::CONTROL) {
+                    match key.code {
+                        KeyCode::Left => {
+                            app.jump_chapter(false);
+                            continue;
                         }
-                        _ => {}
-                    },
-                    InputMode::Queue => match key.code {
-                        KeyCode::Char('q') => return Ok(()),
-                        KeyCode::Char('p') => app.music_handle.play_pause(),
-                        KeyCode::Char('g') => app.music_handle.skip(),
-                        KeyCode::Enter => {
-                            if let Some(i) = app.queue_items.item() {
-                                app.music_handle.play(i);
-                            };
+                        KeyCode::Right => {
+                            app.jump_chapter(true);
+                            continue;
                         }
-                        KeyCode::Down | KeyCode::Char('j') => app.queue_items.next(),
-                        KeyCode::Up | KeyCode::Char('k') => app.queue_items.previous(),
-                        KeyCode::Char('r') => app.queue_items.remove(),
-                        KeyCode::Left | KeyCode::Char('h') => {
-                            app.queue_items.unselect();
-                            app.set_input_mode(InputMode::Browser);
-                            app.browser_items.next();
+                        KeyCode::Char('v') => {
+                            app.paste_from_clipboard();
+                            continue;
                         }
-                        KeyCode::Tab => {
-                            app.next();
-                            match app.input_mode() {
-                                InputMode::Controls => app.set_input_mode(InputMode::Browser),
-                                _ => app.set_input_mode(InputMode::Controls),
-                            };
+                        KeyCode::Char('o') => {
+                            app.open_file_browser();
+                            continue;
                         }
-                        _ => {}
-                    },
-                    InputMode::Controls => match key.code {
-                        KeyCode::Char('q') => return Ok(()),
-                        KeyCode::Char('p') => app.music_handle.play_pause(),
-                        KeyCode::Char('g') => app.music_handle.skip(),
-                        KeyCode::Down | KeyCode::Char('j') => app.control_table.next(),
-                        KeyCode::Up | KeyCode::Char('k') => app.control_table.previous(),
-                        KeyCode::Tab => {
-                            app.next();
-                            match app.input_mode() {
-                                InputMode::Controls => app.set_input_mode(InputMode::Browser),
-                                _ => app.set_input_mode(InputMode::Controls),
-                            };
+                        KeyCode::Char('k') => {
+                            app.open_palette();
+                            continue;
                         }
                         _ => {}
-                    },
+                    }
+                }
+
+                // Esc leaves the tour, every other key goes on as usual
+                if key.code == KeyCode::Esc && app.tutorial.is_some() {
+                    app.end_tutorial();
+                    continue;
+                }
+
+                for sequence_key in sequences.press(key.code, key.modifiers) {
+                    if !handle_key(&mut app, &mut cfg, sequence_key).await {
+                        return Ok(());
+                    }
+                }
+                app.advance_tutorial(key.code);
+            }
+        }
+        // a lone g still skips, just a moment later
+        if let Some(key) = sequences.expired() {
+            if !handle_key(&mut app, &mut cfg, key).await {
+                return Ok(());
+            }
+        }
+        if let Some(change) = app.remote_change.take() {
+            change_remote(&mut app, &mut cfg, &mut remote, change).await;
+        }
+        // back on after being off for a while
+        if remote.as_ref().and_then(|remote| remote.paused_until).is_some_and(|until| Instant::now() >= until) {
+            change_remote(&mut app, &mut cfg, &mut remote, RemoteChange::Toggle).await;
+        }
+        if let Some(name) = app.user_switch.take() {
+            switch_user(&mut app, &mut cfg, cli, &mut queues, name).await;
+        }
+        match app.theme_change.take() {
+            Some(ThemeChange::Show(colors)) => cfg.set_colors(colors),
+            Some(ThemeChange::Save(colors)) => {
+                match cfg.save_theme(colors) {
+                    Ok(path) => app.notify(format!("Theme saved to {}", path.display())),
+                    Err(e) => app.notify(format!("Couldn't save the theme: {}", e)),
                 }
+                theme_modified = cfg.theme_modified();
             }
+            None => {}
         }
         if last_tick.elapsed() >= tick_rate {
             last_tick = Instant::now();
+            // the theme file, edited elsewhere, shows as soon as it's saved
+            let modified = cfg.theme_modified();
+            if modified != theme_modified && app.theme_editor.is_none() {
+                theme_modified = modified;
+                cfg.reload_theme(cli);
+                app.notify(String::from("Theme reloaded"));
+            }
         }
     }
 }
 
-fn ui<B: Backend>(f: &mut Frame, app: &mut App, cfg: &Config) {
-    // Total Size
-    let size = f.size();
+// hand the app over to `name`, with their login, settings and kept episodes,
+// and the queue they left if they've used it earlier this run
+async fn switch_user(app: &mut App<'_>, cfg: &mut Config, cli: &Cli, queues: &mut HashMap<String, Queue>, name: String) {
+    let leaving = profiles::active();
+    profiles::activate(&name);
+    let login = match pinepods_firewood::requests::saved_login() {
+        Ok(login) => login,
+        Err(_) => {
+            profiles::activate(&leaving);
+            app.notify(format!("{} hasn't logged in here yet, start Firewood with --user {} to set them up", name, name));
+            return;
+        }
+    };
+    let mut values = ReqwestValues {
+        url: login.url,
+        api_key: login.api_key,
+        user_id: 2,
+    };
+    match values.get_userid().await {
+        Ok(id) => values.user_id = id,
+        Err(e) => errors::api_error("get_user", &e),
+    }
+    play_history::reload();
+    // the remote control server carries on as it was
+    let (remote_enabled, remote_addr) = (cfg.remote_enabled(), cfg.remote_addr());
+    *cfg = Config::load(cli);
+    cfg.set_remote(remote_enabled, remote_addr);
+    music_handler::set_output_device(cfg.audio_device().map(String::from));
+    if let Err(e) = capture_mouse(cfg.mouse()) {
+        error!("Unable to change mouse capture: {}", e);
+    }
 
-    // chunking from top to bottom, 3 gets tabs displayed, the rest goes to item layouts
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
-        .split(size);
+    let mut next = App::new(Arc::new(Mutex::new(values))).await;
+    next.apply_settings(cfg);
+    // the media keys belong to the computer, not whoever's using it
+    next.media_keys = app.media_keys.take();
+    next.remote_access = app.remote_access.take();
+    if let Some(queue) = queues.remove(&name) {
+        next.queue_items = queue;
+    }
+    let left = std::mem::replace(app, next);
+    queues.insert(leaving, left.queue_items);
+    app.notify(format!("Switched to {}", name));
+}
 
-    // Main Background block, covers entire screen
-    let block = Block::default().style(Style::default().bg(cfg.background()));
-    f.render_widget(block, size);
+// turn the HTTP remote control on or off, or move it, leaving it as it was if
+// the new address can't be listened on
+// the token to serve the remote control with, the kept one when none's set,
+// the banner saying so when remote.token was set empty to go without
+fn remote_token(cfg: &mut Config) -> Option<String> {
+    let given = cfg.remote_token().is_some();
+    let token = cfg.ensure_remote_token();
+    match &token {
+        None => {
+            let warning = format!("remote.token is empty, so anyone who can reach {} can control playback", cfg.remote_addr());
+            if !cfg.warnings().contains(&warning) {
+                cfg.warn(warning);
+            }
+        }
+        Some(token) if !given => info!("No remote.token set, clients need this one: {}", token),
+        Some(_) => {}
+    }
+    token
+}
 
-    // Tab Title items collected
-    let titles = app
-        .titles
-        .iter()
-        .map(|t| {
-            let (first, rest) = t.split_at(1);
-            Line::from(vec![
-                Span::styled(first, Style::default().fg(cfg.highlight_background())), // CHANGE FOR CUSTOMIZATION
-                Span::styled(rest, Style::default().fg(cfg.highlight_background())), // These are tab highlights, first vs rest diff colors
-            ])
-        })
-        .collect();
+async fn change_remote(app: &mut App<'_>, cfg: &mut Config, remote: &mut Option<Remote>, change: RemoteChange) {
+    let current = cfg.remote_addr();
+    let addr = match &change {
+        RemoteChange::Toggle | RemoteChange::Pause | RemoteChange::NewToken => current,
+        RemoteChange::Listen(value) => match value.parse::<SocketAddr>() {
+            Ok(addr) => addr,
+            Err(_) => match value.parse::<u16>() {
+                Ok(port) => SocketAddr::new(current.ip(), port),
+                Err(_) => {
+                    app.notify(format!("\"{}\" isn't a port or an address and port", value));
+                    return;
+                }
+            },
+        },
+    };
+    let remote = remote.get_or_insert_with(Remote::new);
+    app.remote_access = Some(remote.access.clone());
+    let serving = remote.addr.is_some();
+    match change {
+        RemoteChange::Toggle if serving => {
+            remote.stop_http().await;
+            cfg.set_remote(false, current);
+            app.notify(String::from("Remote control off"));
+            return;
+        }
+        RemoteChange::Pause if serving => {
+            remote.pause_http(REMOTE_PAUSE).await;
+            cfg.set_remote(false, current);
+            app.notify(format!("Remote control off for {} minutes", REMOTE_PAUSE.as_secs() / 60));
+            return;
+        }
+        RemoteChange::Pause => {
+            app.notify(String::from("Remote control's already off"));
+            return;
+        }
+        RemoteChange::NewToken => {
+            let token = access::new_token();
+            cfg.set_remote_token(token.clone());
+            if let Some(view) = app.remote_clients.as_mut() {
+                view.new_token = Some(token);
+            }
+            // clients with the old one are cut off as it starts again
+            if !serving {
+                app.notify(String::from("New remote control token, for when it's next on"));
+                return;
+            }
+        }
+        _ => {}
+    }
 
-    // Box Around Tab Items
-    let tabs = Tabs::new(titles)
-        .block(Block::default().borders(Borders::ALL).title("Tabs"))
-        .select(app.active_tab as usize)
-        .style(Style::default().fg(cfg.foreground()))
-        .highlight_style(
-            Style::default()
-                .add_modifier(Modifier::BOLD)
-                .bg(cfg.background()),
-        );
-    f.render_widget(tabs, chunks[0]);
+    let given = cfg.remote_token().is_some();
+    let token = remote_token(cfg);
+    match remote.rebind_http(addr, token.clone()).await {
+        Ok(()) if change == RemoteChange::NewToken => {
+            app.notify(String::from("Remote control token replaced, clients need the new one to carry on"));
+        }
+        Ok(()) => {
+            cfg.set_remote(true, addr);
+            match token.filter(|_| !given) {
+                Some(token) => app.notify(format!("Remote control listening on {}, clients need the token {}", addr, token)),
+                None => app.notify(format!("Remote control listening on {}", addr)),
+            }
+        }
+        Err(e) => {
+            let problem = match e.kind() {
+                io::ErrorKind::AddrInUse => format!("port {} is taken", addr.port()),
+                io::ErrorKind::AddrNotAvailable => format!("{} isn't an address of this computer", addr.ip()),
+                io::ErrorKind::PermissionDenied => format!("port {} needs more permissions", addr.port()),
+                _ => e.to_string(),
+            };
+            let still = match remote.addr {
+                Some(addr) => format!("still listening on {}", addr),
+                None => String::from("remote control is off"),
+            };
+            app.notify(format!("Can't listen on {}, {}, {}", addr, problem, still));
+        }
+    }
+}
 
-    match app.active_tab {
-        AppTab::Music => music_tab::<B>(f, app, chunks[1], cfg),
-        AppTab::Controls => instructions_tab::<B>(f, app, chunks[1], cfg),
+fn handle_text_input(app: &mut App, code: KeyCode) {
+    let Some(input) = app.text_input.as_mut() else {
+        return;
     };
+    match code {
+        KeyCode::Char(c) => input.value.push(c),
+        KeyCode::Backspace => {
+            input.value.pop();
+        }
+        KeyCode::Enter => {
+            let Some(input) = app.text_input.take() else {
+                return;
+            };
+            let value = input.value.trim();
+            match input.prompt {
+                Prompt::LogFilters => logging::set_filters(value),
+                Prompt::SnapshotName if !value.is_empty() => app.save_snapshot(value),
+                Prompt::SnapshotName => {}
+                Prompt::SavedSearch if !value.is_empty() => app.save_search(value),
+                Prompt::SavedSearch => {}
+                Prompt::Search if !value.is_empty() => app.search(value),
+                Prompt::Search => {}
+                Prompt::ImportLink if !value.is_empty() => app.start_import(value),
+                Prompt::ImportLink => {}
+                Prompt::FindPodcasts if !value.is_empty() => app.find_podcasts(value),
+                Prompt::FindPodcasts => {}
+                Prompt::PlanMinutes => app.plan_for(value),
+                Prompt::RemoteAddress if !value.is_empty() => {
+                    app.remote_change = Some(RemoteChange::Listen(value.to_string()))
+                }
+                Prompt::RemoteAddress => {}
+                Prompt::UserPin => app.enter_pin(value),
+                Prompt::NewPin => app.set_pin(value),
+            }
+        }
+        KeyCode::Esc => app.text_input = None,
+        _ => {}
+    }
 }
 
-fn music_tab<B: Backend>(f: &mut Frame, app: &mut App, chunks: Rect, cfg: &Config) {
-    // split into left / right
-    let browser_queue = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)].as_ref())
-        .split(chunks);
-    // f.size()
+// false to quit, for the command that does
+async fn handle_palette_key(app: &mut App<'_>, cfg: &mut Config, code: KeyCode) -> bool {
+    let Some(palette) = app.palette.as_mut() else {
+        return true;
+    };
+    match code {
+        KeyCode::Down => palette.next(),
+        KeyCode::Up => palette.previous(),
+        KeyCode::Backspace => palette.pop(),
+        KeyCode::Char(c) => palette.push(c),
+        KeyCode::Esc => app.close_popup(),
+        KeyCode::Enter => {
+            if let Some(code) = app.jump_from_palette().await {
+                return handle_key(app, cfg, Key::Code(code, 1)).await;
+            }
+        }
+        _ => {}
+    }
+    true
+}
 
-    // queue and playing sections (sltdkh)
-    let queue_playing = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(
-            [
-                Constraint::Percentage(100 - cfg.progress_bar()),
-                Constraint::Percentage(cfg.progress_bar()),
-            ]
-                .as_ref(),
-        )
-        .split(browser_queue[1]);
+// the popup's list, if it has one, moved as the main ones are
+fn move_list(list: &mut impl Navigable, motion: Option<Motion>) {
+    if let Some(motion) = motion {
+        motion.apply(list);
+    }
+}
 
-    // convert app items to text
-    let items: Vec<ListItem> = app
-        .browser_items
-        .items()
-        .iter()
-        .map(|browser_item| {
-            let text = match browser_item {
-                BrowserItem::Podcast(podcast) => {
-                    // Create a string representation for the podcast
-                    // For example, using the podcast title
-                    podcast.PodcastName.clone()
-                },
-                BrowserItem::Episode(episode) => {
-                    // Create a string representation for the episode
-                    // For example, using the episode title
-                    episode.EpisodePubDate.clone() + " - " + &
-                    episode.EpisodeTitle.clone()
+fn handle_popup_key(app: &mut App, key: Key) {
+    // counts and sequences aren't taken here, popups have digits and d of their own
+    let motion = key_sequences::motion(key, app.page_size);
+    let code = match key {
+        Key::Code(code, _) => code,
+        Key::Ctrl(c, _) if motion.is_none() => KeyCode::Char(c),
+        _ => KeyCode::Null,
+    };
+    // these only show something, so all they take is Enter or Esc
+    if app.subscription_changes.is_some() || app.telemetry_preview || app.whats_new.is_some() {
+        if let KeyCode::Enter | KeyCode::Esc = code {
+            app.close_popup();
+        }
+        return;
+    }
+
+    if let Some(editor) = app.theme_editor.as_mut() {
+        match code {
+            KeyCode::Down | KeyCode::Char('j') => editor.row = (editor.row + 1).min(3),
+            KeyCode::Up | KeyCode::Char('k') => editor.row = editor.row.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => editor.channel = (editor.channel + 1).min(2),
+            KeyCode::Left | KeyCode::Char('h') => editor.channel = editor.channel.saturating_sub(1),
+            KeyCode::Char('+' | '=') => app.change_theme_color(true),
+            KeyCode::Char('-') => app.change_theme_color(false),
+            KeyCode::Enter => app.save_theme(),
+            KeyCode::Esc => app.close_popup(),
+            _ => {}
+        }
+        return;
+    }
+
+    if app.podcast_settings.is_some() {
+        match code {
+            KeyCode::Down | KeyCode::Char('j') => {
+                if let Some(settings) = app.podcast_settings.as_mut() {
+                    settings.row = (settings.row + 1).min(3);
                 }
-            };
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                if let Some(settings) = app.podcast_settings.as_mut() {
+                    settings.row = settings.row.saturating_sub(1);
+                }
+            }
+            KeyCode::Right | KeyCode::Char('l' | '+' | '=') => app.change_podcast_setting(true),
+            KeyCode::Left | KeyCode::Char('h' | '-') => app.change_podcast_setting(false),
+            // back to the detail underneath
+            KeyCode::Enter | KeyCode::Esc => app.podcast_settings = None,
+            _ => {}
+        }
+        return;
+    }
 
-            // Convert the string to Text
-            ListItem::new(Text::from(text))
-        })
-        .collect();
+    if let Some(detail) = app.podcast_detail.as_mut() {
+        let link = detail
+            .funding
+            .selected()
+            .and_then(|i| detail.funding.items().get(i))
+            .map(|funding| funding.url.clone());
+        match (code, link) {
+            (KeyCode::Char('o'), Some(url)) => {
+                detail.notice = Some(match gen_funcs::open_link(&url) {
+                    Ok(()) => format!("Opened {}", url),
+                    Err(e) => format!("Unable to open a browser: {}", e),
+                })
+            }
+            (KeyCode::Char('y'), Some(url)) => {
+                detail.notice = Some(match gen_funcs::copy_to_clipboard(&url) {
+                    Ok(()) => format!("Copied {}", url),
+                    Err(e) => format!("Unable to copy: {}", e),
+                })
+            }
+            (KeyCode::Char('n'), _) => app.cycle_podcast_notify(),
+            (KeyCode::Char('h'), _) => app.toggle_podcast_priority(),
+            (KeyCode::Char('p'), _) => app.open_podcast_settings(),
+            (KeyCode::Enter | KeyCode::Esc, _) => app.close_popup(),
+            _ => move_list(&mut detail.funding, motion),
+        }
+        return;
+    }
 
-    // Create a List from all list items and highlight the currently selected one // RENDER 1
-    let items = List::new(items)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Browser")
-                .title_alignment(Alignment::Left)
-                .border_type(BorderType::Rounded),
-        )
-        .style(Style::default().fg(cfg.foreground()))
-        .highlight_style(
-            Style::default()
-                .bg(cfg.highlight_background())
-                .fg(cfg.highlight_foreground())
-                .add_modifier(Modifier::BOLD),
-        )
-        .highlight_symbol(">> ");
-    f.render_stateful_widget(items, browser_queue[0], &mut app.browser_items.state());
+    if let Some(detail) = app.episode_detail.as_mut() {
+        match code {
+            KeyCode::Char('J') => detail.scroll = detail.scroll.saturating_add(1),
+            KeyCode::Char('K') => detail.scroll = detail.scroll.saturating_sub(1),
+            KeyCode::PageDown => detail.scroll = detail.scroll.saturating_add(app.page_size as u16),
+            KeyCode::PageUp => detail.scroll = detail.scroll.saturating_sub(app.page_size as u16),
+            KeyCode::Char(c @ '0'..='9') => app.type_link_number(c.to_digit(10).unwrap_or(0)),
+            KeyCode::Enter if detail.link_typed > 0 => app.open_typed_link(),
+            KeyCode::Enter if !detail.soundbites.empty() => app.play_soundbite(),
+            KeyCode::Enter | KeyCode::Esc => app.close_popup(),
+            _ => move_list(&mut detail.soundbites, motion),
+        }
+        return;
+    }
 
-    let queue_items: Vec<ListItem> = app
-        .queue_items
-        .items()
-        .iter()
-        .map(|i| ListItem::new(Text::from(gen_funcs::audio_display(i))))
-        .collect();
+    if app.pasted.is_some() {
+        match code {
+            KeyCode::Enter | KeyCode::Char('y') => app.confirm_paste(),
+            KeyCode::Esc | KeyCode::Char('n') => app.close_popup(),
+            _ => {}
+        }
+        return;
+    }
 
-    let queue_title = format!(
-        "| Queue: {queue_items} Episodes |{total_time}",
-        queue_items = app.queue_items.length(),
-        total_time = app.queue_items.total_time(),
-    );
+    if app.queue_conflict.is_some() {
+        match code {
+            KeyCode::Enter | KeyCode::Char('m') => app.settle_queue_conflict(Settle::Merge),
+            KeyCode::Char('h') => app.settle_queue_conflict(Settle::Here),
+            KeyCode::Char('s') => app.settle_queue_conflict(Settle::Server),
+            KeyCode::Esc => app.close_popup(),
+            _ => {}
+        }
+        return;
+    }
 
-    let queue_items = List::new(queue_items)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(queue_title)
-                .title_alignment(Alignment::Left)
-                .border_type(BorderType::Rounded),
-        )
-        .style(Style::default().fg(cfg.foreground()))
-        .highlight_style(
-            Style::default()
-                .bg(cfg.highlight_background())
-                .fg(cfg.highlight_foreground())
-                .add_modifier(Modifier::BOLD),
-        )
-        .highlight_symbol(">> ");
-    f.render_stateful_widget(queue_items, queue_playing[0], &mut app.queue_items.state());
+    if app.download_confirm.is_some() {
+        match code {
+            KeyCode::Enter | KeyCode::Char('y') => app.download_unplayed(),
+            KeyCode::Esc | KeyCode::Char('n') => app.close_popup(),
+            _ => {}
+        }
+        return;
+    }
 
-    let playing_title = format!("| {current_song} |", current_song = app.current_song());
+    if app.listening_plan.is_some() {
+        match code {
+            KeyCode::Enter => app.queue_listening_plan(),
+            KeyCode::Char('s') => app.shuffle_listening_plan(),
+            KeyCode::Char('r') => app.regenerate_listening_plan(),
+            KeyCode::Esc => app.close_popup(),
+            _ => {}
+        }
+        return;
+    }
 
-    // Note Gauge is using background color for progress
-    let playing = Gauge::default()
-        .block(
-            Block::default()
-                .title(playing_title)
+    if let Some(picker) = app.filter_picker.as_mut() {
+        match code {
+            KeyCode::Char(' ') => app.toggle_filtered(),
+            KeyCode::Char('m') => app.switch_filter_mode(),
+            KeyCode::Char('e') => app.toggle_filter(),
+            KeyCode::Enter | KeyCode::Esc => app.close_popup(),
+            _ => move_list(picker, motion),
+        }
+        return;
+    }
+
+    if let Some(picker) = app.kept_picker.as_mut() {
+        match code {
+            KeyCode::Enter => app.play_kept(),
+            KeyCode::Char('p') => app.pause_download(),
+            KeyCode::Char('d') => app.remove_kept(),
+            KeyCode::Char('o') => app.reveal_kept(false),
+            KeyCode::Char('y') => app.reveal_kept(true),
+            KeyCode::Esc => app.close_popup(),
+            _ => move_list(picker, motion),
+        }
+        return;
+    }
+
+    if let Some(picker) = app.user_picker.as_mut() {
+        match code {
+            KeyCode::Enter => app.switch_to_selected_user(),
+            KeyCode::Char('p') => {
+                app.close_popup();
+                app.prompt(Prompt::NewPin, String::new());
+            }
+            KeyCode::Esc => app.close_popup(),
+            _ => move_list(picker, motion),
+        }
+        return;
+    }
+
+    if let Some(browser) = app.file_browser.as_mut() {
+        match code {
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => app.open_browsed(),
+            KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') => app.browse_up(),
+            KeyCode::Char('a') => app.queue_browsed(),
+            KeyCode::Char('b') => app.toggle_browsed_audiobook(),
+            KeyCode::Esc => app.close_popup(),
+            _ => move_list(&mut browser.entries, motion),
+        }
+        return;
+    }
+
+    if let Some(search) = app.podcast_search.as_mut() {
+        match code {
+            KeyCode::Tab => app.switch_search_provider(),
+            KeyCode::Enter => app.preview_found(),
+            KeyCode::Char('s') => app.subscribe_found(),
+            KeyCode::Char('/') => {
+                let query = search.query.clone();
+                app.close_popup();
+                app.prompt(Prompt::FindPodcasts, query);
+            }
+            KeyCode::Esc => app.close_popup(),
+            _ => move_list(&mut search.results, motion),
+        }
+        return;
+    }
+
+    if let Some(picker) = app.cast_picker.as_mut() {
+        match code {
+            KeyCode::Enter => app.cast_to_selected(),
+            KeyCode::Esc => app.close_popup(),
+            _ => move_list(picker, motion),
+        }
+        return;
+    }
+
+    if let Some(picker) = app.chapter_picker.as_mut() {
+        match code {
+            KeyCode::Enter => app.play_chapter(),
+            KeyCode::Esc => app.close_popup(),
+            _ => move_list(picker, motion),
+        }
+        return;
+    }
+
+    if app.remote_clients.is_some() {
+        match code {
+            KeyCode::Char('x') => app.revoke_selected_client(),
+            KeyCode::Char('t') => app.remote_change = Some(RemoteChange::NewToken),
+            KeyCode::Char('o') => app.remote_change = Some(RemoteChange::Pause),
+            KeyCode::Esc => app.close_popup(),
+            _ => {
+                if let Some(view) = app.remote_clients.as_mut() {
+                    move_list(&mut view.clients, motion);
+                }
+            }
+        }
+        return;
+    }
+
+    if app.transcript.is_some() {
+        match code {
+            KeyCode::Enter => app.play_from_transcript(),
+            KeyCode::Char('f') => app.follow_transcript(),
+            KeyCode::Char(' ') | KeyCode::Char('p') => app.music_handle.play_pause(),
+            KeyCode::Esc => app.close_popup(),
+            _ => {
+                if let Some(motion) = motion {
+                    app.move_in_transcript(motion);
+                }
+            }
+        }
+        return;
+    }
+
+    if let Some(picker) = app.backup_picker.as_mut() {
+        match code {
+            KeyCode::Enter => app.restore_backup(Restore::Everything),
+            KeyCode::Char('q') => app.restore_backup(Restore::Queue),
+            KeyCode::Char('p') => app.restore_backup(Restore::Podcasts),
+            KeyCode::Char('s') => app.restore_backup(Restore::Settings),
+            KeyCode::Esc => app.close_popup(),
+            _ => move_list(picker, motion),
+        }
+        return;
+    }
+
+    let Some(picker) = app.snapshot_picker.as_mut() else {
+        return;
+    };
+
+    let name = picker.selected().and_then(|i| picker.items().get(i)).cloned();
+    match code {
+        KeyCode::Enter | KeyCode::Char('a') => {
+            if let Some(name) = name {
+                app.restore_snapshot(&name, code == KeyCode::Char('a'));
+            }
+            app.close_popup();
+        }
+        KeyCode::Char('d') => {
+            if let Some(name) = name {
+                app.snapshots.delete(&name);
+                app.open_snapshot_picker();
+            }
+        }
+        KeyCode::Esc => app.close_popup(),
+        _ => move_list(picker, motion),
+    }
+}
+
+// with layout.mouse on the terminal sends clicks and the wheel, and keeps
+// them for selecting text otherwise
+fn capture_mouse(on: bool) -> io::Result<()> {
+    match on {
+        true => execute!(io::stdout(), EnableMouseCapture),
+        false => execute!(io::stdout(), DisableMouseCapture),
+    }
+}
+
+// the wheel moves through whatever's under it as the arrow keys would, popups
+// included, and a click picks the tab, list row, or place on the progress bar
+// to seek to, under it. False to quit, as for a key.
+async fn handle_mouse(app: &mut App<'_>, cfg: &mut Config, mouse: MouseEvent) -> bool {
+    let (column, row) = (mouse.column, mouse.row);
+    let inside = |area: Rect| area.intersects(Rect::new(column, row, 1, 1));
+    let code = match mouse.kind {
+        MouseEventKind::ScrollDown => KeyCode::Down,
+        MouseEventKind::ScrollUp => KeyCode::Up,
+        MouseEventKind::Down(MouseButton::Left) => {
+            if app.focus() == Focus::Main && app.palette.is_none() {
+                click(app, cfg, column, row);
+            }
+            return true;
+        }
+        _ => return true,
+    };
+
+    if app.palette.is_some() {
+        return handle_palette_key(app, cfg, code).await;
+    }
+    match app.focus() {
+        Focus::Popup => {
+            handle_popup_key(app, Key::Code(code, 1));
+            return true;
+        }
+        Focus::TextInput => return true,
+        Focus::Main => {}
+    }
+    if matches!(app.active_tab, AppTab::Music) {
+        if inside(app.regions.queue) && !matches!(app.input_mode(), InputMode::Queue) {
+            app.go_to(InputMode::Queue);
+        } else if inside(app.regions.browser) && !matches!(app.input_mode(), InputMode::Browser) {
+            app.go_to(InputMode::Browser);
+        }
+    }
+    handle_key(app, cfg, Key::Code(code, 1)).await
+}
+
+fn click(app: &mut App, cfg: &Config, column: u16, row: u16) {
+    let inside = |area: Rect| area.intersects(Rect::new(column, row, 1, 1));
+    // inside the border, as `Block::inner` has it
+    let inner = |area: Rect| area.inner(&Margin { horizontal: 1, vertical: 1 });
+
+    if inside(inner(app.regions.tab_bar)) {
+        // each title has a space either side, then the divider
+        let divider = cfg.glyphs().divider().chars().count() as u16;
+        let mut x = app.regions.tab_bar.x + 1;
+        let tab = app.titles.iter().position(|title| {
+            let end = x + title.chars().count() as u16 + 2;
+            let hit = column < end;
+            x = end + divider;
+            hit
+        });
+        match tab {
+            Some(0) if !matches!(app.active_tab, AppTab::Music) => app.go_to(InputMode::Browser),
+            Some(1) if !matches!(app.active_tab, AppTab::Controls) => app.go_to(InputMode::Controls),
+            _ => {}
+        }
+        return;
+    }
+    if !matches!(app.active_tab, AppTab::Music) {
+        return;
+    }
+
+    let browser = inner(app.regions.browser);
+    if inside(browser) && !app.showing_podcast_grid() {
+        let len = app.browser_items.items().len();
+        let top = stateful_list::top(len, app.browser_items.selected(), browser.height);
+        let i = top + usize::from(row - browser.y);
+        if i < len {
+            if !matches!(app.input_mode(), InputMode::Browser) {
+                app.go_to(InputMode::Browser);
+            }
+            app.browser_items.select(i);
+        }
+        return;
+    }
+
+    let queue = inner(app.regions.queue);
+    if inside(queue) {
+        let entries = app.queue_items.entries();
+        let top = stateful_list::top(entries.len(), app.queue_items.state().selected(), queue.height);
+        let Some(entry) = entries.get(top + usize::from(row - queue.y)) else {
+            return;
+        };
+        if !matches!(app.input_mode(), InputMode::Queue) {
+            app.go_to(InputMode::Queue);
+        }
+        match entry {
+            Entry::Header(group) => app.queue_items.select_item(group.start),
+            Entry::Item(i) => app.queue_items.select_item(*i),
+        }
+        return;
+    }
+
+    // the bar itself, as chapter_ticks finds it
+    let player = match app.regions.player.height {
+        0..=2 => app.regions.player,
+        _ => inner(app.regions.player),
+    };
+    let length = app.music_handle.song_length();
+    if inside(player) && player.width > 0 && length > 0 && !app.music_handle.is_live() {
+        let position = f64::from(column - player.x) / f64::from(player.width) * f64::from(length);
+        app.seek_to(position as u16);
+    }
+}
+
+// the list that has focus moved by `motion`
+fn move_selection(app: &mut App, motion: Motion) {
+    match app.input_mode() {
+        // a row of tiles at a time in the grid
+        InputMode::Browser => match motion {
+            Motion::Step(rows) if app.showing_podcast_grid() => {
+                app.browser_items.move_by(rows * app.grid_columns as isize)
+            }
+            _ => motion.apply(&mut app.browser_items),
+        },
+        InputMode::Queue => motion.apply(&mut app.queue_items),
+        InputMode::Controls => motion.apply(&mut app.control_table),
+    }
+}
+
+async fn handle_key(app: &mut App<'_>, cfg: &mut Config, key: Key) -> bool {
+    if let Some(motion) = key_sequences::motion(key, app.page_size) {
+        move_selection(app, motion);
+        return true;
+    }
+
+    let code = match key {
+        Key::Code(code, _) => code,
+        // without a motion for it, as it was before Ctrl was told apart
+        Key::Ctrl(c, _) => KeyCode::Char(c),
+        Key::Sequence(sequence) => {
+            match (app.input_mode(), sequence) {
+                (InputMode::Queue, "dd") => app.remove_queued(),
+                (InputMode::Queue, "za") => app.queue_items.toggle_fold(),
+                (InputMode::Queue, "zi") => {
+                    let grouped = !app.queue_items.is_grouped();
+                    app.queue_items.set_grouped(grouped);
+                }
+                (InputMode::Queue, "zM") => app.queue_items.fold_all(true),
+                (InputMode::Queue, "zR") => app.queue_items.fold_all(false),
+                _ => {}
+            }
+            return true;
+        }
+    };
+
+    // config problems aren't fatal, the banner only takes its own keys
+    if !cfg.warnings().is_empty() {
+        match code {
+            KeyCode::Char('R') => {
+                app.back_up("before a settings reset");
+                cfg.reset();
+                return true;
+            }
+            KeyCode::Esc => {
+                cfg.dismiss_warnings();
+                return true;
+            }
+            _ => {}
+        }
+    }
+
+    // the same on every tab
+    match code {
+        KeyCode::Char('q') => return false,
+        KeyCode::Char('p') | KeyCode::Char(' ') => {
+            app.music_handle.play_pause();
+            app.show_osd(Osd::Position);
+            return true;
+        }
+        KeyCode::Char('g') => {
+            app.music_handle.skip();
+            app.show_osd(Osd::Position);
+            return true;
+        }
+        KeyCode::Char('n') => {
+            app.play_adjacent(1);
+            return true;
+        }
+        KeyCode::Char('N') => {
+            app.play_adjacent(-1);
+            return true;
+        }
+        KeyCode::Char('S') => {
+            app.toggle_data_saver();
+            return true;
+        }
+        KeyCode::Char('c') => {
+            app.toggle_recording();
+            return true;
+        }
+        KeyCode::Char('C') => {
+            app.open_chapter_picker();
+            return true;
+        }
+        KeyCode::Char('W') => {
+            app.open_transcript();
+            return true;
+        }
+        KeyCode::Char('H') => {
+            app.pull_playback();
+            return true;
+        }
+        KeyCode::Char('U') => {
+            app.push_playback();
+            return true;
+        }
+        KeyCode::Char('X') => {
+            app.open_cast_picker(cfg.remote_token().map(String::from));
+            return true;
+        }
+        KeyCode::Char('+') | KeyCode::Char('=') => {
+            app.change_volume(VOLUME_STEP);
+            return true;
+        }
+        // m marks for removing in the queue
+        KeyCode::Char('M') => {
+            app.toggle_mute();
+            return true;
+        }
+        KeyCode::Char('m') if !matches!(app.input_mode(), InputMode::Queue) => {
+            app.toggle_mute();
+            return true;
+        }
+        KeyCode::Char('V') => {
+            app.toggle_duck();
+            return true;
+        }
+        KeyCode::Char('-') => {
+            app.change_volume(-VOLUME_STEP);
+            return true;
+        }
+        KeyCode::Char('[') => {
+            app.seek_by(-SEEK_STEP);
+            return true;
+        }
+        KeyCode::Char('<') => {
+            app.change_speed(-SPEED_STEP);
+            return true;
+        }
+        KeyCode::Char('>') => {
+            app.change_speed(SPEED_STEP);
+            return true;
+        }
+        KeyCode::Char(']') => {
+            app.seek_by(SEEK_STEP);
+            return true;
+        }
+        _ => {}
+    }
+
+    match app.input_mode() {
+        // error!("setting key press...");
+        InputMode::Browser => match code {
+            KeyCode::Char('a') => {
+                if let Some(SelectedItem::Episode(episode)) = app.selected_item() {
+                    app.queue_items.add(episode.clone(), episode.EpisodeDuration);
+                }
+            }
+            KeyCode::Char('A') => app.play_next(),
+            KeyCode::Enter => app.evaluate().await,
+            KeyCode::Char('i') => app.open_detail(),
+            KeyCode::Char('L') => app.play_live(),
+            KeyCode::Char('/') => app.prompt(Prompt::SavedSearch, String::new()),
+            KeyCode::Char('s') => app.prompt(Prompt::Search, String::new()),
+            KeyCode::Char('e') => app.prompt(Prompt::FindPodcasts, String::new()),
+            KeyCode::Char('x') => app.delete_search(),
+            KeyCode::Char('f') => app.open_filter_picker(),
+            KeyCode::Char('F') => app.toggle_filter(),
+            KeyCode::Char('o') => app.toggle_smart_sort(),
+            KeyCode::Char('P') => app.open_listening_plan(),
+            KeyCode::Char('D') => app.confirm_download_unplayed().await,
+            KeyCode::Char('d') => app.keep_selected(),
+            KeyCode::Char('O') => app.open_kept_picker(),
+            KeyCode::Backspace => app.backpedal().await,
+            KeyCode::Char('v') => app.podcast_grid = !app.podcast_grid,
+            KeyCode::Char('b') => app.toggle_podcast_rail(),
+            KeyCode::Char('J') => app.move_along_rail(1).await,
+            KeyCode::Char('K') => app.move_along_rail(-1).await,
+            KeyCode::Left | KeyCode::Char('h') if app.grid_has_neighbour(-1) => {
+                app.browser_items.move_by(-1)
+            }
+            KeyCode::Right | KeyCode::Char('l') if app.grid_has_neighbour(1) => {
+                app.browser_items.move_by(1)
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                app.browser_items.unselect();
+                app.set_input_mode(InputMode::Queue);
+                app.queue_items.next();
+            }
+            KeyCode::Tab => {
+                app.next();
+                match app.input_mode() {
+                    InputMode::Controls => app.set_input_mode(InputMode::Browser),
+                    _ => app.set_input_mode(InputMode::Controls),
+                };
+            }
+            _ => {}
+        },
+        InputMode::Queue => match code {
+            KeyCode::Enter => {
+                if let Some(i) = app.queue_items.item() {
+                    app.music_handle.play(i);
+                    app.playing_from = None;
+                };
+            }
+            KeyCode::Char('r') => app.remove_queued(),
+            KeyCode::Char('m') => app.queue_items.toggle_mark(),
+            KeyCode::Char('J') => app.move_queued(1),
+            KeyCode::Char('K') => app.move_queued(-1),
+            KeyCode::Char('t') => app.move_queued_to_next(),
+            KeyCode::Char('P') => app.play_from_here(),
+            KeyCode::Char('a') => app.toggle_continuous(),
+            KeyCode::Char('s') => app.prompt(Prompt::SnapshotName, String::new()),
+            KeyCode::Char('o') => app.open_snapshot_picker(),
+            KeyCode::Char('I') => app.prompt(Prompt::ImportLink, String::new()),
+            KeyCode::Char('i') => app.open_queued_detail(),
+            KeyCode::Left | KeyCode::Char('h') => {
+                app.queue_items.unselect();
+                app.set_input_mode(InputMode::Browser);
+                app.browser_items.next();
+            }
+            KeyCode::Tab => {
+                app.next();
+                match app.input_mode() {
+                    InputMode::Controls => app.set_input_mode(InputMode::Browser),
+                    _ => app.set_input_mode(InputMode::Controls),
+                };
+            }
+            _ => {}
+        },
+        InputMode::Controls => match code {
+            KeyCode::Char('L') => logging::cycle_level(),
+            KeyCode::Char('F') => app.prompt(Prompt::LogFilters, logging::filters()),
+            KeyCode::Char('t') => telemetry::set_enabled(!telemetry::enabled()),
+            KeyCode::Char('T') => app.telemetry_preview = true,
+            KeyCode::Char('E') => match playback_log::export() {
+                Ok(path) => app.notify(format!("Playback log saved to {}", path.display())),
+                Err(e) => app.notify(format!("Couldn't save the playback log: {}", e)),
+            },
+            KeyCode::Char('b') => {
+                app.back_up("taken by hand");
+                app.notify(String::from("Backed up the subscriptions, queue and settings"));
+            }
+            KeyCode::Char('B') => app.open_backup_picker(),
+            KeyCode::Char('r') => app.remote_change = Some(RemoteChange::Toggle),
+            KeyCode::Char('a') => app.open_remote_clients(),
+            KeyCode::Char('A') => app.prompt(Prompt::RemoteAddress, cfg.remote_addr().to_string()),
+            KeyCode::Char('u') => app.open_user_picker(),
+            KeyCode::Char('e') => app.open_theme_editor(cfg.colors()),
+            KeyCode::Char('w') => app.whats_new = changelog::current(),
+            KeyCode::Char('?') => app.start_tutorial(),
+            KeyCode::Tab => {
+                app.next();
+                match app.input_mode() {
+                    InputMode::Controls => app.set_input_mode(InputMode::Browser),
+                    _ => app.set_input_mode(InputMode::Controls),
+                };
+            }
+            _ => {}
+        },
+    }
+
+    true
+}
+
+// how long the remote control's off for when it's turned off for a while
+const REMOTE_PAUSE: Duration = Duration::from_secs(15 * 60);
+
+// the keys the hint bar has for each part of the screen, written as they are
+// in the Controls tab, and the scopes their rows there can be for
+fn hint_keys(mode: InputMode) -> (&'static [&'static str], &'static [&'static str]) {
+    match mode {
+        InputMode::Browser => (
+            &["Enter", "Backspace", "A", "I", "S", "E", "D", "V"],
+            &["Browser", "Podcasts", "Episodes"],
+        ),
+        InputMode::Queue => (&["R / DD", "M", "Shift + J / K", "T", "Shift + P", "A", "ZI", "ZA", "S", "O"], &["Queue"]),
+        InputMode::Controls => (&["B", "Shift + B", "R", "A", "U", "E", "W", "?"], &["Controls Tab"]),
+    }
+}
+
+// after the ones for whatever has focus
+const EVERYWHERE_HINTS: [&str; 4] = ["P / Space", "G", "Tab", "Q"];
+
+// worded from the Controls tab's table, so the two always agree
+fn hint_bar(f: &mut Frame, app: &App, area: Rect, cfg: &Config) {
+    let (keys, scopes) = hint_keys(app.input_mode());
+    let key_style = Style::default()
+        .fg(cfg.highlight_foreground())
+        .bg(cfg.highlight_background());
+    let spans: Vec<Span> = keys
+        .iter()
+        .chain(EVERYWHERE_HINTS.iter())
+        .filter_map(|key| Some((key, app.control_table.describe(key, scopes)?)))
+        .flat_map(|(key, description)| {
+            [
+                Span::styled(format!(" {} ", key), key_style),
+                Span::raw(format!(" {}  ", description)),
+            ]
+        })
+        .collect();
+    let hints = Paragraph::new(Line::from(spans)).style(Style::default().fg(cfg.foreground()).bg(cfg.background()));
+    let hints = match cfg.hints() {
+        Hints::Full => hints.wrap(Wrap { trim: true }).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(cfg.border(BorderType::Plain))
+                .title("Keys | Every One Is In The Controls Tab"),
+        ),
+        _ => hints,
+    };
+    f.render_widget(hints, area);
+}
+
+// the whole screen, for what's keeping the app from starting
+fn startup_screen(f: &mut Frame, gate: &Gate, cfg: &Config) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(cfg.border(BorderType::Rounded))
+        .title("| Firewood |")
+        .title_alignment(Alignment::Center)
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()));
+    let area = centered_rect(80, 60, f.size());
+    let inner = block.inner(area);
+    f.render_widget(Clear, f.size());
+    f.render_widget(block, area);
+
+    let problem = match (gate.checking, gate.problem()) {
+        (false, Some(problem)) => problem,
+        _ => {
+            let checking = Paragraph::new("Checking the server and the audio output...").alignment(Alignment::Center);
+            f.render_widget(checking, centered_rect(100, 20, inner));
+            return;
+        }
+    };
+
+    let key_style = Style::default()
+        .fg(cfg.highlight_foreground())
+        .bg(cfg.highlight_background());
+    let title = match gate.left() {
+        1 => String::from(problem.title()),
+        left => format!("{} (And {} More)", problem.title(), left - 1),
+    };
+    let mut lines = vec![
+        Line::from(Span::styled(title, Style::default().add_modifier(Modifier::BOLD))),
+        Line::from(""),
+    ];
+    lines.extend(problem.details().into_iter().map(Line::from));
+    if let Some(message) = &gate.message {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(message.clone(), Style::default().add_modifier(Modifier::BOLD))));
+    }
+    if let Some(input) = &gate.input {
+        let label = match input.field {
+            Field::Url => "Server URL, like https://pinepods.example.com",
+            Field::ApiKey => "API Key",
+        };
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!("{} | Enter: Try It | Esc: Cancel", label)));
+        lines.push(Line::from(Span::styled(format!("{}_", input.value), key_style)));
+    }
+    lines.push(Line::from(""));
+    let keys: Vec<Span> = problem
+        .actions()
+        .iter()
+        .flat_map(|(key, description)| {
+            [
+                Span::styled(format!(" {} ", key), key_style),
+                Span::raw(format!(" {}  ", description)),
+            ]
+        })
+        .collect();
+    lines.push(Line::from(keys));
+
+    let height = inner.height.min(lines.len() as u16 + 1);
+    let text_area = Rect { height, ..inner };
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), text_area);
+
+    if let Some(devices) = &gate.devices {
+        let items: Vec<ListItem> = devices.items().iter().map(|device| ListItem::new(device.as_str())).collect();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_set(cfg.border(BorderType::Rounded))
+                    .title("Audio Outputs | Enter: Play On It | Esc: Cancel"),
+            )
+            .highlight_style(key_style)
+            .highlight_symbol(">> ");
+        let list_area = Rect {
+            y: inner.y + height,
+            height: inner.height.saturating_sub(height),
+            ..inner
+        };
+        f.render_stateful_widget(list, list_area, &mut devices.state());
+    }
+}
+
+fn ui<B: Backend>(f: &mut Frame, app: &mut App, cfg: &Config) {
+    // Total Size
+    let size = f.size();
+
+    // config problems get a banner under the tabs until dismissed or reset
+    let banner_height = match cfg.warnings().len() {
+        0 => 0,
+        n => n as u16 + 2,
+    };
+    // failed requests too, repeats counted instead of stacking up, until
+    // one gets through
+    let server_error = errors::banner();
+    let error_height = if server_error.is_some() { 3 } else { 0 };
+    let hints_height = match cfg.hints() {
+        Hints::Hidden => 0,
+        Hints::Compact => 1,
+        Hints::Full => 4,
+    };
+
+    // chunking from top to bottom, 3 gets tabs displayed, the rest goes to item layouts
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(3),
+                Constraint::Length(banner_height),
+                Constraint::Length(error_height),
+                Constraint::Min(0),
+                Constraint::Length(hints_height),
+            ]
+            .as_ref(),
+        )
+        .split(size);
+
+    // Main Background block, covers entire screen
+    let block = Block::default().style(Style::default().bg(cfg.background()));
+    f.render_widget(block, size);
+
+    // Tab Title items collected
+    let titles = app
+        .titles
+        .iter()
+        .map(|t| {
+            let (first, rest) = t.split_at(1);
+            Line::from(vec![
+                Span::styled(first, Style::default().fg(cfg.highlight_background())), // CHANGE FOR CUSTOMIZATION
+                Span::styled(rest, Style::default().fg(cfg.highlight_background())), // These are tab highlights, first vs rest diff colors
+            ])
+        })
+        .collect();
+
+    let mut tabs_title = String::from("Tabs");
+    if app.data_saver {
+        tabs_title.push_str(" | Data Saver");
+    }
+    if !app.outbox.is_empty() {
+        tabs_title.push_str(&format!(" | {} Pending", app.outbox.len()));
+    }
+    if app.outbox.downloads() > 0 {
+        tabs_title.push_str(&format!(" | {} Downloads To Send", app.outbox.downloads()));
+    }
+    if !app.server_downloads.is_empty() {
+        tabs_title.push_str(&format!(" | {} Downloading On Server", app.server_downloads.len()));
+    }
+    if !app.server_downloads.failed().is_empty() {
+        tabs_title.push_str(&format!(" | {} Server Downloads Failed", app.server_downloads.failed().len()));
+    }
+
+    // Box Around Tab Items
+    let tabs = Tabs::new(titles)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(cfg.border(BorderType::Plain))
+                .title(tabs_title),
+        )
+        .divider(cfg.glyphs().divider())
+        .select(app.active_tab as usize)
+        .style(Style::default().fg(cfg.foreground()))
+        .highlight_style(
+            Style::default()
+                .add_modifier(Modifier::BOLD)
+                .bg(cfg.background()),
+        );
+    f.render_widget(tabs, chunks[0]);
+
+    if banner_height > 0 {
+        let warnings: Vec<Line> = cfg.warnings().iter().map(|w| Line::from(w.as_str())).collect();
+        let banner = Paragraph::new(warnings)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_set(cfg.border(BorderType::Plain))
+                    .title("Config Problems | R: Reset To Defaults | Esc: Dismiss"),
+            )
+            .style(Style::default().fg(cfg.color(Color::LightRed)).bg(cfg.background()));
+        f.render_widget(banner, chunks[1]);
+    }
+
+    if let Some(server_error) = server_error {
+        let mut title = String::from("Server Problems");
+        if server_error.count > 1 {
+            title.push_str(&format!(" | Seen {} Times", server_error.count));
+        }
+        if server_error.others > 0 {
+            title.push_str(&format!(" | {} Other Errors", server_error.others));
+        }
+        let banner = Paragraph::new(server_error.message)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_set(cfg.border(BorderType::Plain))
+                    .title(title),
+            )
+            .style(Style::default().fg(cfg.color(Color::LightRed)).bg(cfg.background()));
+        f.render_widget(banner, chunks[2]);
+    }
+
+    // rows a list shows at most, less its borders
+    app.page_size = usize::from(chunks[3].height.saturating_sub(2)).max(1);
+    app.regions.tab_bar = chunks[0];
+
+    let drawing = Instant::now();
+    let page = match app.active_tab {
+        AppTab::Music => {
+            music_tab::<B>(f, app, chunks[3], cfg);
+            "Podcasts"
+        }
+        AppTab::Controls => {
+            instructions_tab::<B>(f, app, chunks[3], cfg);
+            "Controls"
+        }
+    };
+    if let Some(profile) = app.profile.as_mut() {
+        profile.page(page, drawing.elapsed());
+    }
+    if hints_height > 0 {
+        hint_bar(f, app, chunks[4], cfg);
+    }
+
+    // the Music tab has its own progress bar, but nothing showing the volume
+    match (app.active_tab, app.osd()) {
+        (AppTab::Controls, Some(osd)) | (_, Some(osd @ (Osd::Volume | Osd::Speed))) => {
+            osd_overlay(f, app, osd, cfg)
+        }
+        _ => {}
+    }
+
+    if let Some(message) = app.notification() {
+        notification_overlay(f, message, cfg);
+    }
+
+    if let Some(step) = app.tutorial {
+        tutorial_overlay(f, app, step, cfg);
+    }
+
+    if let Some(palette) = &app.palette {
+        palette_popup(f, palette, cfg);
+    }
+
+    if let Some(input) = &app.text_input {
+        text_input_prompt(f, input, cfg);
+    }
+
+    if let Some(detail) = &app.podcast_detail {
+        podcast_detail_popup(f, detail, cfg);
+    }
+    if let Some(settings) = &app.podcast_settings {
+        podcast_settings_popup(f, settings, app.usual_speed(), cfg);
+    }
+
+    if let Some(detail) = &app.episode_detail {
+        episode_detail_popup(f, detail, cfg);
+    }
+
+    if let Some(picker) = &app.snapshot_picker {
+        snapshot_picker_popup(f, picker, cfg);
+    }
+
+    if let Some(picker) = &app.kept_picker {
+        kept_picker_popup(f, picker, &app.downloads.jobs(), cfg);
+    }
+    if let Some(picker) = &app.backup_picker {
+        backup_picker_popup(f, picker, cfg);
+    }
+    if let Some(picker) = &app.user_picker {
+        user_picker_popup(f, picker, cfg);
+    }
+    if let Some(editor) = &app.theme_editor {
+        theme_editor_popup(f, editor, cfg);
+    }
+    if let Some(browser) = &app.file_browser {
+        file_browser_popup(f, browser, cfg);
+    }
+
+    if let Some(search) = &app.podcast_search {
+        podcast_search_popup(f, search, cfg);
+    }
+
+    if let Some(picker) = &app.cast_picker {
+        cast_picker_popup(f, picker, cfg);
+    }
+
+    if let Some(picker) = &app.chapter_picker {
+        chapter_picker_popup(f, picker, app.current_chapter().map(|chapter| chapter.start), cfg);
+    }
+
+    if let Some(view) = &app.transcript {
+        transcript_popup(f, view, cfg);
+    }
+
+    if let Some(view) = &app.remote_clients {
+        remote_clients_popup(f, view, cfg);
+    }
+
+    if let Some(picker) = &app.filter_picker {
+        filter_picker_popup(f, picker, &app.podcast_filter, cfg);
+    }
+
+    if let Some(conflict) = &app.queue_conflict {
+        queue_conflict_popup(f, conflict, app.queue_items.items(), cfg);
+    }
+    if let Some(confirm) = &app.download_confirm {
+        download_confirm_popup(f, confirm, cfg);
+    }
+
+    if let Some(pasted) = &app.pasted {
+        paste_popup(f, pasted, cfg);
+    }
+
+    if let Some(plan) = &app.listening_plan {
+        listening_plan_popup(f, &plan.episodes, app.smart_sort.available, cfg);
+    }
+
+    if app.telemetry_preview {
+        telemetry_preview_popup(f, cfg);
+    }
+
+    if let Some(release) = app.whats_new {
+        whats_new_popup(f, release, cfg);
+    }
+
+    if let Some(changes) = &app.subscription_changes {
+        subscription_changes_popup(f, changes, cfg);
+    }
+
+    if let Some(profile) = &app.profile {
+        profile_overlay(f, profile, app, cfg);
+    }
+}
+
+// top left, over whatever is there, times in milliseconds
+fn profile_overlay(f: &mut Frame, profile: &Profile, app: &App, cfg: &Config) {
+    let timing = |name: &str, timing: &Timing| {
+        Line::from(format!(
+            "{:<10} {:>7.2} {:>7.2} {:>7.2}",
+            name,
+            timing.last.as_secs_f64() * 1000.0,
+            timing.average.as_secs_f64() * 1000.0,
+            timing.max.as_secs_f64() * 1000.0
+        ))
+    };
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("{:<10} {:>7} {:>7} {:>7}", "", "last", "avg", "max"),
+            Style::default().add_modifier(Modifier::DIM),
+        )),
+        timing("Frame", &profile.frame),
+        timing("Tick", &profile.tick),
+    ];
+    lines.extend(profile.pages.iter().map(|(page, page_timing)| timing(page, page_timing)));
+    lines.push(Line::from(format!("{} frames a second", profile.fps)));
+    lines.push(Line::from(format!(
+        "{} fetches running, {} actions to send",
+        app.background_tasks(),
+        app.outbox.len()
+    )));
+    lines.push(Line::from(match profiler::memory() {
+        Some(bytes) => format!("{:.1} MB resident", bytes as f64 / (1024.0 * 1024.0)),
+        None => String::from("Memory use unknown here"),
+    }));
+
+    let size = f.size();
+    let area = Rect::new(
+        size.x,
+        size.y,
+        42.min(size.width),
+        (lines.len() as u16 + 2).min(size.height),
+    );
+    let overlay = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title("Profile | F12: Close")
+                .borders(Borders::ALL)
+                .border_set(cfg.border(BorderType::Rounded)),
+        )
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()));
+    f.render_widget(Clear, area);
+    f.render_widget(overlay, area);
+}
+
+// outlines what the current step is about and explains it in a box that
+// stays clear of the outline
+fn tutorial_overlay(f: &mut Frame, app: &App, step: Step, cfg: &Config) {
+    let size = f.size();
+    // everything but the tab bar is only on the Music tab
+    let target = step
+        .target()
+        .filter(|t| *t == Target::TabBar || matches!(app.active_tab, AppTab::Music))
+        .map(|t| app.regions.get(t))
+        .filter(|area| area.area() > 0);
+
+    if let Some(area) = target {
+        let outline = Block::default()
+            .borders(Borders::ALL)
+            .border_set(cfg.border(BorderType::Thick))
+            .border_style(
+                Style::default()
+                    .fg(cfg.highlight_background())
+                    .add_modifier(Modifier::BOLD),
+            );
+        f.render_widget(outline, area);
+    }
+
+    let width = (size.width * 3 / 5).max(20).min(size.width);
+    let height = size.height.min(6);
+    let low_target = target.is_some_and(|area| area.y + area.height / 2 > size.height / 2);
+    let y = match low_target {
+        true => size.height.min(3),
+        false => size.height.saturating_sub(height),
+    };
+    let area = Rect::new((size.width - width) / 2, y, width, height.min(size.height - y));
+
+    let text = Paragraph::new(step.text())
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .title("Tour | Esc: Leave")
+                .borders(Borders::ALL)
+                .border_set(cfg.border(BorderType::Rounded)),
+        )
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()));
+    f.render_widget(Clear, area);
+    f.render_widget(text, area);
+}
+
+fn whats_new_popup(f: &mut Frame, release: &Release, cfg: &Config) {
+    let area = centered_rect(70, 70, f.size());
+    let heading = Style::default().add_modifier(Modifier::BOLD);
+
+    let mut lines = vec![Line::from(Span::styled("New", heading))];
+    lines.extend(release.features.iter().map(|feature| Line::from(format!("  * {}", feature))));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("Keys", heading)));
+    lines.extend(release.keys.iter().map(|key| Line::from(format!("  * {}", key))));
+    lines.push(Line::from(""));
+    lines.push(Line::from("Press Enter or Esc to dismiss, w on the Controls tab shows this again"));
+
+    let popup = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(format!("What's New In v{}", release.version))
+                .borders(Borders::ALL)
+                .border_set(cfg.border(BorderType::Rounded)),
+        )
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()));
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+// everything the next upload would send, as it would be sent
+fn telemetry_preview_popup(f: &mut Frame, cfg: &Config) {
+    let area = centered_rect(70, 70, f.size());
+    let status = match (telemetry::enabled(), telemetry::url()) {
+        (false, _) => String::from("Telemetry is off, nothing is collected or sent"),
+        (true, None) => String::from("Telemetry is on, but no URL is set so nothing is sent"),
+        (true, Some(url)) => format!("Telemetry is on, reports go to {}", url),
+    };
+    let reports = telemetry::preview();
+    let body = match reports.is_empty() {
+        true => String::from("No reports waiting"),
+        false => serde_json::to_string_pretty(&reports).unwrap_or_default(),
+    };
+
+    let mut lines = vec![Line::from(status), Line::from("")];
+    lines.extend(body.lines().map(|line| Line::from(line.to_string())));
+
+    let popup = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title("Telemetry Preview | Enter or Esc: Close")
+                .borders(Borders::ALL)
+                .border_set(cfg.border(BorderType::Rounded)),
+        )
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()));
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn text_input_prompt(f: &mut Frame, input: &TextInput, cfg: &Config) {
+    let area = centered_rect(60, 20, f.size());
+    let (title, hint) = match input.prompt {
+        Prompt::LogFilters => (
+            "Log Filters | Enter: Apply | Esc: Cancel",
+            "Same as RUST_LOG, e.g. warn,pinepods_firewood::remote=debug",
+        ),
+        Prompt::SnapshotName => (
+            "Save Queue | Enter: Save | Esc: Cancel",
+            "Saving under an existing name replaces that snapshot",
+        ),
+        Prompt::SavedSearch => (
+            "Saved Search | Enter: Save | Esc: Cancel",
+            "Lists every episode with this in its title or description as a podcast of its own",
+        ),
+        Prompt::Search => (
+            "Search Episodes | Enter: Search | Esc: Cancel",
+            "Words from the title or description, of every podcast's episodes",
+        ),
+        Prompt::ImportLink => (
+            "Import To Queue | Enter: Import | Esc: Cancel",
+            "A shared episode link, a playlist link or a playlist id",
+        ),
+        Prompt::FindPodcasts => (
+            "Find Podcasts | Enter: Search | Esc: Cancel",
+            "A podcast's name, who makes it or what it's about, looked for in the podcast directory",
+        ),
+        Prompt::PlanMinutes => (
+            "Listening Plan | Enter: Plan | Esc: Cancel",
+            "Minutes there are to listen, from the episodes showing or every podcast's from the podcast list",
+        ),
+        Prompt::RemoteAddress => (
+            "Remote Control Address | Enter: Listen There | Esc: Cancel",
+            "A port, or an address and port like 127.0.0.1:8042, for this run only",
+        ),
+        Prompt::UserPin => (
+            "PIN | Enter: Switch User | Esc: Cancel",
+            "The PIN of the user being switched to",
+        ),
+        Prompt::NewPin => (
+            "Your PIN | Enter: Set | Esc: Cancel",
+            "Asked for before anyone switches to you, leave it empty to stop asking",
+        ),
+    };
+    let typed = match input.prompt {
+        Prompt::UserPin | Prompt::NewPin => "*".repeat(input.value.chars().count()),
+        _ => input.value.clone(),
+    };
+    let lines = vec![
+        Line::from(format!("{}_", typed)),
+        Line::from(""),
+        Line::from(Span::styled(hint, Style::default().add_modifier(Modifier::DIM))),
+    ];
+
+    let prompt = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_set(cfg.border(BorderType::Rounded)),
+        )
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()));
+    f.render_widget(Clear, area);
+    f.render_widget(prompt, area);
+}
+
+fn podcast_detail_popup(f: &mut Frame, detail: &PodcastDetail, cfg: &Config) {
+    let area = centered_rect(70, 70, f.size());
+    let heading = Style::default().add_modifier(Modifier::BOLD);
+    let block = Block::default()
+        .title(format!("{} | N: Notifications | H: Priority | P: Playback | Enter or Esc: Close", detail.name))
+        .borders(Borders::ALL)
+        .border_set(cfg.border(BorderType::Rounded));
+    let inner = block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(
+        block.style(Style::default().fg(cfg.foreground()).bg(cfg.background())),
+        area,
+    );
+
+    let links = detail.funding.items().len() as u16;
+    let notice = detail.notice.clone().or_else(|| reading_feed(detail.loading, cfg));
+    let sections = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            // a gap, heading and summary, then the weeks
+            Constraint::Length(if detail.stats.is_some() { 6 } else { 0 }),
+            // heading and a line per link, or just the notice
+            Constraint::Length(if links > 0 { links + 2 } else { 0 }),
+            Constraint::Length(u16::from(notice.is_some())),
+        ])
+        .split(inner);
+
+    let mut about = vec![Line::from(format!("By {}", detail.author))];
+    if !detail.website.is_empty() {
+        about.push(Line::from(detail.website.clone()));
+    }
+    let mut flags = format!("Notify: {}", detail.flags.notify.label());
+    if detail.flags.high_priority {
+        flags.push_str(" | High Priority");
+    }
+    if let Some(speed) = detail.flags.speed {
+        flags.push_str(&format!(" | {:.1}x", speed));
+    }
+    match (detail.flags.skip_intro, detail.flags.skip_outro) {
+        (0, 0) => {}
+        (intro, outro) => flags.push_str(&format!(" | Skips {}s In, {}s Out", intro, outro)),
+    }
+    if detail.flags.audiobook {
+        flags.push_str(" | Audiobook");
+    }
+    about.push(Line::from(Span::styled(flags, Style::default().add_modifier(Modifier::DIM))));
+    about.push(Line::from(""));
+    about.extend(html::render(&detail.description, cfg.glyphs().bullet()).lines);
+    f.render_widget(Paragraph::new(about).wrap(Wrap { trim: true }), sections[0]);
+
+    if let Some(stats) = &detail.stats {
+        podcast_stats(f, stats, sections[1], cfg);
+    }
+
+    if links > 0 {
+        let support = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(2), Constraint::Min(0)])
+            .split(sections[2]);
+        f.render_widget(
+            Paragraph::new(vec![
+                Line::from(""),
+                Line::from(vec![
+                    Span::styled("Support This Show", heading),
+                    Span::styled(" | O: Open | Y: Copy", Style::default().add_modifier(Modifier::DIM)),
+                ]),
+            ]),
+            support[0],
+        );
+        let items: Vec<ListItem> = detail
+            .funding
+            .items()
+            .iter()
+            .map(|funding| ListItem::new(format!("{} ({})", funding.label, funding.url)))
+            .collect();
+        let list = List::new(items)
+            .highlight_style(
+                Style::default()
+                    .bg(cfg.highlight_background())
+                    .fg(cfg.highlight_foreground())
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol(">> ");
+        f.render_stateful_widget(list, support[1], &mut detail.funding.state());
+    }
+
+    if let Some(notice) = notice {
+        let notice = Paragraph::new(notice).style(Style::default().add_modifier(Modifier::DIM));
+        f.render_widget(notice, sections[3]);
+    }
+}
+
+// spinning in place of the notice while the feed a popup wants is fetched
+fn reading_feed(loading: Option<Instant>, cfg: &Config) -> Option<String> {
+    loading.map(|since| format!("{} Reading the feed...", cfg.glyphs().spinner(since.elapsed())))
+}
+
+fn podcast_settings_popup(f: &mut Frame, settings: &PodcastSettings, usual_speed: f32, cfg: &Config) {
+    let area = centered_rect(50, 30, f.size());
+    let title = format!("{} Playback | H / L: Change | Esc: Back", settings.podcast);
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_set(cfg.border(BorderType::Rounded));
+    f.render_widget(Clear, area);
+
+    let flags = &settings.flags;
+    let speed = match flags.speed {
+        Some(speed) => format!("{:.1}x", speed),
+        None => format!("{:.1}x, Same As Everything Else", usual_speed),
+    };
+    let skip = |seconds: u16| match seconds {
+        0 => String::from("Off"),
+        _ => format::clock(seconds.into()),
+    };
+    let items = vec![
+        ListItem::new(format!("{:<12}{}", "Speed", speed)),
+        ListItem::new(format!("{:<12}{}", "Skip Intro", skip(flags.skip_intro))),
+        ListItem::new(format!("{:<12}{}", "Skip Outro", skip(flags.skip_outro))),
+        ListItem::new(format!(
+            "{:<12}{}",
+            "Audiobook",
+            if flags.audiobook { "On, Episodes In Order Oldest First" } else { "Off" }
+        )),
+    ];
+    let list = List::new(items)
+        .block(block)
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()))
+        .highlight_style(
+            Style::default()
+                .bg(cfg.highlight_background())
+                .fg(cfg.highlight_foreground())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(list, area, &mut ListState::default().with_selected(Some(settings.row)));
+}
+
+// how much of the podcast has been listened to, and a bar a week for the last
+// twelve
+fn podcast_stats(f: &mut Frame, stats: &PodcastStats, area: Rect, cfg: &Config) {
+    let parts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(area);
+    let mut summary = vec![
+        format!("{} Episodes Played", stats.episodes),
+        format::minutes(stats.minutes),
+    ];
+    if let Some(completion) = stats.completion {
+        summary.push(format!("{}% Through On Average", completion));
+    }
+    if let Some(last_played) = &stats.last_played {
+        summary.push(format!("Last Played {}", cfg.date(last_played)));
+    }
+    f.render_widget(
+        Paragraph::new(vec![
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("Listening", Style::default().add_modifier(Modifier::BOLD)),
+                Span::styled(
+                    format!(" | The Last {} Weeks Below", stats::WEEKS),
+                    Style::default().add_modifier(Modifier::DIM),
+                ),
+            ]),
+            Line::from(summary.join(" | ")),
+        ]),
+        parts[0],
+    );
+    // a few columns to a week, so twelve of them aren't lost in the width
+    let columns = (usize::from(parts[1].width) / stats::WEEKS).clamp(1, 4);
+    let weeks: Vec<u64> = stats
+        .weekly
+        .iter()
+        .flat_map(|minutes| std::iter::repeat_n(*minutes, columns))
+        .collect();
+    let sparkline = Sparkline::default()
+        .data(&weeks)
+        .bar_set(cfg.glyphs().bars())
+        .style(Style::default().fg(cfg.highlight_background()));
+    f.render_widget(sparkline, parts[1]);
+}
+
+fn episode_detail_popup(f: &mut Frame, detail: &EpisodeDetail, cfg: &Config) {
+    let area = centered_rect(70, 70, f.size());
+    let heading = Style::default().add_modifier(Modifier::BOLD);
+    let episode = &detail.episode;
+    let close = match detail.soundbites.empty() {
+        true => "Enter or Esc: Close",
+        false => "Enter: Play Soundbite | Esc: Close",
+    };
+    let links = match detail.links.is_empty() {
+        true => "",
+        false => "Number: Open Link | ",
+    };
+    let block = Block::default()
+        .title(format!("{} | Shift + J / K: Scroll | {}{}", episode.EpisodeTitle, links, close))
+        .borders(Borders::ALL)
+        .border_set(cfg.border(BorderType::Rounded));
+    let inner = block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(
+        block.style(Style::default().fg(cfg.foreground()).bg(cfg.background())),
+        area,
+    );
+
+    let soundbites = detail.soundbites.items().len() as u16;
+    let notice = detail.notice.clone().or_else(|| reading_feed(detail.loading, cfg));
+    let sections = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(if soundbites > 0 { soundbites + 2 } else { 0 }),
+            Constraint::Length(u16::from(notice.is_some())),
+        ])
+        .split(inner);
+
+    let mut about = vec![
+        Line::from(episode.PodcastName.clone().unwrap_or_default()),
+        Line::from(format!(
+            "{} | {}",
+            cfg.date(&episode.EpisodePubDate),
+            format::length(episode.EpisodeDuration)
+        )),
+    ];
+    if let Some(variant) = &detail.variant {
+        about.push(Line::from(format!("Version: {}", variant)));
+    }
+    about.push(Line::from(""));
+    about.extend(html::render(&episode.EpisodeDescription, cfg.glyphs().bullet()).lines);
+    if !detail.links.is_empty() {
+        about.push(Line::from(""));
+        about.push(Line::from(Span::styled("Links", heading)));
+        for (i, link) in detail.links.iter().enumerate() {
+            about.push(Line::from(vec![
+                Span::styled(format!("[{}] ", i + 1), Style::default().add_modifier(Modifier::DIM)),
+                Span::raw(link.clone()),
+            ]));
+        }
+    }
+    f.render_widget(
+        Paragraph::new(about).wrap(Wrap { trim: true }).scroll((detail.scroll, 0)),
+        sections[0],
+    );
+
+    if soundbites > 0 {
+        let list_area = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(2), Constraint::Min(0)])
+            .split(sections[1]);
+        f.render_widget(
+            Paragraph::new(vec![Line::from(""), Line::from(Span::styled("Soundbites", heading))]),
+            list_area[0],
+        );
+        let items: Vec<ListItem> = detail
+            .soundbites
+            .items()
+            .iter()
+            .map(|soundbite| {
+                let title = match soundbite.title.is_empty() {
+                    true => "Untitled",
+                    false => soundbite.title.as_str(),
+                };
+                ListItem::new(format!(
+                    "{} {} ({}s)",
+                    format::clock(soundbite.start as i64),
+                    title,
+                    soundbite.duration.round()
+                ))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_style(
+                Style::default()
+                    .bg(cfg.highlight_background())
+                    .fg(cfg.highlight_foreground())
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol(">> ");
+        f.render_stateful_widget(list, list_area[1], &mut detail.soundbites.state());
+    }
+
+    if let Some(notice) = notice {
+        let notice = Paragraph::new(notice).style(Style::default().add_modifier(Modifier::DIM));
+        f.render_widget(notice, sections[2]);
+    }
+}
+
+fn backup_picker_popup(f: &mut Frame, picker: &StatefulList<Backup>, cfg: &Config) {
+    let area = centered_rect(70, 50, f.size());
+    let title = "Backups | Enter: Restore All | Q: Queue | P: Podcasts | S: Settings | Esc: Close";
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_set(cfg.border(BorderType::Rounded));
+    f.render_widget(Clear, area);
+
+    if picker.empty() {
+        let empty = Paragraph::new("No backups yet, press b on the Controls tab to take one")
+            .block(block)
+            .style(Style::default().fg(cfg.foreground()).bg(cfg.background()));
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = picker
+        .items()
+        .iter()
+        .map(|backup| ListItem::new(backup.label()))
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()))
+        .highlight_style(
+            Style::default()
+                .bg(cfg.highlight_background())
+                .fg(cfg.highlight_foreground())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(list, area, &mut picker.state());
+}
+
+fn user_picker_popup(f: &mut Frame, picker: &StatefulList<String>, cfg: &Config) {
+    let area = centered_rect(50, 40, f.size());
+    let title = "Users | Enter: Switch To | P: Set Your PIN | Esc: Close";
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_set(cfg.border(BorderType::Rounded));
+    f.render_widget(Clear, area);
+
+    let profiles = Profiles::load();
+    let active = profiles::active();
+    let mut items: Vec<ListItem> = picker
+        .items()
+        .iter()
+        .map(|name| {
+            let pin = if profiles.has_pin(name) { "  (PIN)" } else { "" };
+            let current = if *name == active { "  (you)" } else { "" };
+            ListItem::new(format!("{}{}{}", name, pin, current))
+        })
+        .collect();
+    if picker.items().len() == 1 {
+        items.push(ListItem::new(Span::styled(
+            "Start Firewood with --user NAME to add someone",
+            Style::default().add_modifier(Modifier::DIM),
+        )));
+    }
+    let list = List::new(items)
+        .block(block)
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()))
+        .highlight_style(
+            Style::default()
+                .bg(cfg.highlight_background())
+                .fg(cfg.highlight_foreground())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(list, area, &mut picker.state());
+}
+
+// every color already shows as it'll look, this has the numbers and a swatch
+fn theme_editor_popup(f: &mut Frame, editor: &ThemeEditor, cfg: &Config) {
+    let area = centered_rect(60, 30, f.size());
+    let title = "Theme | J / K: Color | H / L: Red, Green, Blue | + / -: Change | Enter: Save | Esc: Cancel";
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_set(cfg.border(BorderType::Rounded));
+    f.render_widget(Clear, area);
+
+    let labels = ["Text", "Background", "Selected Text", "Selected Background"];
+    let swatches = [cfg.foreground(), cfg.background(), cfg.highlight_foreground(), cfg.highlight_background()];
+    let items: Vec<ListItem> = labels
+        .iter()
+        .zip(swatches)
+        .zip(editor.colors)
+        .enumerate()
+        .map(|(row, ((label, swatch), (r, g, b)))| {
+            let mut spans = vec![
+                Span::raw(format!("{:<22}", label)),
+                Span::styled("      ", Style::default().bg(swatch)),
+                Span::raw(format!("  #{:02x}{:02x}{:02x} ", r, g, b)),
+            ];
+            for (channel, (name, value)) in [("R", r), ("G", g), ("B", b)].into_iter().enumerate() {
+                let text = format!(" {} {:>3}", name, value);
+                spans.push(match row == editor.row && channel == editor.channel {
+                    true => Span::styled(text, Style::default().add_modifier(Modifier::REVERSED)),
+                    false => Span::raw(text),
+                });
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()))
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(list, area, &mut ListState::default().with_selected(Some(editor.row)));
+}
+
+fn file_browser_popup(f: &mut Frame, browser: &FileBrowser, cfg: &Config) {
+    let area = centered_rect(70, 60, f.size());
+    let title = "Local Files | Enter: Open / Play | Backspace: Up | A: Queue | B: Audiobook | Esc: Close";
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_set(cfg.border(BorderType::Rounded));
+    f.render_widget(Clear, area);
+    let inner = block.inner(area);
+    f.render_widget(
+        block.style(Style::default().fg(cfg.foreground()).bg(cfg.background())),
+        area,
+    );
+    let sections = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(inner);
+    let dir = match browser.audiobook {
+        true => format!("{} (audiobook)", browser.dir.display()),
+        false => browser.dir.display().to_string(),
+    };
+    f.render_widget(
+        Paragraph::new(dir).style(Style::default().add_modifier(Modifier::DIM)),
+        sections[0],
+    );
+
+    if browser.entries.empty() {
+        f.render_widget(Paragraph::new("No folders or audio files in here"), sections[1]);
+        return;
+    }
+    let items: Vec<ListItem> = browser
+        .entries
+        .items()
+        .iter()
+        .map(|entry| match (entry.folder, entry.position) {
+            (true, _) => ListItem::new(format!("{}/", entry.name())),
+            (false, Some(position)) => ListItem::new(Line::from(vec![
+                Span::raw(entry.name()),
+                Span::styled(
+                    format!("  (at {})", format::clock(position.into())),
+                    Style::default().add_modifier(Modifier::DIM),
+                ),
+            ])),
+            (false, None) => ListItem::new(entry.name()),
+        })
+        .collect();
+    let list = List::new(items)
+        .highlight_style(
+            Style::default()
+                .bg(cfg.highlight_background())
+                .fg(cfg.highlight_foreground())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(list, sections[1], &mut browser.entries.state());
+}
+
+fn podcast_search_popup(f: &mut Frame, search: &PodcastSearch, cfg: &Config) {
+    let area = centered_rect(80, 70, f.size());
+    let title = format!(
+        "Find Podcasts On {} | Tab: Switch Directory | Enter: Preview | S: Subscribe | /: Search Again | Esc: Close",
+        search.provider.name()
+    );
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_set(cfg.border(BorderType::Rounded));
+    f.render_widget(Clear, area);
+    let inner = block.inner(area);
+    f.render_widget(
+        block.style(Style::default().fg(cfg.foreground()).bg(cfg.background())),
+        area,
+    );
+
+    if search.results.empty() {
+        let message = match search.searching {
+            true => format!("Searching {} for \"{}\"...", search.provider.name(), search.query),
+            false => format!("Nothing on {} for \"{}\", Tab to try the other directory", search.provider.name(), search.query),
+        };
+        f.render_widget(Paragraph::new(message), inner);
+        return;
+    }
+
+    let sections = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(inner);
+    let items: Vec<ListItem> = search
+        .results
+        .items()
+        .iter()
+        .map(|podcast| {
+            ListItem::new(Line::from(vec![
+                Span::raw(podcast.PodcastName.clone()),
+                Span::styled(format!("  {}", podcast.Author), Style::default().add_modifier(Modifier::DIM)),
+            ]))
+        })
+        .collect();
+    let list = List::new(items)
+        .highlight_style(
+            Style::default()
+                .bg(cfg.highlight_background())
+                .fg(cfg.highlight_foreground())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(list, sections[0], &mut search.results.state());
+
+    let Some(podcast) = search.results.selected().and_then(|i| search.results.items().get(i)) else {
+        return;
+    };
+    let preview = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(5), Constraint::Min(0)])
+        .split(sections[1].inner(&Margin::new(1, 0)));
+    let head = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(10), Constraint::Min(0)])
+        .split(preview[0]);
+
+    // the artwork's colors can't be drawn here, so the same tile as the grid
+    let padding = usize::from(head[0].height.saturating_sub(1) / 2);
+    let mut tile = vec![Line::from(""); padding];
+    tile.push(Line::from(Span::styled(
+        initials(&podcast.PodcastName),
+        Style::default().add_modifier(Modifier::BOLD),
+    )));
+    let artwork = Paragraph::new(tile).alignment(Alignment::Center).style(
+        Style::default()
+            .fg(cfg.color(Color::Black))
+            .bg(cfg.color(placeholder_color(&podcast.PodcastName))),
+    );
+    f.render_widget(artwork, head[0]);
+
+    let dim = Style::default().add_modifier(Modifier::DIM);
+    let episodes = match podcast.EpisodeCount {
+        0 => String::from("Episodes not known yet, Enter to look"),
+        1 => String::from("1 episode"),
+        count => format!("{} episodes", count),
+    };
+    let about = vec![
+        Line::from(Span::styled(podcast.PodcastName.clone(), Style::default().add_modifier(Modifier::BOLD))),
+        Line::from(podcast.Author.clone()),
+        Line::from(episodes),
+        Line::from(Span::styled(podcast.Categories.clone(), dim)),
+        Line::from(Span::styled(podcast.FeedURL.clone(), dim)),
+    ];
+    f.render_widget(
+        Paragraph::new(about).wrap(Wrap { trim: true }),
+        head[1].inner(&Margin::new(1, 0)),
+    );
+
+    let description = match (search.previewing, podcast.Description.is_empty()) {
+        (true, true) => String::from("Reading its feed..."),
+        (false, true) => String::from("Enter to read what it's about from its feed"),
+        (_, false) => podcast.Description.clone(),
+    };
+    f.render_widget(
+        Paragraph::new(html::render(&description, cfg.glyphs().bullet()).lines).wrap(Wrap { trim: true }),
+        preview[1].inner(&Margin::new(0, 1)),
+    );
+}
+
+fn cast_picker_popup(f: &mut Frame, picker: &StatefulList<Peer>, cfg: &Config) {
+    let area = centered_rect(60, 40, f.size());
+    let title = "Cast To Another Firewood | Enter: Play There | Esc: Close";
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_set(cfg.border(BorderType::Rounded));
+    f.render_widget(Clear, area);
+
+    // they're found as they answer, which can take a moment
+    if picker.empty() {
+        let empty = Paragraph::new("Looking for other Firewoods with the remote control on...")
+            .block(block)
+            .style(Style::default().fg(cfg.foreground()).bg(cfg.background()));
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = picker
+        .items()
+        .iter()
+        .map(|peer| {
+            let version = match &peer.version {
+                Some(version) => format!("  v{}", version),
+                None => String::from("  (older version)"),
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(peer.name.clone()),
+                Span::styled(
+                    format!("  {}{}", peer.addr, version),
+                    Style::default().add_modifier(Modifier::DIM),
+                ),
+            ]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()))
+        .highlight_style(
+            Style::default()
+                .bg(cfg.highlight_background())
+                .fg(cfg.highlight_foreground())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(list, area, &mut picker.state());
+}
+
+fn chapter_picker_popup(f: &mut Frame, picker: &StatefulList<Chapter>, current: Option<f64>, cfg: &Config) {
+    let area = centered_rect(60, 50, f.size());
+    let title = "Chapters | Enter: Jump To | Esc: Close";
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_set(cfg.border(BorderType::Rounded));
+    f.render_widget(Clear, area);
+
+    if picker.empty() {
+        let empty = Paragraph::new("No chapters for what's playing")
+            .block(block)
+            .style(Style::default().fg(cfg.foreground()).bg(cfg.background()));
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = picker
+        .items()
+        .iter()
+        .enumerate()
+        .map(|(i, chapter)| {
+            let title = match chapter.title.is_empty() {
+                true => format!("Chapter {}", i + 1),
+                false => chapter.title.clone(),
+            };
+            let playing = match current == Some(chapter.start) {
+                true => "  (playing)",
+                false => "",
+            };
+            ListItem::new(format!("{:>10}  {}{}", format::clock(chapter.start as i64), title, playing))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()))
+        .highlight_style(
+            Style::default()
+                .bg(cfg.highlight_background())
+                .fg(cfg.highlight_foreground())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(list, area, &mut picker.state());
+}
+
+fn remote_clients_popup(f: &mut Frame, view: &RemoteClients, cfg: &Config) {
+    let area = centered_rect(80, 70, f.size());
+    let block = Block::default()
+        .title("Remote Control Clients | X: Revoke / Allow | T: New Token | O: Off For A While | Esc: Close")
+        .borders(Borders::ALL)
+        .border_set(cfg.border(BorderType::Rounded));
+    let inner = block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(
+        block.style(Style::default().fg(cfg.foreground()).bg(cfg.background())),
+        area,
+    );
+
+    let heading = Style::default().add_modifier(Modifier::BOLD);
+    let dim = Style::default().add_modifier(Modifier::DIM);
+    let token_rows = if view.new_token.is_some() { 2 } else { 0 };
+    let clients_rows = (view.clients.items().len().max(1) as u16 + 1).min(inner.height / 2);
+    let sections = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(token_rows),
+            Constraint::Length(clients_rows),
+            Constraint::Length(1),
+            Constraint::Min(0),
+        ])
+        .split(inner);
+
+    if let Some(token) = &view.new_token {
+        let lines = vec![
+            Line::from(vec![Span::styled("New token: ", heading), Span::raw(token.clone())]),
+            Line::from(Span::styled("For this run, put it in [remote] token to keep it", dim)),
+        ];
+        f.render_widget(Paragraph::new(lines), sections[0]);
+    }
+
+    let clients = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(sections[1]);
+    f.render_widget(Paragraph::new(Span::styled("Clients", heading)), clients[0]);
+    if view.clients.empty() {
+        f.render_widget(Paragraph::new(Span::styled("Nobody's used it yet", dim)), clients[1]);
+    } else {
+        let items: Vec<ListItem> = view
+            .clients
+            .items()
+            .iter()
+            .map(|client| {
+                let connected = match client.connections {
+                    0 => format!("last seen {}", client.last_seen.format("%H:%M:%S")),
+                    1 => String::from("connected"),
+                    n => format!("{} connections", n),
+                };
+                let mut spans = vec![
+                    Span::raw(format!("{:<18}", client.ip)),
+                    Span::raw(format!("{:<18}", connected)),
+                    Span::styled(format!("{} requests, {} failed", client.requests, client.failed), dim),
+                ];
+                if client.revoked {
+                    spans.push(Span::raw("  (revoked)"));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_style(
+                Style::default()
+                    .bg(cfg.highlight_background())
+                    .fg(cfg.highlight_foreground())
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol(">> ");
+        f.render_stateful_widget(list, clients[1], &mut view.clients.state());
+    }
+
+    f.render_widget(Paragraph::new(Span::styled("Requests", heading)), sections[2]);
+    let lines: Vec<Line> = view
+        .requests
+        .iter()
+        .map(|access| {
+            Line::from(vec![
+                Span::styled(format!("{}  ", access.at.format("%H:%M:%S")), dim),
+                Span::raw(format!("{:<18}", access.client)),
+                Span::raw(format!("{:<28}", access.endpoint)),
+                Span::raw(access.status.to_string()),
+            ])
+        })
+        .collect();
+    let requests = match lines.is_empty() {
+        true => Paragraph::new(Span::styled("None yet", dim)),
+        false => Paragraph::new(lines),
+    };
+    f.render_widget(requests, sections[3]);
+}
+
+fn palette_popup(f: &mut Frame, palette: &Palette<Jump>, cfg: &Config) {
+    let area = centered_rect(70, 60, f.size());
+    let block = Block::default()
+        .title("Go To | Up / Down: Pick | Enter: Go Or Play | Esc: Close")
+        .borders(Borders::ALL)
+        .border_set(cfg.border(BorderType::Rounded));
+    let inner = block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(
+        block.style(Style::default().fg(cfg.foreground()).bg(cfg.background())),
+        area,
+    );
+
+    let sections = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(0)])
+        .split(inner);
+    let typed = match palette.query().is_empty() {
+        true => Line::from(vec![
+            Span::raw("_"),
+            Span::styled(
+                "  Podcasts, episodes, the queue, downloads and commands",
+                Style::default().add_modifier(Modifier::DIM),
+            ),
+        ]),
+        false => Line::from(format!("{}_", palette.query())),
+    };
+    f.render_widget(Paragraph::new(typed), sections[0]);
+
+    let dim = Style::default().add_modifier(Modifier::DIM);
+    let items: Vec<ListItem> = palette
+        .found()
+        .map(|entry| {
+            let mut spans = vec![
+                Span::styled(format!("{:<12}", entry.kind), dim),
+                Span::raw(entry.label.clone()),
+            ];
+            if !entry.detail.is_empty() {
+                spans.push(Span::styled(format!("  {}", entry.detail), dim));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    if items.is_empty() {
+        f.render_widget(Paragraph::new("Nothing matches"), sections[1]);
+        return;
+    }
+    let list = List::new(items)
+        .highlight_style(
+            Style::default()
+                .bg(cfg.highlight_background())
+                .fg(cfg.highlight_foreground())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(list, sections[1], &mut palette.state());
+}
+
+fn transcript_popup(f: &mut Frame, view: &TranscriptView, cfg: &Config) {
+    let area = centered_rect(70, 70, f.size());
+    let title = match view.follow {
+        true => "Transcript | Enter: Play From Here | Esc: Close",
+        false => "Transcript | Enter: Play From Here | F: Follow Along | Esc: Close",
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_set(cfg.border(BorderType::Rounded));
+    f.render_widget(Clear, area);
+
+    let empty = match (&view.error, view.loading) {
+        (Some(e), _) => Some(format!("No transcript to show: {}", e)),
+        (None, true) => Some(String::from("Loading...")),
+        (None, false) if view.lines.empty() => Some(String::from("The transcript is empty")),
+        (None, false) => None,
+    };
+    if let Some(empty) = empty {
+        let empty = Paragraph::new(empty)
+            .block(block)
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(cfg.foreground()).bg(cfg.background()));
+        f.render_widget(empty, area);
+        return;
+    }
+
+    // lines wrapped under the time, less the borders and the highlight symbol
+    let width = usize::from(area.width.saturating_sub(2 + 3 + 10)).max(20);
+    let items: Vec<ListItem> = view
+        .lines
+        .items()
+        .iter()
+        .map(|line| {
+            let text = match &line.speaker {
+                Some(speaker) => format!("{}: {}", speaker, line.text),
+                None => line.text.clone(),
+            };
+            let mut rows: Vec<String> = Vec::new();
+            for word in text.split_whitespace() {
+                match rows.last_mut() {
+                    Some(row) if row.chars().count() + 1 + word.chars().count() <= width => {
+                        row.push(' ');
+                        row.push_str(word);
+                    }
+                    _ => rows.push(word.to_string()),
+                }
+            }
+            let rows: Vec<Line> = rows
+                .into_iter()
+                .enumerate()
+                .map(|(i, row)| match i {
+                    0 => Line::from(format!("{:>8}  {}", format::clock(line.start as i64), row)),
+                    _ => Line::from(format!("{:>8}  {}", "", row)),
+                })
+                .collect();
+            ListItem::new(rows)
+        })
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()))
+        .highlight_style(
+            Style::default()
+                .bg(cfg.highlight_background())
+                .fg(cfg.highlight_foreground())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(list, area, &mut view.lines.state());
+}
+
+fn kept_picker_popup(f: &mut Frame, picker: &StatefulList<KeptItem>, downloads: &[Progress], cfg: &Config) {
+    let area = centered_rect(70, 50, f.size());
+    let title = "Kept On This Computer | Enter: Play | P: Pause / Resume | D: Delete | O: Show File | Y: Copy Path | Esc: Close";
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_set(cfg.border(BorderType::Rounded));
+    f.render_widget(Clear, area);
+
+    if picker.empty() {
+        let empty = Paragraph::new("Nothing kept yet, press d on an episode to keep it for offline")
+            .block(block)
+            .style(Style::default().fg(cfg.foreground()).bg(cfg.background()));
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let (rows, mut state) = picker.window(area.height);
+    let items: Vec<ListItem> = picker.items()[rows]
+        .iter()
+        .map(|item| match item {
+            KeptItem::Kept(kept) => ListItem::new(format!("{:>16}  {}", "Kept", kept.label())),
+            KeptItem::Downloading(url) => match downloads.iter().find(|job| job.episode.EpisodeURL == *url) {
+                Some(job) => ListItem::new(format!("{:>16}  {}", download_progress(job), job.episode.EpisodeTitle)),
+                None => ListItem::new(format!("{:>16}  {}", "Gone", url)),
+            },
+        })
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()))
+        .highlight_style(
+            Style::default()
+                .bg(cfg.highlight_background())
+                .fg(cfg.highlight_foreground())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+// "42% 1.3 MB/s", or what's stopping it
+fn download_progress(job: &Progress) -> String {
+    let done = match job.percent() {
+        Some(percent) => format!("{}%", percent),
+        None => format!("{} MB", job.received / (1024 * 1024)),
+    };
+    match &job.state {
+        DownloadState::Queued => String::from("Waiting"),
+        DownloadState::Fetching if job.speed >= 1024 * 1024 => {
+            format!("{} {:.1} MB/s", done, job.speed as f64 / (1024.0 * 1024.0))
+        }
+        DownloadState::Fetching => format!("{} {} KB/s", done, job.speed / 1024),
+        DownloadState::Paused => format!("{} Paused", done),
+        DownloadState::Processing => String::from("Processing"),
+        DownloadState::Cancelled => String::from("Cancelling"),
+        DownloadState::Done => String::from("Done"),
+        DownloadState::Failed(_) => String::from("Failed"),
+    }
+}
+
+fn snapshot_picker_popup(f: &mut Frame, picker: &StatefulList<String>, cfg: &Config) {
+    let area = centered_rect(50, 50, f.size());
+    let title = "Saved Queues | Enter: Replace | A: Append | D: Delete | Esc: Close";
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_set(cfg.border(BorderType::Rounded));
+    f.render_widget(Clear, area);
+
+    if picker.empty() {
+        let empty = Paragraph::new("No saved queues yet, press s in the queue to save one")
+            .block(block)
+            .style(Style::default().fg(cfg.foreground()).bg(cfg.background()));
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = picker
+        .items()
+        .iter()
+        .map(|name| ListItem::new(name.as_str()))
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()))
+        .highlight_style(
+            Style::default()
+                .bg(cfg.highlight_background())
+                .fg(cfg.highlight_foreground())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(list, area, &mut picker.state());
+}
+
+fn download_confirm_popup(f: &mut Frame, confirm: &DownloadConfirm, cfg: &Config) {
+    let area = centered_rect(50, 25, f.size());
+    let (title, lines) = match confirm.episodes.len() {
+        0 => (
+            "Download Unplayed | Esc: Close",
+            vec![Line::from(format!("Everything from {} has been played", confirm.podcast))],
+        ),
+        count => (
+            "Download Unplayed | Enter: Download | Esc: Cancel",
+            vec![
+                Line::from(format!("Have the server download {} episodes of {}?", count, confirm.podcast)),
+                Line::from(""),
+                Line::from(format!("{} of listening", format::clock(confirm.duration))),
+            ],
+        ),
+    };
+
+    let popup = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_set(cfg.border(BorderType::Rounded)),
+        )
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()))
+        .wrap(Wrap { trim: false });
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+// the top of each side's order, for telling which is which
+fn queue_conflict_popup(f: &mut Frame, conflict: &QueueConflict, here: &VecDeque<PinepodsEpisodes>, cfg: &Config) {
+    const SHOWN: usize = 5;
+    let area = centered_rect(60, 50, f.size());
+    let title = "Queue Changed Here And Elsewhere | Enter: Merge | H: Keep This One's | S: Take The Server's | Esc: Later";
+    let mut lines = vec![
+        Line::from("It's been reordered here and on another device since they last synced."),
+        Line::from(format!(
+            "Merging keeps the order here, with {} added elsewhere and {} removed there.",
+            conflict.merge.added.len(),
+            conflict.merge.removed.len()
+        )),
+    ];
+    let sides = [
+        ("Here", here.iter().filter(|episode| episode.EpisodeID.is_some()).collect::<Vec<_>>()),
+        ("On the server", conflict.server.iter().collect()),
+    ];
+    for (side, episodes) in sides {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(side, Style::default().add_modifier(Modifier::BOLD))));
+        for (i, episode) in episodes.iter().take(SHOWN).enumerate() {
+            lines.push(Line::from(format!("{}. {}", i + 1, episode.EpisodeTitle)));
+        }
+        if episodes.len() > SHOWN {
+            lines.push(Line::from(format!("and {} more", episodes.len() - SHOWN)));
+        }
+    }
+
+    let popup = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_set(cfg.border(BorderType::Rounded)),
+        )
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()))
+        .wrap(Wrap { trim: false });
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn paste_popup(f: &mut Frame, pasted: &Pasted, cfg: &Config) {
+    let area = centered_rect(50, 25, f.size());
+    let (title, lines) = match pasted {
+        Pasted::Subscribe(podcast) => (
+            "Pasted A Feed | Enter: Subscribe | Esc: Cancel",
+            vec![
+                Line::from(format!("Subscribe to {}?", podcast.PodcastName)),
+                Line::from(""),
+                Line::from(match podcast.Author.is_empty() {
+                    true => format!("{} episodes", podcast.EpisodeCount),
+                    false => format!("By {}, {} episodes", podcast.Author, podcast.EpisodeCount),
+                }),
+                Line::from(podcast.FeedURL.clone()),
+            ],
+        ),
+        Pasted::Queue(episode) => (
+            "Pasted An Episode | Enter: Queue | Esc: Cancel",
+            vec![
+                Line::from(format!("Queue {}?", episode.EpisodeTitle)),
+                Line::from(""),
+                Line::from(episode.PodcastName.clone().unwrap_or_else(|| episode.EpisodeURL.clone())),
+            ],
+        ),
+        Pasted::Import(import) => {
+            let mut lines = vec![Line::from(format!("Queue {} episodes?", import.episodes.len())), Line::from("")];
+            lines.extend(import.episodes.iter().take(5).map(|episode| Line::from(gen_funcs::audio_display(episode))));
+            if import.episodes.len() > 5 {
+                lines.push(Line::from(format!("and {} more", import.episodes.len() - 5)));
+            }
+            if !import.unmatched.is_empty() {
+                lines.push(Line::from(format!("{} couldn't be matched and are left out", import.unmatched.len())));
+            }
+            ("Pasted A Share Link | Enter: Queue | Esc: Cancel", lines)
+        }
+    };
+
+    let popup = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_set(cfg.border(BorderType::Rounded)),
+        )
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()))
+        .wrap(Wrap { trim: false });
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn listening_plan_popup(f: &mut Frame, plan: &[PinepodsEpisodes], available: i64, cfg: &Config) {
+    let area = centered_rect(60, 50, f.size());
+    let title = format!(
+        "Fits In {} Minutes | Enter: Queue All | S: Shuffle | R: Regenerate | Esc: Close",
+        available / 60
+    );
+    let left = |episode: &PinepodsEpisodes| {
+        (episode.EpisodeDuration - episode.ListenDuration.unwrap_or(0)).max(0)
+    };
+
+    let mut lines: Vec<Line> = plan
+        .iter()
+        .map(|episode| {
+            Line::from(format!(
+                "{}  {} - {}",
+                format::clock(left(episode)),
+                episode.PodcastName.as_deref().unwrap_or("?"),
+                episode.EpisodeTitle
+            ))
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push(Line::from("Nothing here fits, try a longer listening time"));
+    } else {
+        let total = plan.iter().map(left).sum();
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!("{} in all", format::clock(total))));
+    }
+
+    let popup = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_set(cfg.border(BorderType::Rounded)),
+        )
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()))
+        .wrap(Wrap { trim: false });
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn filter_picker_popup(f: &mut Frame, picker: &StatefulList<String>, filter: &PodcastFilter, cfg: &Config) {
+    let area = centered_rect(50, 60, f.size());
+    let (mode, mark) = match filter.mode() {
+        FilterMode::Exclude => ("Hiding Checked", "[x] "),
+        FilterMode::Include => ("Only Checked", "[+] "),
+    };
+    let state = if filter.enabled() { mode } else { "Off" };
+    let title = format!(
+        "Filter Podcasts: {} | Space: Check | M: Hide / Only | E: On / Off | Esc: Done",
+        state
+    );
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_set(cfg.border(BorderType::Rounded));
+    f.render_widget(Clear, area);
+
+    let items: Vec<ListItem> = picker
+        .items()
+        .iter()
+        .map(|name| {
+            let check = if filter.picked(name) { mark } else { "[ ] " };
+            ListItem::new(format!("{}{}", check, name))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()))
+        .highlight_style(
+            Style::default()
+                .bg(cfg.highlight_background())
+                .fg(cfg.highlight_foreground())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(list, area, &mut picker.state());
+}
+
+// top right, over the tab bar
+fn notification_overlay(f: &mut Frame, message: &str, cfg: &Config) {
+    let size = f.size();
+    let width = (message.chars().count() as u16 + 4).min(size.width);
+    let area = Rect::new(size.right() - width, size.y, width, size.height.min(3));
+    let notification = Paragraph::new(message)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(cfg.border(BorderType::Rounded)),
+        )
+        .style(
+            Style::default()
+                .fg(cfg.highlight_foreground())
+                .bg(cfg.highlight_background()),
+        );
+    f.render_widget(Clear, area);
+    f.render_widget(notification, area);
+}
+
+// small bar along the bottom edge, over whatever tab is open
+fn osd_overlay(f: &mut Frame, app: &App, osd: Osd, cfg: &Config) {
+    let size = f.size();
+    let height = size.height.min(3);
+    let width = size.width / 2;
+    let area = Rect::new(
+        size.x + (size.width - width) / 2,
+        size.bottom().saturating_sub(height + 1),
+        width,
+        height,
+    );
+
+    let (title, percent, label) = match osd {
+        Osd::Position if app.music_handle.is_live() => {
+            let played = format::clock(app.music_handle.time_played().into());
+            (app.current_song(), 100, format!("LIVE | {}", played))
+        }
+        Osd::Position => {
+            let played = app.music_handle.time_played();
+            let length = app.music_handle.song_length();
+            let percent = (u32::from(played) * 100)
+                .checked_div(u32::from(length))
+                .map_or(0, |p| p.min(100) as u16);
+            let label = format!(
+                "{} / {}",
+                format::clock(played.into()),
+                format::clock(length.into())
+            );
+            (app.current_song(), percent, label)
+        }
+        Osd::Volume => {
+            let percent = (app.music_handle.volume() * 100.0).round() as u16;
+            match (app.music_handle.is_muted(), app.music_handle.is_ducked()) {
+                (true, _) => (String::from("Volume"), 0, format!("Muted, {}% Unmuted", percent)),
+                (false, true) => {
+                    let ducked = (app.music_handle.volume().min(DUCKED_VOLUME) * 100.0).round() as u16;
+                    (String::from("Volume"), ducked, format!("Ducked To {}%, {}% After", ducked, percent))
+                }
+                (false, false) => (String::from("Volume"), percent, format!("{}%", percent)),
+            }
+        }
+        Osd::Speed => {
+            let speed = app.music_handle.speed();
+            let percent = ((speed - MIN_SPEED) / (MAX_SPEED - MIN_SPEED) * 100.0).round() as u16;
+            (String::from("Speed"), percent, format!("{:.1}x", speed))
+        }
+    };
+
+    let gauge = Gauge::default()
+        .block(
+            Block::default()
+                .title(format!("| {} |", title))
                 .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
+                .border_set(cfg.border(BorderType::Rounded))
                 .title_alignment(Alignment::Center),
         )
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()))
+        .gauge_style(Style::default().fg(cfg.highlight_background()))
+        .percent(percent)
+        .label(label);
+    f.render_widget(Clear, area);
+    f.render_widget(gauge, area);
+}
+
+// rect of the given percentage size in the middle of r
+fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(r);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
+fn subscription_changes_popup(f: &mut Frame, changes: &SubscriptionChanges, cfg: &Config) {
+    let area = centered_rect(60, 50, f.size());
+    let heading = Style::default().add_modifier(Modifier::BOLD);
+
+    let mut lines = Vec::new();
+    if !changes.added.is_empty() {
+        lines.push(Line::from(Span::styled("Added", heading)));
+        lines.extend(changes.added.iter().map(|name| Line::from(format!("  + {}", name))));
+    }
+    if !changes.removed.is_empty() {
+        lines.push(Line::from(Span::styled("Removed", heading)));
+        lines.extend(changes.removed.iter().map(|name| Line::from(format!("  - {}", name))));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from("Press Enter or Esc to dismiss"));
+
+    let popup = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title("Changes Since Last Session")
+                .borders(Borders::ALL)
+                .border_set(cfg.border(BorderType::Rounded)),
+        )
+        .style(Style::default().fg(cfg.foreground()).bg(cfg.background()));
+    f.render_widget(Clear, area);
+    f.render_widget(popup, area);
+}
+
+fn music_tab<B: Backend>(f: &mut Frame, app: &mut App, chunks: Rect, cfg: &Config) {
+    // split into left / right, the browser gets more room with two panels in it
+    let browser_width = if app.showing_podcast_rail() { 55 } else { 35 };
+    let browser_queue = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(browser_width), Constraint::Percentage(100 - browser_width)].as_ref())
+        .split(chunks);
+    // f.size()
+
+    // also starts the next episode in the queue, so it's worked out even
+    // when the bar isn't drawn
+    let percent = app.song_progress();
+
+    // queue and playing sections (sltdkh), the queue gets whatever the bar doesn't
+    let player_height = match (app.music_handle.sink_empty() && cfg.player_hide_idle(), cfg.player_compact()) {
+        (true, _) => Constraint::Length(0),
+        (false, true) => Constraint::Length(1),
+        (false, false) => Constraint::Percentage(cfg.progress_bar()),
+    };
+    let constraints = match cfg.player() {
+        PlayerPosition::Bottom => [Constraint::Min(0), player_height],
+        PlayerPosition::Top => [player_height, Constraint::Min(0)],
+    };
+    let queue_playing = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(browser_queue[1]);
+    let (queue_area, player_area) = match cfg.player() {
+        PlayerPosition::Bottom => (queue_playing[0], queue_playing[1]),
+        PlayerPosition::Top => (queue_playing[1], queue_playing[0]),
+    };
+    // listening stats, then live and upcoming streams, go above the list of
+    // podcasts. The stats only when there's room for them.
+    let podcast_mode = matches!(app.content_state, ContentState::PodcastMode { .. });
+    let live = match podcast_mode {
+        true => app.live_shows.len().min(4) as u16,
+        false => 0,
+    };
+    let stats = app
+        .stats
+        .as_ref()
+        .filter(|_| podcast_mode && browser_queue[0].height >= 20);
+    let browser_live = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(if stats.is_some() { 5 } else { 0 }),
+            Constraint::Length(if live > 0 { live + 2 } else { 0 }),
+            Constraint::Min(0),
+        ])
+        .split(browser_queue[0]);
+    let mut browser_area = browser_live[2];
+    if app.showing_podcast_rail() {
+        let rail_episodes = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(browser_area);
+        podcast_rail(f, app, rail_episodes[0], cfg);
+        browser_area = rail_episodes[1];
+    }
+    if let Some(stats) = stats {
+        listening_stats(f, stats, browser_live[0], cfg);
+    }
+    if live > 0 {
+        live_shows(f, &app.live_shows, browser_live[1], cfg);
+    }
+
+    app.regions.browser = browser_area;
+    app.regions.queue = queue_area;
+    app.regions.player = player_area;
+
+    // convert app items to text, those in view
+    let (rows, mut browser_state) = app.browser_items.window(browser_area.height);
+    let items: Vec<ListItem> = app.browser_items.items()[rows]
+        .iter()
+        .map(|browser_item| {
+            let text = match browser_item {
+                BrowserItem::Podcast(podcast) => {
+                    // Create a string representation for the podcast
+                    // For example, using the podcast title
+                    podcast.PodcastName.clone()
+                },
+                // with what matched picked out, in search results
+                BrowserItem::Episode(episode) if app.search_term().is_some() => {
+                    let term = app.search_term().unwrap_or_default();
+                    let mut spans = vec![Span::raw(cfg.date(&episode.EpisodePubDate) + " - ")];
+                    spans.extend(highlighted(&episode.EpisodeTitle, term));
+                    return ListItem::new(Line::from(spans));
+                }
+                BrowserItem::Episode(episode) => {
+                    // Create a string representation for the episode
+                    // For example, using the episode title
+                    let mut text = cfg.date(&episode.EpisodePubDate) + " - " + &
+                    episode.EpisodeTitle.clone();
+                    match episode.EpisodeID {
+                        Some(id) if app.server_downloads.waiting_for(id) => text.push_str(" (Downloading On Server)"),
+                        Some(id) if app.server_downloads.failed().contains(&id) => text.push_str(" (Server Download Failed)"),
+                        _ => {}
+                    }
+                    text
+                }
+                // with how many episodes it found, once it has run
+                BrowserItem::Search(term) => match app.search_results.get(term) {
+                    Some(found) => format!("Search: {} ({})", term, found.len()),
+                    None => format!("Search: {}", term),
+                },
+                BrowserItem::More(more) => {
+                    let more = Span::styled(format!("Load more, {} left", more), Style::default().add_modifier(Modifier::DIM));
+                    return ListItem::new(Line::from(more));
+                }
+            };
+
+            // Convert the string to Text
+            ListItem::new(Text::from(text))
+        })
+        .collect();
+
+    // Create a List from all list items and highlight the currently selected one // RENDER 1
+    let items = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(browser_title(app))
+                .title_alignment(Alignment::Left)
+                .border_set(cfg.border(BorderType::Rounded)),
+        )
+        .style(Style::default().fg(cfg.foreground()))
+        .highlight_style(
+            Style::default()
+                .bg(cfg.highlight_background())
+                .fg(cfg.highlight_foreground())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+    if app.showing_podcast_grid() {
+        podcast_grid(f, app, browser_area, cfg);
+    } else {
+        f.render_stateful_widget(items, browser_area, &mut browser_state);
+    }
+    if let Some(since) = app.loading {
+        loading_overlay(f, app, browser_area, cfg, since);
+    }
+
+    // dimmed above where it'll carry on from, those won't play
+    let passed = app.queue_items.passed();
+    let items = app.queue_items.items();
+    let entries = app.queue_items.entries();
+    let (rows, mut queue_state) =
+        stateful_list::window(entries.len(), app.queue_items.state().selected(), queue_area.height.saturating_sub(2));
+    let queue_items: Vec<ListItem> = entries[rows]
+        .iter()
+        .cloned()
+        .map(|row| match row {
+            Entry::Header(group) => {
+                let length: i64 = items.range(group.start..group.start + group.len).map(|i| i.EpisodeDuration).sum();
+                let header = format!(
+                    "{} {} ({} {}, {})",
+                    cfg.glyphs().fold(app.queue_items.is_folded(&group)),
+                    group.podcast,
+                    group.len,
+                    if group.len == 1 { "episode" } else { "episodes" },
+                    format::length(length),
+                );
+                let item = ListItem::new(Text::from(header)).style(Style::default().add_modifier(Modifier::BOLD));
+                match group.start + group.len <= passed {
+                    true => item.style(Style::default().add_modifier(Modifier::BOLD | Modifier::DIM)),
+                    false => item,
+                }
+            }
+            Entry::Item(n) => {
+                let i = &items[n];
+                let mark = if app.queue_items.is_marked(i) { "* " } else { "" };
+                // the header says which podcast it is
+                let label = match app.queue_items.is_grouped() {
+                    true => format!("  {}{}", mark, i.EpisodeTitle),
+                    false => format!("{}{}", mark, gen_funcs::audio_display(i)),
+                };
+                let item = ListItem::new(Text::from(label));
+                match n < passed {
+                    true => item.style(Style::default().add_modifier(Modifier::DIM)),
+                    false => item,
+                }
+            }
+        })
+        .collect();
+
+    let queue_title = format!(
+        "| Queue: {queue_items} Episodes |{total_time}",
+        queue_items = app.queue_items.length(),
+        total_time = app.queue_items.total_time(),
+    );
+
+    let queue_items = List::new(queue_items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(queue_title)
+                .title_alignment(Alignment::Left)
+                .border_set(cfg.border(BorderType::Rounded)),
+        )
+        .style(Style::default().fg(cfg.foreground()))
+        .highlight_style(
+            Style::default()
+                .bg(cfg.highlight_background())
+                .fg(cfg.highlight_foreground())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(queue_items, queue_area, &mut queue_state);
+
+    if player_area.height == 0 {
+        return;
+    }
+
+    // no length to show progress through, only how long it's been on
+    let label = match app.music_handle.is_live() && !app.music_handle.sink_empty() {
+        true => format!(
+            "LIVE{} | {}",
+            if app.music_handle.is_recording() { " | REC" } else { "" },
+            format::clock(app.music_handle.time_played().into())
+        ),
+        false => match app.current_chapter() {
+            Some(chapter) if !chapter.title.is_empty() => format!("{} | {}%", chapter.title, percent),
+            _ => format!("{}%", percent),
+        },
+    };
+    let label = match app.book_left() {
+        Some(left) => format!("{} | {} Left In Book", label, format::clock(left)),
+        None => label,
+    };
+    let label = match app.music_handle.speed() {
+        speed if speed != 1.0 && !app.music_handle.is_live() => format!("{} | {:.1}x", label, speed),
+        _ => label,
+    };
+    let label = match (app.music_handle.is_muted(), app.music_handle.is_ducked()) {
+        (true, _) => format!("{} | MUTED", label),
+        (false, true) => format!("{} | DUCKED", label),
+        (false, false) => label,
+    };
+
+    // Note Gauge is using background color for progress
+    let playing = Gauge::default()
         .style(Style::default().fg(cfg.foreground()))
         .gauge_style(Style::default().fg(cfg.highlight_background()))
-        .percent(app.song_progress());
-    f.render_widget(playing, queue_playing[1]);
+        .percent(percent);
+    // a single line has no room for a border, the title goes in the label
+    let playing = match cfg.player_compact() {
+        true => playing.label(format!("{} | {}", app.current_song(), label)),
+        false => {
+            let mut block = Block::default()
+                .title(format!("| {current_song} |", current_song = app.current_song()))
+                .borders(Borders::ALL)
+                .border_set(cfg.border(BorderType::Rounded))
+                .title_alignment(Alignment::Center);
+            let up_next = match (app.continuous, app.queue_items.up_next()) {
+                (true, Some(episode)) => Some(format!("| Up next: {} |", episode.EpisodeTitle)),
+                (false, Some(_)) => Some(String::from("| Stops after this one |")),
+                (_, None) => None,
+            };
+            if let Some(up_next) = up_next.filter(|_| !app.music_handle.sink_empty()) {
+                block = block.title(
+                    Title::from(Span::styled(up_next, Style::default().add_modifier(Modifier::DIM)))
+                        .position(Position::Bottom)
+                        .alignment(Alignment::Right),
+                );
+            }
+            playing.label(label).block(block)
+        }
+    };
+    f.render_widget(playing, player_area);
+    chapter_ticks(f, app, player_area);
+}
+
+// a mark on the bar where each chapter after the first starts
+fn chapter_ticks(f: &mut Frame, app: &App, area: Rect) {
+    let length = f64::from(app.music_handle.song_length());
+    let chapters = app.chapters();
+    if length <= 0.0 || chapters.is_empty() {
+        return;
+    }
+    let inner = match area.height {
+        0..=2 => area,
+        _ => area.inner(&Margin { horizontal: 1, vertical: 1 }),
+    };
+    if inner.width == 0 || inner.height == 0 {
+        return;
+    }
+    let row = inner.bottom() - 1;
+    for chapter in chapters.iter().filter(|chapter| chapter.start > 0.0 && chapter.start < length) {
+        let x = inner.x + (chapter.start / length * f64::from(inner.width)) as u16;
+        f.buffer_mut().get_mut(x.min(inner.right() - 1), row).set_char('|');
+    }
+}
+
+fn listening_stats(f: &mut Frame, stats: &Stats, area: Rect, cfg: &Config) {
+    let summary = format!(
+        "{} Day Streak | {} Today | {} This Week, {} On Average",
+        stats.streak,
+        format::minutes(stats.today),
+        format::minutes(stats.week),
+        format::minutes(stats.weekly_average)
+    );
+    let mut block = Block::default()
+        .title(summary)
+        .borders(Borders::ALL)
+        .border_set(cfg.border(BorderType::Rounded));
+    if let Some(first) = stats.year_ago.first() {
+        let more = match stats.year_ago.len() {
+            1 => String::new(),
+            n => format!(" and {} more", n - 1),
+        };
+        block = block.title(
+            Title::from(format!("A year ago: {}{}", first, more)).position(Position::Bottom),
+        );
+    }
+
+    // as many of the most recent days as fit
+    let width = usize::from(area.width.saturating_sub(2));
+    let days = &stats.daily[stats.daily.len().saturating_sub(width)..];
+    let sparkline = Sparkline::default()
+        .block(block)
+        .data(days)
+        .bar_set(cfg.glyphs().bars())
+        .style(Style::default().fg(cfg.highlight_background()));
+    f.render_widget(sparkline, area);
+}
+
+fn live_shows(f: &mut Frame, shows: &[LiveShow], area: Rect, cfg: &Config) {
+    let live = Style::default()
+        .fg(cfg.highlight_background())
+        .add_modifier(Modifier::BOLD);
+    let lines: Vec<Line> = shows
+        .iter()
+        .map(|show| match show.item.status {
+            LiveStatus::Live => Line::from(vec![
+                Span::styled("LIVE ", live),
+                Span::raw(format!("{} - {}", show.podcast, show.item.title)),
+            ]),
+            _ => Line::from(format!(
+                "{} - {}, {}",
+                show.podcast,
+                show.item.title,
+                show.item.start.replacen('T', " ", 1)
+            )),
+        })
+        .collect();
+
+    let panel = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title("Live | L: Listen")
+                .borders(Borders::ALL)
+                .border_set(cfg.border(BorderType::Rounded)),
+        )
+        .style(Style::default().fg(cfg.foreground()));
+    f.render_widget(panel, area);
+}
+
+// the podcast list beside the open podcast's episodes, with how many of
+// each podcast's episodes haven't been started
+fn podcast_rail(f: &mut Frame, app: &App, area: Rect, cfg: &Config) {
+    let (podcasts, open) = app.rail();
+    let items: Vec<ListItem> = podcasts
+        .iter()
+        .map(|podcast| match app.unplayed(&podcast.PodcastName) {
+            Some(count) if count > 0 => ListItem::new(format!("{} ({})", podcast.PodcastName, count)),
+            _ => ListItem::new(podcast.PodcastName.clone()),
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Podcasts | Shift + J / K")
+                .title_alignment(Alignment::Left)
+                .border_set(cfg.border(BorderType::Rounded)),
+        )
+        .style(Style::default().fg(cfg.foreground()))
+        .highlight_style(
+            Style::default()
+                .fg(cfg.highlight_background())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+    let mut state = ListState::default();
+    state.select(open);
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+const TILE_WIDTH: u16 = 18;
+const TILE_HEIGHT: u16 = 7;
+
+// subscriptions as tiles, a colored placeholder with initials standing in for the artwork
+fn podcast_grid(f: &mut Frame, app: &mut App, area: Rect, cfg: &Config) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(browser_title(app))
+        .title_alignment(Alignment::Left)
+        .border_set(cfg.border(BorderType::Rounded))
+        .style(Style::default().fg(cfg.foreground()));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let columns = usize::from(inner.width / TILE_WIDTH).max(1);
+    let rows = usize::from(inner.height / TILE_HEIGHT).max(1);
+    app.grid_columns = columns;
+
+    // scroll just far enough to keep the selected row in view
+    let selected = app.browser_items.selected();
+    let first_row = selected.map_or(0, |i| (i / columns).saturating_sub(rows - 1));
+    let tile_width = inner.width / columns as u16;
+
+    let visible = app
+        .browser_items
+        .items()
+        .iter()
+        .enumerate()
+        .skip(first_row * columns)
+        .take(rows * columns);
+    for (slot, (i, item)) in visible.enumerate() {
+        let name = match item {
+            BrowserItem::Podcast(podcast) => podcast.PodcastName.clone(),
+            BrowserItem::Search(term) => format!("Search: {}", term),
+            BrowserItem::Episode(_) | BrowserItem::More(_) => continue,
+        };
+        let tile = Rect::new(
+            inner.x + (slot % columns) as u16 * tile_width,
+            inner.y + (slot / columns) as u16 * TILE_HEIGHT,
+            tile_width,
+            TILE_HEIGHT.min(inner.height),
+        );
+
+        let border = match selected == Some(i) {
+            true => Style::default()
+                .fg(cfg.highlight_background())
+                .add_modifier(Modifier::BOLD),
+            false => Style::default().fg(cfg.foreground()),
+        };
+        let frame = Block::default()
+            .borders(Borders::ALL)
+            .border_set(cfg.border(BorderType::Rounded))
+            .border_style(border)
+            .title(name.as_str());
+        let artwork_area = frame.inner(tile);
+        f.render_widget(frame, tile);
+
+        let padding = usize::from(artwork_area.height.saturating_sub(1) / 2);
+        let mut lines = vec![Line::from(""); padding];
+        lines.push(Line::from(Span::styled(
+            initials(&name),
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+        let artwork = Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .style(
+                Style::default()
+                    .fg(cfg.color(Color::Black))
+                    .bg(cfg.color(placeholder_color(&name))),
+            );
+        f.render_widget(artwork, artwork_area);
+    }
+}
+
+// so a short list doesn't look like missing subscriptions
+// `text` with the words `query` matched underlined
+fn highlighted<'t>(text: &'t str, query: &str) -> Vec<Span<'t>> {
+    let mut spans = Vec::new();
+    let mut last = 0;
+    for range in search_index::highlights(text, query) {
+        spans.push(Span::raw(&text[last..range.start]));
+        spans.push(Span::styled(
+            &text[range.clone()],
+            Style::default().add_modifier(Modifier::UNDERLINED | Modifier::BOLD),
+        ));
+        last = range.end;
+    }
+    spans.push(Span::raw(&text[last..]));
+    spans
+}
+
+// over the browser while its page loads, with nothing cached to show meanwhile
+fn loading_overlay(f: &mut Frame, app: &App, area: Rect, cfg: &Config, since: Instant) {
+    let what = match app.content_state {
+        ContentState::EpisodeMode { .. } | ContentState::SearchMode { .. } => "episodes",
+        _ => "podcasts",
+    };
+    let text = format!("{} Loading {}...", cfg.glyphs().spinner(since.elapsed()), what);
+    let width = (text.chars().count() as u16 + 4).min(area.width);
+    let height = 3.min(area.height);
+    let area = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    let loading = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(cfg.foreground()))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(cfg.border(BorderType::Rounded)),
+        );
+    f.render_widget(Clear, area);
+    f.render_widget(loading, area);
+}
+
+// with how old it is while it's from the cache
+fn browser_title(app: &App) -> String {
+    let stale = app.stale.map(|fetched| {
+        let minutes = (chrono::Utc::now().timestamp() - fetched).max(0) / 60;
+        match minutes {
+            0 => String::from("saved just now"),
+            _ => format!("saved {} ago", format::minutes(minutes as u64)),
+        }
+    });
+    let filtered = app.podcast_filter.active().then(|| String::from("Filtered"));
+    let notes: Vec<String> = filtered.into_iter().chain(stale).collect();
+    match notes.is_empty() {
+        true => String::from("Browser"),
+        false => format!("Browser ({})", notes.join(", ")),
+    }
+}
+
+// first letter of the first two words
+fn initials(name: &str) -> String {
+    name.split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .take(2)
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+// same name, same color, every time
+fn placeholder_color(name: &str) -> Color {
+    const PALETTE: [Color; 10] = [
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::LightRed,
+        Color::LightGreen,
+        Color::LightYellow,
+        Color::LightMagenta,
+    ];
+    let hash = name.bytes().fold(0usize, |hash, b| hash.wrapping_mul(31).wrapping_add(b.into()));
+    PALETTE[hash % PALETTE.len()]
 }
 
 fn instructions_tab<B: Backend>(f: &mut Frame, app: &mut App, chunks: Rect, cfg: &Config) {
@@ -493,7 +4002,20 @@ fn instructions_tab<B: Backend>(f: &mut Frame, app: &mut App, chunks: Rect, cfg:
 
     let t = Table::new(rows)
         .header(header)
-        .block(Block::default().borders(Borders::ALL).title("Controls"))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(cfg.border(BorderType::Plain))
+                .title(format!(
+                    "Controls | Log: {} | Telemetry: {} | Remote: {}",
+                    logging::filters(),
+                    if telemetry::enabled() { "On" } else { "Off" },
+                    match cfg.remote_enabled() {
+                        true => cfg.remote_addr().to_string(),
+                        false => String::from("Off"),
+                    }
+                )),
+        )
         .style(Style::default().fg(cfg.foreground()).bg(cfg.background()))
         .highlight_style(
             Style::default()
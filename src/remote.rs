@@ -0,0 +1,194 @@
+pub mod access;
+pub mod cast;
+pub mod discovery;
+pub mod ipc;
+pub mod openapi;
+pub mod push;
+pub mod server;
+pub mod webhooks;
+pub mod websocket;
+
+use std::io;
+use std::net::{SocketAddr, TcpListener};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use log::error;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+use pinepods_firewood::requests::PinepodsEpisodes;
+
+/// What the remote control servers can ask the player to do. Over the local
+/// socket these are sent as JSON, e.g. {"command": "play_pause"},
+/// {"command": "queue", "episode": {...}} or {"command": "seek", "position": 90}.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Command {
+    Status,
+    /// from `position` seconds in if given, the start otherwise
+    Play {
+        episode: PinepodsEpisodes,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        position: Option<u16>,
+    },
+    Queue { episode: PinepodsEpisodes },
+    PlayPause,
+    Pause,
+    Resume,
+    Skip,
+    /// `position` seconds into what's playing
+    Seek { position: u16 },
+    /// between 0.0 and 1.0
+    Volume { volume: f32 },
+    Speed { speed: f32 },
+    /// what's queued, from the top down
+    QueueList,
+    /// move the queued episode at `from` to `to`, counting from 0 at the top
+    QueueMove { from: usize, to: usize },
+    /// episodes matching `query` in what's been fetched so far
+    Search { query: String },
+}
+
+pub struct Request {
+    pub command: Command,
+    pub reply: oneshot::Sender<Reply>,
+}
+
+/// The answer to a `Command`, the status after it ran for most of them.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum Reply {
+    Status(Status),
+    Episodes(Vec<PinepodsEpisodes>),
+    Error { error: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Status {
+    pub episode: Option<String>,
+    pub episode_id: Option<i64>,
+    pub paused: bool,
+    pub position: u16,
+    pub duration: u16,
+    pub queue_length: usize,
+    /// what it's turned up to, muted or ducked it plays quieter than that
+    pub volume: f32,
+    pub muted: bool,
+    pub ducked: bool,
+    pub speed: f32,
+    pub live: bool,
+}
+
+/// Hand `command` to whichever loop owns the player and wait for the answer.
+/// None once the player is shutting down.
+pub async fn dispatch(requests: &mpsc::Sender<Request>, command: Command) -> Option<Reply> {
+    let (reply, status) = oneshot::channel();
+    requests.send(Request { command, reply }).await.ok()?;
+    status.await.ok()
+}
+
+/// Commands from every remote control server, however many are running.
+pub struct Remote {
+    pub requests: mpsc::Receiver<Request>,
+    sender: mpsc::Sender<Request>,
+    pub addr: Option<SocketAddr>,
+    pub socket: Option<PathBuf>,
+    /// every request over HTTP, whichever address it was served on
+    pub access: access::AccessLog,
+    /// turned off for a while, to be served again then
+    pub paused_until: Option<Instant>,
+    http: Option<JoinHandle<()>>,
+    push: push::Push,
+    // advertisement goes away when this is dropped
+    _discovery: Option<discovery::Advertisement>,
+}
+
+impl Default for Remote {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Remote {
+    /// Nothing is listening until one of the serve methods is called.
+    pub fn new() -> Self {
+        let (sender, requests) = mpsc::channel(16);
+        Self {
+            requests,
+            sender,
+            addr: None,
+            socket: None,
+            access: access::AccessLog::default(),
+            paused_until: None,
+            http: None,
+            push: push::Push::new(),
+            _discovery: None,
+        }
+    }
+
+    /// The player's status as of now, for the clients waiting on changes to it.
+    pub fn publish(&mut self, status: Status) {
+        self.push.update(status);
+    }
+
+    pub fn serve_http(&mut self, addr: SocketAddr, token: Option<String>) -> io::Result<()> {
+        self.serve_http_on(TcpListener::bind(addr)?, token)
+    }
+
+    /// Serve HTTP on a socket that's already bound, e.g. one passed in by systemd.
+    pub fn serve_http_on(&mut self, listener: TcpListener, token: Option<String>) -> io::Result<()> {
+        let addr = listener.local_addr()?;
+        let wants_token = token.is_some();
+        let http = server::start(listener, token, self.sender.clone(), self.push.sender(), self.access.clone())
+            .map_err(io::Error::other)?;
+
+        self.addr = Some(addr);
+        self.http = Some(http);
+        self.paused_until = None;
+        self._discovery = discovery::advertise(addr.port(), wants_token);
+        Ok(())
+    }
+
+    /// Stop serving HTTP, and advertising it, once the port is free again.
+    pub async fn stop_http(&mut self) {
+        if let Some(http) = self.http.take() {
+            // dropping the server closes its listener and connections
+            http.abort();
+            let _ = http.await;
+        }
+        self.addr = None;
+        self.paused_until = None;
+        self._discovery = None;
+    }
+
+    /// Stop serving HTTP for `duration`, after which it's up to whoever owns
+    /// this to serve it again, see `paused_until`.
+    pub async fn pause_http(&mut self, duration: Duration) {
+        self.stop_http().await;
+        self.paused_until = Some(Instant::now() + duration);
+    }
+
+    /// Serve HTTP on `addr` instead of wherever it is now. If `addr` can't be
+    /// bound the old address is served again and the error returned.
+    pub async fn rebind_http(&mut self, addr: SocketAddr, token: Option<String>) -> io::Result<()> {
+        let previous = self.addr;
+        self.stop_http().await;
+        let Err(e) = self.serve_http(addr, token.clone()) else {
+            return Ok(());
+        };
+        if let Some(previous) = previous {
+            if let Err(e) = self.serve_http(previous, token) {
+                error!("Unable to serve remote control on {} again: {}", previous, e);
+            }
+        }
+        Err(e)
+    }
+
+    /// Listen on the local socket (a named pipe on Windows).
+    pub fn serve_socket(&mut self) -> io::Result<()> {
+        self.socket = Some(ipc::start(self.sender.clone())?);
+        Ok(())
+    }
+}
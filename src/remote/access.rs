@@ -0,0 +1,177 @@
+// Who's been using the HTTP remote control, since it's open to the LAN: the
+// last requests made, where from, what for and how they were answered, and
+// the clients they came from, with how many connections each has open now.
+//
+// A client can have its access revoked, which turns its requests away with a
+// 403 from then on, token or not, until Firewood is restarted. The log is only
+// kept in memory, for the Controls tab to show (see main.rs).
+
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::BuildHasher;
+use std::fs;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Local};
+use log::error;
+use pinepods_firewood::requests;
+
+// the token made for a remote control that wasn't given one, in the app directory
+const TOKEN_FILE: &str = "remote_token";
+
+// requests kept, the oldest dropped past this
+const KEPT: usize = 200;
+
+#[derive(Debug, Clone)]
+pub struct Access {
+    pub at: DateTime<Local>,
+    pub client: IpAddr,
+    /// method and path, without the query, which can hold the token
+    pub endpoint: String,
+    pub status: u16,
+}
+
+#[derive(Debug, Clone)]
+pub struct Client {
+    pub ip: IpAddr,
+    pub last_seen: DateTime<Local>,
+    pub requests: usize,
+    /// answered with an error, a wrong token among them
+    pub failed: usize,
+    /// connections open now, WebSockets for events included
+    pub connections: usize,
+    pub revoked: bool,
+}
+
+#[derive(Default)]
+struct Log {
+    requests: VecDeque<Access>,
+    // every client seen this run, whether or not its requests are still kept
+    clients: HashMap<IpAddr, Client>,
+    revoked: HashSet<IpAddr>,
+}
+
+/// Shared between the server's connections and whatever shows it.
+#[derive(Clone, Default)]
+pub struct AccessLog {
+    log: Arc<Mutex<Log>>,
+}
+
+/// Counts as an open connection of its client's until dropped.
+pub struct Connection {
+    log: AccessLog,
+    ip: IpAddr,
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let mut log = self.log.log.lock().unwrap();
+        if let Some(client) = log.clients.get_mut(&self.ip) {
+            client.connections = client.connections.saturating_sub(1);
+        }
+    }
+}
+
+impl AccessLog {
+    fn client(log: &mut Log, ip: IpAddr) -> &mut Client {
+        let revoked = log.revoked.contains(&ip);
+        log.clients.entry(ip).or_insert_with(|| Client {
+            ip,
+            last_seen: Local::now(),
+            requests: 0,
+            failed: 0,
+            connections: 0,
+            revoked,
+        })
+    }
+
+    pub fn connected(&self, ip: IpAddr) -> Connection {
+        let mut log = self.log.lock().unwrap();
+        let client = Self::client(&mut log, ip);
+        client.connections += 1;
+        client.last_seen = Local::now();
+        Connection { log: self.clone(), ip }
+    }
+
+    pub fn record(&self, ip: IpAddr, endpoint: String, status: u16) {
+        let mut log = self.log.lock().unwrap();
+        let client = Self::client(&mut log, ip);
+        client.requests += 1;
+        if status >= 400 {
+            client.failed += 1;
+        }
+        client.last_seen = Local::now();
+        if log.requests.len() == KEPT {
+            log.requests.pop_front();
+        }
+        log.requests.push_back(Access {
+            at: Local::now(),
+            client: ip,
+            endpoint,
+            status,
+        });
+    }
+
+    pub fn is_revoked(&self, ip: IpAddr) -> bool {
+        self.log.lock().unwrap().revoked.contains(&ip)
+    }
+
+    /// Turn away `ip` from now on, or let it back in if it already was.
+    pub fn toggle_revoked(&self, ip: IpAddr) -> bool {
+        let mut log = self.log.lock().unwrap();
+        let revoked = !log.revoked.remove(&ip);
+        if revoked {
+            log.revoked.insert(ip);
+        }
+        Self::client(&mut log, ip).revoked = revoked;
+        revoked
+    }
+
+    /// Newest first.
+    pub fn requests(&self) -> Vec<Access> {
+        self.log.lock().unwrap().requests.iter().rev().cloned().collect()
+    }
+
+    /// Those connected now first, then the last seen.
+    pub fn clients(&self) -> Vec<Client> {
+        let mut clients: Vec<Client> = self.log.lock().unwrap().clients.values().cloned().collect();
+        clients.sort_by_key(|client| (client.connections == 0, std::cmp::Reverse(client.last_seen)));
+        clients
+    }
+}
+
+/// A fresh token to replace the one clients have, 32 hex digits.
+pub fn new_token() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_nanos())
+        .unwrap_or_default();
+    // RandomState's keys come from the system's random numbers
+    (0..2)
+        .map(|i| format!("{:016x}", RandomState::new().hash_one((now, std::process::id(), i))))
+        .collect()
+}
+
+/// The token made earlier for a remote control without remote.token, or a
+/// fresh one kept for next time, so clients don't need a new one each start.
+pub fn kept_token() -> String {
+    let path = requests::get_app_path().map(|p| p.join(TOKEN_FILE));
+    let saved = path
+        .as_ref()
+        .and_then(|p| fs::read_to_string(p).ok())
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty());
+    if let Some(token) = saved {
+        return token;
+    }
+
+    let token = new_token();
+    if let Some(path) = &path {
+        if let Err(e) = fs::write(path, &token) {
+            error!("Unable to save the remote control token to {:?}: {}", path, e);
+        }
+    }
+    token
+}
@@ -0,0 +1,41 @@
+// Casting what's playing here to another Firewood found on the LAN (see
+// discovery.rs). It's asked over its HTTP remote control, like any other
+// client, to play the episode from where it's got to here.
+
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use reqwest::StatusCode;
+
+use pinepods_firewood::requests::PinepodsEpisodes;
+
+use super::discovery::Peer;
+
+/// Play `episode` on `peer` from `position` seconds in. The token is the one
+/// set for the remote control here, the same one usually goes for every
+/// Firewood someone has.
+pub async fn cast(peer: &Peer, token: Option<&str>, episode: &PinepodsEpisodes, position: u16) -> Result<()> {
+    if !peer.serves("play") {
+        bail!("{} can't be told what to play", peer.name);
+    }
+    if peer.token && token.is_none() {
+        bail!("{} wants a token and there isn't one set here", peer.name);
+    }
+    // don't keep the cast waiting the full tcp timeout on one that's gone
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()?;
+    let mut request = client
+        .post(format!("http://{}/api/play?position={}", peer.addr, position))
+        .json(episode);
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+
+    let response = request.send().await?;
+    match response.status() {
+        StatusCode::UNAUTHORIZED => bail!("{} doesn't take the token set here", peer.name),
+        status if !status.is_success() => bail!("{} answered {}", peer.name, status),
+        _ => Ok(()),
+    }
+}
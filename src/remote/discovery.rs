@@ -0,0 +1,219 @@
+// mDNS advertisement so the remote control server can be found on the LAN
+// without knowing its address, and browsing for the other Firewoods
+// advertising theirs. Just dropping the daemon leaves its thread advertising,
+// so an Advertisement withdraws the service when dropped, for when the server
+// moves to another port or stops.
+//
+// Each one is advertised as user@host:port, so the tui and the daemon, or two
+// people on one computer, don't take each other's name. Its TXT record says
+// what it is:
+//
+//   version    of Firewood
+//   user,host  who it's playing for and where
+//   auth       token when it wants one, none otherwise
+//   api        where the endpoints below are, /api
+//   endpoints  those it serves, comma separated (see server.rs)
+
+use std::net::SocketAddr;
+use std::sync::Mutex;
+
+use log::error;
+use mdns_sd::{Receiver, ServiceDaemon, ServiceEvent, ServiceInfo};
+
+use super::server::ENDPOINTS;
+
+pub const SERVICE_TYPE: &str = "_pinepods-remote._tcp.local.";
+
+// what this process advertises, so browsing leaves it out
+static ADVERTISED: Mutex<Option<String>> = Mutex::new(None);
+
+pub struct Advertisement {
+    daemon: ServiceDaemon,
+    fullname: String,
+}
+
+impl Drop for Advertisement {
+    fn drop(&mut self) {
+        if let Err(e) = self.daemon.unregister(&self.fullname) {
+            error!("Unable to withdraw the mDNS advertisement: {}", e);
+        }
+        let _ = self.daemon.shutdown();
+        let mut advertised = ADVERTISED.lock().unwrap();
+        if advertised.as_deref() == Some(&self.fullname) {
+            *advertised = None;
+        }
+    }
+}
+
+/// Advertise the remote control server on `port` for as long as the returned
+/// advertisement lives. Failing to advertise isn't fatal, the server still
+/// works by address.
+pub fn advertise(port: u16, token: bool) -> Option<Advertisement> {
+    let hostname = gethostname::gethostname().to_string_lossy().into_owned();
+    let user = user();
+    // a dot would be taken as the end of the name
+    let instance = format!("{}@{}:{}", user, hostname, port).replace('.', "-");
+    let properties = [
+        ("version", String::from(env!("CARGO_PKG_VERSION"))),
+        ("user", user),
+        ("host", hostname.clone()),
+        ("auth", String::from(if token { "token" } else { "none" })),
+        ("api", String::from("/api")),
+        ("endpoints", ENDPOINTS.join(",")),
+    ];
+
+    let service = ServiceInfo::new(
+        SERVICE_TYPE,
+        &instance,
+        &format!("{}.local.", hostname),
+        "",
+        port,
+        &properties[..],
+    )
+    .map(ServiceInfo::enable_addr_auto);
+
+    let result = ServiceDaemon::new().and_then(|daemon| {
+        let service = service?;
+        let fullname = service.get_fullname().to_string();
+        daemon.register(service)?;
+        Ok(Advertisement { daemon, fullname })
+    });
+    match result {
+        Ok(advertisement) => {
+            *ADVERTISED.lock().unwrap() = Some(advertisement.fullname.clone());
+            Some(advertisement)
+        }
+        Err(e) => {
+            error!("Unable to advertise remote control over mDNS: {}", e);
+            None
+        }
+    }
+}
+
+fn user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| String::from("firewood"))
+}
+
+/// Another Firewood on the LAN, as it advertises itself.
+#[derive(Debug, Clone)]
+pub struct Peer {
+    pub fullname: String,
+    /// user@host, or just the host for Firewoods from before the TXT record
+    pub name: String,
+    pub addr: SocketAddr,
+    /// None from before the TXT record
+    pub version: Option<String>,
+    pub token: bool,
+    pub endpoints: Vec<String>,
+}
+
+impl Peer {
+    fn from_service(service: &ServiceInfo) -> Option<Self> {
+        let addresses = service.get_addresses();
+        // IPv6 link-local ones would need their interface along with them
+        let ip = addresses
+            .iter()
+            .find(|ip| ip.is_ipv4())
+            .or_else(|| addresses.iter().next())?;
+        let fullname = service.get_fullname().to_string();
+        let property = |key| service.get_property_val_str(key).filter(|value| !value.is_empty());
+        let name = match (property("user"), property("host")) {
+            (Some(user), Some(host)) => format!("{}@{}", user, host),
+            _ => instance(&fullname).to_string(),
+        };
+        Some(Self {
+            name,
+            addr: SocketAddr::new(*ip, service.get_port()),
+            version: property("version").map(String::from),
+            token: property("auth") == Some("token"),
+            endpoints: property("endpoints")
+                .map(|endpoints| endpoints.split(',').map(String::from).collect())
+                .unwrap_or_default(),
+            fullname,
+        })
+    }
+
+    /// Whether it says it serves `endpoint`. Those advertising nothing are
+    /// taken to serve what every Firewood has, status, play and the like.
+    pub fn serves(&self, endpoint: &str) -> bool {
+        self.endpoints.is_empty() || self.endpoints.iter().any(|served| served == endpoint)
+    }
+}
+
+// the user@host:port of user@host:port._pinepods-remote._tcp.local.
+fn instance(fullname: &str) -> &str {
+    fullname.strip_suffix(SERVICE_TYPE).map_or(fullname, |name| name.trim_end_matches('.'))
+}
+
+/// The other Firewoods on the LAN, as they're found and go away. Browsing
+/// stops when this is dropped.
+pub struct Browser {
+    daemon: ServiceDaemon,
+    events: Receiver<ServiceEvent>,
+    peers: Vec<Peer>,
+}
+
+impl Drop for Browser {
+    fn drop(&mut self) {
+        let _ = self.daemon.stop_browse(SERVICE_TYPE);
+        let _ = self.daemon.shutdown();
+    }
+}
+
+/// Start looking for other Firewoods. None if mDNS can't be used here.
+pub fn browse() -> Option<Browser> {
+    let result = ServiceDaemon::new().and_then(|daemon| {
+        let events = daemon.browse(SERVICE_TYPE)?;
+        Ok(Browser {
+            daemon,
+            events,
+            peers: Vec::new(),
+        })
+    });
+    match result {
+        Ok(browser) => Some(browser),
+        Err(e) => {
+            error!("Unable to look for other Firewoods over mDNS: {}", e);
+            None
+        }
+    }
+}
+
+impl Browser {
+    /// Take in whatever's been heard since the last look, true if the peers
+    /// changed.
+    pub fn poll(&mut self) -> bool {
+        let advertised = ADVERTISED.lock().unwrap().clone();
+        let mut changed = false;
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                ServiceEvent::ServiceResolved(service) => {
+                    if advertised.as_deref() == Some(service.get_fullname()) {
+                        continue;
+                    }
+                    let Some(peer) = Peer::from_service(&service) else {
+                        continue;
+                    };
+                    match self.peers.iter_mut().find(|known| known.fullname == peer.fullname) {
+                        Some(known) => *known = peer,
+                        None => self.peers.push(peer),
+                    }
+                    changed = true;
+                }
+                ServiceEvent::ServiceRemoved(_, fullname) => {
+                    let before = self.peers.len();
+                    self.peers.retain(|peer| peer.fullname != fullname);
+                    changed |= self.peers.len() != before;
+                }
+                _ => {}
+            }
+        }
+        changed
+    }
+
+    pub fn peers(&self) -> &[Peer] {
+        &self.peers
+    }
+}
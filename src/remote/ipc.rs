@@ -0,0 +1,154 @@
+// Local remote control, for scripts on the same machine: a Unix socket at
+// <app_path>/firewood.sock, or the \\.\pipe\pinepods-firewood named pipe on
+// Windows. No network port, no token, just the user's own permissions.
+//
+// One JSON command per line in (see `Command`), one JSON line out: the status
+// after the command ran, the episodes asked for (see `Reply`), or
+// {"error": "..."}.
+//
+//   echo '{"command": "play_pause"}' | nc -U ~/.config/pinepods/firewood.sock
+//
+// The subcommands (see subcommands.rs) are the other end of it.
+
+use std::io;
+use std::path::PathBuf;
+
+use log::error;
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+
+use super::{Command, Request};
+
+#[cfg(windows)]
+const PIPE: &str = r"\\.\pipe\pinepods-firewood";
+
+#[cfg(unix)]
+pub fn start(requests: mpsc::Sender<Request>) -> io::Result<PathBuf> {
+    use std::os::unix::fs::PermissionsExt;
+    use tokio::net::UnixListener;
+
+    let dir = pinepods_firewood::requests::base_app_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("firewood.sock");
+
+    // a socket left behind by a crash is in the way, one that answers isn't ours
+    if path.exists() {
+        if std::os::unix::net::UnixStream::connect(&path).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("{} is in use by another instance", path.display()),
+            ));
+        }
+        std::fs::remove_file(&path)?;
+    }
+
+    let listener = UnixListener::bind(&path)?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    tokio::spawn(serve_connection(stream, requests.clone()));
+                }
+                Err(e) => {
+                    error!("Local remote control stopped: {}", e);
+                    break;
+                }
+            }
+        }
+    });
+    Ok(path)
+}
+
+#[cfg(windows)]
+pub fn start(requests: mpsc::Sender<Request>) -> io::Result<PathBuf> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    // fails right away if another instance already has the pipe
+    let mut server = ServerOptions::new().first_pipe_instance(true).create(PIPE)?;
+
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = server.connect().await {
+                error!("Local remote control stopped: {}", e);
+                break;
+            }
+            // the next client needs a fresh instance to connect to
+            let connected = server;
+            server = match ServerOptions::new().create(PIPE) {
+                Ok(next) => next,
+                Err(e) => {
+                    error!("Local remote control stopped: {}", e);
+                    break;
+                }
+            };
+            tokio::spawn(serve_connection(connected, requests.clone()));
+        }
+    });
+    Ok(PathBuf::from(PIPE))
+}
+
+/// Send `command` to the instance already listening and return its answer,
+/// None if there's no instance to send it to.
+pub async fn send(command: &Command) -> io::Result<Option<Value>> {
+    let stream = match connect().await {
+        Ok(stream) => stream,
+        Err(e) if matches!(e.kind(), io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused) => {
+            return Ok(None)
+        }
+        Err(e) => return Err(e),
+    };
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut line = serde_json::to_string(command)?;
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await?;
+
+    let answer = BufReader::new(reader)
+        .lines()
+        .next_line()
+        .await?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "the player hung up without answering"))?;
+    Ok(Some(serde_json::from_str(&answer)?))
+}
+
+#[cfg(unix)]
+async fn connect() -> io::Result<tokio::net::UnixStream> {
+    let dir = pinepods_firewood::requests::base_app_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+    tokio::net::UnixStream::connect(dir.join("firewood.sock")).await
+}
+
+#[cfg(windows)]
+async fn connect() -> io::Result<tokio::net::windows::named_pipe::NamedPipeClient> {
+    tokio::net::windows::named_pipe::ClientOptions::new().open(PIPE)
+}
+
+async fn serve_connection<S>(stream: S, requests: mpsc::Sender<Request>)
+where
+    S: AsyncRead + AsyncWrite,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Command>(&line) {
+            Ok(command) => match super::dispatch(&requests, command).await {
+                Some(reply) => serde_json::to_value(reply).unwrap_or_default(),
+                None => serde_json::json!({ "error": "player is shutting down" }),
+            },
+            Err(e) => serde_json::json!({ "error": e.to_string() }),
+        };
+        let mut response = response.to_string();
+        response.push('\n');
+        if writer.write_all(response.as_bytes()).await.is_err() {
+            break;
+        }
+    }
+}
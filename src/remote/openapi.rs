@@ -0,0 +1,174 @@
+// The HTTP remote control (server.rs) described as an OpenAPI 3 document, so
+// scripts and home automation tools can be pointed at /api/openapi.json
+// rather than at the source. Episodes are left as loose objects, they're the
+// Pinepods server's JSON and it has the say over what's in them.
+
+use serde_json::{json, Value};
+
+use pinepods_firewood::music_handler::{MAX_SPEED, MIN_SPEED};
+
+pub fn document() -> Value {
+    let status = json!({ "$ref": "#/components/responses/Status" });
+    let episodes = json!({ "$ref": "#/components/responses/Episodes" });
+    let episode_body = json!({
+        "required": true,
+        "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Episode" } } }
+    });
+    let command = |summary: &str| json!({ "summary": summary, "responses": { "200": status } });
+    let with_body = |summary: &str, properties: Value| {
+        let required: Vec<&String> = properties.as_object().map(|p| p.keys().collect()).unwrap_or_default();
+        json!({
+            "summary": summary,
+            "requestBody": {
+                "required": true,
+                "content": { "application/json": { "schema": {
+                    "type": "object",
+                    "required": required,
+                    "properties": properties
+                } } }
+            },
+            "responses": { "200": status, "400": { "$ref": "#/components/responses/Error" } }
+        })
+    };
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Firewood remote control",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Control the Firewood player. Without a token set every request is let in, \
+                with one it goes in an Authorization: Bearer header or as ?token=..."
+        },
+        "security": [{ "bearer": [] }, { "query": [] }],
+        "paths": {
+            "/api/status": { "get": command("What's playing, where it's at, the volume and speed") },
+            "/api/play": { "post": {
+                "summary": "Play the episode in the body now",
+                "parameters": [{
+                    "name": "position",
+                    "in": "query",
+                    "description": "seconds in to start from, the start unless given",
+                    "schema": { "type": "integer", "minimum": 0 }
+                }],
+                "requestBody": episode_body,
+                "responses": { "200": status }
+            } },
+            "/api/queue": {
+                "get": { "summary": "The episodes queued, from the top down", "responses": { "200": episodes } },
+                "post": {
+                    "summary": "Add the episode in the body to the queue",
+                    "requestBody": episode_body,
+                    "responses": { "200": status }
+                }
+            },
+            "/api/queue/move": { "post": with_body(
+                "Move a queued episode, counting from 0 at the top",
+                json!({
+                    "from": { "type": "integer", "minimum": 0 },
+                    "to": { "type": "integer", "minimum": 0 }
+                })
+            ) },
+            "/api/play_pause": { "post": command("Pause or resume") },
+            "/api/pause": { "post": command("Pause") },
+            "/api/resume": { "post": command("Resume") },
+            "/api/skip": { "post": command("Skip to the next in the queue") },
+            "/api/seek": { "post": with_body(
+                "Jump to a position in what's playing",
+                json!({ "position": { "type": "integer", "minimum": 0, "description": "seconds in" } })
+            ) },
+            "/api/volume": { "post": with_body(
+                "Set the volume",
+                json!({ "volume": { "type": "number", "minimum": 0.0, "maximum": 1.0 } })
+            ) },
+            "/api/speed": { "post": with_body(
+                "Set the playback speed, in tenths",
+                json!({ "speed": { "type": "number", "minimum": MIN_SPEED, "maximum": MAX_SPEED } })
+            ) },
+            "/api/search": { "get": {
+                "summary": "Episodes matching a search, from those fetched so far",
+                "parameters": [{ "name": "q", "in": "query", "required": true, "schema": { "type": "string" } }],
+                "responses": { "200": episodes, "400": { "$ref": "#/components/responses/Error" } }
+            } },
+            "/api/history": { "get": {
+                "summary": "What this device has played, newest first",
+                "parameters": [{ "name": "limit", "in": "query", "schema": { "type": "integer", "default": 50 } }],
+                "responses": { "200": { "description": "Plays", "content": { "application/json": {
+                    "schema": { "type": "array", "items": { "type": "object" } }
+                } } } }
+            } },
+            "/api/stats": { "get": {
+                "summary": "Time listened on this device, overall, today, this week and per podcast",
+                "responses": { "200": { "description": "Totals", "content": { "application/json": {
+                    "schema": { "type": "object" }
+                } } } }
+            } },
+            "/api/events": { "get": {
+                "summary": "A WebSocket of changes to the status as they happen",
+                "description": "Each message is {\"event\": ..., \"status\": {...}} with event one of status \
+                    (sent on connecting), track, playing, paused, position, volume, speed or queue",
+                "responses": {
+                    "101": { "description": "Switched to a WebSocket" },
+                    "400": { "$ref": "#/components/responses/Error" }
+                }
+            } },
+            "/api/openapi.json": { "get": {
+                "summary": "This document",
+                "responses": { "200": { "description": "OpenAPI document", "content": { "application/json": {
+                    "schema": { "type": "object" }
+                } } } }
+            } }
+        },
+        "components": {
+            "securitySchemes": {
+                "bearer": { "type": "http", "scheme": "bearer" },
+                "query": { "type": "apiKey", "in": "query", "name": "token" }
+            },
+            "schemas": {
+                "Status": {
+                    "type": "object",
+                    "properties": {
+                        "episode": { "type": "string", "nullable": true },
+                        "episode_id": { "type": "integer", "nullable": true },
+                        "paused": { "type": "boolean" },
+                        "position": { "type": "integer", "description": "seconds in" },
+                        "duration": { "type": "integer", "description": "seconds" },
+                        "queue_length": { "type": "integer" },
+                        "volume": { "type": "number" },
+                        "muted": { "type": "boolean" },
+                        "ducked": { "type": "boolean", "description": "playing at no more than 0.2 for now" },
+                        "speed": { "type": "number" },
+                        "live": { "type": "boolean" }
+                    }
+                },
+                "Episode": {
+                    "type": "object",
+                    "description": "An episode as the Pinepods server has it",
+                    "required": [
+                        "EpisodeTitle",
+                        "EpisodePubDate",
+                        "EpisodeDescription",
+                        "EpisodeArtwork",
+                        "EpisodeURL",
+                        "EpisodeDuration"
+                    ],
+                    "additionalProperties": true
+                },
+                "Error": {
+                    "type": "object",
+                    "properties": { "error": { "type": "string" } }
+                }
+            },
+            "responses": {
+                "Status": { "description": "The status after the command ran", "content": { "application/json": {
+                    "schema": { "$ref": "#/components/schemas/Status" }
+                } } },
+                "Episodes": { "description": "Episodes", "content": { "application/json": {
+                    "schema": { "type": "array", "items": { "$ref": "#/components/schemas/Episode" } }
+                } } },
+                "Error": { "description": "What was wrong with the request", "content": { "application/json": {
+                    "schema": { "$ref": "#/components/schemas/Error" }
+                } } }
+            }
+        }
+    })
+}
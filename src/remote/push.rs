@@ -0,0 +1,82 @@
+// Player state pushed to remote control clients, so they don't have to poll
+// /api/status. Whichever loop owns the player hands over the status as it
+// goes (see `Remote::push`), and every change in it is broadcast to the
+// WebSocket clients of GET /api/events (server.rs) as one JSON text message:
+//
+//   {"event": "position", "status": {...}}
+//
+// with event one of track (another episode, or none), playing, paused,
+// position (about once a second while playing), volume, speed or queue. A
+// client is sent {"event": "status", ...} as it connects, to start from.
+
+use tokio::sync::broadcast;
+
+use super::Status;
+
+// messages a slow client can fall behind by before it misses some
+const CAPACITY: usize = 64;
+
+pub struct Push {
+    sender: broadcast::Sender<String>,
+    last: Option<Status>,
+}
+
+impl Default for Push {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Push {
+    pub fn new() -> Self {
+        Self {
+            sender: broadcast::channel(CAPACITY).0,
+            last: None,
+        }
+    }
+
+    /// For the server to subscribe its clients with.
+    pub fn sender(&self) -> broadcast::Sender<String> {
+        self.sender.clone()
+    }
+
+    /// Broadcast whatever changed between the last status and `status`.
+    pub fn update(&mut self, status: Status) {
+        let Some(last) = self.last.replace(status) else {
+            return;
+        };
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+        let status = self.last.as_ref().unwrap();
+
+        let mut events = Vec::new();
+        if last.episode_id != status.episode_id || last.episode != status.episode {
+            events.push("track");
+        }
+        if last.paused != status.paused {
+            events.push(if status.paused { "paused" } else { "playing" });
+        }
+        if last.position != status.position || last.duration != status.duration {
+            events.push("position");
+        }
+        if last.volume != status.volume || last.muted != status.muted || last.ducked != status.ducked {
+            events.push("volume");
+        }
+        if last.speed != status.speed {
+            events.push("speed");
+        }
+        if last.queue_length != status.queue_length {
+            events.push("queue");
+        }
+        for event in events {
+            // no one listening is fine, they're checked for again next time
+            let _ = self.sender.send(message(event, status));
+        }
+    }
+}
+
+/// One message to the clients, `event` with the status it left things in.
+pub fn message(event: &str, status: &Status) -> String {
+    serde_json::json!({ "event": event, "status": status }).to_string()
+}
@@ -0,0 +1,440 @@
+// HTTP remote control.
+//
+// Requests are turned into a `Command` and handed to whichever loop owns the
+// player (the tui or the daemon), so the player itself never leaves that loop.
+//
+//   GET  /api/status        what's playing
+//   POST /api/play          play the episode in the body now, from
+//                           ?position=... seconds in if given
+//   POST /api/queue         add the episode in the body to the queue
+//   POST /api/play_pause    toggle
+//   POST /api/pause
+//   POST /api/resume
+//   POST /api/skip
+//   POST /api/seek          {"position": seconds} into what's playing
+//   POST /api/volume        {"volume": 0.0 to 1.0}
+//   POST /api/speed         {"speed": 0.5 to 3.0}
+//   GET  /api/queue         the episodes queued, from the top down
+//   POST /api/queue/move    {"from": 3, "to": 0}, counting from 0 at the top
+//   GET  /api/search        episodes matching ?q=..., from those fetched so far
+//   GET  /api/history       what this device has played, newest first, at
+//                           most ?limit=... (50 unless given)
+//   GET  /api/stats         time listened on this device, overall, today,
+//                           this week and per podcast
+//   GET  /api/events        a WebSocket of changes to the status as they
+//                           happen, see push.rs
+//   GET  /api/openapi.json  all of this as an OpenAPI document (openapi.rs)
+//
+// Episodes use the same JSON as the Pinepods server. Every endpoint but those
+// listing episodes, history and stats answers with the status after the
+// command ran: what's playing, where it's at, the volume, speed and how much
+// is queued.
+//
+// Hardware buttons (Stream Deck and the like) usually only do plain GETs, so
+// there are also
+//
+//   GET /play  /pause  /toggle  /next  /status
+//
+// and the token can be given as ?token=... instead of a header.
+//
+// Every request is noted in the access log (access.rs), and those from a
+// client whose access has been revoked are turned away before anything else.
+
+use std::convert::Infallible;
+use std::io;
+use std::net::{IpAddr, TcpListener};
+use std::sync::Arc;
+
+use hyper::header::{AUTHORIZATION, CONNECTION, CONTENT_TYPE, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY, UPGRADE};
+use hyper::server::conn::AddrStream;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::upgrade::Upgraded;
+use hyper::{Body, Method, Response, Server, StatusCode};
+use log::{debug, error};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use pinepods_firewood::play_history;
+
+use super::access::{AccessLog, Connection};
+use super::{openapi, push, websocket, Command, Reply, Request};
+
+// plays /api/history returns without a ?limit=
+const HISTORY_LIMIT: usize = 50;
+
+/// What's served under /api, as advertised over mDNS (discovery.rs).
+pub const ENDPOINTS: [&str; 16] = [
+    "status",
+    "play",
+    "queue",
+    "queue/move",
+    "play_pause",
+    "pause",
+    "resume",
+    "skip",
+    "seek",
+    "volume",
+    "speed",
+    "search",
+    "history",
+    "stats",
+    "events",
+    "openapi.json",
+];
+
+/// Serve on `listener` in the background, sending commands to `tx`, passing
+/// on `events` to WebSocket clients and noting every request in `access`,
+/// until the returned task is aborted.
+pub fn start(
+    listener: TcpListener,
+    token: Option<String>,
+    tx: mpsc::Sender<Request>,
+    events: broadcast::Sender<String>,
+    access: AccessLog,
+) -> Result<JoinHandle<()>, hyper::Error> {
+    let token: Option<Arc<str>> = token.map(Into::into);
+
+    let builder = Server::from_tcp(listener)?;
+    let make_service = make_service_fn(move |conn: &AddrStream| {
+        let tx = tx.clone();
+        let token = token.clone();
+        let events = events.clone();
+        let access = access.clone();
+        let ip = conn.remote_addr().ip();
+        // open for as long as the service is, which is as long as the connection
+        let connection = Arc::new(access.connected(ip));
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                let client = Client {
+                    ip,
+                    access: access.clone(),
+                    connection: connection.clone(),
+                };
+                handle(req, client, tx.clone(), token.clone(), events.clone())
+            }))
+        }
+    });
+
+    Ok(tokio::spawn(async move {
+        if let Err(e) = builder.serve(make_service).await {
+            error!("Remote control server stopped: {}", e);
+        }
+    }))
+}
+
+// who a request is from, and their connection, kept open by a WebSocket too
+struct Client {
+    ip: IpAddr,
+    access: AccessLog,
+    connection: Arc<Connection>,
+}
+
+async fn handle(
+    req: hyper::Request<Body>,
+    client: Client,
+    requests: mpsc::Sender<Request>,
+    token: Option<Arc<str>>,
+    events: broadcast::Sender<String>,
+) -> Result<Response<Body>, Infallible> {
+    let endpoint = format!("{} {}", req.method(), req.uri().path());
+    let (ip, access) = (client.ip, client.access.clone());
+    let response = match access.is_revoked(ip) {
+        true => error_response(StatusCode::FORBIDDEN, "this client's access has been revoked"),
+        false => answer(req, client.connection, requests, token, events).await,
+    };
+    access.record(ip, endpoint, response.status().as_u16());
+    Ok(response)
+}
+
+async fn answer(
+    req: hyper::Request<Body>,
+    connection: Arc<Connection>,
+    requests: mpsc::Sender<Request>,
+    token: Option<Arc<str>>,
+    events: broadcast::Sender<String>,
+) -> Response<Body> {
+    if let Some(token) = token {
+        let header = req
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        let query = query_param(&req, "token").map(decode);
+        let matches = |given: Option<&str>| given.is_some_and(|given| same_token(given, &token));
+        if !matches(header) && !matches(query.as_deref()) {
+            return error_response(StatusCode::UNAUTHORIZED, "missing or wrong token");
+        }
+    }
+
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+    // answered here, they don't need the player
+    match (&method, path.as_str()) {
+        (&Method::GET, "/api/history") => {
+            let limit = query_param(&req, "limit")
+                .and_then(|limit| limit.parse().ok())
+                .unwrap_or(HISTORY_LIMIT);
+            return json_response(StatusCode::OK, &play_history::history(limit));
+        }
+        (&Method::GET, "/api/stats") => {
+            return json_response(StatusCode::OK, &play_history::summary());
+        }
+        (&Method::GET, "/api/openapi.json") => {
+            return json_response(StatusCode::OK, &openapi::document());
+        }
+        (&Method::GET, "/api/events") => return events_socket(req, connection, requests, events.subscribe()),
+        _ => {}
+    }
+    let command = match (method, path.as_str()) {
+        (Method::GET, "/api/status") => Command::Status,
+        (Method::POST, "/api/play") => {
+            let position = match query_param(&req, "position").map(str::parse) {
+                Some(Ok(position)) => Some(position),
+                Some(Err(_)) => return error_response(StatusCode::BAD_REQUEST, "?position= isn't seconds"),
+                None => None,
+            };
+            match json_body(req).await {
+                Ok(episode) => Command::Play { episode, position },
+                Err(response) => return response,
+            }
+        }
+        (Method::GET, "/api/queue") => Command::QueueList,
+        (Method::POST, "/api/queue") => match json_body(req).await {
+            Ok(episode) => Command::Queue { episode },
+            Err(response) => return response,
+        },
+        (Method::POST, "/api/queue/move") => match command_body(req, "queue_move").await {
+            Ok(command) => command,
+            Err(response) => return response,
+        },
+        (Method::POST, "/api/seek") => match command_body(req, "seek").await {
+            Ok(command) => command,
+            Err(response) => return response,
+        },
+        (Method::POST, "/api/volume") => match command_body(req, "volume").await {
+            Ok(command) => command,
+            Err(response) => return response,
+        },
+        (Method::POST, "/api/speed") => match command_body(req, "speed").await {
+            Ok(command) => command,
+            Err(response) => return response,
+        },
+        (Method::GET, "/api/search") => match query_param(&req, "q").map(decode) {
+            Some(query) => Command::Search { query },
+            None => return error_response(StatusCode::BAD_REQUEST, "missing ?q="),
+        },
+        (Method::POST, "/api/play_pause") => Command::PlayPause,
+        (Method::POST, "/api/pause") => Command::Pause,
+        (Method::POST, "/api/resume") => Command::Resume,
+        (Method::POST, "/api/skip") => Command::Skip,
+        (Method::GET, "/status") => Command::Status,
+        (Method::GET, "/play") => Command::Resume,
+        (Method::GET, "/pause") => Command::Pause,
+        (Method::GET, "/toggle") => Command::PlayPause,
+        (Method::GET, "/next") => Command::Skip,
+        _ => return error_response(StatusCode::NOT_FOUND, "no such endpoint"),
+    };
+
+    match super::dispatch(&requests, command).await {
+        Some(Reply::Error { error }) => error_response(StatusCode::BAD_REQUEST, &error),
+        Some(reply) => json_response(StatusCode::OK, &reply),
+        None => error_response(StatusCode::SERVICE_UNAVAILABLE, "player is shutting down"),
+    }
+}
+
+// switch the connection over to a WebSocket, and push the events down it
+// until the client goes
+fn events_socket(
+    req: hyper::Request<Body>,
+    connection: Arc<Connection>,
+    requests: mpsc::Sender<Request>,
+    events: broadcast::Receiver<String>,
+) -> Response<Body> {
+    let upgrade = req
+        .headers()
+        .get(UPGRADE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("websocket"));
+    let key = req.headers().get(SEC_WEBSOCKET_KEY).and_then(|v| v.to_str().ok());
+    let Some(accept) = key.filter(|_| upgrade).map(websocket::accept) else {
+        return error_response(StatusCode::BAD_REQUEST, "a WebSocket is needed for events");
+    };
+
+    tokio::spawn(async move {
+        // the client's still connected for as long as this is
+        let _connection = connection;
+        match hyper::upgrade::on(req).await {
+            Ok(socket) => {
+                if let Err(e) = stream_events(socket, requests, events).await {
+                    debug!("Remote control events client gone: {}", e);
+                }
+            }
+            Err(e) => error!("Unable to switch to a WebSocket for events: {}", e),
+        }
+    });
+    Response::builder()
+        .status(StatusCode::SWITCHING_PROTOCOLS)
+        .header(UPGRADE, "websocket")
+        .header(CONNECTION, "Upgrade")
+        .header(SEC_WEBSOCKET_ACCEPT, accept)
+        .body(Body::empty())
+        .unwrap_or_default()
+}
+
+async fn stream_events(
+    socket: Upgraded,
+    requests: mpsc::Sender<Request>,
+    mut events: broadcast::Receiver<String>,
+) -> io::Result<()> {
+    let (mut reader, mut writer) = tokio::io::split(socket);
+    // read on its own, a frame half read when an event goes out would be lost
+    let (frames_tx, mut frames) = mpsc::channel(4);
+    let reading = tokio::spawn(async move {
+        while let Ok(frame) = websocket::read(&mut reader).await {
+            let closing = frame.0 == websocket::CLOSE;
+            if frames_tx.send(frame).await.is_err() || closing {
+                break;
+            }
+        }
+    });
+
+    let streaming = async {
+        if let Some(Reply::Status(status)) = super::dispatch(&requests, Command::Status).await {
+            let message = push::message("status", &status);
+            websocket::write(&mut writer, websocket::TEXT, message.as_bytes()).await?;
+        }
+        loop {
+            tokio::select! {
+                event = events.recv() => match event {
+                    Ok(message) => websocket::write(&mut writer, websocket::TEXT, message.as_bytes()).await?,
+                    // every message has the whole status, the next one catches it up
+                    Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => return websocket::write(&mut writer, websocket::CLOSE, &[]).await,
+                },
+                frame = frames.recv() => match frame {
+                    Some((websocket::PING, payload)) => {
+                        websocket::write(&mut writer, websocket::PONG, &payload).await?
+                    }
+                    Some((websocket::CLOSE, _)) => {
+                        return websocket::write(&mut writer, websocket::CLOSE, &[]).await
+                    }
+                    Some(_) => {}
+                    None => return Ok(()),
+                },
+            }
+        }
+    };
+    let result = streaming.await;
+    reading.abort();
+    result
+}
+
+async fn json_body<T: DeserializeOwned>(req: hyper::Request<Body>) -> Result<T, Response<Body>> {
+    let bytes = hyper::body::to_bytes(req.into_body())
+        .await
+        .map_err(|e| error_response(StatusCode::BAD_REQUEST, &e.to_string()))?;
+    serde_json::from_slice(&bytes).map_err(|e| error_response(StatusCode::BAD_REQUEST, &e.to_string()))
+}
+
+// the command called `name` with the fields in the body, {"position": 90}
+// for a seek and so on
+async fn command_body(req: hyper::Request<Body>, name: &str) -> Result<Command, Response<Body>> {
+    let mut fields: Map<String, Value> = json_body(req).await?;
+    fields.insert(String::from("command"), Value::from(name));
+    serde_json::from_value(Value::Object(fields))
+        .map_err(|e| error_response(StatusCode::BAD_REQUEST, &e.to_string()))
+}
+
+fn query_param<'a>(req: &'a hyper::Request<Body>, name: &str) -> Option<&'a str> {
+    req.uri().query()?.split('&').find_map(|pair| {
+        pair.strip_prefix(name)?.strip_prefix('=')
+    })
+}
+
+// compared in constant time, so how long a wrong one takes to be turned
+// away says nothing about how much of it was right
+fn same_token(given: &str, token: &str) -> bool {
+    let (given, token) = (given.as_bytes(), token.as_bytes());
+    given.len() == token.len() && given.iter().zip(token).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+// %xx and + in a query value, as browsers send them
+fn decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|hex| bytes[i] == b'%' && hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match (bytes[i], escaped) {
+            (_, Some(byte)) => {
+                decoded.push(byte);
+                i += 2;
+            }
+            (b'+', None) => decoded.push(b' '),
+            (byte, None) => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn json_response<T: Serialize>(status: StatusCode, body: &T) -> Response<Body> {
+    let body = serde_json::to_string(body).unwrap_or_default();
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .unwrap_or_default()
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Body> {
+    json_response(status, &serde_json::json!({ "error": message }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_escapes_and_plus() {
+        assert_eq!(decode("a%20b+c"), "a b c");
+        assert_eq!(decode("%2B%2b%25"), "++%");
+        assert_eq!(decode("caf%C3%A9"), "café");
+        assert_eq!(decode("plain"), "plain");
+        assert_eq!(decode(""), "");
+    }
+
+    #[test]
+    fn bad_escapes_left_as_they_are() {
+        // from_str_radix would take the sign, they need to be two hex digits
+        assert_eq!(decode("%+1"), "% 1");
+        assert_eq!(decode("%-1x"), "%-1x");
+        assert_eq!(decode("%zz"), "%zz");
+        assert_eq!(decode("%4"), "%4");
+        assert_eq!(decode("100%"), "100%");
+        assert_eq!(decode("%%41"), "%A");
+    }
+
+    #[test]
+    fn invalid_utf8_replaced() {
+        assert_eq!(decode("%FF"), "\u{FFFD}");
+    }
+
+    #[test]
+    fn tokens_match_exactly() {
+        assert!(same_token("abc123", "abc123"));
+        assert!(same_token("", ""));
+        assert!(!same_token("abc124", "abc123"));
+        assert!(!same_token("abc12", "abc123"));
+        assert!(!same_token("abc1234", "abc123"));
+        assert!(!same_token("ABC123", "abc123"));
+        assert!(!same_token("", "abc123"));
+    }
+}
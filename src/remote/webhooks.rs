@@ -0,0 +1,61 @@
+// Outgoing webhooks. Every configured url is POSTed the player status as
+// JSON whenever what's playing, or whether it's paused, changes:
+//
+//   {"event": "playing", "status": {...}}
+//
+// with event one of playing, paused or stopped. Position changes alone
+// don't count, or there'd be a call every second.
+
+use log::error;
+use serde_json::json;
+
+use super::Status;
+
+#[derive(Default)]
+pub struct Webhooks {
+    urls: Vec<String>,
+    client: reqwest::Client,
+    // what the last call was about, (event, episode)
+    last: Option<(&'static str, Option<String>)>,
+}
+
+impl Webhooks {
+    pub fn new(urls: Vec<String>) -> Self {
+        Self {
+            urls,
+            ..Self::default()
+        }
+    }
+
+    /// Call the webhooks if `status` is a change from the last one seen. The
+    /// calls go out in the background, a slow hook doesn't hold up the player.
+    pub fn update(&mut self, status: &Status) {
+        let event = match (&status.episode, status.paused) {
+            (None, _) => "stopped",
+            (Some(_), true) => "paused",
+            (Some(_), false) => "playing",
+        };
+        let state = (event, status.episode.clone());
+        if self.last.as_ref() == Some(&state) {
+            return;
+        }
+        // nothing worth telling about at startup
+        let first = self.last.is_none();
+        self.last = Some(state);
+        if first && event == "stopped" {
+            return;
+        }
+
+        let body = json!({ "event": event, "status": status });
+        for url in &self.urls {
+            let request = self.client.post(url).json(&body);
+            let url = url.clone();
+            tokio::spawn(async move {
+                let result = request.send().await.and_then(|r| r.error_for_status());
+                if let Err(e) = result {
+                    error!("Webhook {} failed: {}", url, e);
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,76 @@
+// As much of WebSocket (RFC 6455) as the push channel (push.rs) needs: the
+// handshake, and text frames out to the client. What comes back is only read
+// to answer pings and see the client closing, nothing it says is acted on.
+
+use std::io;
+
+use base64::Engine;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use pinepods_firewood::sha1;
+
+// added to the client's key before hashing, the same for every server
+const GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+// nothing a client has reason to send comes near this, anything bigger is
+// taken as it misbehaving
+const MAX_PAYLOAD: u64 = 64 * 1024;
+
+pub const TEXT: u8 = 0x1;
+pub const CLOSE: u8 = 0x8;
+pub const PING: u8 = 0x9;
+pub const PONG: u8 = 0xa;
+
+/// The Sec-WebSocket-Accept header answering a client's Sec-WebSocket-Key.
+pub fn accept(key: &str) -> String {
+    let digest = sha1::digest(format!("{}{}", key.trim(), GUID).as_bytes());
+    base64::engine::general_purpose::STANDARD.encode(digest)
+}
+
+/// Send one unfragmented frame. Frames from the server aren't masked.
+pub async fn write<W: AsyncWrite + Unpin>(io: &mut W, opcode: u8, payload: &[u8]) -> io::Result<()> {
+    let mut frame = Vec::with_capacity(payload.len() + 10);
+    frame.push(0x80 | opcode);
+    match payload.len() {
+        len @ 0..=125 => frame.push(len as u8),
+        len @ 126..=0xffff => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    io.write_all(&frame).await?;
+    io.flush().await
+}
+
+/// The next frame from the client, (opcode, payload) with the payload
+/// unmasked.
+pub async fn read<R: AsyncRead + Unpin>(io: &mut R) -> io::Result<(u8, Vec<u8>)> {
+    let mut header = [0u8; 2];
+    io.read_exact(&mut header).await?;
+    let opcode = header[0] & 0x0f;
+    let masked = header[1] & 0x80 != 0;
+    let len = match header[1] & 0x7f {
+        126 => u64::from(io.read_u16().await?),
+        127 => io.read_u64().await?,
+        len => u64::from(len),
+    };
+    if len > MAX_PAYLOAD {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "frame too large"));
+    }
+    let mut mask = [0u8; 4];
+    if masked {
+        io.read_exact(&mut mask).await?;
+    }
+    let mut payload = vec![0u8; len as usize];
+    io.read_exact(&mut payload).await?;
+    if masked {
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask[i % 4];
+        }
+    }
+    Ok((opcode, payload))
+}
@@ -0,0 +1,291 @@
+// What can keep Firewood from working, checked once the terminal's set up
+// and before the app starts: the server not answering, it not taking the
+// API key, there being no audio output to play on, and a config file that
+// couldn't be read. Each gets a screen of its own (see main.rs) saying what's
+// wrong, with keys to fix it there and then or check again, instead of the
+// app starting with nothing in it, or panicking on the way in.
+//
+// Everything but there being no audio output can be carried on past, to the
+// pages cached last time (see cache.rs) or the built in settings.
+
+use std::time::Duration;
+
+use crossterm::event::KeyCode;
+use pinepods_firewood::music_handler;
+use pinepods_firewood::requests::{PinepodsError, ReqwestValues};
+use pinepods_firewood::stateful_list::StatefulList;
+
+use crate::config::Config;
+
+// long enough for a slow server, not so long it looks stuck
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// the server didn't answer, or not the way a Pinepods server does
+    Unreachable { url: String, error: String },
+    /// the server's there but doesn't take the API key
+    InvalidSession { url: String },
+    NoAudio(String),
+    /// the config file couldn't be read, so none of it was
+    Settings(String),
+}
+
+impl Problem {
+    pub fn title(&self) -> &'static str {
+        match self {
+            Problem::Unreachable { .. } => "Can't Reach The Server",
+            Problem::InvalidSession { .. } => "The Server Didn't Take The API Key",
+            Problem::NoAudio(_) => "No Audio Output To Play On",
+            Problem::Settings(_) => "The Settings Couldn't Be Read",
+        }
+    }
+
+    /// What's wrong, a line at a time.
+    pub fn details(&self) -> Vec<String> {
+        match self {
+            Problem::Unreachable { url, error } => vec![
+                format!("Nothing answered at {} as a Pinepods server would.", url),
+                error.clone(),
+                String::from("Carrying on shows what was fetched last time, if anything was."),
+            ],
+            Problem::InvalidSession { url } => vec![
+                format!("{} is a Pinepods server, but the API key isn't one it knows.", url),
+                String::from("It may have been deleted, or be for another server."),
+            ],
+            Problem::NoAudio(error) => vec![
+                String::from("There's no audio output to play episodes on."),
+                error.clone(),
+                String::from("Plug one in and check again, or pick another if there are others."),
+                String::from("One picked here is for this run, playback.audio_device in config.toml keeps it."),
+            ],
+            Problem::Settings(error) => vec![
+                error.clone(),
+                String::from("The built in settings are used until it's fixed."),
+                String::from("Resetting moves it aside to config.toml.bak."),
+            ],
+        }
+    }
+
+    /// The keys there are for it, as the Controls tab writes them, and what
+    /// each does.
+    pub fn actions(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Problem::Unreachable { .. } => &[
+                ("R", "Check Again"),
+                ("E", "Change The Server URL"),
+                ("C", "Carry On Without It"),
+                ("Q", "Quit"),
+            ],
+            Problem::InvalidSession { .. } => &[
+                ("R", "Check Again"),
+                ("L", "Log In With Another API Key"),
+                ("C", "Carry On Without It"),
+                ("Q", "Quit"),
+            ],
+            Problem::NoAudio(_) => &[("R", "Check Again"), ("D", "Pick An Audio Output"), ("Q", "Quit")],
+            Problem::Settings(_) => &[
+                ("Shift + R", "Reset The Settings"),
+                ("C", "Carry On With The Built In Ones"),
+                ("Q", "Quit"),
+            ],
+        }
+    }
+
+    // the same kind of problem, whatever the details
+    fn kind(&self) -> u8 {
+        match self {
+            Problem::Unreachable { .. } => 0,
+            Problem::InvalidSession { .. } => 1,
+            Problem::NoAudio(_) => 2,
+            Problem::Settings(_) => 3,
+        }
+    }
+
+    fn is_login(&self) -> bool {
+        matches!(self, Problem::Unreachable { .. } | Problem::InvalidSession { .. })
+    }
+}
+
+/// Everything wrong with starting up with `values` and `cfg`, the settings
+/// first, then the server, then the audio output.
+pub async fn check(values: &ReqwestValues, cfg: &Config) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    if let Some(error) = cfg.unreadable() {
+        problems.push(Problem::Settings(error.to_string()));
+    }
+
+    let url = values.url.clone();
+    let reached = match tokio::time::timeout(TIMEOUT, values.make_request()).await {
+        Ok(Ok(check)) if check.status_code == 200 => Ok(()),
+        Ok(Ok(check)) => Err(format!("It answered with {}.", check.status_code)),
+        Ok(Err(e)) => Err(described(e)),
+        Err(_) => Err(format!("It didn't answer within {} seconds.", TIMEOUT.as_secs())),
+    };
+    match reached {
+        Ok(()) => {
+            if !matches!(tokio::time::timeout(TIMEOUT, values.get_userid()).await, Ok(Ok(_))) {
+                problems.push(Problem::InvalidSession { url });
+            }
+        }
+        Err(error) => problems.push(Problem::Unreachable { url, error }),
+    }
+
+    if let Err(e) = music_handler::check_output() {
+        problems.push(Problem::NoAudio(format!("{}.", e)));
+    }
+    problems
+}
+
+fn described(error: PinepodsError) -> String {
+    match error {
+        PinepodsError::Reqwest(e) => format!("{}.", e),
+        PinepodsError::Serde(_) => String::from("Something answered, but not as a Pinepods server does."),
+        PinepodsError::Status(status) => format!("It answered with {}.", status),
+    }
+}
+
+/// What a key on the screen asks for.
+pub enum Action {
+    Retry,
+    Quit,
+    ResetSettings,
+    SetUrl(String),
+    SetApiKey(String),
+    Device(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Url,
+    ApiKey,
+}
+
+/// Being typed into place of what's wrong.
+pub struct Input {
+    pub field: Field,
+    pub value: String,
+}
+
+#[derive(Default)]
+pub struct Gate {
+    problems: Vec<Problem>,
+    // the kinds carried on past, not shown again after checking again
+    carried_on: Vec<u8>,
+    pub input: Option<Input>,
+    pub devices: Option<StatefulList<String>>,
+    /// how the last fix went, when it didn't
+    pub message: Option<String>,
+    pub checking: bool,
+}
+
+impl Gate {
+    pub fn set_problems(&mut self, problems: Vec<Problem>) {
+        self.problems = problems;
+    }
+
+    /// The problem to show, None once there's nothing stopping the app.
+    pub fn problem(&self) -> Option<&Problem> {
+        self.problems
+            .iter()
+            .find(|problem| !self.carried_on.contains(&problem.kind()))
+    }
+
+    /// How many problems there are left to show, the one showing included.
+    pub fn left(&self) -> usize {
+        self.problems
+            .iter()
+            .filter(|problem| !self.carried_on.contains(&problem.kind()))
+            .count()
+    }
+
+    /// Whether the server can't be reached or won't take the API key, as of
+    /// the last check, carried on past or not.
+    pub fn login_failing(&self) -> bool {
+        self.problems.iter().any(Problem::is_login)
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        if let Some(input) = self.input.as_mut() {
+            input.value.extend(text.chars().filter(|c| !c.is_control()));
+        }
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> Option<Action> {
+        if let Some(input) = self.input.as_mut() {
+            match code {
+                KeyCode::Char(c) => input.value.push(c),
+                KeyCode::Backspace => {
+                    input.value.pop();
+                }
+                KeyCode::Esc => self.input = None,
+                KeyCode::Enter => {
+                    let input = self.input.take()?;
+                    let value = input.value.trim().to_string();
+                    if value.is_empty() {
+                        return None;
+                    }
+                    return Some(match input.field {
+                        Field::Url => Action::SetUrl(value.trim_end_matches('/').to_string()),
+                        Field::ApiKey => Action::SetApiKey(value),
+                    });
+                }
+                _ => {}
+            }
+            return None;
+        }
+
+        if let Some(devices) = self.devices.as_mut() {
+            match code {
+                KeyCode::Down | KeyCode::Char('j') => devices.next(),
+                KeyCode::Up | KeyCode::Char('k') => devices.previous(),
+                KeyCode::Esc => self.devices = None,
+                KeyCode::Enter => {
+                    let device = devices.selected().map(|_| devices.item().clone());
+                    self.devices = None;
+                    return device.map(Action::Device);
+                }
+                _ => {}
+            }
+            return None;
+        }
+
+        let problem = self.problem()?.clone();
+        match (code, &problem) {
+            (KeyCode::Char('q'), _) => return Some(Action::Quit),
+            (KeyCode::Char('r'), Problem::Unreachable { .. } | Problem::InvalidSession { .. } | Problem::NoAudio(_)) => {
+                return Some(Action::Retry)
+            }
+            (KeyCode::Char('e'), Problem::Unreachable { url, .. }) => {
+                self.input = Some(Input {
+                    field: Field::Url,
+                    value: url.clone(),
+                });
+            }
+            (KeyCode::Char('l'), Problem::InvalidSession { .. }) => {
+                self.input = Some(Input {
+                    field: Field::ApiKey,
+                    value: String::new(),
+                });
+            }
+            (KeyCode::Char('d'), Problem::NoAudio(_)) => {
+                let devices = music_handler::output_devices();
+                match devices.is_empty() {
+                    true => self.message = Some(String::from("There aren't any audio outputs to pick from.")),
+                    false => {
+                        let mut devices = StatefulList::with_items(devices);
+                        devices.next();
+                        self.devices = Some(devices);
+                    }
+                }
+            }
+            (KeyCode::Char('R'), Problem::Settings(_)) => return Some(Action::ResetSettings),
+            (KeyCode::Char('c'), Problem::Unreachable { .. } | Problem::InvalidSession { .. } | Problem::Settings(_)) => {
+                self.carried_on.push(problem.kind());
+                self.message = None;
+            }
+            _ => {}
+        }
+        None
+    }
+}
@@ -0,0 +1,218 @@
+// Subcommands, for scripts and window manager key bindings: each does one
+// thing and exits, without the terminal ui. What's for the player goes to the
+// Firewood already running here, over the local remote control socket (see
+// remote/ipc.rs), so it needs no login and answers straight away. The queue
+// and subscriptions are the server's, those go to it directly, but for adding
+// to the queue, which goes to the player's if there's one running.
+
+use std::io;
+
+use pinepods_firewood::feed;
+use pinepods_firewood::format;
+use pinepods_firewood::handoff;
+use pinepods_firewood::paste::{self, Pasted};
+use pinepods_firewood::requests::{PinepodsEpisodes, ReqwestValues};
+use serde_json::Value;
+
+use crate::cli::{Action, QueueAction};
+use crate::remote::{ipc, Command};
+
+/// For when a subcommand fails: just the reason, for whoever ran it.
+pub fn fail(e: io::Error) -> ! {
+    eprintln!("{}", e);
+    std::process::exit(1)
+}
+
+/// Hand `action` to the player already running, before logging in. False
+/// when it needs the server, see `run`.
+pub async fn forward(action: &Action) -> io::Result<bool> {
+    let command = match action {
+        Action::Pause => Command::Pause,
+        Action::Resume => Command::Resume,
+        Action::Toggle => Command::PlayPause,
+        Action::Skip => Command::Skip,
+        Action::Status => Command::Status,
+        Action::Play { .. } | Action::Queue(_) | Action::Subscribe { .. } | Action::Add { .. } => return Ok(false),
+    };
+    match ipc::send(&command).await? {
+        Some(answer) => print_status(&answer)?,
+        None if matches!(action, Action::Status) => println!("Firewood isn't running"),
+        None => return Err(io::Error::other("Firewood isn't running, there's no player to control")),
+    }
+    Ok(true)
+}
+
+/// Do `action` with the server. The episode to play is given back when
+/// there's no player running to hand it to.
+pub async fn run(action: &Action, values: &ReqwestValues) -> io::Result<Option<PinepodsEpisodes>> {
+    match action {
+        Action::Play { episode_id } => {
+            let episode = find_episode(values, *episode_id).await?;
+            let play = Command::Play {
+                episode: episode.clone(),
+                position: None,
+            };
+            match ipc::send(&play).await? {
+                Some(answer) => print_status(&answer)?,
+                None => return Ok(Some(episode)),
+            }
+        }
+        Action::Queue(QueueAction::List) => {
+            let queue = values.queued_episodes().await.map_err(io::Error::other)?;
+            // the id first, for `play`
+            for episode in queue {
+                println!(
+                    "{}\t{} - {} ({})",
+                    episode.EpisodeID.map(|id| id.to_string()).unwrap_or_default(),
+                    episode.PodcastName.as_deref().unwrap_or("Unknown Podcast"),
+                    episode.EpisodeTitle,
+                    format::length(episode.EpisodeDuration)
+                );
+            }
+        }
+        Action::Subscribe { url } => subscribe(values, url).await?,
+        Action::Add { url } => add(values, url).await?,
+        // nothing of these left to do once forwarded
+        Action::Pause | Action::Resume | Action::Toggle | Action::Skip | Action::Status => {}
+    }
+    Ok(None)
+}
+
+// the server has no lookup by id, so it's looked for where an episode someone
+// wants to play by id is likely to be: the queue, what's new and the history
+async fn find_episode(values: &ReqwestValues, episode_id: i64) -> io::Result<PinepodsEpisodes> {
+    let is_it = |episode: &PinepodsEpisodes| episode.EpisodeID == Some(episode_id);
+    for episodes in [values.queued_episodes().await, values.recent_episodes().await] {
+        if let Some(episode) = episodes.map_err(io::Error::other)?.into_iter().find(is_it) {
+            return Ok(episode);
+        }
+    }
+    let history = values.user_history().await.map_err(io::Error::other)?;
+    history
+        .iter()
+        .filter(|entry| entry.episode_url.is_some())
+        .map(handoff::episode)
+        .find(is_it)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("episode {} isn't in the queue, the recent episodes or the history", episode_id),
+            )
+        })
+}
+
+async fn subscribe(values: &ReqwestValues, url: &str) -> io::Result<()> {
+    let feed = feed::fetch(url).await.map_err(io::Error::other)?;
+    if feed.channel.title.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} doesn't look like a podcast feed", url),
+        ));
+    }
+    let podcast = feed.podcast(url);
+    values
+        .add_podcast(&podcast)
+        .await
+        .map_err(|e| io::Error::other(format!("unable to subscribe: {:?}", e)))?;
+    println!("Subscribed to {}", podcast.PodcastName);
+    Ok(())
+}
+
+// the episodes a link is to, worked out as a pasted one is (see paste.rs),
+// and queued as the server has them where it does, with their ids and how far
+// they've been played
+async fn add(values: &ReqwestValues, url: &str) -> io::Result<()> {
+    let episodes = match paste::resolve(values, url).await.map_err(io::Error::other)? {
+        Pasted::Queue(episode) => vec![episode],
+        Pasted::Import(import) => {
+            for title in &import.unmatched {
+                eprintln!("Nothing to play in {}", title);
+            }
+            import.episodes
+        }
+        Pasted::Subscribe(podcast) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is the feed of {}, not an episode, subscribe to it instead", url, podcast.PodcastName),
+            ))
+        }
+    };
+    if episodes.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("there's no episode to queue at {}", url),
+        ));
+    }
+
+    for episode in episodes {
+        let (episode, matched) = match on_server(values, &episode.EpisodeURL).await {
+            Some(known) => (known, "as the server has it"),
+            None if episode.EpisodeID.is_some() => (episode, "as the server has it"),
+            None => (episode, "from the link, the server doesn't have it"),
+        };
+        let label = format!(
+            "{} - {}",
+            episode.PodcastName.as_deref().unwrap_or("Unknown Podcast"),
+            episode.EpisodeTitle
+        );
+        let queue = Command::Queue {
+            episode: episode.clone(),
+        };
+        match (ipc::send(&queue).await?, episode.EpisodeID) {
+            (Some(answer), _) => match answer["error"].as_str() {
+                Some(error) => return Err(io::Error::other(error.to_string())),
+                None => println!("Queued {}, {}", label, matched),
+            },
+            (None, Some(episode_id)) => {
+                values
+                    .queue_episode(episode_id)
+                    .await
+                    .map_err(|e| io::Error::other(format!("unable to queue {}: {:?}", label, e)))?;
+                println!("Queued {} on the server, {}", label, matched);
+            }
+            (None, None) => {
+                return Err(io::Error::other(format!(
+                    "Firewood isn't running, and {} isn't on the server to queue there",
+                    label
+                )))
+            }
+        }
+    }
+    Ok(())
+}
+
+// the episode with the file at `url`, where the server's likely to have it:
+// the queue, what's new and the history
+async fn on_server(values: &ReqwestValues, url: &str) -> Option<PinepodsEpisodes> {
+    for episodes in [values.queued_episodes().await, values.recent_episodes().await] {
+        if let Some(episode) = episodes.ok()?.into_iter().find(|episode| episode.EpisodeURL == url) {
+            return Some(episode);
+        }
+    }
+    let history = values.user_history().await.ok()?;
+    history
+        .iter()
+        .filter(|entry| entry.episode_url.as_deref() == Some(url))
+        .map(handoff::episode)
+        .next()
+}
+
+// the status a player answered with, on one line
+fn print_status(answer: &Value) -> io::Result<()> {
+    if let Some(error) = answer["error"].as_str() {
+        return Err(io::Error::other(error.to_string()));
+    }
+    let queued = answer["queue_length"].as_u64().unwrap_or(0);
+    match answer["episode"].as_str() {
+        Some(episode) => println!(
+            "{} {}, {} of {}, {} queued",
+            if answer["paused"].as_bool().unwrap_or(false) { "Paused" } else { "Playing" },
+            episode,
+            format::clock(answer["position"].as_i64().unwrap_or(0)),
+            format::length(answer["duration"].as_i64().unwrap_or(0)),
+            queued
+        ),
+        None => println!("Nothing playing, {} queued", queued),
+    }
+    Ok(())
+}
@@ -0,0 +1,86 @@
+// Guided tour for first-time users. Each step points at part of the screen
+// and waits for the key it describes. The key still does what it normally
+// does, so the tour is the real app rather than a slideshow.
+
+use crossterm::event::KeyCode;
+use ratatui::layout::Rect;
+
+use crate::app::AppTab;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Tabs,
+    Browse,
+    Open,
+    Queue,
+    Player,
+    Done,
+}
+
+/// Part of the screen a step is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    TabBar,
+    Browser,
+    Queue,
+    Player,
+}
+
+/// Where each target was drawn last, kept up to date while drawing. Only the
+/// tab bar is there on every tab.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Regions {
+    pub tab_bar: Rect,
+    pub browser: Rect,
+    pub queue: Rect,
+    pub player: Rect,
+}
+
+impl Regions {
+    pub fn get(&self, target: Target) -> Rect {
+        match target {
+            Target::TabBar => self.tab_bar,
+            Target::Browser => self.browser,
+            Target::Queue => self.queue,
+            Target::Player => self.player,
+        }
+    }
+}
+
+impl Step {
+    pub fn target(self) -> Option<Target> {
+        match self {
+            Step::Tabs => Some(Target::TabBar),
+            Step::Browse | Step::Open => Some(Target::Browser),
+            Step::Queue => Some(Target::Queue),
+            Step::Player => Some(Target::Player),
+            Step::Done => None,
+        }
+    }
+
+    pub fn text(self) -> &'static str {
+        match self {
+            Step::Tabs => "Tab switches between Podcasts and Controls. Try it, then come back to Podcasts.",
+            Step::Browse => "These are your podcasts. Move through them with j and k, or the arrow keys.",
+            Step::Open => "Enter opens the selected podcast, Backspace goes back. Open one now.",
+            Step::Queue => "On an episode, a adds it to the queue on the right. Queue one up.",
+            Step::Player => "What's playing shows here. Space plays and pauses, [ and ] seek, + and - change the volume. Press Space.",
+            Step::Done => "That's the tour. Every key is listed on the Controls tab, ? there starts this again. Press Enter to finish.",
+        }
+    }
+
+    /// The step to show once `code` has been handled, None when the tour is
+    /// over. `tab` is the tab showing after the key.
+    pub fn advance(self, code: KeyCode, tab: AppTab) -> Option<Step> {
+        let next = match (self, code) {
+            (Step::Tabs, KeyCode::Tab) if matches!(tab, AppTab::Music) => Step::Browse,
+            (Step::Browse, KeyCode::Char('j' | 'k') | KeyCode::Down | KeyCode::Up) => Step::Open,
+            (Step::Open, KeyCode::Enter) => Step::Queue,
+            (Step::Queue, KeyCode::Char('a')) => Step::Player,
+            (Step::Player, KeyCode::Char(' ' | 'p')) => Step::Done,
+            (Step::Done, KeyCode::Enter) => return None,
+            _ => self,
+        };
+        Some(next)
+    }
+}